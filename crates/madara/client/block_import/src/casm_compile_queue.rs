@@ -0,0 +1,150 @@
+//! Background compilation of the Sierra classes imported with a deferred CASM compilation, see
+//! [`crate::BlockValidationContext::lazy_casm_compilation`].
+//!
+//! Compiling a class is much slower than importing it. When a lot of classes are declared in a short range of blocks,
+//! the classes waiting for compilation would pile up in memory. The queue is bounded: once it is full, pushing a new
+//! class waits for a compilation to finish, which slows down block import until the compiler catches up.
+
+use mc_db::{db_block_id::DbBlockId, MadaraBackend};
+use mp_class::SierraClassInfo;
+use opentelemetry::metrics::Gauge;
+use starknet_types_core::felt::Felt;
+use std::sync::Arc;
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+
+struct DeferredClass {
+    block_n: u64,
+    class_hash: Felt,
+    info: SierraClassInfo,
+    /// Released once the class has been compiled.
+    _slot: OwnedSemaphorePermit,
+}
+
+pub struct CasmCompileQueue {
+    sender: mpsc::UnboundedSender<DeferredClass>,
+    slots: Arc<Semaphore>,
+    capacity: usize,
+    depth_gauge: Gauge<u64>,
+}
+
+impl CasmCompileQueue {
+    /// Spawns the compilation thread. At most `capacity` classes are waiting for or undergoing compilation at
+    /// any given time.
+    pub fn new(backend: Arc<MadaraBackend>, capacity: usize, depth_gauge: Gauge<u64>) -> anyhow::Result<Self> {
+        let capacity = capacity.max(1);
+        let slots = Arc::new(Semaphore::new(capacity));
+        let (sender, mut receiver) = mpsc::unbounded_channel::<DeferredClass>();
+
+        std::thread::Builder::new().name("casm-compiler".into()).spawn({
+            let slots = Arc::clone(&slots);
+            let depth_gauge = depth_gauge.clone();
+            move || {
+                while let Some(class) = receiver.blocking_recv() {
+                    if let Err(err) = backend.get_or_compile_sierra_compiled(
+                        &DbBlockId::Number(class.block_n),
+                        &class.class_hash,
+                        &class.info,
+                    ) {
+                        tracing::error!("Failed to compile class {:#x}: {err:#}", class.class_hash);
+                    }
+                    drop(class);
+                    depth_gauge.record((capacity - slots.available_permits()) as u64, &[]);
+                }
+            }
+        })?;
+
+        Ok(Self { sender, slots, capacity, depth_gauge })
+    }
+
+    /// Queues a class declared in block `block_n` for compilation. This waits for a free slot when the queue is full.
+    pub async fn push(&self, block_n: u64, class_hash: Felt, info: SierraClassInfo) {
+        let slot = Arc::clone(&self.slots).acquire_owned().await.expect("Semaphore is never closed");
+        if self.sender.send(DeferredClass { block_n, class_hash, info, _slot: slot }).is_err() {
+            tracing::error!("CASM compilation thread has stopped, class {class_hash:#x} will be compiled on first use");
+        }
+        self.depth_gauge.record(self.depth() as u64, &[]);
+    }
+
+    /// Number of classes waiting for or undergoing compilation.
+    pub fn depth(&self) -> usize {
+        self.capacity - self.slots.available_permits()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::BlockMetrics;
+    use mc_db::tests::common::{finalized_block_zero, finalized_state_diff_zero};
+    use mp_block::{BlockId, BlockTag, Header};
+    use mp_chain_config::ChainConfig;
+    use mp_class::{ConvertedClass, FlattenedSierraClass, SierraConvertedClass};
+    use starknet_core::types::contract::SierraClass;
+    use std::time::Duration;
+
+    const ERC20_CLASS_DEFINITION: &[u8] =
+        include_bytes!("../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json");
+    const ACCOUNT_CLASS_DEFINITION: &[u8] =
+        include_bytes!("../../../../../cairo-artifacts/openzeppelin_AccountUpgradeable.contract_class.json");
+
+    fn lazy_class(definition: &[u8]) -> SierraConvertedClass {
+        let class: SierraClass = serde_json::from_slice(definition).unwrap();
+        let contract_class: FlattenedSierraClass = class.flatten().unwrap().into();
+        let class_hash = contract_class.compute_class_hash().unwrap();
+        let (compiled_class_hash, _) = contract_class.compile_to_casm().unwrap();
+        SierraConvertedClass {
+            class_hash,
+            info: SierraClassInfo { contract_class: Arc::new(contract_class), compiled_class_hash },
+            compiled: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_casm_compile_queue_backpressure() {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::madara_test()));
+        let classes = [lazy_class(ERC20_CLASS_DEFINITION), lazy_class(ACCOUNT_CLASS_DEFINITION)];
+        backend
+            .store_block(
+                finalized_block_zero(Header::default()),
+                finalized_state_diff_zero(),
+                classes.iter().cloned().map(ConvertedClass::Sierra).collect(),
+                None,
+                None,
+            )
+            .unwrap();
+
+        let metrics = BlockMetrics::register(0).unwrap();
+        let queue = CasmCompileQueue::new(Arc::clone(&backend), 2, metrics.casm_compile_queue_depth).unwrap();
+
+        // While every slot is taken, pushing waits for one to be freed.
+        let all_slots = Arc::clone(&queue.slots).acquire_many_owned(queue.capacity() as u32).await.unwrap();
+        assert_eq!(queue.depth(), queue.capacity());
+        let mut push = std::pin::pin!(queue.push(0, classes[0].class_hash, classes[0].info.clone()));
+        assert!(tokio::time::timeout(Duration::from_millis(200), &mut push).await.is_err());
+        drop(all_slots);
+        tokio::time::timeout(Duration::from_secs(5), push).await.expect("Push did not resume once a slot was freed");
+
+        // Flood the queue with declarations: pushing waits for the compiler instead of growing the queue.
+        for class in classes.iter().cycle().take(20) {
+            queue.push(0, class.class_hash, class.info.clone()).await;
+            assert!(queue.depth() <= queue.capacity());
+        }
+
+        tokio::time::timeout(Duration::from_secs(120), async {
+            while queue.depth() > 0 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("Compilation queue did not drain");
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        for class in &classes {
+            assert!(backend.get_sierra_compiled(&latest, &class.info.compiled_class_hash).unwrap().is_some());
+        }
+    }
+}
@@ -41,16 +41,20 @@
 use anyhow::Context;
 use mc_db::{MadaraBackend, MadaraStorageError};
 use metrics::BlockMetrics;
-use mp_class::{class_hash::ComputeClassHashError, compile::ClassCompilationError};
+use mp_class::{
+    class_hash::ComputeClassHashError, compile::ClassCompilationError, convert::DecompressionLimiter, ConvertedClass,
+};
 use starknet_types_core::felt::Felt;
 use std::{borrow::Cow, sync::Arc};
 
+mod casm_compile_queue;
 mod metrics;
 mod pre_validate;
 mod rayon;
 pub mod tests;
 mod types;
 mod verify_apply;
+pub use casm_compile_queue::*;
 pub use pre_validate::*;
 pub use rayon::*;
 pub use types::*;
@@ -119,6 +123,8 @@ pub struct BlockImporter {
     backend: Arc<MadaraBackend>,
     verify_apply: VerifyApply,
     metrics: BlockMetrics,
+    casm_compile_queue: Option<CasmCompileQueue>,
+    decompression_limiter: DecompressionLimiter,
 }
 
 impl BlockImporter {
@@ -140,10 +146,31 @@ impl BlockImporter {
             pool,
             metrics: BlockMetrics::register(starting_block).context("Registering metrics for block import")?,
             backend,
+            casm_compile_queue: None,
+            decompression_limiter: DecompressionLimiter::default(),
         })
     }
 
+    /// Bounds the number of declared classes being decompressed at the same time during pre-validation. Blocks
+    /// wait for a permit for all of their classes before being pre-validated. 0 disables the limit.
+    pub fn with_class_decompression_limit(mut self, max: usize) -> Self {
+        self.decompression_limiter = DecompressionLimiter::new(max);
+        self
+    }
+
+    /// Compiles the classes imported with [`BlockValidationContext::lazy_casm_compilation`] in the background,
+    /// instead of on their first use. Block import waits when more than `capacity` classes are waiting for
+    /// compilation.
+    pub fn with_casm_compile_queue(mut self, capacity: usize) -> anyhow::Result<Self> {
+        self.casm_compile_queue = Some(
+            CasmCompileQueue::new(Arc::clone(&self.backend), capacity, self.metrics.casm_compile_queue_depth.clone())
+                .context("Starting the CASM compilation thread")?,
+        );
+        Ok(self)
+    }
+
     /// Perform [`BlockImporter::pre_validate`] followed by [`BlockImporter::verify_apply`] to import a block.
+    /// With [`BlockValidationContext::dry_run`], the block is only validated and nothing is written to the database.
     #[tracing::instrument(skip(self, block, validation), fields(module = "BlockImporter"))]
     pub async fn add_block(
         &self,
@@ -160,6 +187,7 @@ impl BlockImporter {
         block: UnverifiedFullBlock,
         validation: BlockValidationContext,
     ) -> Result<PreValidatedBlock, BlockImportError> {
+        let _permit = self.decompression_limiter.acquire_many(block.declared_classes.len()).await;
         pre_validate(&self.pool, block, validation).await
     }
 
@@ -169,8 +197,33 @@ impl BlockImporter {
         block: PreValidatedBlock,
         validation: BlockValidationContext,
     ) -> Result<BlockImportResult, BlockImportError> {
+        let dry_run = validation.dry_run;
+        let deferred_classes: Vec<_> = if self.casm_compile_queue.is_some() && !dry_run {
+            block
+                .converted_classes
+                .iter()
+                .filter_map(|class| match class {
+                    ConvertedClass::Sierra(sierra) if sierra.compiled.is_none() => {
+                        Some((sierra.class_hash, sierra.info.clone()))
+                    }
+                    _ => None,
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+
         let result = self.verify_apply.verify_apply(block, validation).await?;
+        if dry_run {
+            return Ok(result);
+        }
         self.metrics.update(&result.header, &self.backend);
+
+        if let Some(queue) = &self.casm_compile_queue {
+            for (class_hash, info) in deferred_classes {
+                queue.push(result.header.block_number, class_hash, info).await;
+            }
+        }
         Ok(result)
     }
 
@@ -180,6 +233,7 @@ impl BlockImporter {
         block: UnverifiedPendingFullBlock,
         validation: BlockValidationContext,
     ) -> Result<PreValidatedPendingBlock, BlockImportError> {
+        let _permit = self.decompression_limiter.acquire_many(block.declared_classes.len()).await;
         pre_validate_pending(&self.pool, block, validation).await
     }
 
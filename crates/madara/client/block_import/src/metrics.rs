@@ -31,6 +31,8 @@ pub struct BlockMetrics {
     // L1 network metrics
     pub l1_gas_price_wei: Gauge<f64>,
     pub l1_gas_price_strk: Gauge<f64>,
+    // Class compilation metrics
+    pub casm_compile_queue_depth: Gauge<u64>,
 }
 
 impl BlockMetrics {
@@ -106,6 +108,13 @@ impl BlockMetrics {
             "".to_string(),
         );
 
+        let casm_compile_queue_depth = register_gauge_metric_instrument(
+            &block_import_meter,
+            "casm_compile_queue_depth".to_string(),
+            "Number of classes waiting for a deferred CASM compilation".to_string(),
+            "".to_string(),
+        );
+
         Ok(Self {
             starting_block,
             starting_time: Instant::now(),
@@ -123,6 +132,8 @@ impl BlockMetrics {
 
             l1_gas_price_wei,
             l1_gas_price_strk,
+
+            casm_compile_queue_depth,
         })
     }
 
@@ -4,6 +4,8 @@ use crate::{
 };
 use bitvec::vec::BitVec;
 use mp_chain_config::StarknetVersion;
+use mp_class::class_hash::VerifyClassHashError;
+use mp_class::raw_class::UnknownClassFields;
 use mp_class::{ConvertedClass, LegacyClassInfo, LegacyConvertedClass, SierraClassInfo, SierraConvertedClass};
 use mp_convert::ToFelt;
 use mp_receipt::TransactionReceipt;
@@ -46,6 +48,7 @@ pub fn pre_validate_inner(
     validation: BlockValidationContext,
 ) -> Result<PreValidatedBlock, BlockImportError> {
     let classes = mem::take(&mut block.declared_classes);
+    let unknown_class_fields = collect_unknown_class_fields(&classes);
 
     // unfortunately this is ugly but rayon::join does not have the fast error short circuiting behavior that
     // collecting into a Result has.
@@ -74,6 +77,7 @@ pub fn pre_validate_inner(
         receipts: block.receipts,
         commitments,
         converted_classes,
+        unknown_class_fields,
         unverified_global_state_root: block.commitments.global_state_root,
         unverified_block_hash: block.commitments.block_hash,
         unverified_block_number: block.unverified_block_number,
@@ -88,6 +92,7 @@ pub fn pre_validate_pending_inner(
 ) -> Result<PreValidatedPendingBlock, BlockImportError> {
     let starknet_version = block.header.protocol_version;
     let classes = mem::take(&mut block.declared_classes);
+    let unknown_class_fields = collect_unknown_class_fields(&classes);
 
     let converted_classes = convert_classes(classes, &validation)?;
     let _tx_hashes = transaction_hashes(&block.receipts, &block.transactions, starknet_version, &validation)?;
@@ -98,6 +103,7 @@ pub fn pre_validate_pending_inner(
         state_diff: block.state_diff,
         receipts: block.receipts,
         converted_classes,
+        unknown_class_fields,
         visited_segments: block.visited_segments,
     })
 }
@@ -140,6 +146,14 @@ fn block_commitments(
     })
 }
 
+fn collect_unknown_class_fields(declared_classes: &[DeclaredClass]) -> Vec<(Felt, UnknownClassFields)> {
+    declared_classes
+        .iter()
+        .filter(|class| !class.unknown_fields().is_empty())
+        .map(|class| (class.class_hash(), class.unknown_fields().clone()))
+        .collect()
+}
+
 fn convert_classes(
     declared_classes: Vec<DeclaredClass>,
     validation: &BlockValidationContext,
@@ -155,13 +169,24 @@ fn class_conversion(
         DeclaredClass::Sierra(sierra) => {
             tracing::trace!("Converting class with hash {:#x}", sierra.class_hash);
             if !validation.trust_class_hashes {
-                let class_hash = sierra
-                    .contract_class
-                    .compute_class_hash()
-                    .map_err(|e| BlockImportError::ComputeClassHash { class_hash: sierra.class_hash, error: e })?;
-                if class_hash != sierra.class_hash {
-                    return Err(BlockImportError::ClassHash { got: sierra.class_hash, expected: class_hash });
-                }
+                sierra.contract_class.verify_class_hash(sierra.class_hash).map_err(|err| match err {
+                    VerifyClassHashError::ClassHashMismatch { expected, computed } => {
+                        BlockImportError::ClassHash { got: expected, expected: computed }
+                    }
+                    VerifyClassHashError::ComputeClassHash(error) => {
+                        BlockImportError::ComputeClassHash { class_hash: sierra.class_hash, error }
+                    }
+                })?;
+            }
+            if validation.lazy_casm_compilation && !validation.dry_run {
+                return Ok(ConvertedClass::Sierra(SierraConvertedClass {
+                    class_hash: sierra.class_hash,
+                    info: SierraClassInfo {
+                        contract_class: Arc::new(sierra.contract_class),
+                        compiled_class_hash: sierra.compiled_class_hash,
+                    },
+                    compiled: None,
+                }));
             }
             let (compiled_class_hash, compiled_class) = sierra
                 .contract_class
@@ -177,20 +202,33 @@ fn class_conversion(
             Ok(ConvertedClass::Sierra(SierraConvertedClass {
                 class_hash: sierra.class_hash,
                 info: SierraClassInfo { contract_class: Arc::new(sierra.contract_class), compiled_class_hash },
-                compiled: Arc::new(compiled_class),
+                compiled: Some(Arc::new(compiled_class)),
             }))
         }
         DeclaredClass::Legacy(legacy) => {
             tracing::trace!("Converting legacy class with hash {:#x}", legacy.class_hash);
             if !validation.trust_class_hashes {
-                let class_hash = legacy
-                    .contract_class
-                    .compute_class_hash()
-                    .map_err(|e| BlockImportError::ComputeClassHash { class_hash: legacy.class_hash, error: e })?;
-                if class_hash != legacy.class_hash {
-                    // TODO: For now we skip the exceptions for the legacy class hash mismatch
-                    tracing::debug!("Class hash mismatch: got {:#x}, expected {:#x}", class_hash, legacy.class_hash,);
-                    // return Err(BlockImportError::ClassHash { got: class_hash, expected: legacy.class_hash });
+                match legacy.contract_class.verify_class_hash(legacy.class_hash) {
+                    Ok(()) => {}
+                    Err(VerifyClassHashError::ClassHashMismatch { expected, computed })
+                        if !validation.reject_legacy_class_hash_mismatches =>
+                    {
+                        // Some legacy classes of the early networks do not hash to their class hash.
+                        tracing::warn!("Legacy class {expected:#x} hashes to {computed:#x}, importing it anyway");
+                    }
+                    Err(VerifyClassHashError::ClassHashMismatch { expected, computed })
+                        if validation.allowed_legacy_class_hash_mismatches.contains(&expected) =>
+                    {
+                        tracing::warn!(
+                            "Legacy class {expected:#x} hashes to {computed:#x}, accepted as it is in the allow-list"
+                        );
+                    }
+                    Err(VerifyClassHashError::ClassHashMismatch { expected, computed }) => {
+                        return Err(BlockImportError::ClassHash { got: expected, expected: computed })
+                    }
+                    Err(VerifyClassHashError::ComputeClassHash(error)) => {
+                        return Err(BlockImportError::ComputeClassHash { class_hash: legacy.class_hash, error })
+                    }
                 }
             }
             Ok(ConvertedClass::Legacy(LegacyConvertedClass {
@@ -387,6 +425,86 @@ fn compute_merkle_root<H: StarkHash + Send + Sync>(values: &[Felt]) -> Felt {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{LegacyDeclaredClass, SierraDeclaredClass};
+    use mp_chain_config::ChainConfig;
+    use starknet_core::types::contract::SierraClass;
+
+    #[test]
+    fn test_class_conversion_rejects_tampered_class() {
+        let class: SierraClass = serde_json::from_slice(include_bytes!(
+            "../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+        ))
+        .unwrap();
+        let class_hash = class.class_hash().unwrap();
+        let declared_class = |contract_class| {
+            DeclaredClass::Sierra(SierraDeclaredClass {
+                class_hash,
+                contract_class,
+                compiled_class_hash: Felt::ZERO,
+                unknown_fields: Default::default(),
+            })
+        };
+        let mut validation = BlockValidationContext::new(ChainConfig::madara_test().chain_id);
+        validation.lazy_casm_compilation = true;
+
+        let contract_class: mp_class::FlattenedSierraClass = class.flatten().unwrap().into();
+        let Ok(ConvertedClass::Sierra(converted)) =
+            class_conversion(declared_class(contract_class.clone()), &validation)
+        else {
+            panic!("Expected a Sierra class");
+        };
+        // The compiled class hash of a deferred compilation is checked when the class is first compiled.
+        assert!(matches!(
+            converted.to_blockifier_class(),
+            Err(mp_class::compile::ClassCompilationError::CompiledClassHashMismatch { expected, .. })
+                if expected == Felt::ZERO
+        ));
+
+        let mut tampered = contract_class;
+        tampered.sierra_program[42] += Felt::ONE;
+        let computed = tampered.compute_class_hash().unwrap();
+        assert!(matches!(
+            class_conversion(declared_class(tampered.clone()), &validation),
+            Err(BlockImportError::ClassHash { got, expected }) if got == class_hash && expected == computed
+        ));
+
+        // Class hashes are not checked when they are trusted.
+        validation.trust_class_hashes = true;
+        assert!(class_conversion(declared_class(tampered), &validation).is_ok());
+    }
+
+    #[test]
+    fn test_class_conversion_legacy_class_hash_mismatch() {
+        let class: starknet_core::types::contract::legacy::LegacyContractClass =
+            serde_json::from_slice(include_bytes!("../../../../../cairo-artifacts/madara_contracts_UDC.json")).unwrap();
+        let computed = class.class_hash().unwrap();
+        let contract_class: mp_class::CompressedLegacyContractClass = class.compress().unwrap().into();
+        let wrong_hash = computed + Felt::ONE;
+        let declared_class = |class_hash| {
+            DeclaredClass::Legacy(LegacyDeclaredClass {
+                class_hash,
+                contract_class: contract_class.clone(),
+                unknown_fields: Default::default(),
+            })
+        };
+        let validation = BlockValidationContext::new(ChainConfig::madara_test().chain_id);
+
+        // Mismatches are only logged by default.
+        assert!(class_conversion(declared_class(computed), &validation).is_ok());
+        assert!(class_conversion(declared_class(wrong_hash), &validation).is_ok());
+
+        let validation = validation.reject_legacy_class_hash_mismatches(true);
+        assert!(class_conversion(declared_class(computed), &validation).is_ok());
+        assert!(matches!(
+            class_conversion(declared_class(wrong_hash), &validation),
+            Err(BlockImportError::ClassHash { got, expected }) if got == wrong_hash && expected == computed
+        ));
+
+        // Only the classes in the allow-list are accepted with a mismatching class hash.
+        let validation = validation.allowed_legacy_class_hash_mismatches(vec![wrong_hash]);
+        assert!(class_conversion(declared_class(wrong_hash), &validation).is_ok());
+        assert!(class_conversion(declared_class(wrong_hash + Felt::ONE), &validation).is_err());
+    }
 
     #[test]
     fn test_compute_root() {
@@ -57,6 +57,10 @@ pub fn create_validation_context(ignore_block_order: bool) -> BlockValidationCon
         trust_global_tries: false,
         trust_transaction_hashes: false,
         trust_class_hashes: false,
+        lazy_casm_compilation: false,
+        dry_run: false,
+        reject_legacy_class_hash_mismatches: false,
+        allowed_legacy_class_hash_mismatches: vec![],
     }
 }
 
@@ -104,6 +108,7 @@ pub fn create_dummy_block() -> PreValidatedBlock {
         receipts: vec![],
         state_diff: StateDiff::default(),
         converted_classes: Default::default(),
+        unknown_class_fields: Default::default(),
         visited_segments: None,
     }
 }
@@ -153,6 +158,7 @@ pub fn create_dummy_pending_block() -> PreValidatedPendingBlock {
         receipts: vec![],
         state_diff: StateDiff::default(),
         converted_classes: Default::default(),
+        unknown_class_fields: Default::default(),
         visited_segments: None,
     }
 }
@@ -8,6 +8,7 @@ use mp_block::{
 use mp_chain_config::StarknetVersion;
 use mp_class::{
     class_update::{ClassUpdate, LegacyClassUpdate, SierraClassUpdate},
+    raw_class::UnknownClassFields,
     CompressedLegacyContractClass, ConvertedClass, FlattenedSierraClass,
 };
 use mp_receipt::TransactionReceipt;
@@ -45,6 +46,19 @@ pub struct BlockValidationContext {
     pub trust_global_tries: bool,
     /// Ignore the order of the blocks to allow starting at some height.
     pub ignore_block_order: bool,
+    /// Do not compile Sierra classes to CASM during import. The compiled class is computed, checked against the
+    /// declared compiled class hash and stored the first time it is requested instead.
+    pub lazy_casm_compilation: bool,
+    /// Perform every validation step, but do not write anything to the database. Since the global tries cannot be
+    /// updated without writing to them, the global state root is not recomputed: the block hash is only checked when
+    /// the block comes with its global state root. Classes are always compiled in this mode.
+    pub dry_run: bool,
+    /// Reject the legacy classes whose class hash does not match their definition. The class hash of some legacy
+    /// classes declared on the early networks cannot be recomputed, so by default a mismatch is only logged.
+    pub reject_legacy_class_hash_mismatches: bool,
+    /// Legacy classes which are imported even though their class hash does not match their definition, when
+    /// [`Self::reject_legacy_class_hash_mismatches`] is set.
+    pub allowed_legacy_class_hash_mismatches: Vec<Felt>,
     /// The chain id of the current block.
     pub chain_id: ChainId,
 }
@@ -57,6 +71,10 @@ impl BlockValidationContext {
             trust_global_tries: false,
             chain_id,
             ignore_block_order: false,
+            lazy_casm_compilation: false,
+            dry_run: false,
+            reject_legacy_class_hash_mismatches: false,
+            allowed_legacy_class_hash_mismatches: vec![],
         }
     }
     pub fn trust_transaction_hashes(mut self, v: bool) -> Self {
@@ -71,6 +89,22 @@ impl BlockValidationContext {
         self.trust_global_tries = v;
         self
     }
+    pub fn lazy_casm_compilation(mut self, v: bool) -> Self {
+        self.lazy_casm_compilation = v;
+        self
+    }
+    pub fn dry_run(mut self, v: bool) -> Self {
+        self.dry_run = v;
+        self
+    }
+    pub fn reject_legacy_class_hash_mismatches(mut self, v: bool) -> Self {
+        self.reject_legacy_class_hash_mismatches = v;
+        self
+    }
+    pub fn allowed_legacy_class_hash_mismatches(mut self, v: Vec<Felt>) -> Self {
+        self.allowed_legacy_class_hash_mismatches = v;
+        self
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -86,6 +120,13 @@ impl DeclaredClass {
             DeclaredClass::Sierra(c) => c.class_hash,
         }
     }
+
+    pub fn unknown_fields(&self) -> &UnknownClassFields {
+        match self {
+            DeclaredClass::Legacy(c) => &c.unknown_fields,
+            DeclaredClass::Sierra(c) => &c.unknown_fields,
+        }
+    }
 }
 
 impl From<ClassUpdate> for DeclaredClass {
@@ -101,11 +142,18 @@ impl From<ClassUpdate> for DeclaredClass {
 pub struct LegacyDeclaredClass {
     pub class_hash: Felt,
     pub contract_class: CompressedLegacyContractClass,
+    /// Top-level fields of the class definition which are not part of the class, see [`UnknownClassFields`].
+    #[serde(default)]
+    pub unknown_fields: UnknownClassFields,
 }
 
 impl From<LegacyClassUpdate> for LegacyDeclaredClass {
     fn from(value: LegacyClassUpdate) -> Self {
-        Self { class_hash: value.class_hash, contract_class: value.contract_class }
+        Self {
+            class_hash: value.class_hash,
+            contract_class: value.contract_class,
+            unknown_fields: value.unknown_fields,
+        }
     }
 }
 
@@ -114,6 +162,9 @@ pub struct SierraDeclaredClass {
     pub class_hash: Felt,
     pub contract_class: FlattenedSierraClass,
     pub compiled_class_hash: Felt,
+    /// Top-level fields of the class definition which are not part of the class, see [`UnknownClassFields`].
+    #[serde(default)]
+    pub unknown_fields: UnknownClassFields,
 }
 
 impl From<SierraClassUpdate> for SierraDeclaredClass {
@@ -122,6 +173,7 @@ impl From<SierraClassUpdate> for SierraDeclaredClass {
             class_hash: value.class_hash,
             contract_class: value.contract_class,
             compiled_class_hash: value.compiled_class_hash,
+            unknown_fields: value.unknown_fields,
         }
     }
 }
@@ -191,6 +243,8 @@ pub struct PreValidatedBlock {
     pub receipts: Vec<TransactionReceipt>,
     pub commitments: ValidatedCommitments,
     pub converted_classes: Vec<ConvertedClass>,
+    /// Unknown top-level fields of the declared class definitions, for the classes that have some.
+    pub unknown_class_fields: Vec<(Felt, UnknownClassFields)>,
     pub unverified_global_state_root: Option<Felt>,
     pub unverified_block_hash: Option<Felt>,
     pub unverified_block_number: Option<u64>,
@@ -205,6 +259,8 @@ pub struct PreValidatedPendingBlock {
     pub state_diff: StateDiff,
     pub receipts: Vec<TransactionReceipt>,
     pub converted_classes: Vec<ConvertedClass>,
+    /// See [`PreValidatedBlock::unknown_class_fields`].
+    pub unknown_class_fields: Vec<(Felt, UnknownClassFields)>,
     pub visited_segments: Option<VisitedSegments>,
 }
 
@@ -79,6 +79,10 @@ pub fn verify_apply_inner(
     let (block_number, parent_block_hash) =
         check_parent_hash_and_num(backend, block.header.parent_block_hash, block.unverified_block_number, &validation)?;
 
+    if validation.dry_run {
+        return dry_run_block_hash(block, &validation, block_number, parent_block_hash);
+    }
+
     // Update contract and its storage tries
     let global_state_root = update_tries(backend, &block, &validation, block_number)?;
 
@@ -105,7 +109,32 @@ pub fn verify_apply_inner(
             None,
         )
         .map_err(make_db_error("storing block in db"))?;
+    backend
+        .store_class_unknown_fields(&block.unknown_class_fields)
+        .map_err(make_db_error("storing unknown class fields in db"))?;
+
+    Ok(BlockImportResult { header, block_hash })
+}
 
+/// Dry run of [`verify_apply_inner`]: updating the tries would write to the database, so the global state root of
+/// the block is trusted instead. Without it, the block hash cannot be verified. The previous blocks of a dry run are
+/// not stored either, so the parent hash the block comes with is used when the block order is not checked.
+fn dry_run_block_hash(
+    mut block: PreValidatedBlock,
+    validation: &BlockValidationContext,
+    block_number: u64,
+    parent_block_hash: Felt,
+) -> Result<BlockImportResult, BlockImportError> {
+    let global_state_root = match block.unverified_global_state_root {
+        Some(global_state_root) => global_state_root,
+        None => {
+            block.unverified_block_hash = None;
+            Felt::ZERO
+        }
+    };
+    let parent_block_hash = block.header.parent_block_hash.unwrap_or(parent_block_hash);
+    let (block_hash, header) = block_hash(&block, validation, block_number, parent_block_hash, global_state_root)?;
+    tracing::debug!("verify_apply_inner dry run, not storing block {}", header.block_number);
     Ok(BlockImportResult { header, block_hash })
 }
 
@@ -118,6 +147,10 @@ pub fn verify_apply_pending_inner(
     let (_block_number, parent_block_hash) =
         check_parent_hash_and_num(backend, block.header.parent_block_hash, None, &validation)?;
 
+    if validation.dry_run {
+        return Ok(PendingBlockImportResult {});
+    }
+
     let UnverifiedHeader {
         parent_block_hash: _,
         sequencer_address,
@@ -150,6 +183,9 @@ pub fn verify_apply_pending_inner(
             None,
         )
         .map_err(make_db_error("storing block in db"))?;
+    backend
+        .store_class_unknown_fields(&block.unknown_class_fields)
+        .map_err(make_db_error("storing unknown class fields in db"))?;
 
     Ok(PendingBlockImportResult {})
 }
@@ -530,6 +566,10 @@ mod verify_apply_tests {
             trust_global_tries,
             trust_transaction_hashes: false,
             trust_class_hashes: false,
+            lazy_casm_compilation: false,
+            dry_run: false,
+            reject_legacy_class_hash_mismatches: false,
+            allowed_legacy_class_hash_mismatches: vec![],
         };
 
         // WHEN: We call update_tries with these parameters
@@ -593,6 +633,10 @@ mod verify_apply_tests {
                 trust_global_tries: false,
                 trust_transaction_hashes: false,
                 trust_class_hashes: false,
+                lazy_casm_compilation: false,
+                dry_run: false,
+                reject_legacy_class_hash_mismatches: false,
+                allowed_legacy_class_hash_mismatches: vec![],
             },
             1466,
             felt!("0x1"),
@@ -706,6 +750,45 @@ mod verify_apply_tests {
             assert!(matches!(result.unwrap_err(), BlockImportError::LatestBlockN { .. }));
             assert_eq!(backend.get_latest_block_n().unwrap(), Some(0));
         }
+
+        /// Test the dry run mode.
+        ///
+        /// Verifies that:
+        /// 1. A valid block is verified, but neither the block nor its state diff is stored.
+        /// 2. Validation errors are still reported.
+        #[rstest]
+        #[tokio::test]
+        async fn test_verify_apply_inner_dry_run_does_not_store_block(setup_test_backend: Arc<MadaraBackend>) {
+            let backend = setup_test_backend;
+            let mut header = create_dummy_header();
+            header.block_number = 0;
+            let pending_block = finalized_block_zero(header);
+            backend.store_block(pending_block.clone(), finalized_state_diff_zero(), vec![], None, None).unwrap();
+
+            let mut block = create_dummy_block();
+            block.header.parent_block_hash = Some(felt!("0x12345"));
+            block.unverified_global_state_root = Some(felt!("0x0"));
+            block.state_diff.storage_diffs = vec![ContractStorageDiffItem {
+                address: felt!("0x1"),
+                storage_entries: vec![StorageEntry { key: felt!("0x2"), value: felt!("0x3") }],
+            }];
+            let validation = create_validation_context(false).dry_run(true);
+
+            let result = verify_apply_inner(&backend, block.clone(), validation.clone()).unwrap();
+
+            assert_eq!(result.header.block_number, 1);
+            assert_eq!(backend.get_latest_block_n().unwrap(), Some(0));
+            assert!(backend.get_block_info(&BlockId::Number(1)).unwrap().is_none());
+            for block_id in [BlockId::Tag(BlockTag::Latest), BlockId::Tag(BlockTag::Pending)] {
+                assert_eq!(backend.get_contract_storage_at(&block_id, &felt!("0x1"), &felt!("0x2")).unwrap(), None);
+            }
+
+            block.unverified_block_number = Some(2); // Mismatch to trigger an error
+            let result = verify_apply_inner(&backend, block, validation);
+
+            assert!(matches!(result.unwrap_err(), BlockImportError::LatestBlockN { .. }));
+            assert_eq!(backend.get_latest_block_n().unwrap(), Some(0));
+        }
     }
 
     mod verify_apply_pending_tests {
@@ -532,7 +532,7 @@ impl<Mempool: MempoolProvider> BlockProductionTask<Mempool> {
 
     /// This creates a block, continuing the current pending block state up to the full bouncer limit.
     #[tracing::instrument(skip(self), fields(module = "BlockProductionTask"))]
-    pub(crate) async fn on_block_time(&mut self) -> Result<(), Error> {
+    pub async fn on_block_time(&mut self) -> Result<(), Error> {
         let block_n = self.block_n();
         tracing::debug!("closing block #{}", block_n);
 
@@ -754,7 +754,7 @@ mod tests {
                 }),
                 compiled_class_hash,
             },
-            compiled: Arc::new(mp_class::CompiledSierra("".to_string())),
+            compiled: Some(Arc::new(mp_class::CompiledSierra("".to_string()))),
         })
     }
 
@@ -28,7 +28,7 @@ const ROW_PENDING_STATE_UPDATE: &[u8] = b"pending_state_update";
 const ROW_PENDING_SEGMENTS: &[u8] = b"pending_segments";
 const ROW_PENDING_BOUNCER_WEIGHTS: &[u8] = b"pending_bouncer_weights";
 const ROW_PENDING_INNER: &[u8] = b"pending";
-const ROW_SYNC_TIP: &[u8] = b"sync_tip";
+pub(crate) const ROW_SYNC_TIP: &[u8] = b"sync_tip";
 const ROW_L1_LAST_CONFIRMED_BLOCK: &[u8] = b"l1_last";
 
 #[tracing::instrument(skip(db), fields(module = "BlockDB"))]
@@ -412,6 +412,13 @@ impl MadaraBackend {
         }
     }
 
+    /// State diff of block `block_n` in the Starknet `StateDiff` JSON format, as used by the RPC specs.
+    #[tracing::instrument(skip(self), fields(module = "BlockDB"))]
+    pub fn state_diff_json(&self, block_n: u64) -> Result<Option<serde_json::Value>> {
+        let Some(state_diff) = self.get_state_update(block_n)? else { return Ok(None) };
+        Ok(Some(serde_json::to_value(mp_rpc::StateDiff::from(state_diff))?))
+    }
+
     #[tracing::instrument(skip(self, id), fields(module = "BlockDB"))]
     pub fn contains_block(&self, id: &impl DbBlockIdResolvable) -> Result<bool> {
         let Some(ty) = id.resolve_db_block_id(self)? else { return Ok(false) };
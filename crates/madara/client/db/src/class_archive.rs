@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::{fmt, fs, io};
+
+use anyhow::Context;
+use mp_class::ClassInfo;
+use serde::{Deserialize, Serialize};
+use starknet_types_core::felt::Felt;
+
+use crate::{
+    db_block_id::{DbBlockId, DbBlockIdResolvable},
+    MadaraBackend, MadaraStorageError,
+};
+
+/// A class definition served by a [`ClassArchive`], along with the block in which it was declared.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedClass {
+    pub block_n: u64,
+    pub class_info: ClassInfo,
+}
+
+/// A cold storage tier for class definitions.
+///
+/// On a pruned node, the definitions of classes declared in historical blocks may have been moved out
+/// of the database to some slower storage (a remote blob store, for example). When configured, the
+/// archive is used as a fallback by [`MadaraBackend::get_class_info_or_archived`] for classes which are
+/// not found locally.
+pub trait ClassArchive: fmt::Debug + Send + Sync {
+    /// Returns the class with hash `class_hash`, or `None` if the archive does not have it.
+    fn get_class(&self, class_hash: &Felt) -> anyhow::Result<Option<ArchivedClass>>;
+}
+
+/// A [`ClassArchive`] in a directory, with one file per class named after its class hash in hex (`0x...`). Each
+/// file holds the bincode encoding of an [`ArchivedClass`]. A remote blob store can be used by mounting it as a
+/// directory.
+#[derive(Debug)]
+pub struct DirClassArchive {
+    path: PathBuf,
+}
+
+impl DirClassArchive {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn class_path(&self, class_hash: &Felt) -> PathBuf {
+        self.path.join(format!("{class_hash:#x}"))
+    }
+
+    /// Adds a class to the archive, replacing the archived class with the same hash if any.
+    pub fn put_class(&self, class_hash: &Felt, class: &ArchivedClass) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.path).with_context(|| format!("Creating class archive at {}", self.path.display()))?;
+        let path = self.class_path(class_hash);
+        fs::write(&path, bincode::serialize(class)?).with_context(|| format!("Writing {}", path.display()))
+    }
+}
+
+impl ClassArchive for DirClassArchive {
+    fn get_class(&self, class_hash: &Felt) -> anyhow::Result<Option<ArchivedClass>> {
+        let path = self.class_path(class_hash);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err).with_context(|| format!("Reading {}", path.display())),
+        };
+        let class = bincode::deserialize(&bytes).with_context(|| format!("Decoding {}", path.display()))?;
+        Ok(Some(class))
+    }
+}
+
+impl MadaraBackend {
+    /// Configures the archive tier used as a fallback for class lookups. This can only be done once.
+    pub fn set_class_archive(&self, archive: Arc<dyn ClassArchive>) -> anyhow::Result<()> {
+        self.class_archive.set(archive).map_err(|_| anyhow::anyhow!("A class archive is already configured"))
+    }
+
+    pub fn class_archive(&self) -> Option<&Arc<dyn ClassArchive>> {
+        self.class_archive.get()
+    }
+
+    /// Same as [`MadaraBackend::get_class_info`], but falls back to the class archive tier when the class
+    /// is not in the database. Returns `None` when neither the database nor the archive has the class
+    /// at the requested block.
+    #[tracing::instrument(skip(self, id, class_hash), fields(module = "ClassDB"))]
+    pub fn get_class_info_or_archived(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        class_hash: &Felt,
+    ) -> Result<Option<ClassInfo>, MadaraStorageError> {
+        let Some(requested_id) = id.resolve_db_block_id(self)? else { return Ok(None) };
+
+        if let Some(class_info) = self.get_class_info(&requested_id, class_hash)? {
+            return Ok(Some(class_info));
+        }
+
+        let Some(archive) = self.class_archive() else { return Ok(None) };
+
+        tracing::debug!("class {class_hash:#x} not found locally, querying the class archive");
+
+        let Some(archived) = archive.get_class(class_hash).map_err(MadaraStorageError::ClassArchive)? else {
+            return Ok(None);
+        };
+
+        let valid = match requested_id {
+            DbBlockId::Pending => true,
+            DbBlockId::Number(block_n) => archived.block_n <= block_n,
+        };
+        if !valid {
+            return Ok(None);
+        }
+
+        Ok(Some(archived.class_info))
+    }
+}
@@ -0,0 +1,184 @@
+//! In-memory cache of class definitions, with the classes read the most kept across restarts.
+
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+use lru::LruCache;
+use mp_class::ClassInfo;
+use starknet_types_core::felt::Felt;
+
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError};
+
+pub use mp_class::casm_cache::{CasmCache, DEFAULT_CASM_CACHE_SIZE};
+
+/// Default number of class definitions kept by the [`ClassCache`].
+pub const DEFAULT_CLASS_CACHE_CAPACITY: usize = 256;
+
+/// Number of most read classes saved when the backend is closed, see [`MadaraBackend::warm_class_cache_with_hot_classes`].
+pub const SAVED_HOT_CLASSES: usize = 64;
+
+const ROW_HOT_CLASSES: &[u8] = b"hot_classes";
+
+/// Cache of the definitions of classes declared in closed blocks, keyed by class hash. These never change once
+/// stored, so they can be served from memory at any block after the one they were declared in. Pending classes are
+/// never cached.
+///
+/// Each entry counts how many reads it served, so that the most read classes can be loaded again when the node
+/// restarts. The read which loads a class in the cache is counted along with the following ones, while classes
+/// warmed at startup start from zero. Reads of unknown class hashes do not take any memory, and lookups of the
+/// declaration block alone, see [`MadaraBackend::class_declared_at`], are not counted.
+///
+/// When the cache is full, the least recently read entry is evicted along with its count. A capacity of 0 disables
+/// the cache.
+#[derive(Debug)]
+pub struct ClassCache {
+    inner: Option<Mutex<LruCache<Felt, CachedClass>>>,
+}
+
+#[derive(Debug)]
+struct CachedClass {
+    class_info: ClassInfo,
+    /// Block in which the class was declared.
+    block_n: u64,
+    reads: u64,
+}
+
+impl Default for ClassCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CLASS_CACHE_CAPACITY)
+    }
+}
+
+impl ClassCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { inner: NonZeroUsize::new(capacity).map(|capacity| Mutex::new(LruCache::new(capacity))) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.inner.as_ref().map_or(0, |inner| inner.lock().expect("Poisoned lock").cap().get())
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.as_ref().map_or(0, |inner| inner.lock().expect("Poisoned lock").len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, class_hash: &Felt) -> bool {
+        self.inner.as_ref().is_some_and(|inner| inner.lock().expect("Poisoned lock").contains(class_hash))
+    }
+
+    /// Removes every cached class, and their read counts.
+    pub fn clear(&self) {
+        if let Some(inner) = &self.inner {
+            inner.lock().expect("Poisoned lock").clear();
+        }
+    }
+
+    /// Returns the class info and the block in which the class was declared, and counts the read.
+    pub(crate) fn get(&self, class_hash: &Felt) -> Option<(ClassInfo, u64)> {
+        let mut inner = self.inner.as_ref()?.lock().expect("Poisoned lock");
+        let cached = inner.get_mut(class_hash)?;
+        cached.reads += 1;
+        Some((cached.class_info.clone(), cached.block_n))
+    }
+
+    /// Returns the block in which the class was declared, without counting a read or refreshing the entry.
+    pub(crate) fn declared_at(&self, class_hash: &Felt) -> Option<u64> {
+        let inner = self.inner.as_ref()?.lock().expect("Poisoned lock");
+        inner.peek(class_hash).map(|cached| cached.block_n)
+    }
+
+    /// Caches a class without counting a read, used when warming the cache.
+    pub(crate) fn insert(&self, class_hash: Felt, class_info: ClassInfo, block_n: u64) {
+        self.insert_with_reads(class_hash, class_info, block_n, 0);
+    }
+
+    /// Caches a class which was just read from the database, and counts that read.
+    pub(crate) fn insert_read(&self, class_hash: Felt, class_info: ClassInfo, block_n: u64) {
+        self.insert_with_reads(class_hash, class_info, block_n, 1);
+    }
+
+    fn insert_with_reads(&self, class_hash: Felt, class_info: ClassInfo, block_n: u64, reads: u64) {
+        let Some(inner) = &self.inner else { return };
+        let mut inner = inner.lock().expect("Poisoned lock");
+        // Already cached entries keep their read count.
+        let cached = inner.get_or_insert_mut(class_hash, || CachedClass { class_info, block_n, reads: 0 });
+        cached.reads += reads;
+    }
+
+    /// The `n` cached classes which served the most reads, the most read first.
+    pub fn most_read(&self, n: usize) -> Vec<Felt> {
+        let Some(inner) = &self.inner else { return vec![] };
+        let inner = inner.lock().expect("Poisoned lock");
+        let mut reads: Vec<_> = inner
+            .iter()
+            .filter(|(_, cached)| cached.reads > 0)
+            .map(|(class_hash, cached)| (cached.reads, *class_hash))
+            .collect();
+        reads.sort_unstable_by(|a, b| b.cmp(a));
+        reads.into_iter().take(n).map(|(_, class_hash)| class_hash).collect()
+    }
+}
+
+impl MadaraBackend {
+    pub fn class_cache(&self) -> &ClassCache {
+        &self.class_cache
+    }
+
+    /// Sets the size of the [`CasmCache`], in bytes of compiled classes. 0 disables it.
+    pub fn set_casm_cache_size(&self, max_size: usize) -> anyhow::Result<()> {
+        self.casm_cache
+            .set(CasmCache::new(max_size))
+            .map_err(|_| anyhow::anyhow!("A CASM cache size is already configured"))
+    }
+
+    /// Cache of the Sierra classes compiled when converting declare transactions, see [`CasmCache`].
+    pub fn casm_cache(&self) -> &CasmCache {
+        self.casm_cache.get_or_init(CasmCache::default)
+    }
+
+    /// Loads the given classes into the class cache, so that the following reads are served from memory. Classes
+    /// which are not found, or only declared in the pending block, are skipped.
+    ///
+    /// Returns the number of classes found.
+    #[tracing::instrument(skip(self, class_hashes), fields(module = "ClassDB"))]
+    pub fn warm_class_cache(&self, class_hashes: &[Felt]) -> Result<usize, MadaraStorageError> {
+        let mut warmed = 0;
+        for (class_hash, class) in class_hashes.iter().zip(self.class_db_get_closed_class_infos(class_hashes)?) {
+            if let Some((class_info, block_n)) = class {
+                self.class_cache.insert(*class_hash, class_info, block_n);
+                warmed += 1;
+            }
+        }
+        tracing::debug!("Warmed the class cache with {warmed} of {} classes", class_hashes.len());
+        Ok(warmed)
+    }
+
+    /// Saves the most read classes, to warm the class cache with them on the next start.
+    pub fn save_hot_classes(&self) -> Result<(), MadaraStorageError> {
+        let hot_classes = self.class_cache.most_read(SAVED_HOT_CLASSES);
+        if hot_classes.is_empty() {
+            return Ok(());
+        }
+        let col = self.db.get_column(Column::BlockStorageMeta);
+        self.db.put_cf(&col, ROW_HOT_CLASSES, bincode::serialize(&hot_classes)?)?;
+        Ok(())
+    }
+
+    /// The most read classes saved by [`Self::save_hot_classes`], the most read first.
+    pub fn hot_classes(&self) -> Result<Vec<Felt>, MadaraStorageError> {
+        let col = self.db.get_column(Column::BlockStorageMeta);
+        let Some(res) = self.db.get_pinned_cf(&col, ROW_HOT_CLASSES)? else { return Ok(vec![]) };
+        Ok(bincode::deserialize(&res)?)
+    }
+
+    /// Warms the class cache with up to `n` of the classes read the most before the node was last stopped.
+    pub fn warm_class_cache_with_hot_classes(&self, n: usize) -> Result<usize, MadaraStorageError> {
+        let mut hot_classes = self.hot_classes()?;
+        hot_classes.truncate(n);
+        self.warm_class_cache(&hot_classes)
+    }
+}
@@ -1,32 +1,103 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use mp_class::{ClassInfo, CompiledSierra, ConvertedClass, LegacyConvertedClass, SierraConvertedClass};
+use mp_class::{
+    raw_class::UnknownClassFields, ClassInfo, CompiledSierra, ConvertedClass, LegacyConvertedClass, SierraClassInfo,
+    SierraConvertedClass,
+};
 use rayon::{iter::ParallelIterator, slice::ParallelSlice};
-use rocksdb::WriteOptions;
+use rocksdb::{IteratorMode, WriteOptions};
 use starknet_types_core::felt::Felt;
 
 use crate::{
+    class_storage_format::decode_class_metadata,
     db_block_id::{DbBlockId, DbBlockIdResolvable},
-    Column, DatabaseExt, MadaraBackend, MadaraStorageError, WriteBatchWithTransaction, DB_UPDATES_BATCH_SIZE,
+    ClassMetadata, Column, DatabaseExt, MadaraBackend, MadaraStorageError, WriteBatchWithTransaction,
+    DB_UPDATES_BATCH_SIZE,
 };
 
 const LAST_KEY: &[u8] = &[0xFF; 64];
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct ClassInfoWithBlockNumber {
     class_info: ClassInfo,
     block_id: DbBlockId,
 }
 
+/// Values of the class columns. Class infos are stored with the configured [`crate::ClassStorageFormat`], everything
+/// else is bincode-encoded. Decoding a deduplicated class info reads its definition from the backend.
+trait ClassDbValue: Sized {
+    fn decode(backend: &MadaraBackend, bytes: &[u8]) -> Result<Self, MadaraStorageError>;
+}
+
+impl ClassDbValue for ClassInfoWithBlockNumber {
+    fn decode(backend: &MadaraBackend, bytes: &[u8]) -> Result<Self, MadaraStorageError> {
+        let (metadata, class_info) = backend.decode_class(bytes)?;
+        Ok(Self { class_info, block_id: metadata.block_id })
+    }
+}
+
+impl ClassDbValue for (ClassMetadata, ClassInfo) {
+    fn decode(backend: &MadaraBackend, bytes: &[u8]) -> Result<Self, MadaraStorageError> {
+        backend.decode_class(bytes)
+    }
+}
+
+impl ClassDbValue for ClassMetadata {
+    fn decode(_backend: &MadaraBackend, bytes: &[u8]) -> Result<Self, MadaraStorageError> {
+        decode_class_metadata(bytes)
+    }
+}
+
+/// Whether a class declared in `declared_id` exists in the state of `requested_id`.
+fn is_declared_at(requested_id: DbBlockId, declared_id: DbBlockId) -> bool {
+    match (requested_id, declared_id) {
+        (DbBlockId::Pending, _) => true,
+        (DbBlockId::Number(block_n), DbBlockId::Number(real_block_n)) => real_block_n <= block_n,
+        _ => false,
+    }
+}
+
+impl ClassDbValue for CompiledSierra {
+    fn decode(_backend: &MadaraBackend, bytes: &[u8]) -> Result<Self, MadaraStorageError> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+/// An inconsistency found by [`MadaraBackend::verify_all_classes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClassVerificationIssue {
+    /// Recompiling a Sierra class gives a different compiled class hash than the stored one. This indicates a
+    /// compiler version drift or a corrupted database.
+    CompiledClassHashMismatch { class_hash: Felt, stored: Felt, recompiled: Felt },
+    /// A Sierra class could not be recompiled.
+    CompilationFailed { class_hash: Felt, error: String },
+    /// A class record could not be decoded. The key is given as stored, as it may not decode to a class hash either.
+    Corrupt { key: Vec<u8>, error: String },
+}
+
+/// Declaration of a class, see [`MadaraBackend::class_with_metadata`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassDeclaration {
+    pub metadata: ClassMetadata,
+    /// Contract class version of a Sierra class, `None` for legacy classes.
+    pub contract_class_version: Option<String>,
+    /// Version of the compiler used to compile a Sierra class, `None` for legacy classes and for Sierra classes
+    /// which have not been compiled yet.
+    pub compiler_version: Option<String>,
+}
+
 impl MadaraBackend {
     #[tracing::instrument(skip(self, key), fields(module = "ClassDB"))]
-    fn class_db_get_encoded_kv<V: serde::de::DeserializeOwned>(
+    fn class_db_get_encoded_kv<V: ClassDbValue>(
         &self,
         is_pending: bool,
         key: &Felt,
         pending_col: Column,
         nonpending_col: Column,
     ) -> Result<Option<V>, MadaraStorageError> {
+        self.storage_metrics.record_class_read();
+
         // todo: smallint here to avoid alloc
         tracing::debug!("class db get encoded kv, key={key:#x}");
         let key_encoded = bincode::serialize(key)?;
@@ -35,28 +106,74 @@ impl MadaraBackend {
         if is_pending {
             let col = self.db.get_column(pending_col);
             if let Some(res) = self.db.get_pinned_cf(&col, &key_encoded)? {
-                return Ok(Some(bincode::deserialize(&res)?)); // found in pending
+                return Ok(Some(V::decode(self, &res)?)); // found in pending
             }
         }
         tracing::debug!("class db get encoded kv, state is not pending");
 
         let col = self.db.get_column(nonpending_col);
         let Some(val) = self.db.get_pinned_cf(&col, &key_encoded)? else { return Ok(None) };
-        let val = bincode::deserialize(&val)?;
+        let val = V::decode(self, &val)?;
 
         Ok(Some(val))
     }
 
+    /// Batched version of [`Self::class_db_get_encoded_kv`], with a single multi-get per column.
+    fn class_db_multi_get_encoded_kv<V: ClassDbValue>(
+        &self,
+        is_pending: bool,
+        keys: &[Felt],
+        pending_col: Column,
+        nonpending_col: Column,
+    ) -> Result<Vec<Option<V>>, MadaraStorageError> {
+        let keys_encoded = keys.iter().map(bincode::serialize).collect::<Result<Vec<_>, _>>()?;
+
+        let multi_get = |col: Column, keys: &[&Vec<u8>]| -> Result<Vec<Option<V>>, MadaraStorageError> {
+            let col = self.db.get_column(col);
+            self.db
+                .batched_multi_get_cf(&col, keys, false)
+                .into_iter()
+                .map(|res| res?.map(|val| V::decode(self, &val)).transpose())
+                .collect()
+        };
+
+        // Get from pending db, then normal db for the keys which were not found.
+        let mut values = if is_pending {
+            multi_get(pending_col, &keys_encoded.iter().collect::<Vec<_>>())?
+        } else {
+            keys.iter().map(|_| None).collect()
+        };
+        let missing: Vec<usize> = (0..values.len()).filter(|&i| values[i].is_none()).collect();
+        let found = multi_get(nonpending_col, &missing.iter().map(|&i| &keys_encoded[i]).collect::<Vec<_>>())?;
+        for (i, value) in missing.into_iter().zip(found) {
+            values[i] = value;
+        }
+
+        Ok(values)
+    }
+
     #[tracing::instrument(skip(self, id, class_hash), fields(module = "ClassDB"))]
     pub fn get_class_info(
         &self,
         id: &impl DbBlockIdResolvable,
         class_hash: &Felt,
+    ) -> Result<Option<ClassInfo>, MadaraStorageError> {
+        self.storage_metrics.class_data.record(|| self.get_class_info_uncounted(id, class_hash))
+    }
+
+    fn get_class_info_uncounted(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        class_hash: &Felt,
     ) -> Result<Option<ClassInfo>, MadaraStorageError> {
         let Some(requested_id) = id.resolve_db_block_id(self)? else { return Ok(None) };
 
         tracing::debug!("class info {requested_id:?} {class_hash:#x}");
 
+        if let Some((class_info, block_n)) = self.class_cache.get(class_hash) {
+            return Ok(Some(class_info).filter(|_| is_declared_at(requested_id, DbBlockId::Number(block_n))));
+        }
+
         let Some(info) = self.class_db_get_encoded_kv::<ClassInfoWithBlockNumber>(
             requested_id.is_pending(),
             class_hash,
@@ -69,12 +186,11 @@ impl MadaraBackend {
 
         tracing::debug!("class info got {:?}", info.block_id);
 
-        let valid = match (requested_id, info.block_id) {
-            (DbBlockId::Pending, _) => true,
-            (DbBlockId::Number(block_n), DbBlockId::Number(real_block_n)) => real_block_n <= block_n,
-            _ => false,
-        };
-        if !valid {
+        if let DbBlockId::Number(block_n) = info.block_id {
+            self.class_cache.insert_read(*class_hash, info.class_info.clone(), block_n);
+        }
+
+        if !is_declared_at(requested_id, info.block_id) {
             return Ok(None);
         }
         tracing::debug!("class db get class info, state is valid");
@@ -82,11 +198,187 @@ impl MadaraBackend {
         Ok(Some(info.class_info))
     }
 
+    /// Returns the metadata of a class without decoding its definition, which means this succeeds even when the
+    /// stored class definition is corrupt.
+    #[tracing::instrument(skip(self, id, class_hash), fields(module = "ClassDB"))]
+    pub fn get_class_metadata(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        class_hash: &Felt,
+    ) -> Result<Option<ClassMetadata>, MadaraStorageError> {
+        let Some(requested_id) = id.resolve_db_block_id(self)? else { return Ok(None) };
+
+        let Some(metadata) = self.class_db_get_encoded_kv::<ClassMetadata>(
+            requested_id.is_pending(),
+            class_hash,
+            Column::PendingClassInfo,
+            Column::ClassInfo,
+        )?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(metadata).filter(|metadata| is_declared_at(requested_id, metadata.block_id)))
+    }
+
+    /// Returns the number of the block in which a class was declared, or `None` when the class is not declared in
+    /// a closed block. Only the class metadata is read, not the class definition.
+    #[tracing::instrument(skip(self, class_hash), fields(module = "ClassDB"))]
+    pub fn class_declared_at(&self, class_hash: &Felt) -> Result<Option<u64>, MadaraStorageError> {
+        if let Some(block_n) = self.class_cache.declared_at(class_hash) {
+            return Ok(Some(block_n));
+        }
+
+        let metadata = self.class_db_get_encoded_kv::<ClassMetadata>(
+            false,
+            class_hash,
+            Column::PendingClassInfo,
+            Column::ClassInfo,
+        )?;
+        Ok(metadata.and_then(|metadata| metadata.block_id.block_n()))
+    }
+
+    /// Returns a class along with its declaration, including classes declared in the pending block. The class is
+    /// only read and decoded once, and only its metadata is read when the class is cached.
+    #[tracing::instrument(skip(self, class_hash), fields(module = "ClassDB"))]
+    pub fn class_with_metadata(
+        &self,
+        class_hash: &Felt,
+    ) -> Result<Option<(ClassInfo, ClassDeclaration)>, MadaraStorageError> {
+        let found = match self.class_cache.get(class_hash) {
+            Some((class_info, _)) => self
+                .class_db_get_encoded_kv::<ClassMetadata>(
+                    true,
+                    class_hash,
+                    Column::PendingClassInfo,
+                    Column::ClassInfo,
+                )?
+                .map(|metadata| (metadata, class_info)),
+            None => self.class_db_get_encoded_kv::<(ClassMetadata, ClassInfo)>(
+                true,
+                class_hash,
+                Column::PendingClassInfo,
+                Column::ClassInfo,
+            )?,
+        };
+        let Some((metadata, class_info)) = found else { return Ok(None) };
+
+        let (contract_class_version, compiler_version) = match &class_info {
+            ClassInfo::Sierra(info) => (
+                Some(info.contract_class.contract_class_version.clone()),
+                self.get_sierra_compiler_version(&info.compiled_class_hash)?,
+            ),
+            ClassInfo::Legacy(_) => (None, None),
+        };
+        let declaration = ClassDeclaration { metadata, contract_class_version, compiler_version };
+        Ok(Some((class_info, declaration)))
+    }
+
+    /// Same as [`Self::get_class_info`] for many classes at once, with a single database round-trip. The
+    /// returned classes are in the same order as `class_hashes`, with `None` for the classes not found.
+    #[tracing::instrument(skip(self, id, class_hashes), fields(module = "ClassDB"))]
+    pub fn get_class_infos(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        class_hashes: &[Felt],
+    ) -> Result<Vec<Option<ClassInfo>>, MadaraStorageError> {
+        let Some(requested_id) = id.resolve_db_block_id(self)? else {
+            return Ok(class_hashes.iter().map(|_| None).collect());
+        };
+
+        let infos = self.class_db_multi_get_encoded_kv::<ClassInfoWithBlockNumber>(
+            requested_id.is_pending(),
+            class_hashes,
+            Column::PendingClassInfo,
+            Column::ClassInfo,
+        )?;
+
+        Ok(infos
+            .into_iter()
+            .map(|info| info.filter(|info| is_declared_at(requested_id, info.block_id)).map(|info| info.class_info))
+            .collect())
+    }
+
+    /// Compiled class hashes of many classes at once, in the same order as `class_hashes`. Classes in the class
+    /// cache are served from memory, and the others are read with a single database round-trip, without decoding
+    /// their definitions. `None` for legacy classes and for the classes not found.
+    #[tracing::instrument(skip(self, id, class_hashes), fields(module = "ClassDB"))]
+    pub fn get_compiled_class_hashes(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        class_hashes: &[Felt],
+    ) -> Result<Vec<Option<Felt>>, MadaraStorageError> {
+        let Some(requested_id) = id.resolve_db_block_id(self)? else {
+            return Ok(class_hashes.iter().map(|_| None).collect());
+        };
+
+        let mut compiled_class_hashes: Vec<Option<Option<Felt>>> = class_hashes
+            .iter()
+            .map(|class_hash| {
+                let (class_info, block_n) = self.class_cache.get(class_hash)?;
+                Some(
+                    class_info
+                        .compiled_class_hash()
+                        .filter(|_| is_declared_at(requested_id, DbBlockId::Number(block_n))),
+                )
+            })
+            .collect();
+
+        let missing: Vec<usize> = (0..class_hashes.len()).filter(|&i| compiled_class_hashes[i].is_none()).collect();
+        let metadata = self.class_db_multi_get_encoded_kv::<ClassMetadata>(
+            requested_id.is_pending(),
+            &missing.iter().map(|&i| class_hashes[i]).collect::<Vec<_>>(),
+            Column::PendingClassInfo,
+            Column::ClassInfo,
+        )?;
+        for (i, metadata) in missing.into_iter().zip(metadata) {
+            compiled_class_hashes[i] = Some(
+                metadata
+                    .filter(|metadata| is_declared_at(requested_id, metadata.block_id))
+                    .and_then(|metadata| metadata.compiled_class_hash),
+            );
+        }
+
+        Ok(compiled_class_hashes.into_iter().map(Option::flatten).collect())
+    }
+
+    /// The class infos of classes declared in closed blocks, along with the block in which they were declared.
+    pub(crate) fn class_db_get_closed_class_infos(
+        &self,
+        class_hashes: &[Felt],
+    ) -> Result<Vec<Option<(ClassInfo, u64)>>, MadaraStorageError> {
+        let infos = self.class_db_multi_get_encoded_kv::<ClassInfoWithBlockNumber>(
+            false,
+            class_hashes,
+            Column::PendingClassInfo,
+            Column::ClassInfo,
+        )?;
+
+        Ok(infos
+            .into_iter()
+            .map(|info| match info? {
+                ClassInfoWithBlockNumber { class_info, block_id: DbBlockId::Number(block_n) } => {
+                    Some((class_info, block_n))
+                }
+                ClassInfoWithBlockNumber { block_id: DbBlockId::Pending, .. } => None,
+            })
+            .collect())
+    }
+
+    /// Whether the class was declared in a closed block. The class definition is not decoded, and some absent
+    /// classes are answered by rocksdb from memory without reading from disk.
     #[tracing::instrument(skip(self), fields(module = "ClassDB"))]
     pub fn contains_class(&self, class_hash: &Felt) -> Result<bool, MadaraStorageError> {
         let col = self.db.get_column(Column::ClassInfo);
         let key_encoded = bincode::serialize(class_hash)?;
-        Ok(self.db.get_pinned_cf(&col, &key_encoded)?.is_some())
+        if !self.db.key_may_exist_cf(&col, &key_encoded) {
+            return Ok(false);
+        }
+        // Only compare the key the iterator lands on, `get` would read the class definition from its blob file.
+        let mut iter = self.db.raw_iterator_cf(&col);
+        iter.seek(&key_encoded);
+        iter.status()?;
+        Ok(iter.key() == Some(key_encoded.as_slice()))
     }
 
     #[tracing::instrument(skip(self, id, compiled_class_hash), fields(module = "ClassDB"))]
@@ -112,6 +404,212 @@ impl MadaraBackend {
         Ok(Some(compiled))
     }
 
+    /// Version of the Sierra to CASM compiler that produced the compiled class with hash `compiled_class_hash`.
+    /// This is `None` for classes stored before the compiler version was tracked.
+    #[tracing::instrument(skip(self, compiled_class_hash), fields(module = "ClassDB"))]
+    pub fn get_sierra_compiler_version(
+        &self,
+        compiled_class_hash: &Felt,
+    ) -> Result<Option<String>, MadaraStorageError> {
+        let col = self.db.get_column(Column::ClassCompilerVersion);
+        let key_encoded = bincode::serialize(compiled_class_hash)?;
+        let Some(res) = self.db.get_pinned_cf(&col, &key_encoded)? else { return Ok(None) };
+        Ok(Some(bincode::deserialize(&res)?))
+    }
+
+    /// Batched version of [`Self::get_sierra_compiler_version`], with a single multi-get.
+    fn get_sierra_compiler_versions(
+        &self,
+        compiled_class_hashes: &[Felt],
+    ) -> Result<Vec<Option<String>>, MadaraStorageError> {
+        let col = self.db.get_column(Column::ClassCompilerVersion);
+        let keys_encoded = compiled_class_hashes.iter().map(bincode::serialize).collect::<Result<Vec<_>, _>>()?;
+        self.db
+            .batched_multi_get_cf(&col, &keys_encoded, false)
+            .into_iter()
+            .map(|res| Ok(res?.map(|val| bincode::deserialize(&val)).transpose()?))
+            .collect()
+    }
+
+    /// Top-level fields of the definition of a class which are not part of [`mp_class::ContractClass`], if the class
+    /// was declared with some. See [`mp_class::raw_class`].
+    #[tracing::instrument(skip(self, class_hash), fields(module = "ClassDB"))]
+    pub fn get_class_unknown_fields(
+        &self,
+        class_hash: &Felt,
+    ) -> Result<Option<UnknownClassFields>, MadaraStorageError> {
+        let col = self.db.get_column(Column::ClassUnknownFields);
+        let Some(res) = self.db.get_pinned_cf(&col, bincode::serialize(class_hash)?)? else { return Ok(None) };
+        // Stored as JSON, as bincode cannot deserialize arbitrary JSON values.
+        Ok(Some(serde_json::from_slice(&res)?))
+    }
+
+    /// Stores the unknown top-level fields of class definitions, see [`MadaraBackend::get_class_unknown_fields`].
+    #[tracing::instrument(skip(self, classes), fields(module = "ClassDB"))]
+    pub fn store_class_unknown_fields(&self, classes: &[(Felt, UnknownClassFields)]) -> Result<(), MadaraStorageError> {
+        let col = self.db.get_column(Column::ClassUnknownFields);
+        let mut batch = WriteBatchWithTransaction::default();
+        for (class_hash, unknown_fields) in classes {
+            batch.put_cf(&col, bincode::serialize(class_hash)?, serde_json::to_vec(unknown_fields)?);
+        }
+        let mut writeopts = WriteOptions::default();
+        writeopts.disable_wal(true);
+        self.db.write_opt(batch, &writeopts)?;
+        Ok(())
+    }
+
+    /// Same as [`MadaraBackend::get_sierra_compiled`], but when the compiled class is not in the database because
+    /// its compilation was deferred during block import, the Sierra class is compiled now. The compiled class hash
+    /// is checked against the declared one, and the compiled class is then stored so that it is only compiled once.
+    ///
+    /// A stored compiled class which was produced by another version of the compiler than the one currently used for
+    /// this class is compiled again and replaced. If the new compilation does not match the declared compiled class
+    /// hash, the stored compiled class is kept and tagged with the current compiler version, so that it is not compiled
+    /// again until the compiler changes. The compiler version is only checked once per compiled class while the node
+    /// runs.
+    ///
+    /// Compiling a class can take seconds: callers on an async runtime should run this on a blocking thread.
+    #[tracing::instrument(skip(self, id, class_hash, info), fields(module = "ClassDB"))]
+    pub fn get_or_compile_sierra_compiled(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        class_hash: &Felt,
+        info: &SierraClassInfo,
+    ) -> Result<CompiledSierra, MadaraStorageError> {
+        let compiled_class_hash = info.compiled_class_hash;
+        let stored = self.get_sierra_compiled(id, &compiled_class_hash)?;
+        if let Some(compiled) = &stored {
+            if self.is_current_compiled_class(&compiled_class_hash) {
+                return Ok(compiled.clone());
+            }
+            let stored_compiler_version = self.get_sierra_compiler_version(&compiled_class_hash)?;
+            match (stored_compiler_version, info.contract_class.casm_compiler_version()) {
+                (Some(stored_version), Ok(current_version)) if stored_version != current_version => {
+                    tracing::debug!(
+                        "Compiling class {class_hash:#x} again, it was compiled with compiler version \
+                         {stored_version} but the current one is {current_version}"
+                    );
+                }
+                _ => {
+                    self.set_current_compiled_class(compiled_class_hash);
+                    return Ok(compiled.clone());
+                }
+            }
+        } else {
+            tracing::debug!("Compiling class {class_hash:#x} on first use");
+        }
+
+        let (recompiled_class_hash, compiled) = info
+            .contract_class
+            .compile_to_casm()
+            .map_err(|err| MadaraStorageError::CompilationClassError(format!("{err:#}")))?;
+        if recompiled_class_hash != compiled_class_hash {
+            if let Some(stored) = stored {
+                tracing::warn!(
+                    "Class {class_hash:#x} compiles to compiled class hash {recompiled_class_hash:#x} with the \
+                     current compiler, expected {compiled_class_hash:#x}: keeping the stored compiled class"
+                );
+                // Tag the stored compiled class with the current compiler version, so that it is not compiled again
+                // on every read.
+                if let Ok(compiler_version) = info.contract_class.casm_compiler_version() {
+                    let col = self.db.get_column(Column::ClassCompilerVersion);
+                    self.db.put_cf(
+                        &col,
+                        bincode::serialize(&compiled_class_hash)?,
+                        bincode::serialize(compiler_version)?,
+                    )?;
+                }
+                self.set_current_compiled_class(compiled_class_hash);
+                return Ok(stored);
+            }
+            return Err(MadaraStorageError::CompilationClassError(format!(
+                "Class {class_hash:#x} compiles to compiled class hash {recompiled_class_hash:#x}, expected \
+                 {compiled_class_hash:#x}"
+            )));
+        }
+
+        let mut batch = WriteBatchWithTransaction::default();
+        self.batch_put_sierra_compiled(&mut batch, class_hash, info, &compiled)?;
+        // Written with the WAL: unlike block import, nothing flushes the database after a read.
+        self.db.write(batch)?;
+        self.set_current_compiled_class(compiled_class_hash);
+
+        Ok(compiled)
+    }
+
+    fn is_current_compiled_class(&self, compiled_class_hash: &Felt) -> bool {
+        self.current_compiled_classes.lock().expect("Poisoned lock").contains(compiled_class_hash)
+    }
+
+    fn set_current_compiled_class(&self, compiled_class_hash: Felt) {
+        self.current_compiled_classes.lock().expect("Poisoned lock").insert(compiled_class_hash);
+    }
+
+    /// Adds the writes storing the compiled class of a Sierra class, along with the version of the compiler which
+    /// produced it, to `batch`.
+    fn batch_put_sierra_compiled(
+        &self,
+        batch: &mut WriteBatchWithTransaction,
+        class_hash: &Felt,
+        info: &SierraClassInfo,
+        compiled: &CompiledSierra,
+    ) -> Result<(), MadaraStorageError> {
+        // Classes only declared in the pending block have their compiled class in the pending column.
+        let col = match self.contains_class(class_hash)? {
+            true => self.db.get_column(Column::ClassCompiled),
+            false => self.db.get_column(Column::PendingClassCompiled),
+        };
+        let key_bin = bincode::serialize(&info.compiled_class_hash)?;
+        batch.put_cf(&col, &key_bin, bincode::serialize(compiled)?);
+        if let Ok(compiler_version) = info.contract_class.casm_compiler_version() {
+            let col_compiler_version = self.db.get_column(Column::ClassCompilerVersion);
+            batch.put_cf(&col_compiler_version, &key_bin, bincode::serialize(compiler_version)?);
+        }
+        Ok(())
+    }
+
+    /// Compiles the given Sierra classes again with the current compiler, and replaces their stored compiled class
+    /// and compiler version. This is meant to recover from a wrong compiled class being stored, for example after a
+    /// compiler bug.
+    ///
+    /// The stored compiled class is the only one the class can be executed with when the current compiler does not
+    /// reproduce the declared compiled class hash. In that case an error is returned, and no compiled class is
+    /// replaced.
+    ///
+    /// Only the database is updated: in-memory caches are not invalidated. The class cache does not hold compiled
+    /// classes, but execution results already cached by the RPC, and executions running at the same time, may still
+    /// have used the replaced compiled classes.
+    ///
+    /// Returns the number of compiled classes which were replaced. Unknown and legacy classes are skipped.
+    #[tracing::instrument(skip(self, class_hashes), fields(module = "ClassDB"))]
+    pub fn invalidate_sierra_compiled(&self, class_hashes: &[Felt]) -> Result<usize, MadaraStorageError> {
+        let mut invalidated = vec![];
+        let mut batch = WriteBatchWithTransaction::default();
+        for class_hash in class_hashes {
+            let Some(ClassInfo::Sierra(info)) = self.get_class_info(&DbBlockId::Pending, class_hash)? else { continue };
+            let (recompiled_class_hash, compiled) = info
+                .contract_class
+                .compile_to_casm()
+                .map_err(|err| MadaraStorageError::CompilationClassError(format!("{err:#}")))?;
+            if recompiled_class_hash != info.compiled_class_hash {
+                return Err(MadaraStorageError::CompilationClassError(format!(
+                    "Class {class_hash:#x} compiles to compiled class hash {recompiled_class_hash:#x} with the \
+                     current compiler, expected {:#x}: keeping the stored compiled class",
+                    info.compiled_class_hash
+                )));
+            }
+            self.batch_put_sierra_compiled(&mut batch, class_hash, &info, &compiled)?;
+            invalidated.push(info.compiled_class_hash);
+        }
+        self.db.write(batch)?;
+        for compiled_class_hash in &invalidated {
+            self.set_current_compiled_class(*compiled_class_hash);
+        }
+
+        tracing::debug!("Compiled {} of {} classes again", invalidated.len(), class_hashes.len());
+        Ok(invalidated.len())
+    }
+
     /// Get class info + sierra compiled when it's a sierra class.
     // Note/TODO: "ConvertedClass" is the name of the type that has info + sierra compiled, and it is used for blockifier
     // convertion & storage. We should rename it, as this feels like undecipherable madara-specific jargon at this point.
@@ -133,15 +631,12 @@ impl MadaraBackend {
 
         match class_info {
             ClassInfo::Sierra(info) => {
-                let compiled_class_hash = info.compiled_class_hash;
-                let compiled_class = self
-                    .get_sierra_compiled(&id, &info.compiled_class_hash)?
-                    .ok_or(MadaraStorageError::MissingCompiledClass { class_hash: *class_hash, compiled_class_hash })?;
+                let compiled_class = self.get_or_compile_sierra_compiled(&id, class_hash, &info)?;
                 Ok(Some(ConvertedClass::Sierra(SierraConvertedClass {
                     class_hash: *class_hash,
                     info,
                     // TODO(perf): we should do global memoization for these Arcs.
-                    compiled: Arc::new(compiled_class),
+                    compiled: Some(Arc::new(compiled_class)),
                 })))
             }
             ClassInfo::Legacy(info) => {
@@ -150,21 +645,135 @@ impl MadaraBackend {
         }
     }
 
+    /// Same as [`Self::get_converted_class`] for many classes at once. The class infos are read with a single
+    /// database round-trip, see [`Self::get_class_infos`].
+    #[tracing::instrument(skip(self, id, class_hashes), fields(module = "ClassDB"))]
+    pub fn get_converted_classes(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        class_hashes: &[Felt],
+    ) -> Result<Vec<Option<ConvertedClass>>, MadaraStorageError> {
+        let Some(id) = id.resolve_db_block_id(self)? else { return Ok(class_hashes.iter().map(|_| None).collect()) };
+
+        let class_infos = self.get_class_infos(&id, class_hashes)?;
+
+        // Read the compiled classes and their compiler versions in a batch too. The compiled classes which are
+        // missing or were produced by another compiler version go through `get_or_compile_sierra_compiled`.
+        let compiled_class_hashes: Vec<Felt> = class_infos
+            .iter()
+            .filter_map(|info| match info {
+                Some(ClassInfo::Sierra(info)) => Some(info.compiled_class_hash),
+                _ => None,
+            })
+            .collect();
+        let mut stored_compiled = self
+            .class_db_multi_get_encoded_kv::<CompiledSierra>(
+                id.is_pending(),
+                &compiled_class_hashes,
+                Column::PendingClassCompiled,
+                Column::ClassCompiled,
+            )?
+            .into_iter()
+            .zip(self.get_sierra_compiler_versions(&compiled_class_hashes)?);
+
+        class_infos
+            .into_iter()
+            .zip(class_hashes)
+            .map(|(class_info, class_hash)| match class_info {
+                Some(ClassInfo::Sierra(info)) => {
+                    let (stored, stored_compiler_version) =
+                        stored_compiled.next().expect("One compiled class read per Sierra class");
+                    let compiled_class = match stored {
+                        Some(compiled)
+                            if stored_compiler_version.is_none()
+                                || info.contract_class.casm_compiler_version().ok()
+                                    == stored_compiler_version.as_deref() =>
+                        {
+                            compiled
+                        }
+                        _ => self.get_or_compile_sierra_compiled(&id, class_hash, &info)?,
+                    };
+                    Ok(Some(ConvertedClass::Sierra(SierraConvertedClass {
+                        class_hash: *class_hash,
+                        info,
+                        compiled: Some(Arc::new(compiled_class)),
+                    })))
+                }
+                Some(ClassInfo::Legacy(info)) => {
+                    Ok(Some(ConvertedClass::Legacy(LegacyConvertedClass { class_hash: *class_hash, info })))
+                }
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    /// Checks the integrity of every class in the database. Pending classes are not checked.
+    ///
+    /// Sierra classes are compiled to CASM again, and the resulting compiled class hash is compared against
+    /// the stored `compiled_class_hash`. This is slow, as every Sierra class of the chain is recompiled. A Sierra
+    /// class without a stored compiled class is not an issue: its compilation was deferred, and the recompilation
+    /// check tells whether it will succeed on first use.
+    #[tracing::instrument(skip(self), fields(module = "ClassDB"))]
+    pub fn verify_all_classes(&self) -> Result<Vec<ClassVerificationIssue>, MadaraStorageError> {
+        let col_info = self.db.get_column(Column::ClassInfo);
+
+        let mut issues = vec![];
+        for res in self.db.iterator_cf(&col_info, IteratorMode::Start) {
+            let (key, value) = res?;
+            let decoded = bincode::deserialize::<Felt>(&key)
+                .map_err(MadaraStorageError::from)
+                .and_then(|class_hash| Ok((class_hash, self.decode_class(&value)?.1)));
+            let (class_hash, class_info) = match decoded {
+                Ok(decoded) => decoded,
+                Err(err) => {
+                    tracing::warn!("Corrupt class record: {err:#}");
+                    issues.push(ClassVerificationIssue::Corrupt { key: key.into(), error: format!("{err:#}") });
+                    continue;
+                }
+            };
+            let ClassInfo::Sierra(info) = class_info else { continue };
+
+            let compiled_class_hash = info.compiled_class_hash;
+            match info.contract_class.compile_to_casm() {
+                Ok((recompiled, _)) if recompiled != compiled_class_hash => {
+                    tracing::warn!("Class {class_hash:#x} recompiles to {recompiled:#x}, not {compiled_class_hash:#x}");
+                    issues.push(ClassVerificationIssue::CompiledClassHashMismatch {
+                        class_hash,
+                        stored: compiled_class_hash,
+                        recompiled,
+                    });
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    issues.push(ClassVerificationIssue::CompilationFailed { class_hash, error: format!("{err:#}") })
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+
     /// NB: This functions needs to run on the rayon thread pool
     #[tracing::instrument(skip(self, converted_classes, col_info, col_compiled), fields(module = "ClassDB"))]
     pub(crate) fn store_classes(
         &self,
         block_id: DbBlockId,
         converted_classes: &[ConvertedClass],
+        declare_tx_hashes: &HashMap<Felt, Felt>,
         col_info: Column,
         col_compiled: Column,
     ) -> Result<(), MadaraStorageError> {
         let mut writeopts = WriteOptions::new();
         writeopts.disable_wal(true);
 
+        // Pending classes are not deduplicated, as their definitions would be left behind when the pending block
+        // is cleared.
+        let dedup = self.dedup_classes() && col_info == Column::ClassInfo;
         converted_classes.par_chunks(DB_UPDATES_BATCH_SIZE).try_for_each_init(
-            || self.db.get_column(col_info),
-            |col, chunk| {
+            || (self.db.get_column(col_info), self.db.get_column(Column::ClassDefinitions)),
+            |(col, col_definitions), chunk| {
+                let format = self.class_storage_format();
+                let compression_threshold = self.class_compression_threshold();
                 let mut batch = WriteBatchWithTransaction::default();
                 for converted_class in chunk {
                     let class_hash = converted_class.class_hash();
@@ -172,14 +781,21 @@ impl MadaraBackend {
                     // this is a patch because some legacy classes are declared multiple times
                     if !self.contains_class(&class_hash)? {
                         // TODO: find a way to avoid this allocation
-                        batch.put_cf(
-                            col,
-                            &key_bin,
-                            bincode::serialize(&ClassInfoWithBlockNumber {
-                                class_info: converted_class.info(),
-                                block_id,
-                            })?,
-                        );
+                        let class_info = converted_class.info();
+                        let metadata =
+                            ClassMetadata::new(block_id, &class_info, declare_tx_hashes.get(&class_hash).copied());
+                        if dedup {
+                            let (record, digest, definition) =
+                                format.encode_interned_class(&metadata, &class_info, compression_threshold)?;
+                            batch.put_cf(col_definitions, digest, definition);
+                            batch.put_cf(col, &key_bin, record);
+                        } else {
+                            batch.put_cf(
+                                col,
+                                &key_bin,
+                                format.encode_class(&metadata, &class_info, compression_threshold)?,
+                            );
+                        }
                     }
                 }
                 self.db.write_opt(batch, &writeopts)?;
@@ -190,20 +806,26 @@ impl MadaraBackend {
         converted_classes
             .iter()
             .filter_map(|converted_class| match converted_class {
-                ConvertedClass::Sierra(sierra) => Some((sierra.info.compiled_class_hash, sierra.compiled.clone())),
+                // Sierra classes with deferred compilation are compiled and stored on first use.
+                ConvertedClass::Sierra(sierra) => sierra.compiled.clone().map(|compiled| {
+                    (sierra.info.compiled_class_hash, compiled, sierra.info.contract_class.casm_compiler_version().ok())
+                }),
                 _ => None,
             })
             .collect::<Vec<_>>()
             .par_chunks(DB_UPDATES_BATCH_SIZE)
             .try_for_each_init(
-                || self.db.get_column(col_compiled),
-                |col, chunk| {
+                || (self.db.get_column(col_compiled), self.db.get_column(Column::ClassCompilerVersion)),
+                |(col, col_compiler_version), chunk| {
                     let mut batch = WriteBatchWithTransaction::default();
-                    for (key, value) in chunk {
+                    for (key, value, compiler_version) in chunk {
                         tracing::trace!("Class compiled store key={key:#x}");
                         let key_bin = bincode::serialize(key)?;
                         // TODO: find a way to avoid this allocation
                         batch.put_cf(col, &key_bin, bincode::serialize(&value)?);
+                        if let Some(compiler_version) = compiler_version {
+                            batch.put_cf(col_compiler_version, &key_bin, bincode::serialize(compiler_version)?);
+                        }
                     }
                     self.db.write_opt(batch, &writeopts)?;
                     Ok::<_, MadaraStorageError>(())
@@ -214,24 +836,36 @@ impl MadaraBackend {
     }
 
     /// NB: This functions needs to run on the rayon thread pool
-    #[tracing::instrument(skip(self, converted_classes), fields(module = "ClassDB"))]
+    #[tracing::instrument(skip(self, converted_classes, declare_tx_hashes), fields(module = "ClassDB"))]
     pub(crate) fn class_db_store_block(
         &self,
         block_number: u64,
         converted_classes: &[ConvertedClass],
+        declare_tx_hashes: &HashMap<Felt, Felt>,
     ) -> Result<(), MadaraStorageError> {
-        self.store_classes(DbBlockId::Number(block_number), converted_classes, Column::ClassInfo, Column::ClassCompiled)
+        #[cfg(feature = "class-selector-index")]
+        self.class_selector_index_store(converted_classes)?;
+        self.entrypoint_index_store(converted_classes)?;
+        self.store_classes(
+            DbBlockId::Number(block_number),
+            converted_classes,
+            declare_tx_hashes,
+            Column::ClassInfo,
+            Column::ClassCompiled,
+        )
     }
 
     /// NB: This functions needs to run on the rayon thread pool
-    #[tracing::instrument(skip(self, converted_classes), fields(module = "ClassDB"))]
+    #[tracing::instrument(skip(self, converted_classes, declare_tx_hashes), fields(module = "ClassDB"))]
     pub(crate) fn class_db_store_pending(
         &self,
         converted_classes: &[ConvertedClass],
+        declare_tx_hashes: &HashMap<Felt, Felt>,
     ) -> Result<(), MadaraStorageError> {
         self.store_classes(
             DbBlockId::Pending,
             converted_classes,
+            declare_tx_hashes,
             Column::PendingClassInfo,
             Column::PendingClassCompiled,
         )
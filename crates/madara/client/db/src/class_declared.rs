@@ -0,0 +1,66 @@
+//! Notifications of the classes stored in closed blocks, for indexers which react to class declarations.
+
+use mp_class::{ClassInfo, ConvertedClass};
+use starknet_types_core::felt::Felt;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::MadaraBackend;
+
+/// Capacity of the queue of declared classes. A callback which falls further behind misses the oldest classes.
+pub(crate) const CLASS_DECLARED_QUEUE_CAPACITY: usize = 1024;
+
+/// A class stored in a closed block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclaredClass {
+    pub class_hash: Felt,
+    /// The `contract_class_version` of a Sierra class, such as `0.1.0`, and `None` for a legacy class.
+    pub contract_class_version: Option<String>,
+    pub block_n: u64,
+}
+
+impl DeclaredClass {
+    fn new(converted_class: &ConvertedClass, block_n: u64) -> Self {
+        let contract_class_version = match converted_class.info() {
+            ClassInfo::Sierra(sierra) => Some(sierra.contract_class.contract_class_version.clone()),
+            ClassInfo::Legacy(_) => None,
+        };
+        Self { class_hash: converted_class.class_hash(), contract_class_version, block_n }
+    }
+}
+
+impl MadaraBackend {
+    pub fn subscribe_class_declared(&self) -> tokio::sync::broadcast::Receiver<DeclaredClass> {
+        self.sender_class_declared.subscribe()
+    }
+
+    /// Calls `callback` for every class stored in a closed block from now on, once the block is committed.
+    ///
+    /// The classes are queued and the callback runs on its own thread, so a slow callback never delays block
+    /// storage. The thread stops when the backend is dropped.
+    pub fn on_class_declared(
+        &self,
+        mut callback: impl FnMut(DeclaredClass) + Send + 'static,
+    ) -> std::thread::JoinHandle<()> {
+        let mut recv = self.subscribe_class_declared();
+        std::thread::spawn(move || loop {
+            match recv.blocking_recv() {
+                Ok(declared_class) => callback(declared_class),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Class declaration callback is lagging behind, {skipped} classes were skipped")
+                }
+                Err(RecvError::Closed) => break,
+            }
+        })
+    }
+
+    pub(crate) fn publish_declared_classes(&self, converted_classes: &[ConvertedClass], block_n: u64) {
+        if self.sender_class_declared.receiver_count() == 0 {
+            return;
+        }
+        for converted_class in converted_classes {
+            if let Err(e) = self.sender_class_declared.send(DeclaredClass::new(converted_class, block_n)) {
+                tracing::debug!("Failed to send declared class to subscribers: {e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+//! Operator-provided human-readable labels for class hashes (e.g. "OpenZeppelin ERC20 v0.8"), meant to be displayed
+//! by explorers. These are purely informational annotations: they are not part of the chain state.
+
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError};
+use starknet_types_core::felt::Felt;
+
+type Result<T, E = MadaraStorageError> = std::result::Result<T, E>;
+
+impl MadaraBackend {
+    /// Get the label of a class, if one has been set.
+    #[tracing::instrument(skip(self), fields(module = "ClassLabelDB"))]
+    pub fn class_label(&self, class_hash: &Felt) -> Result<Option<String>> {
+        let col = self.db.get_column(Column::ClassLabels);
+        let Some(res) = self.db.get_pinned_cf(&col, bincode::serialize(class_hash)?)? else {
+            return Ok(None);
+        };
+        Ok(Some(bincode::deserialize(&res)?))
+    }
+
+    /// Set the label of a class, replacing any previous label. Passing `None` removes the label.
+    #[tracing::instrument(skip(self), fields(module = "ClassLabelDB"))]
+    pub fn set_class_label(&self, class_hash: &Felt, label: Option<&str>) -> Result<()> {
+        let col = self.db.get_column(Column::ClassLabels);
+        let key = bincode::serialize(class_hash)?;
+        // Written with the WAL: unlike block data, labels cannot be synced again after a crash.
+        match label {
+            Some(label) => self.db.put_cf(&col, key, bincode::serialize(label)?)?,
+            None => self.db.delete_cf(&col, key)?,
+        }
+        Ok(())
+    }
+}
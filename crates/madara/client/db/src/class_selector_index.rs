@@ -0,0 +1,58 @@
+//! Index of the classes exposing a given entry point selector, so that explorers can look up the classes
+//! implementing a function. This index is built when storing blocks, and is only enabled with the
+//! `class-selector-index` feature as it takes a fair amount of storage on mainnet.
+//!
+//! Keys are `selector ++ class_hash` (both big-endian), values are empty.
+
+use mp_class::ConvertedClass;
+use rocksdb::{Direction, IteratorMode};
+use starknet_types_core::felt::Felt;
+
+use crate::entrypoint_index::class_selectors;
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError, WriteBatchWithTransaction};
+
+pub(crate) fn make_key(selector: &Felt, class_hash: &Felt) -> [u8; 64] {
+    let mut key = [0u8; 64];
+    key[..32].copy_from_slice(&selector.to_bytes_be());
+    key[32..].copy_from_slice(&class_hash.to_bytes_be());
+    key
+}
+
+impl MadaraBackend {
+    #[tracing::instrument(skip(self, converted_classes), fields(module = "ClassDB"))]
+    pub(crate) fn class_selector_index_store(
+        &self,
+        converted_classes: &[ConvertedClass],
+    ) -> Result<(), MadaraStorageError> {
+        let col = self.db.get_column(Column::SelectorToClassHashes);
+        let mut batch = WriteBatchWithTransaction::default();
+        for converted_class in converted_classes {
+            let class_hash = converted_class.class_hash();
+            for selector in class_selectors(&converted_class.info()) {
+                batch.put_cf(&col, make_key(&selector, &class_hash), []);
+            }
+        }
+        self.db.write_opt(batch, &self.write_opt_no_wal)?;
+        Ok(())
+    }
+
+    /// Returns the hashes of all the declared classes which have an entry point with this selector, sorted by
+    /// class hash. Classes in the pending block are not indexed.
+    #[tracing::instrument(skip(self), fields(module = "ClassDB"))]
+    pub fn classes_with_selector(&self, selector: &Felt) -> Result<Vec<Felt>, MadaraStorageError> {
+        let col = self.db.get_column(Column::SelectorToClassHashes);
+        let prefix = selector.to_bytes_be();
+
+        let mut class_hashes = vec![];
+        for kv in self.db.iterator_cf(&col, IteratorMode::From(&prefix, Direction::Forward)) {
+            let (key, _) = kv?;
+            let Some(class_hash) = key.strip_prefix(prefix.as_slice()) else { break };
+            let class_hash: [u8; 32] = class_hash.try_into().map_err(|_| {
+                MadaraStorageError::InconsistentStorage("Malformed key in the class selector index".into())
+            })?;
+            class_hashes.push(Felt::from_bytes_be(&class_hash));
+        }
+
+        Ok(class_hashes)
+    }
+}
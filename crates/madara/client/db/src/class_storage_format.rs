@@ -0,0 +1,383 @@
+//! Encoding of the class definitions stored in the database.
+//!
+//! Class definitions are bincode-encoded by default, like every other value in the database. For interop
+//! with tooling outside of Rust, they can instead be stored as JSON. Every stored class records its format in
+//! its first byte, so that classes stored with a different format can still be read after the format is changed.
+//!
+//! The fixed-size metadata of a class is stored ahead of the class definition and is always bincode-encoded, so
+//! that it can be read on its own, even when the class definition is corrupt:
+//!
+//! ```text
+//! record   := <format: 0x42 ('B') or 0x4A ('J')> <metadata length: u32 big-endian> <metadata> <class info>
+//! metadata := bincode encoding of [`ClassMetadata`]
+//! ```
+//!
+//! When class deduplication is enabled, the class definition is stored once per content in the
+//! [`Column::ClassDefinitions`] column, keyed by its SHA3-256 digest, and the record only holds the digest:
+//!
+//! ```text
+//! record     := <0x44 ('D')> <metadata length: u32 big-endian> <metadata> <digest: 32 bytes>
+//! definition := <format: 0x42 ('B') or 0x4A ('J')> <class info>
+//! ```
+//!
+//! Once a class compression threshold is set, see [`MadaraBackend::set_class_compression_threshold`], class
+//! definitions of at least that size are gzip-compressed. A compressed definition is marked with a leading 0x5A
+//! ('Z') in place of its format, which follows it:
+//!
+//! ```text
+//! record     := <0x5A ('Z')> <metadata length: u32 big-endian> <metadata> <format> <gzip of the class info>
+//! definition := <0x5A ('Z')> <format> <gzip of the class info>
+//! ```
+//!
+//! The digest of a deduplicated definition is the one of its uncompressed form.
+//!
+//! The class info has the same shape as `mp_class::ClassInfo` in both formats. Records written before the
+//! metadata was split out are a single bincode value, starting with the class info variant (0x00 or 0x01); they
+//! are still read, but their metadata can only be decoded along with the class definition.
+
+use std::{
+    fmt,
+    io::{Read, Write},
+    str::FromStr,
+};
+
+use anyhow::Context;
+use mp_class::ClassInfo;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use starknet_types_core::felt::Felt;
+
+use crate::{db_block_id::DbBlockId, Column, DatabaseExt, MadaraBackend, MadaraStorageError};
+
+const BINCODE_FORMAT_TAG: u8 = b'B';
+const JSON_FORMAT_TAG: u8 = b'J';
+const INTERNED_TAG: u8 = b'D';
+const COMPRESSED_TAG: u8 = b'Z';
+const RECORD_HEADER_LEN: usize = 5;
+const DIGEST_LEN: usize = 32;
+
+/// Metadata of a stored class, which decodes independently of the class definition.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassMetadata {
+    /// Block in which the class was declared.
+    pub block_id: DbBlockId,
+    /// `None` for legacy classes.
+    pub compiled_class_hash: Option<Felt>,
+    /// Number of felts of the Sierra program, zero for legacy classes.
+    pub sierra_program_length: u64,
+    /// Length of the ABI string, zero for legacy classes.
+    pub abi_length: u64,
+    /// Hash of the declare transaction. `None` when the class was not declared by a transaction, such as the
+    /// classes of a genesis state, and for the classes stored before it was recorded.
+    pub declare_tx_hash: Option<Felt>,
+}
+
+impl ClassMetadata {
+    pub fn new(block_id: DbBlockId, class_info: &ClassInfo, declare_tx_hash: Option<Felt>) -> Self {
+        let contract_class = class_info.contract_class();
+        Self {
+            block_id,
+            compiled_class_hash: class_info.compiled_class_hash(),
+            sierra_program_length: contract_class.sierra_program_length() as u64,
+            abi_length: contract_class.abi_length() as u64,
+            declare_tx_hash,
+        }
+    }
+
+    /// Metadata written before the declare transaction hash was recorded ends with the ABI length.
+    fn decode(bytes: &[u8]) -> Result<Self, MadaraStorageError> {
+        bincode::deserialize(bytes).or_else(|_| {
+            let (block_id, compiled_class_hash, sierra_program_length, abi_length) = bincode::deserialize(bytes)?;
+            Ok(Self { block_id, compiled_class_hash, sierra_program_length, abi_length, declare_tx_hash: None })
+        })
+    }
+
+    /// Whether this is a Sierra class rather than a legacy (Cairo 0) class.
+    pub fn is_sierra(&self) -> bool {
+        self.compiled_class_hash.is_some()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClassStorageFormat {
+    #[default]
+    Bincode,
+    Json,
+}
+
+impl ClassStorageFormat {
+    fn tag(self) -> u8 {
+        match self {
+            Self::Bincode => BINCODE_FORMAT_TAG,
+            Self::Json => JSON_FORMAT_TAG,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            BINCODE_FORMAT_TAG => Some(Self::Bincode),
+            JSON_FORMAT_TAG => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    fn encode_body(self, bytes: &mut Vec<u8>, class_info: &ClassInfo) -> Result<(), MadaraStorageError> {
+        match self {
+            Self::Bincode => bincode::serialize_into(bytes, class_info)?,
+            Self::Json => serde_json::to_writer(bytes, class_info)?,
+        }
+        Ok(())
+    }
+
+    /// Class definitions of at least `compression_threshold` bytes are compressed, 0 compresses none.
+    pub(crate) fn encode_class(
+        self,
+        metadata: &ClassMetadata,
+        class_info: &ClassInfo,
+        compression_threshold: usize,
+    ) -> Result<Vec<u8>, MadaraStorageError> {
+        let mut body = vec![];
+        self.encode_body(&mut body, class_info)?;
+        if should_compress(&body, compression_threshold) {
+            let mut bytes = encode_record_header(COMPRESSED_TAG, metadata)?;
+            bytes.push(self.tag());
+            compress_into(&mut bytes, &body)?;
+            Ok(bytes)
+        } else {
+            let mut bytes = encode_record_header(self.tag(), metadata)?;
+            bytes.extend_from_slice(&body);
+            Ok(bytes)
+        }
+    }
+
+    /// Encodes a class for deduplicated storage: returns the record, the digest of the class definition, and the
+    /// class definition to store under that digest in [`Column::ClassDefinitions`].
+    pub(crate) fn encode_interned_class(
+        self,
+        metadata: &ClassMetadata,
+        class_info: &ClassInfo,
+        compression_threshold: usize,
+    ) -> Result<(Vec<u8>, [u8; DIGEST_LEN], Vec<u8>), MadaraStorageError> {
+        let mut definition = vec![self.tag()];
+        self.encode_body(&mut definition, class_info)?;
+        let digest: [u8; DIGEST_LEN] = Sha3_256::digest(&definition).into();
+        if should_compress(&definition[1..], compression_threshold) {
+            let mut compressed = vec![COMPRESSED_TAG, self.tag()];
+            compress_into(&mut compressed, &definition[1..])?;
+            definition = compressed;
+        }
+
+        let mut record = encode_record_header(INTERNED_TAG, metadata)?;
+        record.extend_from_slice(&digest);
+        Ok((record, digest, definition))
+    }
+}
+
+fn should_compress(body: &[u8], compression_threshold: usize) -> bool {
+    compression_threshold != 0 && body.len() >= compression_threshold
+}
+
+fn compress_into(bytes: &mut Vec<u8>, body: &[u8]) -> Result<(), MadaraStorageError> {
+    let mut encoder = flate2::write::GzEncoder::new(bytes, flate2::Compression::default());
+    encoder.write_all(body)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+fn decompress(body: &[u8]) -> Result<Vec<u8>, MadaraStorageError> {
+    let mut bytes = vec![];
+    flate2::read::GzDecoder::new(body)
+        .read_to_end(&mut bytes)
+        .map_err(|err| MadaraStorageError::CorruptClassBody(format!("Decompressing the class definition: {err}")))?;
+    Ok(bytes)
+}
+
+fn encode_record_header(tag: u8, metadata: &ClassMetadata) -> Result<Vec<u8>, MadaraStorageError> {
+    let metadata = bincode::serialize(metadata)?;
+    let metadata_len = u32::try_from(metadata.len())
+        .map_err(|_| MadaraStorageError::InconsistentStorage("Class metadata is too large".into()))?;
+
+    let mut bytes = Vec::with_capacity(RECORD_HEADER_LEN + metadata.len());
+    bytes.push(tag);
+    bytes.extend_from_slice(&metadata_len.to_be_bytes());
+    bytes.extend_from_slice(&metadata);
+    Ok(bytes)
+}
+
+/// A stored class, split into its metadata and its still encoded class definition.
+enum ClassRecord<'a> {
+    Split {
+        format: ClassStorageFormat,
+        metadata: ClassMetadata,
+        body: &'a [u8],
+    },
+    /// The class definition follows the metadata as `<format> <gzip of the class info>`.
+    Compressed {
+        metadata: ClassMetadata,
+        definition: &'a [u8],
+    },
+    /// The class definition is stored in [`Column::ClassDefinitions`] under this digest.
+    Interned {
+        metadata: ClassMetadata,
+        digest: &'a [u8],
+    },
+    /// Records written before the metadata was split out.
+    Whole(&'a [u8]),
+}
+
+impl<'a> ClassRecord<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, MadaraStorageError> {
+        let format = match bytes.first() {
+            Some(&INTERNED_TAG) => None,
+            Some(&COMPRESSED_TAG) => None,
+            Some(&tag) => match ClassStorageFormat::from_tag(tag) {
+                Some(format) => Some(format),
+                None => return Ok(Self::Whole(bytes)),
+            },
+            None => return Ok(Self::Whole(bytes)),
+        };
+        let Some((header, rest)) = bytes.split_at_checked(RECORD_HEADER_LEN) else {
+            return Err(MadaraStorageError::InconsistentStorage("Truncated class record header".into()));
+        };
+        let metadata_len = u32::from_be_bytes(header[1..].try_into().expect("Header has a 4 bytes length")) as usize;
+        let Some((metadata, body)) = rest.split_at_checked(metadata_len) else {
+            return Err(MadaraStorageError::InconsistentStorage("Truncated class metadata".into()));
+        };
+        let metadata = ClassMetadata::decode(metadata)?;
+        match format {
+            Some(format) => Ok(Self::Split { format, metadata, body }),
+            None if bytes[0] == COMPRESSED_TAG => Ok(Self::Compressed { metadata, definition: body }),
+            None if body.len() == DIGEST_LEN => Ok(Self::Interned { metadata, digest: body }),
+            None => Err(MadaraStorageError::InconsistentStorage("Invalid class definition digest".into())),
+        }
+    }
+}
+
+fn decode_body<T: DeserializeOwned>(format: ClassStorageFormat, body: &[u8]) -> Result<T, MadaraStorageError> {
+    match format {
+        ClassStorageFormat::Bincode => bincode::deserialize(body).map_err(|err| err.to_string()),
+        ClassStorageFormat::Json => serde_json::from_slice(body).map_err(|err| err.to_string()),
+    }
+    .map_err(MadaraStorageError::CorruptClassBody)
+}
+
+/// Decodes a class definition, `<format> <class info>`, or `<format> <gzip of the class info>` when `compressed`.
+fn decode_definition<T: DeserializeOwned>(definition: &[u8], compressed: bool) -> Result<T, MadaraStorageError> {
+    let Some((format, body)) =
+        definition.split_first().and_then(|(tag, body)| Some((ClassStorageFormat::from_tag(*tag)?, body)))
+    else {
+        return Err(MadaraStorageError::CorruptClassBody("Unknown class definition format".into()));
+    };
+    if compressed {
+        decode_body(format, &decompress(body)?)
+    } else {
+        decode_body(format, body)
+    }
+}
+
+fn decode_whole(bytes: &[u8]) -> Result<(ClassMetadata, ClassInfo), MadaraStorageError> {
+    let (class_info, block_id): (ClassInfo, DbBlockId) =
+        bincode::deserialize(bytes).map_err(|err| MadaraStorageError::CorruptClassBody(err.to_string()))?;
+    Ok((ClassMetadata::new(block_id, &class_info, None), class_info))
+}
+
+/// Decodes the metadata of a class stored with any of the formats, without decoding the class definition.
+pub(crate) fn decode_class_metadata(bytes: &[u8]) -> Result<ClassMetadata, MadaraStorageError> {
+    match ClassRecord::parse(bytes)? {
+        ClassRecord::Split { metadata, .. }
+        | ClassRecord::Compressed { metadata, .. }
+        | ClassRecord::Interned { metadata, .. } => Ok(metadata),
+        ClassRecord::Whole(bytes) => Ok(decode_whole(bytes)?.0),
+    }
+}
+
+impl MadaraBackend {
+    /// Decodes a class stored with any of the formats, reading its definition from [`Column::ClassDefinitions`]
+    /// when it is deduplicated. A corrupt class definition is reported as [`MadaraStorageError::CorruptClassBody`].
+    pub(crate) fn decode_class(&self, bytes: &[u8]) -> Result<(ClassMetadata, ClassInfo), MadaraStorageError> {
+        match ClassRecord::parse(bytes)? {
+            ClassRecord::Split { format, metadata, body } => Ok((metadata, decode_body(format, body)?)),
+            ClassRecord::Compressed { metadata, definition } => Ok((metadata, decode_definition(definition, true)?)),
+            ClassRecord::Interned { metadata, digest } => {
+                let col = self.db.get_column(Column::ClassDefinitions);
+                let definition = self.db.get_pinned_cf(&col, digest)?.ok_or_else(|| {
+                    MadaraStorageError::InconsistentStorage("Missing deduplicated class definition".into())
+                })?;
+                let class_info = match definition.split_first() {
+                    Some((&COMPRESSED_TAG, definition)) => decode_definition(definition, true)?,
+                    _ => decode_definition(&definition, false)?,
+                };
+                Ok((metadata, class_info))
+            }
+            ClassRecord::Whole(bytes) => decode_whole(bytes),
+        }
+    }
+}
+
+impl fmt::Display for ClassStorageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bincode => write!(f, "bincode"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for ClassStorageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bincode" => Ok(Self::Bincode),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("Unknown class storage format {s:?}, expected `bincode` or `json`")),
+        }
+    }
+}
+
+impl MadaraBackend {
+    /// Format of the classes stored from now on. Classes which are already stored are not converted.
+    pub fn set_class_storage_format(&self, format: ClassStorageFormat) -> anyhow::Result<()> {
+        self.class_storage_format
+            .set(format)
+            .map_err(|_| anyhow::anyhow!("A class storage format is already configured"))
+    }
+
+    pub fn class_storage_format(&self) -> ClassStorageFormat {
+        self.class_storage_format.get().copied().unwrap_or_default()
+    }
+
+    /// Stores identical class definitions only once from now on, see [`Column::ClassDefinitions`]. Classes which
+    /// are already stored are not deduplicated. This is off by default.
+    pub fn set_dedup_classes(&self, enabled: bool) -> anyhow::Result<()> {
+        self.dedup_classes.set(enabled).map_err(|_| anyhow::anyhow!("Class deduplication is already configured"))
+    }
+
+    pub fn dedup_classes(&self) -> bool {
+        self.dedup_classes.get().copied().unwrap_or_default()
+    }
+
+    /// Compresses the class definitions of at least `threshold` bytes from now on, and stores the smaller ones
+    /// uncompressed: compressing small classes costs more CPU than it saves space. The class columns are then no
+    /// longer compressed by rocksdb, for the files written from now on. 0 leaves the compression of every class to
+    /// rocksdb, which is the default.
+    pub fn set_class_compression_threshold(&self, threshold: usize) -> anyhow::Result<()> {
+        self.class_compression_threshold
+            .set(threshold)
+            .map_err(|_| anyhow::anyhow!("A class compression threshold is already configured"))?;
+        if threshold == 0 {
+            return Ok(());
+        }
+        let options = [("compression", "kNoCompression"), ("bottommost_compression", "kNoCompression")];
+        for column in [Column::ClassInfo, Column::ClassDefinitions] {
+            self.db
+                .set_options_cf(&self.db.get_column(column), &options)
+                .with_context(|| format!("Setting the compression options of column {column}"))?;
+        }
+        Ok(())
+    }
+
+    pub fn class_compression_threshold(&self) -> usize {
+        self.class_compression_threshold.get().copied().unwrap_or_default()
+    }
+}
@@ -1,10 +1,11 @@
 #![doc = include_str!("../docs/flat_storage.md")]
 
-use std::sync::Arc;
+use std::{ops::RangeInclusive, sync::Arc};
 
 use rayon::{iter::ParallelIterator, slice::ParallelSlice};
 use rocksdb::{BoundColumnFamily, IteratorMode, ReadOptions, WriteOptions};
 use serde::Serialize;
+use starknet_api::core::ChainId;
 use starknet_types_core::felt::Felt;
 
 use crate::{
@@ -19,7 +20,24 @@ pub(crate) const CONTRACT_NONCES_PREFIX_EXTRACTOR: usize = 32;
 
 const LAST_KEY: &[u8] = &[0xFF; 64];
 
-fn make_storage_key_prefix(contract_address: Felt, storage_key: Felt) -> [u8; 64] {
+/// The `0x1` address is reserved for block hashes: the storage of this contract maps block numbers to block hashes.
+/// See <https://docs.starknet.io/architecture-and-concepts/network-architecture/starknet-state/#address_0x1>.
+pub const BLOCK_HASH_CONTRACT_ADDRESS: Felt = Felt::ONE;
+
+/// Whether the hash of block `to_check` can be read from [`BLOCK_HASH_CONTRACT_ADDRESS`] by a contract executed in
+/// block `current_block`. The hashes of the last 10 blocks and of the blocks before v0.12.0 are not provided.
+pub fn block_hash_storage_check_range(chain_id: &ChainId, current_block: u64, to_check: u64) -> bool {
+    // Allowed range is first_v0_12_0_block..=(current_block - 10).
+    let first_block = if chain_id == &ChainId::Mainnet { 103_129 } else { 0 };
+
+    if let Some(end) = current_block.checked_sub(10) {
+        (first_block..=end).contains(&to_check)
+    } else {
+        false
+    }
+}
+
+pub(crate) fn make_storage_key_prefix(contract_address: Felt, storage_key: Felt) -> [u8; 64] {
     let mut key = [0u8; 64];
     key[..32].copy_from_slice(contract_address.to_bytes_be().as_ref());
     key[32..].copy_from_slice(storage_key.to_bytes_be().as_ref());
@@ -36,6 +54,8 @@ impl MadaraBackend {
         k: &K,
         make_bin_prefix: impl FnOnce(&K) -> B,
     ) -> Result<Option<V>, MadaraStorageError> {
+        self.storage_metrics.record_history_read();
+
         let Some(id) = id.resolve_db_block_id(self)? else { return Ok(None) };
 
         let block_n = match id {
@@ -83,14 +103,64 @@ impl MadaraBackend {
         }
     }
 
+    /// Batched version of [`Self::resolve_history_kv`]. The pending values are read with a single multi-get. The
+    /// history of each key which is not in the pending block is then resolved with a prefix iteration, as the block
+    /// its latest value was written at is not known in advance.
+    fn resolve_history_kvs<K: serde::Serialize, V: serde::de::DeserializeOwned, B: AsRef<[u8]>>(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        pending_col: Column,
+        nonpending_col: Column,
+        keys: &[K],
+        make_bin_prefix: impl Fn(&K) -> B,
+    ) -> Result<Vec<Option<V>>, MadaraStorageError> {
+        let mut values: Vec<Option<V>> = keys.iter().map(|_| None).collect();
+        let Some(id) = id.resolve_db_block_id(self)? else { return Ok(values) };
+
+        let block_n = match id {
+            DbBlockId::Pending => {
+                // Note: pending has keys in bincode, not bytes
+                let keys_encoded = keys.iter().map(bincode::serialize).collect::<Result<Vec<_>, _>>()?;
+                let col = self.db.get_column(pending_col);
+                for (value, res) in values.iter_mut().zip(self.db.batched_multi_get_cf(&col, &keys_encoded, false)) {
+                    if let Some(res) = res? {
+                        *value = Some(bincode::deserialize(&res)?);
+                    }
+                }
+
+                let Some(block_n) = self.get_latest_block_n()? else { return Ok(values) };
+                block_n
+            }
+            DbBlockId::Number(block_n) => block_n,
+        };
+
+        let id = DbBlockId::Number(block_n);
+        for (key, value) in keys.iter().zip(values.iter_mut()).filter(|(_, value)| value.is_none()) {
+            *value = self.resolve_history_kv(&id, pending_col, nonpending_col, key, &make_bin_prefix)?;
+        }
+        Ok(values)
+    }
+
+    /// Whether a contract is deployed at `contract_addr` at the given block. A contract with a zero class hash is not
+    /// deployed.
     #[tracing::instrument(skip(self, id), fields(module = "ContractDB"))]
     pub fn is_contract_deployed_at(
         &self,
         id: &impl DbBlockIdResolvable,
         contract_addr: &Felt,
     ) -> Result<bool, MadaraStorageError> {
-        // TODO(perf): use rocksdb key_may_exists bloom filters
-        Ok(self.get_contract_class_hash_at(id, contract_addr)?.is_some())
+        Ok(self.get_deployed_contract_class_hash_at(id, contract_addr)?.is_some())
+    }
+
+    /// Same as [`Self::get_contract_class_hash_at`], but a zero class hash is treated as the contract not being
+    /// deployed.
+    #[tracing::instrument(skip(self, id), fields(module = "ContractDB"))]
+    pub fn get_deployed_contract_class_hash_at(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        contract_addr: &Felt,
+    ) -> Result<Option<Felt>, MadaraStorageError> {
+        Ok(self.get_contract_class_hash_at(id, contract_addr)?.filter(|class_hash| *class_hash != Felt::ZERO))
     }
 
     #[tracing::instrument(skip(self, id, contract_addr), fields(module = "ContractDB"))]
@@ -99,22 +169,54 @@ impl MadaraBackend {
         id: &impl DbBlockIdResolvable,
         contract_addr: &Felt,
     ) -> Result<Option<Felt>, MadaraStorageError> {
-        self.resolve_history_kv(
+        self.storage_metrics.contract_class_hash.record(|| {
+            self.resolve_history_kv(
+                id,
+                Column::PendingContractToClassHashes,
+                Column::ContractToClassHashes,
+                contract_addr,
+                |k| k.to_bytes_be(),
+            )
+        })
+    }
+
+    #[tracing::instrument(skip(self, id), fields(module = "ContractDB"))]
+    pub fn get_contract_nonce_at(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        contract_addr: &Felt,
+    ) -> Result<Option<Felt>, MadaraStorageError> {
+        self.storage_metrics.contract_nonces.record(|| {
+            self.resolve_history_kv(id, Column::PendingContractToNonces, Column::ContractToNonces, contract_addr, |k| {
+                k.to_bytes_be()
+            })
+        })
+    }
+
+    /// Batched version of [`Self::get_contract_class_hash_at`], see [`Self::resolve_history_kvs`].
+    #[tracing::instrument(skip(self, id, contract_addrs), fields(module = "ContractDB"))]
+    pub fn get_contract_class_hashes_at(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        contract_addrs: &[Felt],
+    ) -> Result<Vec<Option<Felt>>, MadaraStorageError> {
+        self.resolve_history_kvs(
             id,
             Column::PendingContractToClassHashes,
             Column::ContractToClassHashes,
-            contract_addr,
+            contract_addrs,
             |k| k.to_bytes_be(),
         )
     }
 
-    #[tracing::instrument(skip(self, id), fields(module = "ContractDB"))]
-    pub fn get_contract_nonce_at(
+    /// Batched version of [`Self::get_contract_nonce_at`], see [`Self::resolve_history_kvs`].
+    #[tracing::instrument(skip(self, id, contract_addrs), fields(module = "ContractDB"))]
+    pub fn get_contract_nonces_at(
         &self,
         id: &impl DbBlockIdResolvable,
-        contract_addr: &Felt,
-    ) -> Result<Option<Felt>, MadaraStorageError> {
-        self.resolve_history_kv(id, Column::PendingContractToNonces, Column::ContractToNonces, contract_addr, |k| {
+        contract_addrs: &[Felt],
+    ) -> Result<Vec<Option<Felt>>, MadaraStorageError> {
+        self.resolve_history_kvs(id, Column::PendingContractToNonces, Column::ContractToNonces, contract_addrs, |k| {
             k.to_bytes_be()
         })
     }
@@ -126,13 +228,113 @@ impl MadaraBackend {
         contract_addr: &Felt,
         key: &Felt,
     ) -> Result<Option<Felt>, MadaraStorageError> {
-        self.resolve_history_kv(
-            id,
-            Column::PendingContractStorage,
-            Column::ContractStorage,
-            &(*contract_addr, *key),
-            |(k1, k2)| make_storage_key_prefix(*k1, *k2),
-        )
+        self.storage_metrics.contract_storage.record(|| {
+            if !self.storage_bloom_check(contract_addr, key) {
+                return Ok(None);
+            }
+            self.resolve_history_kv(
+                id,
+                Column::PendingContractStorage,
+                Column::ContractStorage,
+                &(*contract_addr, *key),
+                |(k1, k2)| make_storage_key_prefix(*k1, *k2),
+            )
+        })
+    }
+
+    /// Storage value at the highest stored block at or before `requested_block_n`, along with the block it was read
+    /// at. This is for clients which accept an older state when the block they ask for is not available on this
+    /// node: a block above the latest block, such as on a database restored from an older snapshot, or a block which
+    /// was removed from the database. The missing blocks are walked down one by one.
+    ///
+    /// Fails with [`MadaraStorageError::NoBlockAtOrBefore`] when no block is stored at or before `requested_block_n`.
+    #[tracing::instrument(skip(self, key), fields(module = "ContractDB"))]
+    pub fn get_contract_storage_at_or_before(
+        &self,
+        requested_block_n: u64,
+        contract_addr: &Felt,
+        key: &Felt,
+    ) -> Result<(u64, Option<Felt>), MadaraStorageError> {
+        let latest_block_n =
+            self.get_latest_block_n()?.ok_or(MadaraStorageError::NoBlockAtOrBefore(requested_block_n))?;
+        let mut block_n = requested_block_n.min(latest_block_n);
+        while !self.contains_block(&DbBlockId::Number(block_n))? {
+            block_n = block_n.checked_sub(1).ok_or(MadaraStorageError::NoBlockAtOrBefore(requested_block_n))?;
+        }
+        Ok((block_n, self.get_contract_storage_at(&DbBlockId::Number(block_n), contract_addr, key)?))
+    }
+
+    /// Hash of block `requested_block_n` as read from [`BLOCK_HASH_CONTRACT_ADDRESS`] by a contract executed in block
+    /// `current_block_n`, on top of the state at `id`. Returns `None` when this hash is not provided to contracts
+    /// (see [`block_hash_storage_check_range`]), or is not stored.
+    #[tracing::instrument(skip(self, id), fields(module = "ContractDB"))]
+    pub fn get_block_hash_storage_at(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        current_block_n: u64,
+        requested_block_n: u64,
+    ) -> Result<Option<Felt>, MadaraStorageError> {
+        if !block_hash_storage_check_range(&self.chain_config().chain_id, current_block_n, requested_block_n) {
+            return Ok(None);
+        }
+        self.get_contract_storage_at(id, &BLOCK_HASH_CONTRACT_ADDRESS, &Felt::from(requested_block_n))
+    }
+
+    /// Storage slots of `contract_addr` which were written to in exactly this block, with their new values. This
+    /// is read from the state diff of the block, which is much cheaper than comparing the storage of the contract
+    /// before and after the block. Returns `None` when the block does not exist.
+    #[tracing::instrument(skip(self, id), fields(module = "ContractDB"))]
+    pub fn get_contract_storage_modified_in_block(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        contract_addr: &Felt,
+    ) -> Result<Option<Vec<(Felt, Felt)>>, MadaraStorageError> {
+        let Some(state_diff) = self.get_block_state_diff(id)? else { return Ok(None) };
+        Ok(Some(
+            state_diff
+                .storage_diffs
+                .into_iter()
+                .filter(|diff| &diff.address == contract_addr)
+                .flat_map(|diff| diff.storage_entries.into_iter().map(|entry| (entry.key, entry.value)))
+                .collect(),
+        ))
+    }
+
+    /// Every storage write of the blocks in `blocks`, as `(block_n, contract_address, key, value)`, in block order
+    /// and then in state diff order. This is meant for indexers which mirror the storage into an external database.
+    ///
+    /// The state diffs are read lazily, one block at a time. The stream ends at the first block of the range which
+    /// does not exist, and after the first error. Pending state is not included.
+    pub fn stream_storage_changes(
+        &self,
+        blocks: RangeInclusive<u64>,
+    ) -> impl Iterator<Item = Result<(u64, Felt, Felt, Felt), MadaraStorageError>> + '_ {
+        let mut failed = false;
+        blocks
+            .map_while(move |block_n| {
+                if failed {
+                    return None;
+                }
+                match self.get_block_state_diff(&DbBlockId::Number(block_n)) {
+                    Ok(Some(state_diff)) => Some(
+                        state_diff
+                            .storage_diffs
+                            .into_iter()
+                            .flat_map(|diff| {
+                                diff.storage_entries
+                                    .into_iter()
+                                    .map(move |entry| Ok((block_n, diff.address, entry.key, entry.value)))
+                            })
+                            .collect::<Vec<_>>(),
+                    ),
+                    Ok(None) => None,
+                    Err(err) => {
+                        failed = true;
+                        Some(vec![Err(err)])
+                    }
+                }
+            })
+            .flatten()
     }
 
     /// NB: This functions needs to run on the rayon thread pool
@@ -181,6 +383,7 @@ impl MadaraBackend {
                 write_chunk(&self.db, &writeopts, col, block_number, chunk.iter().map(|(k, v)| (k.to_bytes_be(), *v)))
             },
         )?;
+        self.storage_bloom_insert(contract_kv_updates.iter().map(|(k, _)| k));
         contract_kv_updates.par_chunks(DB_UPDATES_BATCH_SIZE).try_for_each_init(
             || self.db.get_column(Column::ContractStorage),
             |col, chunk| {
@@ -241,6 +444,7 @@ impl MadaraBackend {
             || self.db.get_column(Column::PendingContractToNonces),
             |col, chunk| write_chunk(&self.db, &writeopts, col, chunk.iter().map(|(k, v)| (k, *v))),
         )?;
+        self.storage_bloom_insert(contract_kv_updates.iter().map(|(k, _)| k));
         contract_kv_updates.par_chunks(DB_UPDATES_BATCH_SIZE).try_for_each_init(
             || self.db.get_column(Column::PendingContractStorage),
             |col, chunk| write_chunk(&self.db, &writeopts, col, chunk.iter().map(|((k1, k2), v)| ((k1, k2), *v))),
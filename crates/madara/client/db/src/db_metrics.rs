@@ -1,4 +1,4 @@
-use crate::{Column, DatabaseExt, DB};
+use crate::{Column, DatabaseExt, StorageMetrics, DB};
 use anyhow::Context as _;
 use mc_analytics::register_gauge_metric_instrument;
 use opentelemetry::global::Error;
@@ -13,6 +13,11 @@ pub struct DbMetrics {
     pub mem_table_unflushed: Gauge<u64>,
     pub mem_table_readers_total: Gauge<u64>,
     pub cache_total: Gauge<u64>,
+    pub column_reads: Gauge<u64>,
+    pub history_reads: Gauge<u64>,
+    pub class_reads: Gauge<u64>,
+    #[cfg(feature = "storage-read-latency")]
+    pub column_read_latency: Gauge<u64>,
 }
 
 impl DbMetrics {
@@ -69,10 +74,51 @@ impl DbMetrics {
             "".to_string(),
         );
 
-        Ok(Self { db_size, column_sizes, mem_table_total, mem_table_unflushed, mem_table_readers_total, cache_total })
+        let column_reads = register_gauge_metric_instrument(
+            &rpc_meter,
+            "db_column_reads".to_string(),
+            "Reads of the contract and class columns since the node started".to_string(),
+            "".to_string(),
+        );
+
+        let history_reads = register_gauge_metric_instrument(
+            &rpc_meter,
+            "db_history_reads".to_string(),
+            "Database reads of the contract histories since the node started".to_string(),
+            "".to_string(),
+        );
+
+        let class_reads = register_gauge_metric_instrument(
+            &rpc_meter,
+            "db_class_reads".to_string(),
+            "Class reads from the database since the node started, without the class cache hits".to_string(),
+            "".to_string(),
+        );
+
+        #[cfg(feature = "storage-read-latency")]
+        let column_read_latency = register_gauge_metric_instrument(
+            &rpc_meter,
+            "db_column_read_latency".to_string(),
+            "Reads of the contract and class columns per latency bucket since the node started".to_string(),
+            "".to_string(),
+        );
+
+        Ok(Self {
+            db_size,
+            column_sizes,
+            mem_table_total,
+            mem_table_unflushed,
+            mem_table_readers_total,
+            cache_total,
+            column_reads,
+            history_reads,
+            class_reads,
+            #[cfg(feature = "storage-read-latency")]
+            column_read_latency,
+        })
     }
 
-    pub fn try_update(&self, db: &DB) -> anyhow::Result<u64> {
+    pub fn try_update(&self, db: &DB, storage_metrics: &StorageMetrics) -> anyhow::Result<u64> {
         let mut storage_size = 0;
 
         for &column in Column::ALL.iter() {
@@ -94,12 +140,31 @@ impl DbMetrics {
         self.mem_table_readers_total.record(mem_usage.approximate_mem_table_readers_total(), &[]);
         self.cache_total.record(mem_usage.approximate_cache_total(), &[]);
 
+        for (column, metrics) in storage_metrics.columns() {
+            self.column_reads
+                .record(metrics.hits(), &[KeyValue::new("column", column), KeyValue::new("result", "hit")]);
+            self.column_reads
+                .record(metrics.misses(), &[KeyValue::new("column", column), KeyValue::new("result", "miss")]);
+            #[cfg(feature = "storage-read-latency")]
+            for (reads, bound) in metrics.latency_histogram().into_iter().zip(
+                crate::storage_metrics::READ_LATENCY_BUCKETS_MICROS
+                    .iter()
+                    .map(|bound| bound.to_string())
+                    .chain(["+Inf".into()]),
+            ) {
+                self.column_read_latency
+                    .record(reads, &[KeyValue::new("column", column), KeyValue::new("le_micros", bound)]);
+            }
+        }
+        self.history_reads.record(storage_metrics.history_reads(), &[]);
+        self.class_reads.record(storage_metrics.class_reads(), &[]);
+
         Ok(storage_size)
     }
 
     /// Returns the total storage size
-    pub fn update(&self, db: &DB) -> u64 {
-        match self.try_update(db) {
+    pub fn update(&self, db: &DB, storage_metrics: &StorageMetrics) -> u64 {
+        match self.try_update(db, storage_metrics) {
             Ok(res) => res,
             Err(err) => {
                 tracing::warn!("Error updating db metrics: {err:#}");
@@ -0,0 +1,80 @@
+//! Number of classes, contracts and storage slots tracked by the node, for capacity planning.
+
+use rocksdb::{properties, IteratorMode, ReadOptions};
+
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError};
+
+/// How entries are counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CountMode {
+    /// RocksDB estimates, which are cheap to get but count every historical value of a contract or storage slot
+    /// as a separate entry. Use this for large databases.
+    #[default]
+    Estimate,
+    /// Iterate over the whole column to count distinct entries. This reads the entire column: only use this on
+    /// small databases.
+    Exact,
+}
+
+/// Number of entries tracked in the database. Pending state is not counted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct EntryCounts {
+    /// Declared classes.
+    pub classes: u64,
+    /// Deployed contracts.
+    pub contracts: u64,
+    /// Contract storage slots which have been written to.
+    pub storage_slots: u64,
+}
+
+impl MadaraBackend {
+    fn count_entries(&self, column: Column, prefix_len: usize, mode: CountMode) -> Result<u64, MadaraStorageError> {
+        let col = self.db.get_column(column);
+        if mode == CountMode::Estimate {
+            return Ok(self.db.property_int_value_cf(&col, properties::ESTIMATE_NUM_KEYS)?.unwrap_or(0));
+        }
+
+        let mut options = ReadOptions::default();
+        // The prefix extractors of the history columns would otherwise limit the iteration to a single prefix.
+        options.set_total_order_seek(true);
+
+        // Keys are sorted, so all the historical values of an entry are next to each other.
+        let mut count = 0;
+        let mut previous: Option<Box<[u8]>> = None;
+        for res in self.db.iterator_cf_opt(&col, options, IteratorMode::Start) {
+            let (key, _) = res?;
+            let entry = key.get(..prefix_len).unwrap_or(&key);
+            if previous.as_deref() != Some(entry) {
+                count += 1;
+                previous = Some(entry.into());
+            }
+        }
+        Ok(count)
+    }
+
+    #[tracing::instrument(skip(self), fields(module = "EntryCounts"))]
+    pub fn count_classes(&self, mode: CountMode) -> Result<u64, MadaraStorageError> {
+        self.count_entries(Column::ClassInfo, usize::MAX, mode)
+    }
+
+    /// Keys of the contract class hash history are `<contract> || <block_n>`.
+    #[tracing::instrument(skip(self), fields(module = "EntryCounts"))]
+    pub fn count_contracts(&self, mode: CountMode) -> Result<u64, MadaraStorageError> {
+        self.count_entries(Column::ContractToClassHashes, 32, mode)
+    }
+
+    /// Keys of the contract storage history are `<contract> || <key> || <block_n>`.
+    #[tracing::instrument(skip(self), fields(module = "EntryCounts"))]
+    pub fn count_storage_slots(&self, mode: CountMode) -> Result<u64, MadaraStorageError> {
+        self.count_entries(Column::ContractStorage, 64, mode)
+    }
+
+    #[tracing::instrument(skip(self), fields(module = "EntryCounts"))]
+    pub fn entry_counts(&self, mode: CountMode) -> Result<EntryCounts, MadaraStorageError> {
+        Ok(EntryCounts {
+            classes: self.count_classes(mode)?,
+            contracts: self.count_contracts(mode)?,
+            storage_slots: self.count_storage_slots(mode)?,
+        })
+    }
+}
@@ -0,0 +1,100 @@
+//! Index of the entry point selectors of every declared class, to answer whether a contract exposes an entry point
+//! without loading and parsing its whole class definition.
+//!
+//! Keys are `class_hash ++ selector` (both big-endian), values are empty. Classes in the pending block are not
+//! indexed, and neither are classes stored before the index existed: for those, the selectors are read from the
+//! class definition instead.
+
+use mp_class::{ClassInfo, ConvertedClass};
+use rocksdb::{Direction, IteratorMode};
+use starknet_types_core::felt::Felt;
+
+use crate::db_block_id::DbBlockIdResolvable;
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError, WriteBatchWithTransaction};
+
+/// Sorted and deduplicated entry point selectors of a class.
+pub(crate) fn class_selectors(class_info: &ClassInfo) -> Vec<Felt> {
+    let mut selectors: Vec<Felt> = match class_info {
+        ClassInfo::Sierra(info) => {
+            let entry_points = &info.contract_class.entry_points_by_type;
+            [&entry_points.constructor, &entry_points.external, &entry_points.l1_handler]
+                .into_iter()
+                .flatten()
+                .map(|entry_point| entry_point.selector)
+                .collect()
+        }
+        ClassInfo::Legacy(info) => {
+            let entry_points = &info.contract_class.entry_points_by_type;
+            [&entry_points.constructor, &entry_points.external, &entry_points.l1_handler]
+                .into_iter()
+                .flatten()
+                .map(|entry_point| entry_point.selector)
+                .collect()
+        }
+    };
+    selectors.sort_unstable();
+    selectors.dedup();
+    selectors
+}
+
+pub(crate) fn make_key(class_hash: &Felt, selector: &Felt) -> [u8; 64] {
+    let mut key = [0u8; 64];
+    key[..32].copy_from_slice(&class_hash.to_bytes_be());
+    key[32..].copy_from_slice(&selector.to_bytes_be());
+    key
+}
+
+impl MadaraBackend {
+    #[tracing::instrument(skip(self, converted_classes), fields(module = "ClassDB"))]
+    pub(crate) fn entrypoint_index_store(
+        &self,
+        converted_classes: &[ConvertedClass],
+    ) -> Result<(), MadaraStorageError> {
+        let col = self.db.get_column(Column::ClassToSelectors);
+        let mut batch = WriteBatchWithTransaction::default();
+        for converted_class in converted_classes {
+            let class_hash = converted_class.class_hash();
+            for selector in class_selectors(&converted_class.info()) {
+                batch.put_cf(&col, make_key(&class_hash, &selector), []);
+            }
+        }
+        self.db.write_opt(batch, &self.write_opt_no_wal)?;
+        Ok(())
+    }
+
+    fn is_class_indexed(&self, class_hash: &Felt) -> Result<bool, MadaraStorageError> {
+        let col = self.db.get_column(Column::ClassToSelectors);
+        let prefix = class_hash.to_bytes_be();
+        match self.db.iterator_cf(&col, IteratorMode::From(&prefix, Direction::Forward)).next() {
+            Some(kv) => Ok(kv?.0.starts_with(&prefix)),
+            None => Ok(false),
+        }
+    }
+
+    /// Returns whether the contract deployed at `contract_address` at block `id` has an entry point with this
+    /// selector. This is `false` when no contract is deployed at this address.
+    #[tracing::instrument(skip(self, id), fields(module = "ClassDB"))]
+    pub fn contract_has_entrypoint(
+        &self,
+        id: &impl DbBlockIdResolvable,
+        contract_address: &Felt,
+        selector: &Felt,
+    ) -> Result<bool, MadaraStorageError> {
+        let Some(class_hash) = self.get_contract_class_hash_at(id, contract_address)? else { return Ok(false) };
+
+        let col = self.db.get_column(Column::ClassToSelectors);
+        if self.db.get_pinned_cf(&col, make_key(&class_hash, selector))?.is_some() {
+            return Ok(true);
+        }
+        if self.is_class_indexed(&class_hash)? {
+            return Ok(false);
+        }
+
+        let Some(class_info) = self.get_class_info(id, &class_hash)? else {
+            return Err(MadaraStorageError::InconsistentStorage(
+                format!("Class {class_hash:#x} of contract {contract_address:#x} not found").into(),
+            ));
+        };
+        Ok(class_selectors(&class_info).binary_search(selector).is_ok())
+    }
+}
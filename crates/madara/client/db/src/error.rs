@@ -11,10 +11,16 @@ pub enum MadaraStorageError {
     RocksDB(#[from] rocksdb::Error),
     #[error("Bincode error: {0}")]
     Bincode(#[from] bincode::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
     #[error("Failed to compile class: {0}")]
     CompilationClassError(String),
     #[error("Invalid block number")]
     InvalidBlockNumber,
+    #[error("No block is stored at or before block {0}")]
+    NoBlockAtOrBefore(u64),
     #[error("Invalid nonce")]
     InvalidNonce,
     #[error("Chain info is missing from the database")]
@@ -27,6 +33,10 @@ pub enum MadaraStorageError {
         "Missing compiled class for class with hash {class_hash:#x} (compiled_class_hash={compiled_class_hash:#x}"
     )]
     MissingCompiledClass { class_hash: Felt, compiled_class_hash: Felt },
+    #[error("Corrupt class definition: {0}")]
+    CorruptClassBody(String),
+    #[error("Class archive error: {0:#}")]
+    ClassArchive(anyhow::Error),
 }
 
 pub type BonsaiStorageError = bonsai_trie::BonsaiStorageError<DbError>;
@@ -0,0 +1,112 @@
+//! Rebuilding of the secondary indexes of the database from the class definitions, for when an index gets out of
+//! sync with the classes it indexes.
+
+use rocksdb::{Direction, IteratorMode};
+use starknet_types_core::felt::Felt;
+
+use crate::entrypoint_index::class_selectors;
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError, WriteBatchWithTransaction};
+
+/// Number of classes indexed per write batch. The progress of a rebuild is saved along with every batch.
+const REBUILD_INDEX_BATCH_SIZE: usize = 1024;
+
+/// A secondary index which can be rebuilt with [`MadaraBackend::rebuild_index`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexKind {
+    /// Class hash => entry point selectors, see [`crate::entrypoint_index`].
+    ClassToSelectors,
+    /// Entry point selector => class hashes, see [`crate::class_selector_index`].
+    #[cfg(feature = "class-selector-index")]
+    SelectorToClassHashes,
+}
+
+impl IndexKind {
+    fn column(self) -> Column {
+        match self {
+            Self::ClassToSelectors => Column::ClassToSelectors,
+            #[cfg(feature = "class-selector-index")]
+            Self::SelectorToClassHashes => Column::SelectorToClassHashes,
+        }
+    }
+
+    /// Row of the [`Column::BlockStorageMeta`] column holding the key of the last class indexed by a rebuild
+    /// in progress.
+    pub(crate) fn progress_row(self) -> &'static [u8] {
+        match self {
+            Self::ClassToSelectors => b"rebuild_index_class_to_selectors",
+            #[cfg(feature = "class-selector-index")]
+            Self::SelectorToClassHashes => b"rebuild_index_selector_to_class_hashes",
+        }
+    }
+
+    fn make_key(self, class_hash: &Felt, selector: &Felt) -> [u8; 64] {
+        match self {
+            Self::ClassToSelectors => crate::entrypoint_index::make_key(class_hash, selector),
+            #[cfg(feature = "class-selector-index")]
+            Self::SelectorToClassHashes => crate::class_selector_index::make_key(selector, class_hash),
+        }
+    }
+}
+
+impl MadaraBackend {
+    /// Rebuilds a secondary index from the classes stored in the database, and returns the number of classes
+    /// indexed by this call. Classes in the pending block are not indexed, like when storing blocks.
+    ///
+    /// The rebuild is resumable: its progress is saved with every batch of classes, and calling this again after
+    /// an interruption continues after the last class indexed. The index is cleared when a rebuild starts, so it is
+    /// incomplete until the rebuild finishes.
+    #[tracing::instrument(skip(self), fields(module = "ClassDB"))]
+    pub fn rebuild_index(&self, kind: IndexKind) -> Result<usize, MadaraStorageError> {
+        let col_meta = self.db.get_column(Column::BlockStorageMeta);
+        let col_index = self.db.get_column(kind.column());
+        let col_info = self.db.get_column(Column::ClassInfo);
+
+        let resume_after: Option<Vec<u8>> = match self.db.get_pinned_cf(&col_meta, kind.progress_row())? {
+            Some(progress) => bincode::deserialize(&progress)?,
+            None => {
+                // Index keys are 64 bytes long, so they are all in this range.
+                let mut batch = WriteBatchWithTransaction::default();
+                batch.delete_range_cf(&col_index, [0u8; 64], [0xffu8; 65]);
+                batch.put_cf(&col_meta, kind.progress_row(), bincode::serialize(&None::<Vec<u8>>)?);
+                self.db.write(batch)?;
+                None
+            }
+        };
+        if let Some(key) = &resume_after {
+            tracing::info!(
+                "Resuming the rebuild of the {kind:?} index after class {:#x}",
+                bincode::deserialize::<Felt>(key)?
+            );
+        }
+
+        let mode = match &resume_after {
+            Some(key) => IteratorMode::From(key, Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        let mut indexed = 0;
+        let mut batch = WriteBatchWithTransaction::default();
+        for res in self.db.iterator_cf(&col_info, mode) {
+            let (key, value) = res?;
+            if resume_after.as_deref() == Some(&*key) {
+                continue;
+            }
+            let class_hash: Felt = bincode::deserialize(&key)?;
+            let (_, class_info) = self.decode_class(&value)?;
+            for selector in class_selectors(&class_info) {
+                batch.put_cf(&col_index, kind.make_key(&class_hash, &selector), []);
+            }
+
+            indexed += 1;
+            if indexed % REBUILD_INDEX_BATCH_SIZE == 0 {
+                batch.put_cf(&col_meta, kind.progress_row(), bincode::serialize(&Some(key.to_vec()))?);
+                self.db.write(std::mem::take(&mut batch))?;
+                tracing::debug!("Rebuilding the {kind:?} index: {indexed} classes indexed");
+            }
+        }
+
+        batch.delete_cf(&col_meta, kind.progress_row());
+        self.db.write(batch)?;
+        tracing::info!("Rebuilt the {kind:?} index: {indexed} classes indexed");
+        Ok(indexed)
+    }
+}
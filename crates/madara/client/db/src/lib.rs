@@ -16,8 +16,9 @@ use rocksdb_options::rocksdb_global_options;
 use snapshots::Snapshots;
 use starknet_types_core::felt::Felt;
 use starknet_types_core::hash::{Pedersen, Poseidon, StarkHash};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::{fmt, fs};
 use tokio::sync::{mpsc, oneshot};
 
@@ -29,19 +30,45 @@ mod snapshots;
 
 pub mod block_db;
 pub mod bonsai_db;
+pub mod class_archive;
+pub mod class_cache;
 pub mod class_db;
+pub mod class_declared;
+pub mod class_label_db;
+#[cfg(feature = "class-selector-index")]
+pub mod class_selector_index;
+pub mod class_storage_format;
 pub mod contract_db;
 pub mod db_block_id;
 pub mod db_metrics;
 pub mod devnet_db;
+pub mod entry_counts;
+pub mod entrypoint_index;
+pub mod index_rebuild;
 pub mod l1_db;
 pub mod mempool_db;
+pub mod storage_bloom;
+pub mod storage_export;
+pub mod storage_metrics;
 pub mod storage_updates;
+pub mod storage_usage;
+pub mod sync_tip_check;
 pub mod tests;
 
 pub use bonsai_db::GlobalTrie;
 pub use bonsai_trie::{id::BasicId, MultiProof, ProofNode};
+pub use class_archive::{ArchivedClass, ClassArchive, DirClassArchive};
+pub use class_cache::{CasmCache, ClassCache};
+pub use class_db::{ClassDeclaration, ClassVerificationIssue};
+pub use class_declared::DeclaredClass;
+pub use class_storage_format::{ClassMetadata, ClassStorageFormat};
+pub use entry_counts::{CountMode, EntryCounts};
 pub use error::{BonsaiStorageError, MadaraStorageError, TrieType};
+pub use index_rebuild::IndexKind;
+pub use storage_bloom::StorageBloomFilter;
+pub use storage_metrics::{ColumnReadMetrics, StorageMetrics};
+pub use storage_usage::StorageUsage;
+pub use sync_tip_check::SyncTipCheck;
 pub type DB = DBWithThreadMode<MultiThreaded>;
 pub use rocksdb;
 pub type WriteBatchWithTransaction = rocksdb::WriteBatchWithTransaction<false>;
@@ -116,6 +143,14 @@ pub enum Column {
     ClassCompiled,
     PendingClassInfo,
     PendingClassCompiled,
+    /// Compiled class hash to the version of the compiler that produced the compiled class
+    ClassCompilerVersion,
+    /// Entry point selector ++ class hash => (), only filled with the `class-selector-index` feature
+    SelectorToClassHashes,
+    /// Class hash ++ entry point selector => ()
+    ClassToSelectors,
+    /// Content digest => class definition, only filled when class deduplication is enabled
+    ClassDefinitions,
 
     // History of contract class hashes
     // contract_address history block_number => class_hash
@@ -154,6 +189,11 @@ pub enum Column {
     Devnet,
 
     MempoolTransactions,
+
+    /// Class hash => operator-provided human-readable label
+    ClassLabels,
+    /// Class hash => top-level fields of the class definition unknown to this node, as JSON
+    ClassUnknownFields,
 }
 
 impl fmt::Debug for Column {
@@ -182,6 +222,10 @@ impl Column {
             ClassCompiled,
             PendingClassInfo,
             PendingClassCompiled,
+            ClassCompilerVersion,
+            SelectorToClassHashes,
+            ClassToSelectors,
+            ClassDefinitions,
             ContractToClassHashes,
             ContractToNonces,
             ContractStorage,
@@ -201,6 +245,8 @@ impl Column {
             PendingContractStorage,
             Devnet,
             MempoolTransactions,
+            ClassLabels,
+            ClassUnknownFields,
         ]
     };
     pub const NUM_COLUMNS: usize = Self::ALL.len();
@@ -227,6 +273,10 @@ impl Column {
             ClassCompiled => "class_compiled",
             PendingClassInfo => "pending_class_info",
             PendingClassCompiled => "pending_class_compiled",
+            ClassCompilerVersion => "class_compiler_version",
+            SelectorToClassHashes => "selector_to_class_hashes",
+            ClassToSelectors => "class_to_selectors",
+            ClassDefinitions => "class_definitions",
             ContractToClassHashes => "contract_to_class_hashes",
             ContractToNonces => "contract_to_nonces",
             ContractStorage => "contract_storage",
@@ -237,6 +287,8 @@ impl Column {
             PendingContractStorage => "pending_contract_storage",
             Devnet => "devnet",
             MempoolTransactions => "mempool_transactions",
+            ClassLabels => "class_labels",
+            ClassUnknownFields => "class_unknown_fields",
         }
     }
 }
@@ -419,7 +471,19 @@ pub struct MadaraBackend {
     trie_log_config: TrieLogConfig,
     sender_block_info: tokio::sync::broadcast::Sender<mp_block::MadaraBlockInfo>,
     sender_event: EventChannels,
+    sender_class_declared: tokio::sync::broadcast::Sender<DeclaredClass>,
     write_opt_no_wal: WriteOptions,
+    class_archive: OnceLock<Arc<dyn ClassArchive>>,
+    storage_bloom_filter: OnceLock<StorageBloomFilter>,
+    class_storage_format: OnceLock<ClassStorageFormat>,
+    dedup_classes: OnceLock<bool>,
+    class_compression_threshold: OnceLock<usize>,
+    class_cache: ClassCache,
+    casm_cache: OnceLock<CasmCache>,
+    /// Compiled class hashes whose stored compiled class is known to come from the current compiler, so that its
+    /// compiler version is not read again, see [`MadaraBackend::get_or_compile_sierra_compiled`].
+    current_compiled_classes: Mutex<HashSet<Felt>>,
+    storage_metrics: StorageMetrics,
     #[cfg(any(test, feature = "testing"))]
     _temp_dir: Option<tempfile::TempDir>,
 }
@@ -432,6 +496,12 @@ impl fmt::Debug for MadaraBackend {
             .field("chain_config", &self.chain_config)
             .field("db_metrics", &self.db_metrics)
             .field("sender_block_info", &self.sender_block_info)
+            .field("class_archive", &self.class_archive)
+            .field("storage_bloom_filter", &self.storage_bloom_filter)
+            .field("class_storage_format", &self.class_storage_format)
+            .field("dedup_classes", &self.dedup_classes)
+            .field("class_compression_threshold", &self.class_compression_threshold)
+            .field("storage_metrics", &self.storage_metrics)
             .finish()
     }
 }
@@ -502,6 +572,9 @@ struct BackupRequest {
 impl Drop for MadaraBackend {
     fn drop(&mut self) {
         tracing::info!("⏳ Gracefully closing the database...");
+        if let Err(err) = self.save_hot_classes() {
+            tracing::warn!("Failed to save the most read classes: {err:#}");
+        }
         self.flush().expect("Error when flushing the database"); // flush :)
     }
 }
@@ -525,7 +598,17 @@ impl MadaraBackend {
             trie_log_config: Default::default(),
             sender_block_info: tokio::sync::broadcast::channel(100).0,
             sender_event: EventChannels::new(100),
+            sender_class_declared: tokio::sync::broadcast::channel(class_declared::CLASS_DECLARED_QUEUE_CAPACITY).0,
             write_opt_no_wal: make_write_opt_no_wal(),
+            class_archive: OnceLock::new(),
+            storage_bloom_filter: OnceLock::new(),
+            class_storage_format: OnceLock::new(),
+            dedup_classes: OnceLock::new(),
+            class_compression_threshold: OnceLock::new(),
+            class_cache: ClassCache::default(),
+            casm_cache: OnceLock::new(),
+            current_compiled_classes: Mutex::default(),
+            storage_metrics: StorageMetrics::default(),
             _temp_dir: Some(temp_dir),
         })
     }
@@ -585,7 +668,17 @@ impl MadaraBackend {
             trie_log_config,
             sender_block_info: tokio::sync::broadcast::channel(100).0,
             sender_event: EventChannels::new(100),
+            sender_class_declared: tokio::sync::broadcast::channel(class_declared::CLASS_DECLARED_QUEUE_CAPACITY).0,
             write_opt_no_wal: make_write_opt_no_wal(),
+            class_archive: OnceLock::new(),
+            storage_bloom_filter: OnceLock::new(),
+            class_storage_format: OnceLock::new(),
+            dedup_classes: OnceLock::new(),
+            class_compression_threshold: OnceLock::new(),
+            class_cache: ClassCache::default(),
+            casm_cache: OnceLock::new(),
+            current_compiled_classes: Mutex::default(),
+            storage_metrics: StorageMetrics::default(),
             #[cfg(any(test, feature = "testing"))]
             _temp_dir: None,
         });
@@ -665,7 +758,12 @@ impl MadaraBackend {
 
     /// Returns the total storage size
     pub fn update_metrics(&self) -> u64 {
-        self.db_metrics.update(&self.db)
+        self.db_metrics.update(&self.db, &self.storage_metrics)
+    }
+
+    /// Read counters of the contract and class columns, see [`StorageMetrics`].
+    pub fn storage_metrics(&self) -> &StorageMetrics {
+        &self.storage_metrics
     }
 }
 
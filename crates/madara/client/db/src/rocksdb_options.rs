@@ -3,12 +3,16 @@
 
 use crate::{contract_db, Column};
 use anyhow::{Context, Result};
-use rocksdb::{DBCompressionType, Env, Options, SliceTransform};
+use rocksdb::{BlockBasedOptions, DBCompressionType, Env, Options, SliceTransform};
 
 const KiB: usize = 1024;
 const MiB: usize = 1024 * KiB;
 const GiB: usize = 1024 * MiB;
 
+/// Zstd level of the class definitions in the bottommost level of the database. Higher levels barely shrink classes
+/// further and make compactions of the class columns much slower.
+const CLASS_BOTTOMMOST_ZSTD_LEVEL: i32 = 6;
+
 pub fn rocksdb_global_options() -> Result<Options> {
     let mut options = Options::default();
     options.create_if_missing(true);
@@ -58,8 +62,23 @@ impl Column {
             }
             _ => {}
         }
+        if let Column::ContractStorage | Column::ContractToClassHashes | Column::ContractToNonces = self {
+            // History reads are prefix seeks. A bloom filter of the key prefixes lets rocksdb skip the files and
+            // memtables which have no value for a key, such as a storage slot which has never been written to.
+            let mut block_based_options = BlockBasedOptions::default();
+            block_based_options.set_bloom_filter(10.0, false);
+            block_based_options.set_whole_key_filtering(false);
+            options.set_block_based_table_factory(&block_based_options);
+            options.set_memtable_prefix_bloom_ratio(0.1);
+        }
 
         options.set_compression_type(DBCompressionType::Zstd);
+        if let Column::ClassInfo | Column::ClassDefinitions | Column::ClassCompiled = self {
+            // Class definitions are large, and are mostly read shortly after they are declared. Fresh writes are
+            // compressed fast, and the older classes which reach the bottommost level are compressed harder.
+            options.set_bottommost_compression_type(DBCompressionType::Zstd);
+            options.set_bottommost_compression_options(-14, CLASS_BOTTOMMOST_ZSTD_LEVEL, 0, 0, true);
+        }
         match self {
             Column::BlockNToBlockInfo | Column::BlockNToBlockInner => {
                 options.optimize_universal_style_compaction(1 * GiB);
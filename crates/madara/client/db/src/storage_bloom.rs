@@ -0,0 +1,153 @@
+//! In-memory bloom filter of the populated contract storage keys.
+//!
+//! Contracts commonly read storage slots which have never been written to. The rocksdb bloom filters of the history
+//! columns spare such a read from the disk, but it still has to go through the rocksdb read path just to find out
+//! that there is no value. When this filter is enabled, a read of a
+//! `(contract_address, key)` pair which was never inserted in the filter returns `None` right away. A bloom filter
+//! never has false negatives: a positive answer may be a false positive, in which case the read falls back to the
+//! database as usual.
+//!
+//! There is a single filter for the whole database rather than one per block. It only ever grows: it covers every
+//! key written at any block, which is a superset of the keys populated at any given block. This keeps it valid for
+//! historical reads and across pending block clears, without keeping one filter per block in memory. A key which is
+//! only set at later blocks than the one read is a false positive, and goes to the database.
+//!
+//! The filter is enabled with `--db-storage-bloom-filter-items`.
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use rocksdb::{IteratorMode, ReadOptions};
+use starknet_types_core::felt::Felt;
+
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError};
+
+#[derive(Debug)]
+pub struct StorageBloomFilter {
+    bits: Vec<AtomicU64>,
+    n_hashes: u32,
+    hasher: RandomState,
+    /// The filter is only used once it has been populated with the keys already in the database.
+    ready: AtomicBool,
+    short_circuited: AtomicU64,
+    passed_through: AtomicU64,
+}
+
+impl StorageBloomFilter {
+    /// Creates a filter sized for `expected_items` keys with the given false positive rate.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let expected_items = expected_items.max(1) as f64;
+        let false_positive_rate = false_positive_rate.clamp(f64::MIN_POSITIVE, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+        let n_bits = (-expected_items * false_positive_rate.ln() / (ln2 * ln2)).ceil().max(64.0);
+        let n_hashes = ((n_bits / expected_items) * ln2).round().clamp(1.0, 32.0) as u32;
+
+        Self {
+            bits: (0..(n_bits as usize).div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+            n_hashes,
+            hasher: RandomState::new(),
+            ready: AtomicBool::new(false),
+            short_circuited: AtomicU64::new(0),
+            passed_through: AtomicU64::new(0),
+        }
+    }
+
+    /// Bit indices of a key, using double hashing from the two halves of a single 64-bit hash.
+    fn bit_indices(&self, contract_address: &Felt, key: &Felt) -> impl Iterator<Item = usize> {
+        let hash = self.hasher.hash_one((contract_address, key));
+        let (h1, h2) = (hash & 0xFFFF_FFFF, (hash >> 32) | 1);
+        let n_bits = (self.bits.len() * 64) as u64;
+        (0..self.n_hashes as u64).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % n_bits) as usize)
+    }
+
+    pub fn insert(&self, contract_address: &Felt, key: &Felt) {
+        for bit in self.bit_indices(contract_address, key) {
+            self.bits[bit / 64].fetch_or(1 << (bit % 64), Ordering::Relaxed);
+        }
+    }
+
+    /// Returns `false` when the key has definitely never been inserted.
+    pub fn may_contain(&self, contract_address: &Felt, key: &Felt) -> bool {
+        self.bit_indices(contract_address, key)
+            .all(|bit| self.bits[bit / 64].load(Ordering::Relaxed) & (1 << (bit % 64)) != 0)
+    }
+
+    /// Number of storage reads answered by the filter without going to the database.
+    pub fn short_circuited(&self) -> u64 {
+        self.short_circuited.load(Ordering::Relaxed)
+    }
+
+    /// Number of storage reads which went to the database, including the false positives of the filter.
+    pub fn passed_through(&self) -> u64 {
+        self.passed_through.load(Ordering::Relaxed)
+    }
+}
+
+impl MadaraBackend {
+    /// Enables the storage bloom filter, sized for `expected_items` storage keys. This can only be done once.
+    ///
+    /// Every storage key already in the database is inserted in the filter, which is slow on a big database.
+    /// Storage reads keep going to the database until this is done.
+    #[tracing::instrument(skip(self), fields(module = "ContractDB"))]
+    pub fn enable_storage_bloom_filter(
+        &self,
+        expected_items: usize,
+        false_positive_rate: f64,
+    ) -> Result<(), MadaraStorageError> {
+        // The filter is registered before scanning the database so that no key written in the meantime is missed.
+        self.storage_bloom_filter
+            .set(StorageBloomFilter::new(expected_items, false_positive_rate))
+            .map_err(|_| MadaraStorageError::InconsistentStorage("Storage bloom filter is already enabled".into()))?;
+        let filter = self.storage_bloom_filter.get().expect("Filter was just set");
+
+        let mut options = ReadOptions::default();
+        options.set_total_order_seek(true);
+        let col = self.db.get_column(Column::ContractStorage);
+        for res in self.db.iterator_cf_opt(&col, options, IteratorMode::Start) {
+            let (k, _) = res?;
+            let (Some(contract_address), Some(key)) = (k.get(..32), k.get(32..64)) else {
+                return Err(MadaraStorageError::InconsistentStorage("Malformed contract storage key".into()));
+            };
+            filter.insert(&Felt::from_bytes_be_slice(contract_address), &Felt::from_bytes_be_slice(key));
+        }
+
+        // Pending keys are bincode-encoded.
+        let col = self.db.get_column(Column::PendingContractStorage);
+        for res in self.db.iterator_cf(&col, IteratorMode::Start) {
+            let (k, _) = res?;
+            let (contract_address, key): (Felt, Felt) = bincode::deserialize(&k)?;
+            filter.insert(&contract_address, &key);
+        }
+
+        filter.ready.store(true, Ordering::Release);
+        tracing::debug!("Storage bloom filter is ready");
+        Ok(())
+    }
+
+    pub fn storage_bloom_filter(&self) -> Option<&StorageBloomFilter> {
+        self.storage_bloom_filter.get()
+    }
+
+    /// Returns `false` when the storage key has definitely never been written to, meaning the read can be skipped.
+    pub(crate) fn storage_bloom_check(&self, contract_address: &Felt, key: &Felt) -> bool {
+        let Some(filter) = self.storage_bloom_filter() else { return true };
+        if !filter.ready.load(Ordering::Acquire) {
+            return true;
+        }
+        if filter.may_contain(contract_address, key) {
+            filter.passed_through.fetch_add(1, Ordering::Relaxed);
+            true
+        } else {
+            filter.short_circuited.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+
+    pub(crate) fn storage_bloom_insert<'a>(&self, keys: impl IntoIterator<Item = &'a (Felt, Felt)>) {
+        let Some(filter) = self.storage_bloom_filter() else { return };
+        for (contract_address, key) in keys {
+            filter.insert(contract_address, key);
+        }
+    }
+}
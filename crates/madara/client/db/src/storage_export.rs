@@ -0,0 +1,121 @@
+//! Export and import of the full storage of a single contract, for operators migrating or debugging a node.
+//!
+//! The snapshot format is a sequence of `(key, value)` records with no header, read until the end of the
+//! stream. Each felt is encoded as a one-byte length followed by that many bytes of its big-endian
+//! representation, with the leading zero bytes stripped:
+//!
+//! ```text
+//! record := <key_len: u8> <key: [u8; key_len]> <value_len: u8> <value: [u8; value_len]>
+//! ```
+//!
+//! Records are written in ascending key order.
+
+use std::io::{self, Read, Write};
+
+use rocksdb::{IteratorMode, ReadOptions};
+use starknet_types_core::felt::Felt;
+
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError};
+
+fn write_felt(writer: &mut impl Write, felt: &Felt) -> io::Result<()> {
+    let bytes = felt.to_bytes_be();
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    writer.write_all(&[(bytes.len() - start) as u8])?;
+    writer.write_all(&bytes[start..])
+}
+
+/// Returns `None` on a clean end of stream.
+fn read_felt(reader: &mut impl Read) -> io::Result<Option<Felt>> {
+    let mut len = [0u8; 1];
+    if reader.read(&mut len)? == 0 {
+        return Ok(None);
+    }
+    let len = len[0] as usize;
+    if len > 32 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid felt length {len}")));
+    }
+    let mut bytes = [0u8; 32];
+    reader.read_exact(&mut bytes[32 - len..])?;
+    Ok(Some(Felt::from_bytes_be(&bytes)))
+}
+
+impl MadaraBackend {
+    /// Writes every `(key, value)` storage pair of `contract_address` as of block `block_n` to `writer`, in
+    /// the format described in the [module documentation](self). The pairs are streamed from the database
+    /// and never collected in memory. Returns the number of pairs written.
+    ///
+    /// Pending state is not exported.
+    #[tracing::instrument(skip(self, writer), fields(module = "ContractDB"))]
+    pub fn export_contract_storage(
+        &self,
+        contract_address: &Felt,
+        block_n: u64,
+        mut writer: impl Write,
+    ) -> Result<usize, MadaraStorageError> {
+        let block_n = u32::try_from(block_n).map_err(|_| MadaraStorageError::InvalidBlockNumber)?;
+        let prefix = contract_address.to_bytes_be();
+
+        let mut options = ReadOptions::default();
+        // The prefix extractor of the column covers `<contract> || <key>`: we need a total order seek to
+        // iterate over all the keys of a contract.
+        options.set_total_order_seek(true);
+        options.set_iterate_range(rocksdb::PrefixRange(&prefix as &[u8]));
+        let mode = IteratorMode::From(&prefix, rocksdb::Direction::Forward);
+        let iter = self.db.iterator_cf_opt(&self.db.get_column(Column::ContractStorage), options, mode);
+
+        // Keys are `<contract> || <key> || <block_n>`, so the history of a storage key is sorted by ascending
+        // block number: the last entry at or before `block_n` is the value at that block.
+        let mut current: Option<(Felt, Felt)> = None;
+        let mut written = 0;
+        for res in iter {
+            let (k, v) = res?;
+            let (Some(key), Some(entry_block_n)) = (k.get(32..64), k.get(64..68)) else {
+                return Err(MadaraStorageError::InconsistentStorage("Malformed contract storage key".into()));
+            };
+            let key = Felt::from_bytes_be_slice(key);
+            let entry_block_n = u32::from_be_bytes(entry_block_n.try_into().expect("Slice is 4 bytes long"));
+
+            if let Some((current_key, value)) = current.filter(|(current_key, _)| *current_key != key) {
+                write_felt(&mut writer, &current_key)?;
+                write_felt(&mut writer, &value)?;
+                written += 1;
+                current = None;
+            }
+            if entry_block_n <= block_n {
+                current = Some((key, bincode::deserialize(&v)?));
+            }
+        }
+        if let Some((key, value)) = current {
+            write_felt(&mut writer, &key)?;
+            write_felt(&mut writer, &value)?;
+            written += 1;
+        }
+
+        writer.flush()?;
+        Ok(written)
+    }
+
+    /// Reads a snapshot produced by [`MadaraBackend::export_contract_storage`] and stores its pairs as the
+    /// storage of `contract_address` updated at block `block_n`. Returns the number of pairs imported.
+    ///
+    /// Only the flat storage is written: the global tries and block data are left untouched. As with block
+    /// storage, the writes are not durable until the next [`MadaraBackend::flush`].
+    #[tracing::instrument(skip(self, reader), fields(module = "ContractDB"))]
+    pub fn import_contract_storage(
+        &self,
+        contract_address: &Felt,
+        block_n: u64,
+        mut reader: impl Read,
+    ) -> Result<usize, MadaraStorageError> {
+        let mut kv_updates = vec![];
+        while let Some(key) = read_felt(&mut reader)? {
+            let value = read_felt(&mut reader)?.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::UnexpectedEof, format!("Missing value for storage key {key:#x}"))
+            })?;
+            kv_updates.push(((*contract_address, key), value));
+        }
+
+        self.contract_db_store_block(block_n, &[], &[], &kv_updates)?;
+        Ok(kv_updates.len())
+    }
+}
@@ -0,0 +1,119 @@
+//! Read counters for the contract and class columns, to see which of them dominates during sync or tracing.
+//!
+//! Unlike [`crate::db_metrics::DbMetrics`], which samples the size of the database, these are updated on every
+//! read and only use atomic counters. The read latency histogram is only recorded with the `storage-read-latency`
+//! feature, as it needs a clock read per database access.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "storage-read-latency")]
+use std::time::Instant;
+
+/// Upper bounds of the buckets of the read latency histogram, in microseconds. Reads slower than the last bound
+/// go into an additional last bucket.
+pub const READ_LATENCY_BUCKETS_MICROS: [u64; 8] = [1, 4, 16, 64, 256, 1024, 4096, 16384];
+
+/// Reads of a column, see [`MadaraBackend::storage_metrics`](crate::MadaraBackend::storage_metrics). Failed reads
+/// are not counted.
+#[derive(Debug, Default)]
+pub struct ColumnReadMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    #[cfg(feature = "storage-read-latency")]
+    latency: [AtomicU64; READ_LATENCY_BUCKETS_MICROS.len() + 1],
+}
+
+impl ColumnReadMetrics {
+    /// Reads which found a value.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Reads which did not find a value.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    pub fn reads(&self) -> u64 {
+        self.hits() + self.misses()
+    }
+
+    /// Number of reads in each bucket of [`READ_LATENCY_BUCKETS_MICROS`], followed by the reads slower than the
+    /// last bucket.
+    #[cfg(feature = "storage-read-latency")]
+    pub fn latency_histogram(&self) -> [u64; READ_LATENCY_BUCKETS_MICROS.len() + 1] {
+        std::array::from_fn(|i| self.latency[i].load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn record<T, E>(&self, read: impl FnOnce() -> Result<Option<T>, E>) -> Result<Option<T>, E> {
+        #[cfg(feature = "storage-read-latency")]
+        let start = Instant::now();
+
+        let res = read();
+
+        #[cfg(feature = "storage-read-latency")]
+        {
+            let micros = u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX);
+            let bucket = READ_LATENCY_BUCKETS_MICROS.partition_point(|&bound| bound < micros);
+            self.latency[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+
+        match &res {
+            Ok(Some(_)) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+            }
+            Ok(None) => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(_) => {}
+        }
+        res
+    }
+}
+
+/// Read counters of the contract and class columns, since the backend was opened. They are exported along with
+/// the [`crate::db_metrics::DbMetrics`].
+#[derive(Debug, Default)]
+pub struct StorageMetrics {
+    /// [`MadaraBackend::get_contract_storage_at`](crate::MadaraBackend::get_contract_storage_at)
+    pub contract_storage: ColumnReadMetrics,
+    /// [`MadaraBackend::get_contract_nonce_at`](crate::MadaraBackend::get_contract_nonce_at)
+    pub contract_nonces: ColumnReadMetrics,
+    /// [`MadaraBackend::get_contract_class_hash_at`](crate::MadaraBackend::get_contract_class_hash_at)
+    pub contract_class_hash: ColumnReadMetrics,
+    /// [`MadaraBackend::get_class_info`](crate::MadaraBackend::get_class_info), including the reads served by the
+    /// class cache.
+    pub class_data: ColumnReadMetrics,
+    history_reads: AtomicU64,
+    class_reads: AtomicU64,
+}
+
+impl StorageMetrics {
+    /// Database reads of the contract class hash, nonce and storage histories, whichever backend method made them.
+    pub fn history_reads(&self) -> u64 {
+        self.history_reads.load(Ordering::Relaxed)
+    }
+
+    /// Single class reads from the database. Unlike [`Self::class_data`], the reads served by the class cache are
+    /// not counted.
+    pub fn class_reads(&self) -> u64 {
+        self.class_reads.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_history_read(&self) {
+        self.history_reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_class_read(&self) {
+        self.class_reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The column read metrics, along with the name they are exported with.
+    pub fn columns(&self) -> [(&'static str, &ColumnReadMetrics); 4] {
+        [
+            ("contract_storage", &self.contract_storage),
+            ("contract_nonces", &self.contract_nonces),
+            ("contract_class_hash", &self.contract_class_hash),
+            ("class_data", &self.class_data),
+        ]
+    }
+}
@@ -8,6 +8,7 @@ use mp_class::ConvertedClass;
 use mp_state_update::{
     ContractStorageDiffItem, DeployedContractItem, NonceUpdate, ReplacedClassItem, StateDiff, StorageEntry,
 };
+use mp_transactions::Transaction;
 use starknet_types_core::felt::Felt;
 use std::collections::HashMap;
 
@@ -17,13 +18,39 @@ impl MadaraBackend {
         &self,
         block: MadaraMaybePendingBlock,
         state_diff: StateDiff,
-        converted_classes: Vec<ConvertedClass>,
+        mut converted_classes: Vec<ConvertedClass>,
         visited_segments: Option<VisitedSegments>,
         bouncer_weights: Option<BouncerWeights>,
     ) -> Result<(), MadaraStorageError> {
         let block_n = block.info.block_n();
+
+        // Classes are stored in canonical order (sorted by class hash, without duplicates) so that storing the same
+        // block twice always results in the same database content, regardless of the order in which the classes
+        // were received. The sort is stable, so that only the first occurrence of a class declared multiple times
+        // is kept.
+        converted_classes.sort_by_key(ConvertedClass::class_hash);
+        let class_count = converted_classes.len();
+        converted_classes.dedup_by_key(|converted_class| converted_class.class_hash());
+        if converted_classes.len() != class_count {
+            tracing::warn!(
+                "Block {block_n:?} contains {} duplicate class declarations, only the first ones are stored",
+                class_count - converted_classes.len()
+            );
+        }
         let state_diff_cpy = state_diff.clone();
 
+        // The declare transaction of each class is recorded along with it, see `ClassMetadata::declare_tx_hash`.
+        let declare_tx_hashes: HashMap<Felt, Felt> = block
+            .inner
+            .transactions
+            .iter()
+            .zip(&block.inner.receipts)
+            .filter_map(|(tx, receipt)| match tx {
+                Transaction::Declare(tx) => Some((*tx.class_hash(), receipt.transaction_hash())),
+                _ => None,
+            })
+            .collect();
+
         // Clear in every case, even when storing a pending block
         self.clear_pending_block()?;
 
@@ -76,14 +103,18 @@ impl MadaraBackend {
         };
 
         let task_class_db = || match block_n {
-            None => self.class_db_store_pending(&converted_classes),
-            Some(block_n) => self.class_db_store_block(block_n, &converted_classes),
+            None => self.class_db_store_pending(&converted_classes, &declare_tx_hashes),
+            Some(block_n) => self.class_db_store_block(block_n, &converted_classes, &declare_tx_hashes),
         };
 
         let ((r1, r2), r3) = rayon::join(|| rayon::join(task_block_db, task_contract_db), task_class_db);
 
         r1.and(r2).and(r3)?;
 
+        if let Some(block_n) = block_n {
+            self.publish_declared_classes(&converted_classes, block_n);
+        }
+
         self.snapshots.set_new_head(DbBlockId::from_block_n(block_n));
         Ok(())
     }
@@ -0,0 +1,101 @@
+use rocksdb::properties;
+
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError};
+
+/// Breakdown of the disk (and mem-table) usage of the database, grouped by the kind of data
+/// stored in each column. All sizes are RocksDB estimates in bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StorageUsage {
+    /// Block headers, block bodies and block storage metadata.
+    pub blocks: u64,
+    /// Per-block state diffs.
+    pub state_diffs: u64,
+    /// Secondary indexes (tx hash => block_n, block hash => block_n, selector => class hashes).
+    pub indexes: u64,
+    /// Class definitions. This includes the class ABIs, which are stored alongside the class.
+    pub classes: u64,
+    /// Compiled (CASM) classes, and the version of the compiler which produced them.
+    pub compiled_classes: u64,
+    /// History of contract class hashes.
+    pub contract_class_hashes: u64,
+    /// History of contract nonces.
+    pub nonces: u64,
+    /// History of contract storage values.
+    pub contract_storage: u64,
+    /// Bonsai tries (contracts, contract storage and classes).
+    pub tries: u64,
+    /// Everything else: L1 messaging, devnet keys, mempool.
+    pub other: u64,
+}
+
+impl StorageUsage {
+    pub fn total(&self) -> u64 {
+        let Self {
+            blocks,
+            state_diffs,
+            indexes,
+            classes,
+            compiled_classes,
+            contract_class_hashes,
+            nonces,
+            contract_storage,
+            tries,
+            other,
+        } = self;
+        blocks
+            + state_diffs
+            + indexes
+            + classes
+            + compiled_classes
+            + contract_class_hashes
+            + nonces
+            + contract_storage
+            + tries
+            + other
+    }
+
+    fn category_mut(&mut self, column: Column) -> &mut u64 {
+        use Column::*;
+        match column {
+            BlockNToBlockInfo | BlockNToBlockInner | BlockStorageMeta => &mut self.blocks,
+            BlockNToStateDiff => &mut self.state_diffs,
+            TxHashToBlockN | BlockHashToBlockN | SelectorToClassHashes | ClassToSelectors => &mut self.indexes,
+            ClassInfo | PendingClassInfo | ClassDefinitions | ClassUnknownFields => &mut self.classes,
+            ClassCompiled | PendingClassCompiled | ClassCompilerVersion => &mut self.compiled_classes,
+            ContractToClassHashes | PendingContractToClassHashes => &mut self.contract_class_hashes,
+            ContractToNonces | PendingContractToNonces => &mut self.nonces,
+            ContractStorage | PendingContractStorage => &mut self.contract_storage,
+            BonsaiContractsTrie
+            | BonsaiContractsFlat
+            | BonsaiContractsLog
+            | BonsaiContractsStorageTrie
+            | BonsaiContractsStorageFlat
+            | BonsaiContractsStorageLog
+            | BonsaiClassesTrie
+            | BonsaiClassesFlat
+            | BonsaiClassesLog => &mut self.tries,
+            L1Messaging | L1MessagingNonce | Devnet | MempoolTransactions | ClassLabels => &mut self.other,
+        }
+    }
+}
+
+impl MadaraBackend {
+    /// Reports how storage is split between the different kinds of data held in the database.
+    ///
+    /// The size of each column is the size of its SST files plus the size of its mem-tables, so that
+    /// data which has not been flushed to disk yet is accounted for.
+    #[tracing::instrument(skip(self), fields(module = "StorageUsage"))]
+    pub fn storage_usage_report(&self) -> Result<StorageUsage, MadaraStorageError> {
+        let mut usage = StorageUsage::default();
+
+        for &column in Column::ALL {
+            let col = self.db.get_column(column);
+            let sst_size = self.db.get_column_family_metadata_cf(&col).size;
+            let mem_table_size = self.db.property_int_value_cf(&col, properties::SIZE_ALL_MEM_TABLES)?.unwrap_or(0);
+
+            *usage.category_mut(column) += sst_size + mem_table_size;
+        }
+
+        Ok(usage)
+    }
+}
@@ -0,0 +1,132 @@
+//! Startup check that the sync tip, the number of the last block stored, does not point past the data which is
+//! actually in the database. This can happen when the node is stopped in the middle of storing a block.
+
+use crate::block_db::{get_latest_block_n, ROW_SYNC_TIP};
+use crate::contract_db::make_storage_key_prefix;
+use crate::{Column, DatabaseExt, MadaraBackend, MadaraStorageError};
+use mp_state_update::StateDiff;
+
+/// Result of [`MadaraBackend::check_sync_tip`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncTipCheck {
+    /// Sync tip found in the database.
+    pub sync_tip: Option<u64>,
+    /// Highest block up to the sync tip for which all the checked data is present. `None` when no block is.
+    pub consistent_tip: Option<u64>,
+    /// Whether the sync tip was set back to `consistent_tip`.
+    pub repaired: bool,
+}
+
+impl SyncTipCheck {
+    pub fn is_consistent(&self) -> bool {
+        self.sync_tip == self.consistent_tip
+    }
+}
+
+impl MadaraBackend {
+    /// Checks that the last `depth` blocks up to the sync tip, and at least the sync tip, are fully stored. The block info, state diff and sync
+    /// tip of a block are written in a single batch, but its classes and contract updates are written separately,
+    /// so these are what is checked: every class declared by the block, and every contract class, nonce and
+    /// storage update of its state diff. Blocks below the checked ones are also checked until a complete one is
+    /// found.
+    ///
+    /// When the sync tip is ahead of the data and `repair` is set, it is set back to the highest consistent block
+    /// so that the missing blocks are synced again. The data of blocks above it is left as is, and is overwritten
+    /// when they are synced again.
+    #[tracing::instrument(skip(self), fields(module = "BlockDB"))]
+    pub fn check_sync_tip(&self, depth: u64, repair: bool) -> Result<SyncTipCheck, MadaraStorageError> {
+        let sync_tip = get_latest_block_n(&self.db)?;
+        let Some(tip) = sync_tip else { return Ok(SyncTipCheck { sync_tip, consistent_tip: None, repaired: false }) };
+
+        let mut consistent_tip = Some(tip);
+        let mut block_n = Some(tip);
+        let mut checked = 0;
+        while let Some(n) = block_n {
+            let complete = self.block_data_is_complete(n)?;
+            if !complete {
+                consistent_tip = n.checked_sub(1);
+            }
+            checked += 1;
+            if complete && checked >= depth {
+                break;
+            }
+            block_n = n.checked_sub(1);
+        }
+
+        let mut check = SyncTipCheck { sync_tip, consistent_tip, repaired: false };
+        if check.is_consistent() {
+            return Ok(check);
+        }
+
+        let consistent = consistent_tip.map_or("none".to_string(), |n| format!("#{n}"));
+        tracing::warn!("⚠️ The sync tip is block #{tip}, but the last block fully stored is {consistent}");
+        if repair {
+            let col = self.db.get_column(Column::BlockStorageMeta);
+            match consistent_tip {
+                Some(block_n) => self.db.put_cf(&col, ROW_SYNC_TIP, bincode::serialize(&block_n)?)?,
+                None => self.db.delete_cf(&col, ROW_SYNC_TIP)?,
+            }
+            check.repaired = true;
+            tracing::info!("🔧 Sync tip set back to {consistent}, the following blocks will be synced again");
+        }
+        Ok(check)
+    }
+
+    fn block_data_is_complete(&self, block_n: u64) -> Result<bool, MadaraStorageError> {
+        // The state diff lists what the block wrote: without it, the block cannot be checked.
+        let col_state_diff = self.db.get_column(Column::BlockNToStateDiff);
+        let Some(state_diff) = self.db.get_pinned_cf(&col_state_diff, bincode::serialize(&block_n)?)? else {
+            return Ok(false);
+        };
+        let state_diff: StateDiff = bincode::deserialize(&state_diff)?;
+
+        let declared = state_diff.declared_classes.iter().map(|item| &item.class_hash);
+        for class_hash in declared.chain(&state_diff.deprecated_declared_classes) {
+            if !self.contains_class(class_hash)? {
+                tracing::debug!("Class {class_hash:#x} declared in block #{block_n} is missing");
+                return Ok(false);
+            }
+        }
+
+        // Contract updates are keyed by the contract (and storage key) followed by the block number.
+        let block_suffix = u32::try_from(block_n).map_err(|_| MadaraStorageError::InvalidBlockNumber)?.to_be_bytes();
+        let has_update = |col: Column, prefix: &[u8]| -> Result<bool, MadaraStorageError> {
+            let key = [prefix, &block_suffix].concat();
+            Ok(self.db.get_pinned_cf(&self.db.get_column(col), key)?.is_some())
+        };
+
+        let class_updates = state_diff
+            .deployed_contracts
+            .iter()
+            .map(|item| &item.address)
+            .chain(state_diff.replaced_classes.iter().map(|item| &item.contract_address));
+        for contract_address in class_updates {
+            if !has_update(Column::ContractToClassHashes, &contract_address.to_bytes_be())? {
+                tracing::debug!("Class of contract {contract_address:#x} updated in block #{block_n} is missing");
+                return Ok(false);
+            }
+        }
+        for item in &state_diff.nonces {
+            if !has_update(Column::ContractToNonces, &item.contract_address.to_bytes_be())? {
+                tracing::debug!(
+                    "Nonce of contract {:#x} updated in block #{block_n} is missing",
+                    item.contract_address
+                );
+                return Ok(false);
+            }
+        }
+        for diff in &state_diff.storage_diffs {
+            for entry in &diff.storage_entries {
+                if !has_update(Column::ContractStorage, &make_storage_key_prefix(diff.address, entry.key))? {
+                    tracing::debug!(
+                        "Storage key {:#x} of contract {:#x} updated in block #{block_n} is missing",
+                        entry.key,
+                        diff.address
+                    );
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
@@ -1,4 +1,64 @@
 pub mod common;
 pub mod test_block;
 #[cfg(test)]
+pub mod test_class_archive;
+#[cfg(test)]
+pub mod test_class_batch_get;
+#[cfg(test)]
+pub mod test_class_cache;
+#[cfg(test)]
+pub mod test_class_compiler_version;
+#[cfg(test)]
+pub mod test_class_compression;
+#[cfg(test)]
+pub mod test_class_declared;
+#[cfg(test)]
+pub mod test_class_dedup;
+#[cfg(test)]
+pub mod test_class_eq;
+#[cfg(test)]
+pub mod test_class_label;
+#[cfg(test)]
+pub mod test_class_ordering;
+#[cfg(all(test, feature = "class-selector-index"))]
+pub mod test_class_selector_index;
+#[cfg(test)]
+pub mod test_class_storage_format;
+#[cfg(test)]
+pub mod test_class_with_metadata;
+#[cfg(test)]
+pub mod test_compiled_class_invalidation;
+#[cfg(test)]
+pub mod test_contract_batch_get;
+#[cfg(test)]
+pub mod test_contract_deployed_at;
+#[cfg(test)]
+pub mod test_contract_has_entrypoint;
+#[cfg(test)]
+pub mod test_contract_storage_at_or_before;
+#[cfg(test)]
+pub mod test_contract_storage_modified_in_block;
+#[cfg(test)]
+pub mod test_entry_counts;
+#[cfg(test)]
+pub mod test_index_rebuild;
+#[cfg(test)]
+pub mod test_lazy_casm_compilation;
+#[cfg(test)]
 pub mod test_open;
+#[cfg(test)]
+pub mod test_state_diff_json;
+#[cfg(test)]
+pub mod test_storage_bloom;
+#[cfg(test)]
+pub mod test_storage_export;
+#[cfg(test)]
+pub mod test_storage_metrics;
+#[cfg(test)]
+pub mod test_storage_usage;
+#[cfg(test)]
+pub mod test_stream_storage_changes;
+#[cfg(test)]
+pub mod test_sync_tip_check;
+#[cfg(test)]
+pub mod test_verify_classes;
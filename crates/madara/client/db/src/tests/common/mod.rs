@@ -1,10 +1,15 @@
 use mp_block::header::PendingHeader;
 use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock, MadaraPendingBlockInfo};
+use mp_class::builder::LegacyClassBuilder;
+use mp_class::{
+    ConvertedClass, EntryPointsByType, FlattenedSierraClass, LegacyClassInfo, LegacyContractEntryPoint,
+    LegacyConvertedClass, SierraClassInfo, SierraConvertedClass,
+};
 use mp_receipt::{
     DeclareTransactionReceipt, DeployAccountTransactionReceipt, DeployTransactionReceipt, InvokeTransactionReceipt,
     L1HandlerTransactionReceipt,
 };
-use mp_state_update::StateDiff;
+use mp_state_update::{ContractStorageDiffItem, StateDiff, StorageEntry};
 use mp_transactions::{
     DeclareTransactionV0, DeclareTransactionV1, DeclareTransactionV2, DeployAccountTransactionV1,
     DeployAccountTransactionV3, DeployTransaction, InvokeTransactionV0, InvokeTransactionV1, InvokeTransactionV3,
@@ -12,6 +17,7 @@ use mp_transactions::{
 };
 use starknet_api::felt;
 use starknet_types_core::felt::Felt;
+use std::sync::Arc;
 
 #[cfg(any(test, feature = "testing"))]
 pub mod temp_db {
@@ -140,3 +146,52 @@ pub fn pending_block_two() -> MadaraMaybePendingBlock {
 pub fn pending_state_diff_two() -> StateDiff {
     StateDiff::default()
 }
+
+/// A legacy class with no entry points.
+pub fn legacy_class(class_hash: Felt, program: Vec<u8>) -> ConvertedClass {
+    legacy_converted_class(class_hash, LegacyClassBuilder::new().with_program(program))
+}
+
+/// A legacy class with an empty program and one external entry point per selector.
+pub fn legacy_class_with_selectors(class_hash: Felt, external_selectors: &[Felt]) -> ConvertedClass {
+    let builder =
+        external_selectors.iter().enumerate().fold(LegacyClassBuilder::new(), |builder, (offset, &selector)| {
+            builder.with_external(LegacyContractEntryPoint { offset: offset as u64, selector })
+        });
+    legacy_converted_class(class_hash, builder)
+}
+
+fn legacy_converted_class(class_hash: Felt, builder: LegacyClassBuilder) -> ConvertedClass {
+    ConvertedClass::Legacy(LegacyConvertedClass {
+        class_hash,
+        info: LegacyClassInfo { contract_class: Arc::new(builder.build().expect("Class has no ABI to check")) },
+    })
+}
+
+/// A sierra class with no entry points, stored without its compiled class.
+pub fn sierra_class(class_hash: Felt, compiled_class_hash: Felt) -> ConvertedClass {
+    ConvertedClass::Sierra(SierraConvertedClass {
+        class_hash,
+        info: SierraClassInfo {
+            contract_class: Arc::new(FlattenedSierraClass {
+                sierra_program: vec![Felt::ONE, Felt::TWO, Felt::THREE],
+                contract_class_version: "0.1.0".into(),
+                entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+                abi: "[]".into(),
+            }),
+            compiled_class_hash,
+        },
+        compiled: None,
+    })
+}
+
+/// The storage diff of `address`, setting each key of `entries` to its value.
+pub fn storage_diff(address: Felt, entries: &[(u64, u64)]) -> ContractStorageDiffItem {
+    ContractStorageDiffItem {
+        address,
+        storage_entries: entries
+            .iter()
+            .map(|(key, value)| StorageEntry { key: Felt::from(*key), value: Felt::from(*value) })
+            .collect(),
+    }
+}
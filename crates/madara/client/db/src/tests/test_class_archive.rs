@@ -0,0 +1,83 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::{ArchivedClass, ClassArchive, DirClassArchive};
+use mp_block::{BlockId, BlockTag, Header};
+use mp_class::{ClassInfo, CompressedLegacyContractClass, LegacyClassInfo, LegacyEntryPointsByType};
+use starknet_types_core::felt::Felt;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+struct MockClassArchive {
+    classes: HashMap<Felt, ArchivedClass>,
+}
+
+impl ClassArchive for MockClassArchive {
+    fn get_class(&self, class_hash: &Felt) -> anyhow::Result<Option<ArchivedClass>> {
+        Ok(self.classes.get(class_hash).cloned())
+    }
+}
+
+fn legacy_class_info() -> ClassInfo {
+    LegacyClassInfo {
+        contract_class: Arc::new(CompressedLegacyContractClass {
+            program: vec![1, 2, 3],
+            entry_points_by_type: LegacyEntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+            abi: None,
+        }),
+    }
+    .into()
+}
+
+#[tokio::test]
+async fn test_get_class_info_from_archive() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    backend
+        .store_block(finalized_block_zero(Header::default()), finalized_state_diff_zero(), vec![], None, None)
+        .unwrap();
+    backend.store_block(finalized_block_one(), finalized_state_diff_one(), vec![], None, None).unwrap();
+
+    let class_hash = Felt::from_hex_unchecked("0x9100000001");
+    let class_info = legacy_class_info();
+
+    // Not found anywhere when no archive is configured.
+    assert_eq!(backend.get_class_info(&BlockId::Tag(BlockTag::Latest), &class_hash).unwrap(), None);
+    assert_eq!(backend.get_class_info_or_archived(&BlockId::Tag(BlockTag::Latest), &class_hash).unwrap(), None);
+
+    let archive = MockClassArchive {
+        classes: [(class_hash, ArchivedClass { block_n: 1, class_info: class_info.clone() })].into(),
+    };
+    backend.set_class_archive(Arc::new(archive)).unwrap();
+    assert!(backend.set_class_archive(Arc::new(MockClassArchive::default())).is_err());
+
+    // The hot store still does not have the class, but the archive does.
+    assert_eq!(backend.get_class_info(&BlockId::Tag(BlockTag::Latest), &class_hash).unwrap(), None);
+    assert_eq!(
+        backend.get_class_info_or_archived(&BlockId::Tag(BlockTag::Latest), &class_hash).unwrap(),
+        Some(class_info.clone())
+    );
+    assert_eq!(backend.get_class_info_or_archived(&BlockId::Number(1), &class_hash).unwrap(), Some(class_info));
+
+    // The class was not declared yet at block 0.
+    assert_eq!(backend.get_class_info_or_archived(&BlockId::Number(0), &class_hash).unwrap(), None);
+
+    // Neither the hot store nor the archive have this class.
+    let unknown_class_hash = Felt::from_hex_unchecked("0x123");
+    assert_eq!(backend.get_class_info_or_archived(&BlockId::Tag(BlockTag::Latest), &unknown_class_hash).unwrap(), None);
+}
+
+#[test]
+fn test_dir_class_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive = DirClassArchive::new(dir.path().join("classes"));
+
+    let class_hash = Felt::from_hex_unchecked("0x9100000001");
+    let archived = ArchivedClass { block_n: 1, class_info: legacy_class_info() };
+    assert_eq!(archive.get_class(&class_hash).unwrap(), None);
+
+    archive.put_class(&class_hash, &archived).unwrap();
+    assert_eq!(archive.get_class(&class_hash).unwrap(), Some(archived));
+    assert_eq!(archive.get_class(&Felt::from_hex_unchecked("0x123")).unwrap(), None);
+}
@@ -0,0 +1,138 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::db_block_id::DbBlockId;
+use mp_block::{BlockId, BlockTag, Header};
+use mp_class::ConvertedClass;
+use mp_state_update::{DeclaredClassItem, StateDiff};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_get_class_infos() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let (a, b, c, missing) = (Felt::from(0xa), Felt::from(0xb), Felt::from(0xc), Felt::from(0xdead));
+    let (class_a, class_b, class_c) = (legacy_class(a, vec![1]), legacy_class(b, vec![2]), legacy_class(c, vec![3]));
+
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff { deprecated_declared_classes: vec![a, b], ..Default::default() },
+            vec![class_a.clone(), class_b.clone()],
+            None,
+            None,
+        )
+        .unwrap();
+    backend
+        .store_block(
+            finalized_block_one(),
+            StateDiff { deprecated_declared_classes: vec![c], ..Default::default() },
+            vec![class_c.clone()],
+            None,
+            None,
+        )
+        .unwrap();
+
+    let info = |class: &ConvertedClass| Some(class.info());
+    let latest = BlockId::Tag(BlockTag::Latest);
+
+    // Order of the input is preserved, and missing classes are `None`.
+    assert_eq!(
+        backend.get_class_infos(&latest, &[c, missing, a, b, missing]).unwrap(),
+        vec![info(&class_c), None, info(&class_a), info(&class_b), None]
+    );
+    // Classes declared after the requested block are not visible.
+    assert_eq!(
+        backend.get_class_infos(&DbBlockId::Number(0), &[c, b, a]).unwrap(),
+        vec![None, info(&class_b), info(&class_a)]
+    );
+    assert_eq!(
+        backend.get_converted_classes(&latest, &[missing, b, c]).unwrap(),
+        vec![None, Some(class_b), Some(class_c)]
+    );
+    assert_eq!(backend.get_class_infos(&latest, &[]).unwrap(), vec![]);
+}
+
+#[tokio::test]
+async fn test_get_compiled_class_hashes() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let (legacy, sierra, sierra_later, missing) =
+        (Felt::from(0xa), Felt::from(0xb), Felt::from(0xc), Felt::from(0xdead));
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff {
+                deprecated_declared_classes: vec![legacy],
+                declared_classes: vec![DeclaredClassItem { class_hash: sierra, compiled_class_hash: Felt::from(0xbb) }],
+                ..Default::default()
+            },
+            vec![legacy_class(legacy, vec![1]), sierra_class(sierra, Felt::from(0xbb))],
+            None,
+            None,
+        )
+        .unwrap();
+    backend
+        .store_block(
+            finalized_block_one(),
+            StateDiff {
+                declared_classes: vec![DeclaredClassItem {
+                    class_hash: sierra_later,
+                    compiled_class_hash: Felt::from(0xcc),
+                }],
+                ..Default::default()
+            },
+            vec![sierra_class(sierra_later, Felt::from(0xcc))],
+            None,
+            None,
+        )
+        .unwrap();
+
+    let latest = BlockId::Tag(BlockTag::Latest);
+    let expected = vec![Some(Felt::from(0xcc)), None, None, Some(Felt::from(0xbb))];
+    assert_eq!(backend.get_compiled_class_hashes(&latest, &[sierra_later, legacy, missing, sierra]).unwrap(), expected);
+
+    // Classes in the class cache give the same results.
+    backend.warm_class_cache(&[sierra, legacy]).unwrap();
+    assert!(backend.class_cache().contains(&sierra));
+    assert_eq!(backend.get_compiled_class_hashes(&latest, &[sierra_later, legacy, missing, sierra]).unwrap(), expected);
+
+    // Classes declared after the requested block have no compiled class hash yet.
+    assert_eq!(
+        backend.get_compiled_class_hashes(&DbBlockId::Number(0), &[sierra_later, sierra]).unwrap(),
+        vec![None, Some(Felt::from(0xbb))]
+    );
+    assert_eq!(backend.get_compiled_class_hashes(&latest, &[]).unwrap(), vec![]);
+}
+
+#[tokio::test]
+async fn test_contains_class() {
+    let db = temp_db().await;
+    let backend = db.backend();
+    // The class definitions are compressed, as with class compression enabled.
+    backend.set_class_compression_threshold(1).unwrap();
+
+    // `missing` sorts between the two declared classes.
+    let (a, missing, b) = (Felt::from(0xa), Felt::from(0xb), Felt::from(0xc));
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff { deprecated_declared_classes: vec![a, b], ..Default::default() },
+            vec![legacy_class(a, vec![1]), legacy_class(b, vec![2])],
+            None,
+            None,
+        )
+        .unwrap();
+
+    for flushed in [false, true] {
+        if flushed {
+            // Absent keys are no longer ruled out from the memtable.
+            backend.flush().unwrap();
+        }
+        assert!(backend.contains_class(&a).unwrap(), "flushed: {flushed}");
+        assert!(backend.contains_class(&b).unwrap(), "flushed: {flushed}");
+        assert!(!backend.contains_class(&missing).unwrap(), "flushed: {flushed}");
+        assert!(!backend.contains_class(&Felt::from(0xdead)).unwrap(), "flushed: {flushed}");
+    }
+}
@@ -0,0 +1,132 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::class_cache::SAVED_HOT_CLASSES;
+use crate::ClassCache;
+use mp_block::{BlockId, BlockTag, Header};
+use mp_state_update::StateDiff;
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_warm_class_cache() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let (class_a, class_b) =
+        (legacy_class(Felt::from(0xa), vec![1, 2, 3]), legacy_class(Felt::from(0xb), vec![1, 2, 3]));
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff { deprecated_declared_classes: vec![Felt::from(0xa)], ..Default::default() },
+            vec![class_a.clone()],
+            None,
+            None,
+        )
+        .unwrap();
+    backend
+        .store_block(
+            finalized_block_one(),
+            StateDiff { deprecated_declared_classes: vec![Felt::from(0xb)], ..Default::default() },
+            vec![class_b.clone()],
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert!(backend.class_cache().is_empty());
+    assert_eq!(backend.warm_class_cache(&[Felt::from(0xa), Felt::from(0xb), Felt::from(0xc)]).unwrap(), 2);
+    assert!(backend.class_cache().contains(&Felt::from(0xa)));
+    assert!(backend.class_cache().contains(&Felt::from(0xb)));
+
+    // Warmed classes are served without reading the database.
+    let class_reads = backend.storage_metrics().class_reads();
+    let latest = BlockId::Tag(BlockTag::Latest);
+    for _ in 0..3 {
+        assert_eq!(backend.get_class_info(&latest, &Felt::from(0xa)).unwrap(), Some(class_a.info()));
+    }
+    assert_eq!(backend.get_class_info(&latest, &Felt::from(0xb)).unwrap(), Some(class_b.info()));
+    // Class b is not declared yet at block 0, even if it is cached.
+    assert_eq!(backend.get_class_info(&BlockId::Number(0), &Felt::from(0xb)).unwrap(), None);
+    assert_eq!(backend.storage_metrics().class_reads(), class_reads);
+
+    // The most read classes are saved for the next start.
+    assert!(backend.hot_classes().unwrap().is_empty());
+    backend.save_hot_classes().unwrap();
+    assert_eq!(backend.hot_classes().unwrap(), vec![Felt::from(0xa), Felt::from(0xb)]);
+    assert_eq!(backend.warm_class_cache_with_hot_classes(1).unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_class_cache_fills_on_read() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let class_a = legacy_class(Felt::from(0xa), vec![1, 2, 3]);
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff { deprecated_declared_classes: vec![Felt::from(0xa)], ..Default::default() },
+            vec![class_a.clone()],
+            None,
+            None,
+        )
+        .unwrap();
+
+    let latest = BlockId::Tag(BlockTag::Latest);
+    let class_reads = backend.storage_metrics().class_reads();
+    assert_eq!(backend.get_class_info(&latest, &Felt::from(0xa)).unwrap(), Some(class_a.info()));
+    assert_eq!(backend.storage_metrics().class_reads(), class_reads + 1);
+    assert_eq!(backend.get_class_info(&latest, &Felt::from(0xa)).unwrap(), Some(class_a.info()));
+    assert_eq!(backend.storage_metrics().class_reads(), class_reads + 1);
+}
+
+#[test]
+fn test_class_cache_evicts_least_recently_read() {
+    let cache = ClassCache::new(2);
+    let (a, b, c) = (Felt::from(0xa), Felt::from(0xb), Felt::from(0xc));
+
+    cache.insert(a, legacy_class(a, vec![1, 2, 3]).info(), 0);
+    cache.insert(b, legacy_class(b, vec![1, 2, 3]).info(), 0);
+    assert!(cache.get(&a).is_some());
+    cache.insert(c, legacy_class(c, vec![1, 2, 3]).info(), 1);
+
+    assert!(cache.contains(&a));
+    assert!(!cache.contains(&b));
+    assert!(cache.contains(&c));
+
+    // Misses are not counted and do not grow the cache.
+    assert!(cache.get(&b).is_none());
+    assert!(cache.get(&c).is_some());
+    assert!(cache.get(&c).is_some());
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.most_read(SAVED_HOT_CLASSES), vec![c, a]);
+}
+
+#[tokio::test]
+async fn test_hot_classes_count_class_reads() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let (class_a, class_b) =
+        (legacy_class(Felt::from(0xa), vec![1, 2, 3]), legacy_class(Felt::from(0xb), vec![1, 2, 3]));
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff { deprecated_declared_classes: vec![Felt::from(0xa), Felt::from(0xb)], ..Default::default() },
+            vec![class_a.clone(), class_b.clone()],
+            None,
+            None,
+        )
+        .unwrap();
+
+    // The read which loads a class in the cache is counted.
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(backend.get_class_info(&latest, &Felt::from(0xa)).unwrap(), Some(class_a.info()));
+    assert_eq!(backend.class_cache().most_read(SAVED_HOT_CLASSES), vec![Felt::from(0xa)]);
+
+    // Looking up where a cached class was declared is not a class read.
+    assert_eq!(backend.warm_class_cache(&[Felt::from(0xb)]).unwrap(), 1);
+    for _ in 0..3 {
+        assert_eq!(backend.class_declared_at(&Felt::from(0xb)).unwrap(), Some(0));
+    }
+    assert_eq!(backend.class_cache().most_read(SAVED_HOT_CLASSES), vec![Felt::from(0xa)]);
+}
@@ -0,0 +1,51 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::{BlockId, BlockTag, Header};
+use mp_class::{
+    CompiledSierra, ConvertedClass, EntryPointsByType, FlattenedSierraClass, SierraClassInfo, SierraConvertedClass,
+};
+use starknet_types_core::felt::Felt;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_compiler_version_stored_with_compiled_class() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let class_hash = Felt::from_hex_unchecked("0x9100000001");
+    let compiled_class_hash = Felt::from_hex_unchecked("0x9100000002");
+    let contract_class = FlattenedSierraClass {
+        // Sierra version 1.6.0
+        sierra_program: vec![Felt::from(1), Felt::from(6), Felt::from(0)],
+        contract_class_version: "0.1.0".into(),
+        entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+        abi: "[]".into(),
+    };
+    let expected_compiler_version = contract_class.casm_compiler_version().unwrap();
+    let compiled = CompiledSierra("{}".into());
+
+    let converted_class = ConvertedClass::Sierra(SierraConvertedClass {
+        class_hash,
+        info: SierraClassInfo { contract_class: Arc::new(contract_class), compiled_class_hash },
+        compiled: Some(Arc::new(compiled.clone())),
+    });
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![converted_class],
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(
+        backend.get_sierra_compiled(&BlockId::Tag(BlockTag::Latest), &compiled_class_hash).unwrap(),
+        Some(compiled)
+    );
+    assert_eq!(
+        backend.get_sierra_compiler_version(&compiled_class_hash).unwrap().as_deref(),
+        Some(expected_compiler_version)
+    );
+    assert_eq!(backend.get_sierra_compiler_version(&class_hash).unwrap(), None);
+}
@@ -0,0 +1,101 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::db_block_id::DbBlockId;
+use crate::{Column, DatabaseExt, MadaraBackend};
+use mp_block::Header;
+use mp_class::{ClassInfo, ConvertedClass, LegacyConvertedClass};
+use mp_state_update::StateDiff;
+use starknet_types_core::felt::Felt;
+
+const THRESHOLD: usize = 4096;
+
+fn small_and_large_classes() -> [ConvertedClass; 2] {
+    [
+        legacy_class(Felt::from_hex_unchecked("0x5a11"), vec![1, 2, 3]),
+        legacy_class(Felt::from_hex_unchecked("0x1a49e"), (0..64 * 1024u32).map(|i| (i % 251) as u8).collect()),
+    ]
+}
+
+fn store_classes(backend: &MadaraBackend, classes: &[ConvertedClass]) {
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff {
+                deprecated_declared_classes: classes.iter().map(ConvertedClass::class_hash).collect(),
+                ..Default::default()
+            },
+            classes.to_vec(),
+            None,
+            None,
+        )
+        .unwrap();
+}
+
+fn raw_value(backend: &MadaraBackend, column: Column, key: &[u8]) -> Vec<u8> {
+    backend.db.get_cf(&backend.db.get_column(column), key).unwrap().unwrap()
+}
+
+fn assert_reads_back(backend: &MadaraBackend, classes: [ConvertedClass; 2]) {
+    for class in classes {
+        let ConvertedClass::Legacy(LegacyConvertedClass { class_hash, info }) = class else { unreachable!() };
+        assert_eq!(backend.get_class_info(&DbBlockId::Number(0), &class_hash).unwrap(), Some(ClassInfo::Legacy(info)));
+    }
+}
+
+#[tokio::test]
+async fn test_class_compression_threshold() {
+    let db = temp_db().await;
+    let backend = db.backend();
+    backend.set_class_compression_threshold(THRESHOLD).unwrap();
+    assert!(backend.set_class_compression_threshold(THRESHOLD).is_err());
+
+    let classes = small_and_large_classes();
+    store_classes(backend, &classes);
+
+    // The small class is stored as is, the large one is compressed.
+    let small = raw_value(backend, Column::ClassInfo, &bincode::serialize(&classes[0].class_hash()).unwrap());
+    assert_eq!(small[0], b'B');
+    let large = raw_value(backend, Column::ClassInfo, &bincode::serialize(&classes[1].class_hash()).unwrap());
+    assert_eq!(large[0], b'Z');
+    assert!(large.len() < 64 * 1024, "the large class takes {} bytes", large.len());
+
+    assert_reads_back(backend, classes);
+}
+
+#[tokio::test]
+async fn test_class_compression_threshold_dedup() {
+    let db = temp_db().await;
+    let backend = db.backend();
+    backend.set_class_compression_threshold(THRESHOLD).unwrap();
+    backend.set_dedup_classes(true).unwrap();
+
+    let classes = small_and_large_classes();
+    store_classes(backend, &classes);
+
+    // The records only hold the digest, and the large definition is compressed.
+    let mut definitions = vec![];
+    for class in &classes {
+        let record = raw_value(backend, Column::ClassInfo, &bincode::serialize(&class.class_hash()).unwrap());
+        let digest = &record[record.len() - 32..];
+        definitions.push(raw_value(backend, Column::ClassDefinitions, digest));
+    }
+    assert_eq!(definitions[0][0], b'B');
+    assert_eq!(definitions[1][..2], [b'Z', b'B']);
+
+    assert_reads_back(backend, classes);
+}
+
+#[tokio::test]
+async fn test_class_compression_disabled_by_default() {
+    let db = temp_db().await;
+    let backend = db.backend();
+    assert_eq!(backend.class_compression_threshold(), 0);
+
+    let classes = small_and_large_classes();
+    store_classes(backend, &classes);
+
+    let large = raw_value(backend, Column::ClassInfo, &bincode::serialize(&classes[1].class_hash()).unwrap());
+    assert_eq!(large[0], b'B');
+
+    assert_reads_back(backend, classes);
+}
@@ -0,0 +1,73 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::db_block_id::DbBlockId;
+use crate::DeclaredClass;
+use mp_block::Header;
+use mp_state_update::StateDiff;
+use starknet_types_core::felt::Felt;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_on_class_declared() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let (sender, recv) = std::sync::mpsc::channel();
+    backend.on_class_declared(move |declared_class| sender.send(declared_class).unwrap());
+
+    let class_hash = Felt::from_hex_unchecked("0x9100000001");
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff { deprecated_declared_classes: vec![class_hash], ..Default::default() },
+            vec![legacy_class(class_hash, vec![1, 2, 3])],
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(
+        recv.recv_timeout(Duration::from_secs(5)).unwrap(),
+        DeclaredClass { class_hash, contract_class_version: None, block_n: 0 }
+    );
+
+    // Blocks without classes do not call the callback.
+    backend.store_block(finalized_block_one(), finalized_state_diff_one(), vec![], None, None).unwrap();
+    assert!(recv.recv_timeout(Duration::from_millis(100)).is_err());
+}
+
+#[tokio::test]
+async fn test_class_declared_at() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let class_hash = Felt::from_hex_unchecked("0x9100000042");
+    let pending_class_hash = Felt::from_hex_unchecked("0x9100000043");
+    backend
+        .store_block(
+            finalized_block_zero(Header { block_number: 42, ..Default::default() }),
+            StateDiff { deprecated_declared_classes: vec![class_hash], ..Default::default() },
+            vec![legacy_class(class_hash, vec![1, 2, 3])],
+            None,
+            None,
+        )
+        .unwrap();
+    backend
+        .store_block(
+            pending_block_one(),
+            StateDiff { deprecated_declared_classes: vec![pending_class_hash], ..Default::default() },
+            vec![legacy_class(pending_class_hash, vec![1, 2, 3])],
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(backend.class_declared_at(&class_hash).unwrap(), Some(42));
+    // Classes of the pending block are not declared in a block yet.
+    assert_eq!(backend.class_declared_at(&pending_class_hash).unwrap(), None);
+    assert_eq!(backend.class_declared_at(&Felt::from_hex_unchecked("0x9100000044")).unwrap(), None);
+
+    // Same from the class cache.
+    assert!(backend.get_class_info(&DbBlockId::Number(42), &class_hash).unwrap().is_some());
+    assert_eq!(backend.class_declared_at(&class_hash).unwrap(), Some(42));
+}
@@ -0,0 +1,78 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::db_block_id::DbBlockId;
+use crate::{Column, DatabaseExt, IndexKind, MadaraBackend};
+use mp_block::Header;
+use mp_class::ConvertedClass;
+use mp_state_update::StateDiff;
+use rocksdb::IteratorMode;
+use starknet_types_core::felt::Felt;
+
+fn column_len(backend: &MadaraBackend, column: Column) -> usize {
+    let col = backend.db.get_column(column);
+    backend.db.iterator_cf(&col, IteratorMode::Start).count()
+}
+
+/// Stores three classes in block 0, the first two with identical definitions.
+fn store_classes(backend: &MadaraBackend) -> Vec<ConvertedClass> {
+    let classes = vec![
+        legacy_class_with_selectors(Felt::from(0xc1a55), &[Felt::from(0x100)]),
+        legacy_class_with_selectors(Felt::from(0xc1a56), &[Felt::from(0x100)]),
+        legacy_class_with_selectors(Felt::from(0xc1a57), &[Felt::from(0x101)]),
+    ];
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff {
+                deprecated_declared_classes: classes.iter().map(ConvertedClass::class_hash).collect(),
+                ..Default::default()
+            },
+            classes.clone(),
+            None,
+            None,
+        )
+        .unwrap();
+    classes
+}
+
+#[tokio::test]
+async fn test_dedup_classes() {
+    let db = temp_db().await;
+    let backend = db.backend();
+    backend.set_dedup_classes(true).unwrap();
+    let classes = store_classes(backend);
+
+    // The two identical definitions take a single slot.
+    assert_eq!(column_len(backend, Column::ClassInfo), 3);
+    assert_eq!(column_len(backend, Column::ClassDefinitions), 2);
+
+    for class in &classes {
+        assert_eq!(backend.get_class_info(&DbBlockId::Number(0), &class.class_hash()).unwrap(), Some(class.info()));
+        assert_eq!(
+            backend.get_class_metadata(&DbBlockId::Number(0), &class.class_hash()).unwrap().unwrap().block_id,
+            DbBlockId::Number(0)
+        );
+    }
+    let class_hashes: Vec<_> = classes.iter().map(ConvertedClass::class_hash).collect();
+    assert_eq!(
+        backend.get_class_infos(&DbBlockId::Number(0), &class_hashes).unwrap(),
+        classes.iter().map(|class| Some(class.info())).collect::<Vec<_>>()
+    );
+
+    // Full scans of the classes follow the indirection too.
+    assert_eq!(backend.rebuild_index(IndexKind::ClassToSelectors).unwrap(), 3);
+    assert!(backend.verify_all_classes().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_dedup_classes_off_by_default() {
+    let db = temp_db().await;
+    let backend = db.backend();
+    assert!(!backend.dedup_classes());
+    let classes = store_classes(backend);
+
+    assert_eq!(column_len(backend, Column::ClassDefinitions), 0);
+    for class in &classes {
+        assert_eq!(backend.get_class_info(&DbBlockId::Number(0), &class.class_hash()).unwrap(), Some(class.info()));
+    }
+}
@@ -0,0 +1,60 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::{BlockId, BlockTag, Header};
+use mp_class::{
+    ConvertedClass, FlattenedSierraClass, LegacyClassInfo, LegacyConvertedClass, SierraClassInfo, SierraConvertedClass,
+};
+use starknet_core::types::contract::{legacy::LegacyContractClass, SierraClass};
+use std::sync::Arc;
+
+const ERC20_CLASS_DEFINITION: &[u8] =
+    include_bytes!("../../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json");
+const UDC_CLASS_DEFINITION: &[u8] = include_bytes!("../../../../../../cairo-artifacts/madara_contracts_UDC.json");
+
+#[tokio::test]
+async fn test_class_eq_after_store_load_round_trip() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let sierra: SierraClass = serde_json::from_slice(ERC20_CLASS_DEFINITION).unwrap();
+    let sierra: FlattenedSierraClass = sierra.flatten().unwrap().into();
+    let sierra_class_hash = sierra.compute_class_hash().unwrap();
+    let (compiled_class_hash, compiled) = sierra.compile_to_casm().unwrap();
+    let sierra_class = ConvertedClass::Sierra(SierraConvertedClass {
+        class_hash: sierra_class_hash,
+        info: SierraClassInfo { contract_class: Arc::new(sierra), compiled_class_hash },
+        compiled: Some(Arc::new(compiled)),
+    });
+
+    let legacy: LegacyContractClass = serde_json::from_slice(UDC_CLASS_DEFINITION).unwrap();
+    let legacy_class_hash = legacy.class_hash().unwrap();
+    let legacy_class = ConvertedClass::Legacy(LegacyConvertedClass {
+        class_hash: legacy_class_hash,
+        info: LegacyClassInfo { contract_class: Arc::new(legacy.compress().unwrap().into()) },
+    });
+
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![sierra_class.clone(), legacy_class.clone()],
+            None,
+            None,
+        )
+        .unwrap();
+
+    for class in [sierra_class, legacy_class] {
+        let loaded = backend
+            .get_class_info(&BlockId::Tag(BlockTag::Latest), &class.class_hash())
+            .unwrap()
+            .expect("Class should be stored")
+            .contract_class();
+        assert!(loaded.class_eq(&class.info().contract_class()).unwrap());
+        assert!(loaded.class_eq(&loaded).unwrap());
+    }
+
+    // Different classes are not equal.
+    let sierra = backend.get_class_info(&BlockId::Tag(BlockTag::Latest), &sierra_class_hash).unwrap().unwrap();
+    let legacy = backend.get_class_info(&BlockId::Tag(BlockTag::Latest), &legacy_class_hash).unwrap().unwrap();
+    assert!(!sierra.contract_class().class_eq(&legacy.contract_class()).unwrap());
+}
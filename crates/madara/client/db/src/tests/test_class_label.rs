@@ -0,0 +1,21 @@
+use super::common::temp_db::temp_db;
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_class_label() {
+    let db = temp_db().await;
+    let backend = db.backend();
+    let class_hash = Felt::from_hex_unchecked("0x1234");
+
+    assert_eq!(backend.class_label(&class_hash).unwrap(), None);
+
+    backend.set_class_label(&class_hash, Some("OpenZeppelin ERC20 v0.8")).unwrap();
+    assert_eq!(backend.class_label(&class_hash).unwrap().as_deref(), Some("OpenZeppelin ERC20 v0.8"));
+    assert_eq!(backend.class_label(&Felt::ONE).unwrap(), None);
+
+    backend.set_class_label(&class_hash, Some("OpenZeppelin ERC20 v0.9")).unwrap();
+    assert_eq!(backend.class_label(&class_hash).unwrap().as_deref(), Some("OpenZeppelin ERC20 v0.9"));
+
+    backend.set_class_label(&class_hash, None).unwrap();
+    assert_eq!(backend.class_label(&class_hash).unwrap(), None);
+}
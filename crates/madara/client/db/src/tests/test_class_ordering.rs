@@ -0,0 +1,85 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::{Column, DatabaseExt, MadaraBackend};
+use mp_block::{BlockId, Header};
+use mp_class::{CompiledSierra, ConvertedClass, LegacyConvertedClass, SierraConvertedClass};
+use starknet_types_core::felt::Felt;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_test::traced_test;
+
+/// A sierra class stored along with a compiled class unique to its compiled class hash.
+fn compiled_sierra_class(class_hash: Felt, compiled_class_hash: Felt) -> ConvertedClass {
+    let ConvertedClass::Sierra(class) = sierra_class(class_hash, compiled_class_hash) else { unreachable!() };
+    let compiled = CompiledSierra(format!("{{\"compiled\": \"{compiled_class_hash:#x}\"}}"));
+    ConvertedClass::Sierra(SierraConvertedClass { compiled: Some(Arc::new(compiled)), ..class })
+}
+
+fn column_content(backend: &MadaraBackend, column: Column) -> Vec<(Box<[u8]>, Box<[u8]>)> {
+    let col = backend.db.get_column(column);
+    backend.db.iterator_cf(&col, rocksdb::IteratorMode::Start).collect::<Result<_, _>>().unwrap()
+}
+
+#[tokio::test]
+async fn test_store_classes_is_order_independent() {
+    let classes = vec![
+        legacy_class(Felt::from(3), vec![1, 2, 3]),
+        compiled_sierra_class(Felt::from(1), Felt::from(101)),
+        legacy_class(Felt::from(2), vec![1, 2, 3]),
+        compiled_sierra_class(Felt::from(4), Felt::from(104)),
+        // Some legacy classes are declared multiple times.
+        legacy_class(Felt::from(3), vec![1, 2, 3]),
+    ];
+    let mut reversed = classes.clone();
+    reversed.reverse();
+
+    let db_a = temp_db().await;
+    let db_b = temp_db().await;
+    let (backend_a, backend_b) = (db_a.backend(), db_b.backend());
+    let (sender, recv) = std::sync::mpsc::channel();
+    backend_a.on_class_declared(move |declared_class| sender.send(declared_class.class_hash).unwrap());
+
+    backend_a
+        .store_block(finalized_block_zero(Header::default()), finalized_state_diff_zero(), classes, None, None)
+        .unwrap();
+    backend_b
+        .store_block(finalized_block_zero(Header::default()), finalized_state_diff_zero(), reversed, None, None)
+        .unwrap();
+
+    for column in [Column::ClassInfo, Column::ClassCompiled] {
+        let content = column_content(backend_a, column);
+        assert!(!content.is_empty());
+        assert_eq!(content, column_content(backend_b, column), "{column} differs");
+    }
+
+    // The canonical order is by class hash, with the duplicate dropped.
+    let declared: Vec<_> = (0..4).map(|_| recv.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+    assert_eq!(declared, [1u64, 2, 3, 4].map(Felt::from));
+    assert!(recv.recv_timeout(Duration::from_millis(100)).is_err());
+}
+
+#[tokio::test]
+#[traced_test]
+async fn test_store_duplicate_classes_keeps_first() {
+    let class_hash = Felt::from(3);
+    let first = legacy_class(class_hash, vec![1, 2, 3]);
+    let mut second = first.clone();
+    let ConvertedClass::Legacy(LegacyConvertedClass { info, .. }) = &mut second else { unreachable!() };
+    Arc::make_mut(&mut info.contract_class).program = vec![0xff];
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![first.clone(), legacy_class(Felt::from(2), vec![1, 2, 3]), second],
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(column_content(backend, Column::ClassInfo).len(), 2);
+    assert_eq!(backend.get_class_info(&BlockId::Number(0), &class_hash).unwrap(), Some(first.info()));
+    assert!(logs_contain("contains 1 duplicate class declarations"));
+}
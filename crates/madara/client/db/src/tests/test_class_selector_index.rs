@@ -0,0 +1,31 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::Header;
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_classes_with_selector() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let (transfer, balance_of, approve) = (Felt::from(0x100), Felt::from(0x200), Felt::from(0x300));
+    let (class_a, class_b) = (Felt::from(0xa), Felt::from(0xb));
+
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![
+                legacy_class_with_selectors(class_b, &[transfer, approve]),
+                legacy_class_with_selectors(class_a, &[transfer, balance_of]),
+            ],
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(backend.classes_with_selector(&transfer).unwrap(), vec![class_a, class_b]);
+    assert_eq!(backend.classes_with_selector(&balance_of).unwrap(), vec![class_a]);
+    assert_eq!(backend.classes_with_selector(&approve).unwrap(), vec![class_b]);
+    assert_eq!(backend.classes_with_selector(&Felt::from(0x400)).unwrap(), vec![]);
+}
@@ -0,0 +1,198 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::db_block_id::DbBlockId;
+use crate::{ClassMetadata, ClassStorageFormat, Column, DatabaseExt, MadaraStorageError};
+use mp_block::{BlockId, BlockTag, Header};
+use mp_state_update::{DeclaredClassItem, StateDiff};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_class_storage_format_round_trip() {
+    for format in [ClassStorageFormat::Bincode, ClassStorageFormat::Json] {
+        let db = temp_db().await;
+        let backend = db.backend();
+        backend.set_class_storage_format(format).unwrap();
+        assert_eq!(backend.class_storage_format(), format);
+
+        let classes = [legacy_class(Felt::from(0xa), vec![1, 2, 3]), sierra_class(Felt::from(0xb), Felt::from(0xcc))];
+        let state_diff = StateDiff {
+            deprecated_declared_classes: vec![Felt::from(0xa)],
+            declared_classes: vec![DeclaredClassItem {
+                class_hash: Felt::from(0xb),
+                compiled_class_hash: Felt::from(0xcc),
+            }],
+            ..Default::default()
+        };
+        backend.store_block(finalized_block_zero(Header::default()), state_diff, classes.to_vec(), None, None).unwrap();
+
+        for class in &classes {
+            assert_eq!(
+                backend.get_class_info(&BlockId::Tag(BlockTag::Latest), &class.class_hash()).unwrap(),
+                Some(class.info()),
+                "{format}"
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_class_storage_format_change() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    // Stored with the default format.
+    let class_a = legacy_class(Felt::from(0xa), vec![1, 2, 3]);
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff { deprecated_declared_classes: vec![Felt::from(0xa)], ..Default::default() },
+            vec![class_a.clone()],
+            None,
+            None,
+        )
+        .unwrap();
+
+    backend.set_class_storage_format(ClassStorageFormat::Json).unwrap();
+    assert!(backend.set_class_storage_format(ClassStorageFormat::Bincode).is_err());
+
+    let class_b = legacy_class(Felt::from(0xb), vec![1, 2, 3]);
+    backend
+        .store_block(
+            finalized_block_one(),
+            StateDiff { deprecated_declared_classes: vec![Felt::from(0xb)], ..Default::default() },
+            vec![class_b.clone()],
+            None,
+            None,
+        )
+        .unwrap();
+
+    // Classes stored with both formats can be read.
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(
+        backend.get_class_infos(&latest, &[Felt::from(0xa), Felt::from(0xb)]).unwrap(),
+        vec![Some(class_a.info()), Some(class_b.info())]
+    );
+}
+
+#[tokio::test]
+async fn test_class_metadata_with_corrupt_body() {
+    for format in [ClassStorageFormat::Bincode, ClassStorageFormat::Json] {
+        let db = temp_db().await;
+        let backend = db.backend();
+        backend.set_class_storage_format(format).unwrap();
+
+        let class_hash = Felt::from(0xb);
+        let state_diff = StateDiff {
+            declared_classes: vec![DeclaredClassItem { class_hash, compiled_class_hash: Felt::from(0xcc) }],
+            ..Default::default()
+        };
+        backend
+            .store_block(
+                finalized_block_zero(Header::default()),
+                state_diff,
+                vec![sierra_class(class_hash, Felt::from(0xcc))],
+                None,
+                None,
+            )
+            .unwrap();
+
+        // Truncate the class definition, leaving the metadata untouched.
+        let col = backend.db.get_column(Column::ClassInfo);
+        let key = bincode::serialize(&class_hash).unwrap();
+        let value = backend.db.get_cf(&col, &key).unwrap().unwrap();
+        backend.db.put_cf(&col, &key, &value[..value.len() - 8]).unwrap();
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(
+            backend.get_class_metadata(&latest, &class_hash).unwrap(),
+            Some(ClassMetadata {
+                block_id: DbBlockId::Number(0),
+                compiled_class_hash: Some(Felt::from(0xcc)),
+                sierra_program_length: 3,
+                abi_length: 2,
+                declare_tx_hash: None,
+            }),
+            "{format}"
+        );
+        assert!(
+            matches!(backend.get_class_info(&latest, &class_hash), Err(MadaraStorageError::CorruptClassBody(_))),
+            "{format}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_class_metadata_matches_class_info() {
+    for format in [ClassStorageFormat::Bincode, ClassStorageFormat::Json] {
+        let db = temp_db().await;
+        let backend = db.backend();
+        backend.set_class_storage_format(format).unwrap();
+
+        let (legacy_hash, sierra_hash) = (Felt::from(0xa), Felt::from(0xb));
+        let state_diff = StateDiff {
+            deprecated_declared_classes: vec![legacy_hash],
+            declared_classes: vec![DeclaredClassItem {
+                class_hash: sierra_hash,
+                compiled_class_hash: Felt::from(0xcc),
+            }],
+            ..Default::default()
+        };
+        backend
+            .store_block(
+                finalized_block_zero(Header::default()),
+                state_diff,
+                vec![legacy_class(legacy_hash, vec![1, 2, 3]), sierra_class(sierra_hash, Felt::from(0xcc))],
+                None,
+                None,
+            )
+            .unwrap();
+
+        let latest = BlockId::Tag(BlockTag::Latest);
+        for class_hash in [legacy_hash, sierra_hash] {
+            let metadata = backend.get_class_metadata(&latest, &class_hash).unwrap().unwrap();
+            let class_info = backend.get_class_info(&latest, &class_hash).unwrap().unwrap();
+            let contract_class = class_info.contract_class();
+
+            assert_eq!(metadata.block_id, DbBlockId::Number(0), "{format}");
+            assert_eq!(metadata.is_sierra(), contract_class.is_sierra(), "{format}");
+            assert_eq!(metadata.compiled_class_hash, class_info.compiled_class_hash(), "{format}");
+            assert_eq!(metadata.sierra_program_length, contract_class.sierra_program_length() as u64, "{format}");
+            assert_eq!(metadata.abi_length, contract_class.abi_length() as u64, "{format}");
+        }
+        assert!(!backend.get_class_metadata(&latest, &legacy_hash).unwrap().unwrap().is_sierra());
+        assert_eq!(backend.get_class_metadata(&latest, &sierra_hash).unwrap().unwrap().sierra_program_length, 3);
+    }
+}
+
+#[tokio::test]
+async fn test_class_metadata_without_declare_tx_hash() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    // A record with the metadata layout from before the declare transaction hash was recorded.
+    let class_hash = Felt::from(0xa);
+    let class_info = legacy_class(class_hash, vec![1, 2, 3]).info();
+    let metadata = bincode::serialize(&(DbBlockId::Number(0), None::<Felt>, 0u64, 0u64)).unwrap();
+    let mut record = vec![b'B'];
+    record.extend_from_slice(&(metadata.len() as u32).to_be_bytes());
+    record.extend_from_slice(&metadata);
+    record.extend_from_slice(&bincode::serialize(&class_info).unwrap());
+    backend.store_block(finalized_block_zero(Header::default()), StateDiff::default(), vec![], None, None).unwrap();
+    let col = backend.db.get_column(Column::ClassInfo);
+    backend.db.put_cf(&col, bincode::serialize(&class_hash).unwrap(), record).unwrap();
+
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(
+        backend.get_class_metadata(&latest, &class_hash).unwrap(),
+        Some(ClassMetadata::new(DbBlockId::Number(0), &class_info, None))
+    );
+    assert_eq!(backend.get_class_info(&latest, &class_hash).unwrap(), Some(class_info));
+}
+
+#[test]
+fn test_class_storage_format_from_str() {
+    for format in [ClassStorageFormat::Bincode, ClassStorageFormat::Json] {
+        assert_eq!(format.to_string().parse::<ClassStorageFormat>(), Ok(format));
+    }
+    assert!("scale".parse::<ClassStorageFormat>().is_err());
+}
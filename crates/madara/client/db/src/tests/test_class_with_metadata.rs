@@ -0,0 +1,74 @@
+use super::common::temp_db::temp_db;
+use crate::db_block_id::DbBlockId;
+use crate::{ClassDeclaration, ClassMetadata};
+use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+use mp_class::{
+    ClassInfo, CompiledSierra, ConvertedClass, EntryPointsByType, FlattenedSierraClass, SierraClassInfo,
+    SierraConvertedClass,
+};
+use mp_receipt::DeclareTransactionReceipt;
+use mp_state_update::{DeclaredClassItem, StateDiff};
+use mp_transactions::DeclareTransactionV2;
+use starknet_types_core::felt::Felt;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_class_with_metadata() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let class_hash = Felt::from_hex_unchecked("0x9100000001");
+    let compiled_class_hash = Felt::from_hex_unchecked("0x9100000002");
+    let declare_tx_hash = Felt::from_hex_unchecked("0xdec1a7e");
+    let contract_class = Arc::new(FlattenedSierraClass {
+        // Sierra version 1.6.0
+        sierra_program: vec![Felt::from(1), Felt::from(6), Felt::from(0)],
+        contract_class_version: "0.1.0".into(),
+        entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+        abi: "[]".into(),
+    });
+    let class_info = SierraClassInfo { contract_class: Arc::clone(&contract_class), compiled_class_hash };
+
+    backend
+        .store_block(
+            MadaraMaybePendingBlock {
+                info: MadaraBlockInfo::new(Header::default(), vec![declare_tx_hash], Felt::from(0xb10c)).into(),
+                inner: MadaraBlockInner::new(
+                    vec![DeclareTransactionV2 { class_hash, compiled_class_hash, ..Default::default() }.into()],
+                    vec![DeclareTransactionReceipt { transaction_hash: declare_tx_hash, ..Default::default() }.into()],
+                ),
+            },
+            StateDiff {
+                declared_classes: vec![DeclaredClassItem { class_hash, compiled_class_hash }],
+                ..Default::default()
+            },
+            vec![ConvertedClass::Sierra(SierraConvertedClass {
+                class_hash,
+                info: class_info.clone(),
+                compiled: Some(Arc::new(CompiledSierra("{}".into()))),
+            })],
+            None,
+            None,
+        )
+        .unwrap();
+
+    let class_info = ClassInfo::Sierra(class_info);
+    let expected = ClassDeclaration {
+        metadata: ClassMetadata::new(DbBlockId::Number(0), &class_info, Some(declare_tx_hash)),
+        contract_class_version: Some("0.1.0".into()),
+        compiler_version: Some(contract_class.casm_compiler_version().unwrap().to_string()),
+    };
+    assert_eq!(expected.metadata.compiled_class_hash, Some(compiled_class_hash));
+    assert_eq!(expected.metadata.sierra_program_length, 3);
+    assert_eq!(backend.class_with_metadata(&class_hash).unwrap(), Some((class_info.clone(), expected.clone())));
+    assert_eq!(
+        backend.get_class_metadata(&DbBlockId::Number(0), &class_hash).unwrap().unwrap().declare_tx_hash,
+        Some(declare_tx_hash)
+    );
+
+    // Same result once the class is cached.
+    backend.get_class_info(&DbBlockId::Number(0), &class_hash).unwrap();
+    assert_eq!(backend.class_with_metadata(&class_hash).unwrap(), Some((class_info, expected)));
+
+    assert_eq!(backend.class_with_metadata(&Felt::from_hex_unchecked("0x9100000003")).unwrap(), None);
+}
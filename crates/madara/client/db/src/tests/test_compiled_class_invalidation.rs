@@ -0,0 +1,143 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::{Column, DatabaseExt, MadaraStorageError};
+use mp_block::{BlockId, BlockTag, Header};
+use mp_class::{CompiledSierra, ConvertedClass, FlattenedSierraClass, SierraClassInfo, SierraConvertedClass};
+use starknet_core::types::contract::SierraClass;
+use starknet_types_core::felt::Felt;
+use std::sync::Arc;
+
+const ERC20_CLASS_DEFINITION: &[u8] =
+    include_bytes!("../../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json");
+
+#[tokio::test]
+async fn test_compiled_class_invalidation() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let class: SierraClass = serde_json::from_slice(ERC20_CLASS_DEFINITION).unwrap();
+    let contract_class: FlattenedSierraClass = class.flatten().unwrap().into();
+    let class_hash = contract_class.compute_class_hash().unwrap();
+    let (compiled_class_hash, compiled) = contract_class.compile_to_casm().unwrap();
+    let info = SierraClassInfo { contract_class: Arc::new(contract_class), compiled_class_hash };
+
+    // A wrong compiled class is stored for the class.
+    let wrong_compiled = CompiledSierra("{}".into());
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![ConvertedClass::Sierra(SierraConvertedClass {
+                class_hash,
+                info: info.clone(),
+                compiled: Some(Arc::new(wrong_compiled.clone())),
+            })],
+            None,
+            None,
+        )
+        .unwrap();
+
+    // The compiled class was produced by an older compiler: it is compiled again and replaced.
+    let col = backend.db.get_column(Column::ClassCompilerVersion);
+    let key_bin = bincode::serialize(&compiled_class_hash).unwrap();
+    backend.db.put_cf(&col, &key_bin, bincode::serialize("0.0.0-outdated").unwrap()).unwrap();
+
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(backend.get_or_compile_sierra_compiled(&latest, &class_hash, &info).unwrap(), compiled);
+    assert_eq!(backend.get_sierra_compiled(&latest, &compiled_class_hash).unwrap(), Some(compiled.clone()));
+    assert_eq!(
+        backend.get_sierra_compiler_version(&compiled_class_hash).unwrap().as_deref(),
+        Some(info.contract_class.casm_compiler_version().unwrap())
+    );
+
+    // Once checked, the compiler version is not read again: the class is not compiled again.
+    backend.db.put_cf(&col, &key_bin, bincode::serialize("0.0.0-outdated").unwrap()).unwrap();
+    assert_eq!(backend.get_or_compile_sierra_compiled(&latest, &class_hash, &info).unwrap(), compiled);
+    assert_eq!(backend.get_sierra_compiler_version(&compiled_class_hash).unwrap().as_deref(), Some("0.0.0-outdated"));
+
+    // A wrong compiled class stored by the same compiler version is replaced when the class is invalidated.
+    let col_compiled = backend.db.get_column(Column::ClassCompiled);
+    backend.db.put_cf(&col_compiled, &key_bin, bincode::serialize(&wrong_compiled).unwrap()).unwrap();
+    assert_eq!(backend.get_or_compile_sierra_compiled(&latest, &class_hash, &info).unwrap(), wrong_compiled);
+
+    assert_eq!(backend.invalidate_sierra_compiled(&[class_hash, Felt::from(0xdead)]).unwrap(), 1);
+    assert_eq!(backend.get_sierra_compiled(&latest, &compiled_class_hash).unwrap(), Some(compiled.clone()));
+    assert_eq!(backend.get_or_compile_sierra_compiled(&latest, &class_hash, &info).unwrap(), compiled);
+}
+
+#[tokio::test]
+async fn test_mismatching_recompilation_keeps_stored_compiled_class() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let class: SierraClass = serde_json::from_slice(ERC20_CLASS_DEFINITION).unwrap();
+    let contract_class: FlattenedSierraClass = class.flatten().unwrap().into();
+    let class_hash = contract_class.compute_class_hash().unwrap();
+    // The class was declared with a compiled class hash the current compiler does not produce.
+    let compiled_class_hash = Felt::from(0xc1a55);
+    let info = SierraClassInfo { contract_class: Arc::new(contract_class), compiled_class_hash };
+
+    let stored_compiled = CompiledSierra("{}".into());
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![ConvertedClass::Sierra(SierraConvertedClass {
+                class_hash,
+                info: info.clone(),
+                compiled: Some(Arc::new(stored_compiled.clone())),
+            })],
+            None,
+            None,
+        )
+        .unwrap();
+    let col = backend.db.get_column(Column::ClassCompilerVersion);
+    let key_bin = bincode::serialize(&compiled_class_hash).unwrap();
+    backend.db.put_cf(&col, &key_bin, bincode::serialize("0.0.0-outdated").unwrap()).unwrap();
+
+    // The stored compiled class is kept, and is not compiled again on the next read.
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(backend.get_or_compile_sierra_compiled(&latest, &class_hash, &info).unwrap(), stored_compiled);
+    assert_eq!(
+        backend.get_sierra_compiler_version(&compiled_class_hash).unwrap().as_deref(),
+        Some(info.contract_class.casm_compiler_version().unwrap())
+    );
+    assert_eq!(backend.get_sierra_compiled(&latest, &compiled_class_hash).unwrap(), Some(stored_compiled));
+}
+
+#[tokio::test]
+async fn test_invalidation_with_mismatching_recompilation_fails() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let class: SierraClass = serde_json::from_slice(ERC20_CLASS_DEFINITION).unwrap();
+    let contract_class: FlattenedSierraClass = class.flatten().unwrap().into();
+    let class_hash = contract_class.compute_class_hash().unwrap();
+    // The class was declared with a compiled class hash the current compiler does not produce.
+    let compiled_class_hash = Felt::from(0xc1a55);
+    let info = SierraClassInfo { contract_class: Arc::new(contract_class), compiled_class_hash };
+
+    let stored_compiled = CompiledSierra("{}".into());
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![ConvertedClass::Sierra(SierraConvertedClass {
+                class_hash,
+                info: info.clone(),
+                compiled: Some(Arc::new(stored_compiled.clone())),
+            })],
+            None,
+            None,
+        )
+        .unwrap();
+
+    // The stored compiled class is the only usable one: it is kept.
+    assert!(matches!(
+        backend.invalidate_sierra_compiled(&[class_hash]),
+        Err(MadaraStorageError::CompilationClassError(_))
+    ));
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(backend.get_sierra_compiled(&latest, &compiled_class_hash).unwrap(), Some(stored_compiled.clone()));
+    assert_eq!(backend.get_or_compile_sierra_compiled(&latest, &class_hash, &info).unwrap(), stored_compiled);
+}
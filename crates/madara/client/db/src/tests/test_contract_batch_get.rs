@@ -0,0 +1,58 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::db_block_id::DbBlockId;
+use mp_block::Header;
+use mp_state_update::{DeployedContractItem, NonceUpdate, StateDiff};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_contract_batch_get() {
+    let (a, b, unknown) = (Felt::from(0xa), Felt::from(0xb), Felt::from(0xc));
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff {
+                deployed_contracts: vec![
+                    DeployedContractItem { address: a, class_hash: Felt::from(0xa1) },
+                    DeployedContractItem { address: b, class_hash: Felt::from(0xb1) },
+                ],
+                nonces: vec![
+                    NonceUpdate { contract_address: a, nonce: Felt::from(1) },
+                    NonceUpdate { contract_address: b, nonce: Felt::from(2) },
+                ],
+                ..Default::default()
+            },
+            vec![],
+            None,
+            None,
+        )
+        .unwrap();
+    backend
+        .store_block(
+            pending_block_one(),
+            StateDiff { nonces: vec![NonceUpdate { contract_address: a, nonce: Felt::from(3) }], ..Default::default() },
+            vec![],
+            None,
+            None,
+        )
+        .unwrap();
+
+    let contracts = [a, b, unknown];
+    for id in [DbBlockId::Number(0), DbBlockId::Pending] {
+        let nonces = backend.get_contract_nonces_at(&id, &contracts).unwrap();
+        let class_hashes = backend.get_contract_class_hashes_at(&id, &contracts).unwrap();
+        for (i, contract) in contracts.iter().enumerate() {
+            assert_eq!(nonces[i], backend.get_contract_nonce_at(&id, contract).unwrap(), "{id:?} {contract:#x}");
+            assert_eq!(class_hashes[i], backend.get_contract_class_hash_at(&id, contract).unwrap());
+        }
+    }
+
+    // The pending value of `a` takes precedence over its history.
+    assert_eq!(
+        backend.get_contract_nonces_at(&DbBlockId::Pending, &contracts).unwrap(),
+        [Some(Felt::from(3)), Some(Felt::from(2)), None]
+    );
+}
@@ -0,0 +1,56 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::db_block_id::DbBlockId;
+use mp_block::Header;
+use mp_state_update::{DeployedContractItem, StateDiff};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_contract_deployed_at() {
+    let contract = Felt::from_hex_unchecked("0x5ca1ab1e");
+    let zero_class_contract = Felt::from_hex_unchecked("0xdeadbeef");
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    for block_number in 0..12 {
+        let state_diff = if block_number == 10 {
+            StateDiff {
+                deployed_contracts: vec![
+                    DeployedContractItem { address: contract, class_hash: Felt::ONE },
+                    DeployedContractItem { address: zero_class_contract, class_hash: Felt::ZERO },
+                ],
+                ..Default::default()
+            }
+        } else {
+            StateDiff::default()
+        };
+        backend
+            .store_block(
+                finalized_block_zero(Header { block_number, ..Default::default() }),
+                state_diff,
+                vec![],
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    assert!(!backend.is_contract_deployed_at(&DbBlockId::Number(9), &contract).unwrap());
+    assert!(backend.is_contract_deployed_at(&DbBlockId::Number(10), &contract).unwrap());
+    assert!(backend.is_contract_deployed_at(&DbBlockId::Number(11), &contract).unwrap());
+    assert_eq!(
+        backend.get_deployed_contract_class_hash_at(&DbBlockId::Number(10), &contract).unwrap(),
+        Some(Felt::ONE)
+    );
+
+    // A zero class hash is not a deployed contract.
+    assert!(!backend.is_contract_deployed_at(&DbBlockId::Number(11), &zero_class_contract).unwrap());
+    assert_eq!(
+        backend.get_contract_class_hash_at(&DbBlockId::Number(11), &zero_class_contract).unwrap(),
+        Some(Felt::ZERO)
+    );
+    assert_eq!(
+        backend.get_deployed_contract_class_hash_at(&DbBlockId::Number(11), &zero_class_contract).unwrap(),
+        None
+    );
+}
@@ -0,0 +1,36 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::{BlockId, BlockTag, Header};
+use mp_state_update::{DeployedContractItem, StateDiff};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_contract_has_entrypoint() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let (transfer, balance_of, approve) = (Felt::from(0x100), Felt::from(0x200), Felt::from(0x300));
+    let (class_hash, contract_address) = (Felt::from(0xc1a55), Felt::from(0xc0));
+
+    let state_diff = StateDiff {
+        deprecated_declared_classes: vec![class_hash],
+        deployed_contracts: vec![DeployedContractItem { address: contract_address, class_hash }],
+        ..Default::default()
+    };
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            state_diff,
+            vec![legacy_class_with_selectors(class_hash, &[transfer, balance_of])],
+            None,
+            None,
+        )
+        .unwrap();
+
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert!(backend.contract_has_entrypoint(&latest, &contract_address, &transfer).unwrap());
+    assert!(backend.contract_has_entrypoint(&latest, &contract_address, &balance_of).unwrap());
+    assert!(!backend.contract_has_entrypoint(&latest, &contract_address, &approve).unwrap());
+    // No contract deployed at this address.
+    assert!(!backend.contract_has_entrypoint(&latest, &Felt::from(0xdead), &transfer).unwrap());
+}
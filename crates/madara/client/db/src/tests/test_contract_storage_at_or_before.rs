@@ -0,0 +1,88 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::{Column, DatabaseExt, MadaraStorageError};
+use mp_block::Header;
+use mp_state_update::{ContractStorageDiffItem, StateDiff, StorageEntry};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_contract_storage_at_or_before() {
+    let contract = Felt::from_hex_unchecked("0x5ca1ab1e");
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    assert!(matches!(
+        backend.get_contract_storage_at_or_before(3, &contract, &Felt::ONE),
+        Err(MadaraStorageError::NoBlockAtOrBefore(3))
+    ));
+
+    // Only the blocks below block 4 are stored.
+    for block_number in 0..4 {
+        backend
+            .store_block(
+                finalized_block_zero(Header { block_number, ..Default::default() }),
+                StateDiff {
+                    storage_diffs: vec![ContractStorageDiffItem {
+                        address: contract,
+                        storage_entries: vec![StorageEntry { key: Felt::ONE, value: Felt::from(10 + block_number) }],
+                    }],
+                    ..Default::default()
+                },
+                vec![],
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    // The nearest earlier block is used, and reported.
+    assert_eq!(backend.get_contract_storage_at_or_before(7, &contract, &Felt::ONE).unwrap(), (3, Some(Felt::from(13))));
+    assert_eq!(backend.get_contract_storage_at_or_before(4, &contract, &Felt::ONE).unwrap(), (3, Some(Felt::from(13))));
+
+    // Stored blocks are used as is.
+    assert_eq!(backend.get_contract_storage_at_or_before(3, &contract, &Felt::ONE).unwrap(), (3, Some(Felt::from(13))));
+    assert_eq!(backend.get_contract_storage_at_or_before(1, &contract, &Felt::ONE).unwrap(), (1, Some(Felt::from(11))));
+    assert_eq!(backend.get_contract_storage_at_or_before(7, &contract, &Felt::TWO).unwrap(), (3, None));
+}
+
+#[tokio::test]
+async fn test_contract_storage_at_or_before_pruned() {
+    let contract = Felt::from_hex_unchecked("0x5ca1ab1e");
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    for block_number in 0..5 {
+        backend
+            .store_block(
+                finalized_block_zero(Header { block_number, ..Default::default() }),
+                StateDiff {
+                    storage_diffs: vec![ContractStorageDiffItem {
+                        address: contract,
+                        storage_entries: vec![StorageEntry { key: Felt::ONE, value: Felt::from(10 + block_number) }],
+                    }],
+                    ..Default::default()
+                },
+                vec![],
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    // The store is pruned below block 2, and block 3 is missing as well.
+    let col = backend.db.get_column(Column::BlockNToBlockInfo);
+    for block_n in [0u64, 1, 3] {
+        backend.db.delete_cf(&col, bincode::serialize(&block_n).unwrap()).unwrap();
+    }
+
+    // The nearest earlier block which is stored is used, and reported.
+    assert_eq!(backend.get_contract_storage_at_or_before(3, &contract, &Felt::ONE).unwrap(), (2, Some(Felt::from(12))));
+    assert_eq!(backend.get_contract_storage_at_or_before(7, &contract, &Felt::ONE).unwrap(), (4, Some(Felt::from(14))));
+    assert_eq!(backend.get_contract_storage_at_or_before(2, &contract, &Felt::ONE).unwrap(), (2, Some(Felt::from(12))));
+
+    // No block is stored at or before the requested one.
+    assert!(matches!(
+        backend.get_contract_storage_at_or_before(1, &contract, &Felt::ONE),
+        Err(MadaraStorageError::NoBlockAtOrBefore(1))
+    ));
+}
@@ -0,0 +1,43 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::{BlockId, Header};
+use mp_state_update::StateDiff;
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_contract_storage_modified_in_block() {
+    let contract = Felt::from_hex_unchecked("0x5ca1ab1e");
+    let other_contract = Felt::from_hex_unchecked("0xb0b");
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    for block_number in 0..=6 {
+        let storage_diffs = match block_number {
+            0 => vec![storage_diff(contract, &[(3, 30)])],
+            5 => vec![storage_diff(contract, &[(1, 10), (2, 20)]), storage_diff(other_contract, &[(1, 100)])],
+            6 => vec![storage_diff(contract, &[(1, 11)])],
+            _ => vec![],
+        };
+        backend
+            .store_block(
+                finalized_block_zero(Header { block_number, ..Default::default() }),
+                StateDiff { storage_diffs, ..Default::default() },
+                vec![],
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    // Only the slots written in block 5, not the cumulative storage of the contract.
+    assert_eq!(
+        backend.get_contract_storage_modified_in_block(&BlockId::Number(5), &contract).unwrap(),
+        Some(vec![(Felt::from(1), Felt::from(10)), (Felt::from(2), Felt::from(20))])
+    );
+    assert_eq!(
+        backend.get_contract_storage_modified_in_block(&BlockId::Number(6), &contract).unwrap(),
+        Some(vec![(Felt::from(1), Felt::from(11))])
+    );
+    assert_eq!(backend.get_contract_storage_modified_in_block(&BlockId::Number(4), &contract).unwrap(), Some(vec![]));
+    assert_eq!(backend.get_contract_storage_modified_in_block(&BlockId::Number(7), &contract).unwrap(), None);
+}
@@ -0,0 +1,58 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::{CountMode, EntryCounts};
+use mp_block::Header;
+use mp_state_update::{ContractStorageDiffItem, DeployedContractItem, StateDiff, StorageEntry};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_entry_counts() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let class_hashes = [Felt::from(0xc1), Felt::from(0xc2), Felt::from(0xc3)];
+    let (a, b) = (Felt::from(0xa), Felt::from(0xb));
+    let storage_diff = |address, key: u64, value: u64| ContractStorageDiffItem {
+        address,
+        storage_entries: vec![StorageEntry { key: Felt::from(key), value: Felt::from(value) }],
+    };
+
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff {
+                deprecated_declared_classes: class_hashes[..2].to_vec(),
+                deployed_contracts: vec![DeployedContractItem { address: a, class_hash: class_hashes[0] }],
+                storage_diffs: vec![storage_diff(a, 1, 10)],
+                ..Default::default()
+            },
+            class_hashes[..2].iter().copied().map(|class_hash| legacy_class(class_hash, vec![])).collect(),
+            None,
+            None,
+        )
+        .unwrap();
+    backend
+        .store_block(
+            finalized_block_one(),
+            StateDiff {
+                deprecated_declared_classes: class_hashes[2..].to_vec(),
+                deployed_contracts: vec![DeployedContractItem { address: b, class_hash: class_hashes[2] }],
+                // The same slot is written again: it is only counted once.
+                storage_diffs: vec![storage_diff(a, 1, 11), storage_diff(a, 2, 20), storage_diff(b, 1, 100)],
+                ..Default::default()
+            },
+            vec![legacy_class(class_hashes[2], vec![])],
+            None,
+            None,
+        )
+        .unwrap();
+    backend.flush().unwrap();
+
+    assert_eq!(backend.count_classes(CountMode::Exact).unwrap(), class_hashes.len() as u64);
+    assert_eq!(
+        backend.entry_counts(CountMode::Exact).unwrap(),
+        EntryCounts { classes: 3, contracts: 2, storage_slots: 3 }
+    );
+    // Estimates count every historical value as an entry.
+    assert!(backend.count_classes(CountMode::Estimate).unwrap() > 0);
+}
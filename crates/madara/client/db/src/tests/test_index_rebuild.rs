@@ -0,0 +1,81 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::{Column, DatabaseExt, DatabaseService, IndexKind, MadaraBackend};
+use mp_block::Header;
+use mp_state_update::StateDiff;
+use rocksdb::IteratorMode;
+use starknet_types_core::felt::Felt;
+
+fn index_keys(backend: &MadaraBackend, column: Column) -> Vec<Box<[u8]>> {
+    let col = backend.db.get_column(column);
+    backend.db.iterator_cf(&col, IteratorMode::Start).map(|kv| kv.unwrap().0).collect()
+}
+
+async fn backend_with_classes() -> (DatabaseService, Vec<Felt>) {
+    let db = temp_db().await;
+    let class_hashes: Vec<Felt> = (1..=3).map(|i| Felt::from(0xc1a55 + i)).collect();
+    let classes: Vec<_> = class_hashes
+        .iter()
+        .enumerate()
+        .map(|(i, &class_hash)| {
+            legacy_class_with_selectors(class_hash, &[Felt::from(0x100), Felt::from(0x200 + i as u64)])
+        })
+        .collect();
+    db.backend()
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff { deprecated_declared_classes: class_hashes.clone(), ..Default::default() },
+            classes,
+            None,
+            None,
+        )
+        .unwrap();
+    (db, class_hashes)
+}
+
+#[tokio::test]
+async fn test_rebuild_index() {
+    let (db, class_hashes) = backend_with_classes().await;
+    let backend = db.backend();
+    let expected = index_keys(backend, Column::ClassToSelectors);
+    assert_eq!(expected.len(), 6);
+
+    // Corrupt the index: drop the entries of a class, and add an entry for a class which does not exist.
+    let col = backend.db.get_column(Column::ClassToSelectors);
+    for key in &expected[..2] {
+        backend.db.delete_cf(&col, key).unwrap();
+    }
+    backend.db.put_cf(&col, [0x42u8; 64], []).unwrap();
+    assert_ne!(index_keys(backend, Column::ClassToSelectors), expected);
+
+    assert_eq!(backend.rebuild_index(IndexKind::ClassToSelectors).unwrap(), class_hashes.len());
+    assert_eq!(index_keys(backend, Column::ClassToSelectors), expected);
+}
+
+#[tokio::test]
+async fn test_rebuild_index_resumes() {
+    let (db, class_hashes) = backend_with_classes().await;
+    let backend = db.backend();
+    let expected = index_keys(backend, Column::ClassToSelectors);
+
+    // A rebuild was interrupted after indexing the first class.
+    let col_info = backend.db.get_column(Column::ClassInfo);
+    let first_key = backend.db.iterator_cf(&col_info, IteratorMode::Start).next().unwrap().unwrap().0;
+    let col_meta = backend.db.get_column(Column::BlockStorageMeta);
+    backend
+        .db
+        .put_cf(
+            &col_meta,
+            IndexKind::ClassToSelectors.progress_row(),
+            bincode::serialize(&Some(first_key.to_vec())).unwrap(),
+        )
+        .unwrap();
+
+    // Only the remaining classes are indexed, and the progress is cleared once done.
+    assert_eq!(backend.rebuild_index(IndexKind::ClassToSelectors).unwrap(), class_hashes.len() - 1);
+    assert_eq!(index_keys(backend, Column::ClassToSelectors), expected);
+    assert!(backend.db.get_cf(&col_meta, IndexKind::ClassToSelectors.progress_row()).unwrap().is_none());
+
+    // The next rebuild starts over.
+    assert_eq!(backend.rebuild_index(IndexKind::ClassToSelectors).unwrap(), class_hashes.len());
+}
@@ -0,0 +1,48 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::{BlockId, BlockTag, Header};
+use mp_class::{ConvertedClass, FlattenedSierraClass, SierraClassInfo, SierraConvertedClass};
+use starknet_core::types::contract::SierraClass;
+use std::sync::Arc;
+
+const ERC20_CLASS_DEFINITION: &[u8] =
+    include_bytes!("../../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json");
+
+#[tokio::test]
+async fn test_lazy_casm_compilation() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let class: SierraClass = serde_json::from_slice(ERC20_CLASS_DEFINITION).unwrap();
+    let contract_class: FlattenedSierraClass = class.flatten().unwrap().into();
+    let class_hash = contract_class.compute_class_hash().unwrap();
+    let (compiled_class_hash, compiled) = contract_class.compile_to_casm().unwrap();
+
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![ConvertedClass::Sierra(SierraConvertedClass {
+                class_hash,
+                info: SierraClassInfo { contract_class: Arc::new(contract_class), compiled_class_hash },
+                compiled: None,
+            })],
+            None,
+            None,
+        )
+        .unwrap();
+
+    // Nothing is compiled until the class is requested.
+    let latest = BlockId::Tag(BlockTag::Latest);
+    assert_eq!(backend.get_sierra_compiled(&latest, &compiled_class_hash).unwrap(), None);
+    assert_eq!(backend.get_sierra_compiler_version(&compiled_class_hash).unwrap(), None);
+
+    let Some(ConvertedClass::Sierra(converted)) = backend.get_converted_class(&latest, &class_hash).unwrap() else {
+        panic!("expected a sierra class")
+    };
+    assert_eq!(converted.compiled, Some(Arc::new(compiled.clone())));
+
+    // The compiled class has been stored on first use.
+    assert_eq!(backend.get_sierra_compiled(&latest, &compiled_class_hash).unwrap(), Some(compiled));
+    assert!(backend.get_sierra_compiler_version(&compiled_class_hash).unwrap().is_some());
+}
@@ -0,0 +1,48 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::Header;
+use mp_state_update::{
+    ContractStorageDiffItem, DeclaredClassItem, DeployedContractItem, NonceUpdate, StateDiff, StorageEntry,
+};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_state_diff_json() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let state_diff = StateDiff {
+        storage_diffs: vec![ContractStorageDiffItem {
+            address: Felt::from_hex_unchecked("0xc0"),
+            storage_entries: vec![StorageEntry {
+                key: Felt::from_hex_unchecked("0x1"),
+                value: Felt::from_hex_unchecked("0x2a"),
+            }],
+        }],
+        deprecated_declared_classes: vec![Felt::from_hex_unchecked("0x1e9")],
+        declared_classes: vec![DeclaredClassItem {
+            class_hash: Felt::from_hex_unchecked("0xc1a55"),
+            compiled_class_hash: Felt::from_hex_unchecked("0xca5e"),
+        }],
+        deployed_contracts: vec![DeployedContractItem {
+            address: Felt::from_hex_unchecked("0xc0"),
+            class_hash: Felt::from_hex_unchecked("0xc1a55"),
+        }],
+        replaced_classes: vec![],
+        nonces: vec![NonceUpdate { contract_address: Felt::from_hex_unchecked("0xacc"), nonce: Felt::ONE }],
+    };
+    backend.store_block(finalized_block_zero(Header::default()), state_diff, vec![], None, None).unwrap();
+
+    assert_eq!(
+        backend.state_diff_json(0).unwrap().unwrap(),
+        serde_json::json!({
+            "storage_diffs": [{ "address": "0xc0", "storage_entries": [{ "key": "0x1", "value": "0x2a" }] }],
+            "deprecated_declared_classes": ["0x1e9"],
+            "declared_classes": [{ "class_hash": "0xc1a55", "compiled_class_hash": "0xca5e" }],
+            "deployed_contracts": [{ "address": "0xc0", "class_hash": "0xc1a55" }],
+            "replaced_classes": [],
+            "nonces": [{ "contract_address": "0xacc", "nonce": "0x1" }],
+        })
+    );
+    assert_eq!(backend.state_diff_json(1).unwrap(), None);
+}
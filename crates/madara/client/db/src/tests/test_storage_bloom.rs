@@ -0,0 +1,37 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::{BlockId, BlockTag, Header};
+use mp_state_update::StateDiff;
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_storage_bloom_filter() {
+    let contract = Felt::from_hex_unchecked("0x5ca1ab1e");
+    let latest = BlockId::Tag(BlockTag::Latest);
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    let state_diff_zero = StateDiff { storage_diffs: vec![storage_diff(contract, &[(1, 10)])], ..Default::default() };
+    backend.store_block(finalized_block_zero(Header::default()), state_diff_zero, vec![], None, None).unwrap();
+
+    // Keys already in the database are inserted when enabling the filter.
+    backend.enable_storage_bloom_filter(1_000, 0.0001).unwrap();
+    let filter = backend.storage_bloom_filter().unwrap();
+
+    // A definite miss does not touch the database.
+    assert_eq!(backend.get_contract_storage_at(&latest, &contract, &Felt::from(2)).unwrap(), None);
+    assert_eq!(filter.short_circuited(), 1);
+    assert_eq!(filter.passed_through(), 0);
+
+    // A populated key goes to the database.
+    assert_eq!(backend.get_contract_storage_at(&latest, &contract, &Felt::from(1)).unwrap(), Some(Felt::from(10)));
+    assert_eq!(filter.short_circuited(), 1);
+    assert_eq!(filter.passed_through(), 1);
+
+    // Keys written after the filter is enabled are inserted too.
+    let state_diff_one = StateDiff { storage_diffs: vec![storage_diff(contract, &[(2, 20)])], ..Default::default() };
+    backend.store_block(finalized_block_one(), state_diff_one, vec![], None, None).unwrap();
+    assert_eq!(backend.get_contract_storage_at(&latest, &contract, &Felt::from(2)).unwrap(), Some(Felt::from(20)));
+    assert_eq!(filter.passed_through(), 2);
+    assert!(backend.enable_storage_bloom_filter(1_000, 0.0001).is_err());
+}
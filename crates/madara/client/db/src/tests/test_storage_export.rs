@@ -0,0 +1,66 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::db_block_id::DbBlockId;
+use mp_block::Header;
+use mp_state_update::StateDiff;
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_export_import_contract_storage() {
+    let contract = Felt::from_hex_unchecked("0x5ca1ab1e");
+    let other_contract = Felt::from_hex_unchecked("0x5ca1ab1f");
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    let state_diff_zero = StateDiff {
+        storage_diffs: vec![
+            storage_diff(contract, &[(1, 10), (2, 20), (3, 30)]),
+            storage_diff(other_contract, &[(1, 100)]),
+        ],
+        ..Default::default()
+    };
+    let state_diff_one = StateDiff {
+        storage_diffs: vec![storage_diff(contract, &[(2, 21), (4, 40)]), storage_diff(other_contract, &[(5, 500)])],
+        ..Default::default()
+    };
+    backend.store_block(finalized_block_zero(Header::default()), state_diff_zero, vec![], None, None).unwrap();
+    backend.store_block(finalized_block_one(), state_diff_one, vec![], None, None).unwrap();
+
+    let mut snapshot_zero = vec![];
+    assert_eq!(backend.export_contract_storage(&contract, 0, &mut snapshot_zero).unwrap(), 3);
+    let mut snapshot_one = vec![];
+    assert_eq!(backend.export_contract_storage(&contract, 1, &mut snapshot_one).unwrap(), 4);
+
+    let fresh_db = temp_db().await;
+    let fresh_backend = fresh_db.backend();
+    assert_eq!(fresh_backend.import_contract_storage(&contract, 0, snapshot_one.as_slice()).unwrap(), 4);
+
+    for key in [1u64, 2, 3, 4] {
+        let key = Felt::from(key);
+        assert_eq!(
+            fresh_backend.get_contract_storage_at(&DbBlockId::Number(0), &contract, &key).unwrap(),
+            backend.get_contract_storage_at(&DbBlockId::Number(1), &contract, &key).unwrap(),
+        );
+    }
+    assert_eq!(
+        fresh_backend.get_contract_storage_at(&DbBlockId::Number(0), &other_contract, &Felt::ONE).unwrap(),
+        None
+    );
+
+    let mut reexported = vec![];
+    assert_eq!(fresh_backend.export_contract_storage(&contract, 0, &mut reexported).unwrap(), 4);
+    assert_eq!(reexported, snapshot_one);
+
+    // Values at block 0 do not include the block 1 updates.
+    let fresh_db = temp_db().await;
+    let fresh_backend = fresh_db.backend();
+    fresh_backend.import_contract_storage(&contract, 0, snapshot_zero.as_slice()).unwrap();
+    assert_eq!(
+        fresh_backend.get_contract_storage_at(&DbBlockId::Number(0), &contract, &Felt::TWO).unwrap(),
+        Some(Felt::from(20))
+    );
+    assert_eq!(fresh_backend.get_contract_storage_at(&DbBlockId::Number(0), &contract, &Felt::from(4)).unwrap(), None);
+
+    // Truncated snapshot
+    assert!(fresh_backend.import_contract_storage(&contract, 0, &snapshot_one[..snapshot_one.len() - 1]).is_err());
+}
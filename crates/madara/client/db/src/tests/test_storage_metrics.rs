@@ -0,0 +1,61 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::db_block_id::DbBlockId;
+use mp_block::Header;
+use mp_state_update::{ContractStorageDiffItem, DeployedContractItem, NonceUpdate, StateDiff, StorageEntry};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_storage_metrics() {
+    const N: u64 = 7;
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    let contract = Felt::from_hex_unchecked("0x1234");
+    let unknown = Felt::from_hex_unchecked("0x5678");
+
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff {
+                storage_diffs: vec![ContractStorageDiffItem {
+                    address: contract,
+                    storage_entries: vec![StorageEntry { key: Felt::ONE, value: Felt::TWO }],
+                }],
+                deployed_contracts: vec![DeployedContractItem { address: contract, class_hash: Felt::THREE }],
+                nonces: vec![NonceUpdate { contract_address: contract, nonce: Felt::ONE }],
+                ..Default::default()
+            },
+            vec![],
+            None,
+            None,
+        )
+        .unwrap();
+
+    let metrics = backend.storage_metrics();
+    let block_id = DbBlockId::Number(0);
+    for _ in 0..N {
+        assert_eq!(backend.get_contract_storage_at(&block_id, &contract, &Felt::ONE).unwrap(), Some(Felt::TWO));
+    }
+    assert_eq!((metrics.contract_storage.hits(), metrics.contract_storage.misses()), (N, 0));
+    // Only the storage column was read.
+    assert_eq!(metrics.contract_nonces.reads(), 0);
+    assert_eq!(metrics.contract_class_hash.reads(), 0);
+    assert_eq!(metrics.class_data.reads(), 0);
+    assert!(metrics.history_reads() >= N);
+    assert_eq!(metrics.class_reads(), 0);
+
+    for _ in 0..N {
+        assert_eq!(backend.get_contract_storage_at(&block_id, &unknown, &Felt::ONE).unwrap(), None);
+        assert_eq!(backend.get_contract_nonce_at(&block_id, &contract).unwrap(), Some(Felt::ONE));
+        assert_eq!(backend.get_contract_class_hash_at(&block_id, &unknown).unwrap(), None);
+        assert_eq!(backend.get_class_info(&block_id, &Felt::THREE).unwrap(), None);
+    }
+    assert_eq!((metrics.contract_storage.hits(), metrics.contract_storage.misses()), (N, N));
+    assert_eq!((metrics.contract_nonces.hits(), metrics.contract_nonces.misses()), (N, 0));
+    assert_eq!((metrics.contract_class_hash.hits(), metrics.contract_class_hash.misses()), (0, N));
+    assert_eq!((metrics.class_data.hits(), metrics.class_data.misses()), (0, N));
+
+    #[cfg(feature = "storage-read-latency")]
+    assert_eq!(metrics.contract_storage.latency_histogram().iter().sum::<u64>(), 2 * N);
+}
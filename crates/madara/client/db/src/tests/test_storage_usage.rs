@@ -0,0 +1,52 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::Header;
+use mp_state_update::{
+    ContractStorageDiffItem, DeclaredClassItem, DeployedContractItem, NonceUpdate, StateDiff, StorageEntry,
+};
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_storage_usage_report() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let contract = Felt::from_hex_unchecked("0x781623786");
+    let class_hash = Felt::from_hex_unchecked("0x9100000001");
+    let state_diff = StateDiff {
+        storage_diffs: vec![ContractStorageDiffItem {
+            address: contract,
+            storage_entries: vec![StorageEntry { key: Felt::ONE, value: Felt::TWO }],
+        }],
+        deprecated_declared_classes: vec![],
+        declared_classes: vec![DeclaredClassItem { class_hash, compiled_class_hash: Felt::THREE }],
+        deployed_contracts: vec![DeployedContractItem { address: contract, class_hash }],
+        replaced_classes: vec![],
+        nonces: vec![NonceUpdate { contract_address: contract, nonce: Felt::ONE }],
+    };
+
+    backend.store_block(finalized_block_zero(Header::default()), state_diff, vec![], None, None).unwrap();
+    backend.flush().unwrap();
+
+    let usage = backend.storage_usage_report().unwrap();
+
+    assert!(usage.blocks > 0);
+    assert!(usage.state_diffs > 0);
+    assert!(usage.indexes > 0);
+    assert!(usage.contract_class_hashes > 0);
+    assert!(usage.nonces > 0);
+    assert!(usage.contract_storage > 0);
+    assert_eq!(
+        usage.total(),
+        usage.blocks
+            + usage.state_diffs
+            + usage.indexes
+            + usage.classes
+            + usage.compiled_classes
+            + usage.contract_class_hashes
+            + usage.nonces
+            + usage.contract_storage
+            + usage.tries
+            + usage.other
+    );
+}
@@ -0,0 +1,43 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use mp_block::Header;
+use mp_state_update::StateDiff;
+use starknet_types_core::felt::Felt;
+
+#[tokio::test]
+async fn test_stream_storage_changes() {
+    let (a, b) = (Felt::from_hex_unchecked("0xa"), Felt::from_hex_unchecked("0xb"));
+
+    let db = temp_db().await;
+    let backend = db.backend();
+    for block_number in 0..=3 {
+        let storage_diffs = match block_number {
+            0 => vec![storage_diff(a, &[(1, 10)])],
+            1 => vec![storage_diff(a, &[(1, 11), (2, 20)]), storage_diff(b, &[(1, 100)])],
+            3 => vec![storage_diff(b, &[(1, 101)])],
+            _ => vec![],
+        };
+        backend
+            .store_block(
+                finalized_block_zero(Header { block_number, ..Default::default() }),
+                StateDiff { storage_diffs, ..Default::default() },
+                vec![],
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    let change =
+        |block_n: u64, address: Felt, key: u64, value: u64| (block_n, address, Felt::from(key), Felt::from(value));
+    let changes = |blocks| backend.stream_storage_changes(blocks).collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(
+        changes(0..=3),
+        vec![change(0, a, 1, 10), change(1, a, 1, 11), change(1, a, 2, 20), change(1, b, 1, 100), change(3, b, 1, 101)]
+    );
+    assert_eq!(changes(1..=2), vec![change(1, a, 1, 11), change(1, a, 2, 20), change(1, b, 1, 100)]);
+    assert_eq!(changes(2..=2), vec![]);
+    // The stream ends at the first block which does not exist.
+    assert_eq!(changes(3..=10), vec![change(3, b, 1, 101)]);
+}
@@ -0,0 +1,143 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::block_db::ROW_SYNC_TIP;
+use crate::{Column, DatabaseExt, DatabaseService, SyncTipCheck};
+use mp_block::Header;
+use mp_state_update::{ContractStorageDiffItem, DeployedContractItem, NonceUpdate, StateDiff, StorageEntry};
+use starknet_types_core::felt::Felt;
+
+const CLASS_HASH: Felt = Felt::from_hex_unchecked("0xc1a55");
+const CONTRACT: Felt = Felt::from_hex_unchecked("0xc0");
+const STORAGE_KEY: Felt = Felt::from_hex_unchecked("0x5");
+
+/// Stores blocks 0 and 1, block 0 deploying [`CONTRACT`] and block 1 declaring [`CLASS_HASH`] and updating the
+/// nonce and storage of [`CONTRACT`].
+async fn backend_with_two_blocks() -> DatabaseService {
+    let db = temp_db().await;
+    let backend = db.backend();
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            StateDiff {
+                deployed_contracts: vec![DeployedContractItem { address: CONTRACT, class_hash: Felt::ONE }],
+                ..Default::default()
+            },
+            vec![],
+            None,
+            None,
+        )
+        .unwrap();
+    backend
+        .store_block(
+            finalized_block_one(),
+            StateDiff {
+                deprecated_declared_classes: vec![CLASS_HASH],
+                nonces: vec![NonceUpdate { contract_address: CONTRACT, nonce: Felt::ONE }],
+                storage_diffs: vec![ContractStorageDiffItem {
+                    address: CONTRACT,
+                    storage_entries: vec![StorageEntry { key: STORAGE_KEY, value: Felt::TWO }],
+                }],
+                ..Default::default()
+            },
+            vec![legacy_class(CLASS_HASH, vec![])],
+            None,
+            None,
+        )
+        .unwrap();
+    db
+}
+
+/// Key of an update of `contract` in block `block_n`, for the contract columns.
+fn contract_update_key(contract: Felt, storage_key: Option<Felt>, block_n: u32) -> Vec<u8> {
+    let mut key = contract.to_bytes_be().to_vec();
+    if let Some(storage_key) = storage_key {
+        key.extend(storage_key.to_bytes_be());
+    }
+    key.extend(block_n.to_be_bytes());
+    key
+}
+
+#[tokio::test]
+async fn test_sync_tip_consistent() {
+    let db = backend_with_two_blocks().await;
+    let backend = db.backend();
+
+    let expected = SyncTipCheck { sync_tip: Some(1), consistent_tip: Some(1), repaired: false };
+    assert_eq!(backend.check_sync_tip(1, true).unwrap(), expected);
+    assert_eq!(backend.check_sync_tip(10, true).unwrap(), expected);
+}
+
+#[tokio::test]
+async fn test_sync_tip_ahead_of_blocks() {
+    let db = backend_with_two_blocks().await;
+    let backend = db.backend();
+    let col = backend.db.get_column(Column::BlockStorageMeta);
+    backend.db.put_cf(&col, ROW_SYNC_TIP, bincode::serialize(&3u64).unwrap()).unwrap();
+
+    // Only reported without repair.
+    let check = backend.check_sync_tip(1, false).unwrap();
+    assert_eq!(check, SyncTipCheck { sync_tip: Some(3), consistent_tip: Some(1), repaired: false });
+    assert!(!check.is_consistent());
+    assert_eq!(backend.get_latest_block_n().unwrap(), Some(3));
+
+    let check = backend.check_sync_tip(1, true).unwrap();
+    assert_eq!(check, SyncTipCheck { sync_tip: Some(3), consistent_tip: Some(1), repaired: true });
+    assert_eq!(backend.get_latest_block_n().unwrap(), Some(1));
+    assert!(backend.check_sync_tip(1, false).unwrap().is_consistent());
+}
+
+#[tokio::test]
+async fn test_sync_tip_missing_class() {
+    let db = backend_with_two_blocks().await;
+    let backend = db.backend();
+    let col = backend.db.get_column(Column::ClassInfo);
+    backend.db.delete_cf(&col, bincode::serialize(&CLASS_HASH).unwrap()).unwrap();
+
+    let check = backend.check_sync_tip(1, true).unwrap();
+    assert_eq!(check, SyncTipCheck { sync_tip: Some(1), consistent_tip: Some(0), repaired: true });
+    assert_eq!(backend.get_latest_block_n().unwrap(), Some(0));
+}
+
+#[tokio::test]
+async fn test_sync_tip_missing_contract_update() {
+    let updates = [
+        (Column::ContractToNonces, contract_update_key(CONTRACT, None, 1)),
+        (Column::ContractStorage, contract_update_key(CONTRACT, Some(STORAGE_KEY), 1)),
+    ];
+    for (column, key) in updates {
+        let db = backend_with_two_blocks().await;
+        let backend = db.backend();
+        let col = backend.db.get_column(column);
+        assert!(backend.db.get_pinned_cf(&col, &key).unwrap().is_some());
+        backend.db.delete_cf(&col, &key).unwrap();
+
+        let check = backend.check_sync_tip(1, true).unwrap();
+        assert_eq!(check, SyncTipCheck { sync_tip: Some(1), consistent_tip: Some(0), repaired: true });
+    }
+}
+
+#[tokio::test]
+async fn test_sync_tip_depth() {
+    let db = backend_with_two_blocks().await;
+    let backend = db.backend();
+    let col = backend.db.get_column(Column::ContractToClassHashes);
+    backend.db.delete_cf(&col, contract_update_key(CONTRACT, None, 0)).unwrap();
+
+    // Block 0 is only checked when the depth covers it.
+    assert!(backend.check_sync_tip(1, false).unwrap().is_consistent());
+    let check = backend.check_sync_tip(2, true).unwrap();
+    assert_eq!(check, SyncTipCheck { sync_tip: Some(1), consistent_tip: None, repaired: true });
+    assert_eq!(backend.get_latest_block_n().unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_sync_tip_no_block_stored() {
+    let db = temp_db().await;
+    let backend = db.backend();
+    let col = backend.db.get_column(Column::BlockStorageMeta);
+    backend.db.put_cf(&col, ROW_SYNC_TIP, bincode::serialize(&0u64).unwrap()).unwrap();
+
+    let check = backend.check_sync_tip(1, true).unwrap();
+    assert_eq!(check, SyncTipCheck { sync_tip: Some(0), consistent_tip: None, repaired: true });
+    assert_eq!(backend.get_latest_block_n().unwrap(), None);
+}
@@ -0,0 +1,118 @@
+use super::common::temp_db::temp_db;
+use super::common::*;
+use crate::{ClassVerificationIssue, Column, DatabaseExt};
+use mp_block::Header;
+use mp_class::{ConvertedClass, FlattenedSierraClass, SierraClassInfo, SierraConvertedClass};
+use starknet_core::types::contract::SierraClass;
+use starknet_types_core::felt::Felt;
+use std::sync::Arc;
+
+const ERC20_CLASS_DEFINITION: &[u8] =
+    include_bytes!("../../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json");
+const ACCOUNT_CLASS_DEFINITION: &[u8] =
+    include_bytes!("../../../../../../cairo-artifacts/openzeppelin_AccountUpgradeable.contract_class.json");
+
+fn sierra_class(definition: &[u8]) -> SierraConvertedClass {
+    let class: SierraClass = serde_json::from_slice(definition).unwrap();
+    let contract_class: FlattenedSierraClass = class.flatten().unwrap().into();
+    let class_hash = contract_class.compute_class_hash().unwrap();
+    let (compiled_class_hash, compiled) = contract_class.compile_to_casm().unwrap();
+    SierraConvertedClass {
+        class_hash,
+        info: SierraClassInfo { contract_class: Arc::new(contract_class), compiled_class_hash },
+        compiled: Some(Arc::new(compiled)),
+    }
+}
+
+#[tokio::test]
+async fn test_verify_all_classes_flags_wrong_compiled_class_hash() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let valid_class = sierra_class(ERC20_CLASS_DEFINITION);
+    let mut corrupted_class = sierra_class(ACCOUNT_CLASS_DEFINITION);
+    let compiled_class_hash = corrupted_class.info.compiled_class_hash;
+    let wrong_compiled_class_hash = Felt::from_hex_unchecked("0xbad");
+    corrupted_class.info.compiled_class_hash = wrong_compiled_class_hash;
+    let corrupted_class_hash = corrupted_class.class_hash;
+
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![ConvertedClass::Sierra(valid_class), ConvertedClass::Sierra(corrupted_class)],
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(
+        backend.verify_all_classes().unwrap(),
+        vec![ClassVerificationIssue::CompiledClassHashMismatch {
+            class_hash: corrupted_class_hash,
+            stored: wrong_compiled_class_hash,
+            recompiled: compiled_class_hash,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_verify_all_classes_accepts_deferred_compilation() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    // The compiled class was not stored, as with `lazy_casm_compilation`.
+    let deferred_class = SierraConvertedClass { compiled: None, ..sierra_class(ERC20_CLASS_DEFINITION) };
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![ConvertedClass::Sierra(deferred_class)],
+            None,
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(backend.verify_all_classes().unwrap(), vec![]);
+}
+
+#[tokio::test]
+async fn test_verify_all_classes_reports_corrupt_records() {
+    let db = temp_db().await;
+    let backend = db.backend();
+
+    let mut corrupted_class = sierra_class(ACCOUNT_CLASS_DEFINITION);
+    let compiled_class_hash = corrupted_class.info.compiled_class_hash;
+    corrupted_class.info.compiled_class_hash = Felt::from_hex_unchecked("0xbad");
+    let corrupted_class_hash = corrupted_class.class_hash;
+    backend
+        .store_block(
+            finalized_block_zero(Header::default()),
+            finalized_state_diff_zero(),
+            vec![ConvertedClass::Sierra(corrupted_class)],
+            None,
+            None,
+        )
+        .unwrap();
+
+    let col = backend.db.get_column(Column::ClassInfo);
+    let bad_key = vec![1, 2, 3];
+    backend.db.put_cf(&col, &bad_key, b"garbage").unwrap();
+    let bad_value_key = bincode::serialize(&Felt::from_hex_unchecked("0xc0ff33")).unwrap();
+    backend.db.put_cf(&col, &bad_value_key, b"garbage").unwrap();
+
+    // Every record is checked, the corrupt ones do not stop the verification.
+    let issues = backend.verify_all_classes().unwrap();
+    assert_eq!(issues.len(), 3, "{issues:?}");
+    assert!(issues.contains(&ClassVerificationIssue::CompiledClassHashMismatch {
+        class_hash: corrupted_class_hash,
+        stored: Felt::from_hex_unchecked("0xbad"),
+        recompiled: compiled_class_hash,
+    }));
+    for key in [bad_key, bad_value_key] {
+        assert!(
+            issues.iter().any(|issue| matches!(issue, ClassVerificationIssue::Corrupt { key: k, .. } if *k == key)),
+            "{issues:?}"
+        );
+    }
+}
@@ -101,10 +101,12 @@ impl InitiallyDeclaredClasses {
                     class_hash: c.class_hash,
                     contract_class: c.contract_class,
                     compiled_class_hash: c.compiled_class_hash,
+                    unknown_fields: Default::default(),
                 }),
                 InitiallyDeclaredClass::Legacy(c) => DeclaredClass::Legacy(LegacyDeclaredClass {
                     class_hash: c.class_hash,
                     contract_class: c.contract_class,
+                    unknown_fields: Default::default(),
                 }),
             })
             .collect()
@@ -183,10 +183,12 @@ impl ChainGenesisDescription {
 mod tests {
     use super::*;
     use assert_matches::assert_matches;
+    use blockifier::abi::abi_utils::get_fee_token_var_address;
     use mc_block_import::{BlockImporter, BlockValidationContext};
     use mc_block_production::metrics::BlockProductionMetrics;
     use mc_block_production::BlockProductionTask;
     use mc_db::MadaraBackend;
+    use mc_exec::ExecutionContext;
     use mc_mempool::{transaction_hash, L1DataProvider, Mempool, MockL1DataProvider};
     use mc_mempool::{MempoolLimits, MempoolProvider};
 
@@ -203,6 +205,7 @@ mod tests {
     use mp_transactions::compute_hash::calculate_contract_address;
     use mp_transactions::BroadcastedTransactionExt;
     use rstest::{fixture, rstest};
+    use starknet_api::core::ClassHash;
     use starknet_core::types::contract::SierraClass;
     use std::sync::Arc;
     use std::time::Duration;
@@ -349,6 +352,32 @@ mod tests {
         DevnetForTesting { backend, contracts, block_production, mempool }
     }
 
+    /// An unsigned transfer of `amount` STRK FRI from `from` to `to`.
+    fn transfer_tx(from: Felt, to: Felt, amount: u128, nonce: u64) -> InvokeTxnV3 {
+        InvokeTxnV3 {
+            sender_address: from,
+            calldata: Multicall::default()
+                .with(Call {
+                    to: ERC20_STRK_CONTRACT_ADDRESS,
+                    selector: Selector::from("transfer"),
+                    calldata: vec![to, amount.into(), Felt::ZERO],
+                })
+                .flatten()
+                .collect(),
+            signature: vec![],
+            nonce: nonce.into(),
+            resource_bounds: ResourceBoundsMapping {
+                l1_gas: ResourceBounds { max_amount: 60000, max_price_per_unit: 10000 },
+                l2_gas: ResourceBounds { max_amount: 60000, max_price_per_unit: 10000 },
+            },
+            tip: 0,
+            paymaster_data: vec![],
+            account_deployment_data: vec![],
+            nonce_data_availability_mode: DaMode::L1,
+            fee_data_availability_mode: DaMode::L1,
+        }
+    }
+
     #[rstest]
     #[case(m_cairo_test_contracts::TEST_CONTRACT_SIERRA)]
     fn test_erc_20_declare(mut chain: DevnetForTesting, #[case] contract: &[u8]) {
@@ -648,6 +677,395 @@ mod tests {
         }
     }
 
+    #[rstest]
+    fn test_reexecute_block(mut chain: DevnetForTesting) {
+        let contract_0 = &chain.contracts.0[0];
+        let contract_1 = &chain.contracts.0[1];
+
+        chain
+            .sign_and_add_invoke_tx(
+                BroadcastedInvokeTxn::V3(transfer_tx(contract_0.address, contract_1.address, 24235, 0)),
+                contract_0,
+            )
+            .unwrap();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            chain.block_production.set_current_pending_tick(1);
+            chain.block_production.on_pending_time_tick().await.unwrap();
+        });
+
+        let report = mc_exec::reexecute_block(&chain.backend, BlockId::Tag(BlockTag::Pending)).unwrap();
+        assert_eq!(report.transaction_count, 1);
+        assert_eq!(report.divergences, vec![]);
+        assert!(report.is_consistent());
+    }
+
+    #[rstest]
+    fn test_reexecute_closed_block(mut chain: DevnetForTesting) {
+        let contract_0 = &chain.contracts.0[0];
+        let contract_1 = &chain.contracts.0[1];
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        // Blocks 1 to 9 are empty, so that block 10 stores the hash of the genesis block in the block hash registry.
+        for _ in 1..10 {
+            rt.block_on(chain.block_production.on_block_time()).unwrap();
+        }
+        chain
+            .sign_and_add_invoke_tx(
+                BroadcastedInvokeTxn::V3(transfer_tx(contract_0.address, contract_1.address, 24235, 0)),
+                contract_0,
+            )
+            .unwrap();
+        rt.block_on(chain.block_production.on_block_time()).unwrap();
+        assert_eq!(chain.backend.get_latest_block_n().unwrap(), Some(10));
+
+        let report = mc_exec::reexecute_block(&chain.backend, BlockId::Number(10)).unwrap();
+        assert_eq!(report.transaction_count, 1);
+        assert_eq!(report.divergences, vec![]);
+
+        // The registry entry is part of the comparison.
+        let stored = chain.backend.get_block_state_diff(&BlockId::Number(10)).unwrap().unwrap();
+        let genesis_hash = chain.backend.get_block_hash(&BlockId::Number(0)).unwrap().unwrap();
+        assert!(stored.storage_diffs.iter().any(|item| item.address == Felt::ONE
+            && item.storage_entries.contains(&StorageEntry { key: Felt::ZERO, value: genesis_hash })));
+    }
+
+    #[rstest]
+    fn test_estimate_fee_sees_pending_storage(mut chain: DevnetForTesting) {
+        let contract_0 = &chain.contracts.0[0];
+        let contract_1 = &chain.contracts.0[1];
+        let chain_id = chain.backend.chain_config().chain_id.to_felt();
+        let protocol_version = chain.backend.chain_config().latest_protocol_version;
+
+        // The pending block moves most of the balance of contract 0 to contract 1.
+        chain
+            .sign_and_add_invoke_tx(
+                BroadcastedInvokeTxn::V3(transfer_tx(
+                    contract_0.address,
+                    contract_1.address,
+                    9_000 * STRK_FRI_DECIMALS,
+                    0,
+                )),
+                contract_0,
+            )
+            .unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            chain.block_production.set_current_pending_tick(1);
+            chain.block_production.on_pending_time_tick().await.unwrap();
+        });
+
+        // Contract 1 can only afford this transfer with the balance it received in the pending block.
+        let mut tx = transfer_tx(contract_1.address, contract_0.address, 15_000 * STRK_FRI_DECIMALS, 0);
+        let (unsigned, _) = BroadcastedTxn::Invoke(BroadcastedInvokeTxn::V3(tx.clone()))
+            .into_blockifier(chain_id, protocol_version)
+            .unwrap();
+        let signature = contract_1.secret.sign(&transaction_hash(&unsigned)).unwrap();
+        tx.signature = vec![signature.r, signature.s];
+        let estimate = |block_id: BlockId| {
+            let block_info = chain.backend.get_block_info(&block_id).unwrap().unwrap();
+            let exec_context = ExecutionContext::new_at_block_end(Arc::clone(&chain.backend), &block_info).unwrap();
+            let (tx, _) = BroadcastedTxn::Invoke(BroadcastedInvokeTxn::V3(tx.clone()))
+                .into_blockifier(chain_id, protocol_version)
+                .unwrap();
+            let mut results = exec_context.re_execute_transactions([], [tx], true, true).unwrap();
+            assert_eq!(results.len(), 1);
+            let result = results.pop().unwrap();
+            (result.execution_info.revert_error.clone(), exec_context.execution_result_to_fee_estimate(&result))
+        };
+
+        let (revert_error, fee_estimate) = estimate(BlockId::Tag(BlockTag::Pending));
+        assert_eq!(revert_error, None);
+        assert_ne!(fee_estimate.overall_fee, Felt::ZERO);
+
+        // The latest block does not have the pending transfer.
+        let (revert_error, _) = estimate(BlockId::Tag(BlockTag::Latest));
+        assert!(revert_error.unwrap().contains("ERC20: insufficient balance"));
+    }
+
+    #[rstest]
+    fn test_estimate_fee_matches_charged_fee(mut chain: DevnetForTesting) {
+        let contract_0 = &chain.contracts.0[0];
+        let contract_1 = &chain.contracts.0[1];
+        let chain_id = chain.backend.chain_config().chain_id.to_felt();
+        let protocol_version = chain.backend.chain_config().latest_protocol_version;
+
+        let mut tx = transfer_tx(contract_0.address, contract_1.address, 24235, 0);
+        let (unsigned, _) = BroadcastedTxn::Invoke(BroadcastedInvokeTxn::V3(tx.clone()))
+            .into_blockifier(chain_id, protocol_version)
+            .unwrap();
+        let signature = contract_0.secret.sign(&transaction_hash(&unsigned)).unwrap();
+        tx.signature = vec![signature.r, signature.s];
+        let tx = BroadcastedInvokeTxn::V3(tx);
+
+        chain.mempool.tx_accept_invoke(tx.clone()).unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            chain.block_production.set_current_pending_tick(1);
+            chain.block_production.on_pending_time_tick().await.unwrap();
+        });
+
+        let block = chain.backend.get_block(&BlockId::Tag(BlockTag::Pending)).unwrap().unwrap();
+        assert_eq!(block.inner.transactions.len(), 1);
+        let charged_fee = block.inner.receipts[0].actual_fee().amount;
+
+        // Estimate the transaction as it was signed and sent, on top of the state it was executed on. Like
+        // `starknet_estimateFee`, the fee is not charged.
+        let tx = BroadcastedTxn::Invoke(tx);
+        let exec_context = ExecutionContext::new_at_block_start(Arc::clone(&chain.backend), &block.info).unwrap();
+        let estimate = || {
+            let (tx, _) = tx.clone().into_blockifier(chain_id, protocol_version).unwrap();
+            let mut results = exec_context.re_execute_transactions([], [tx], false, true).unwrap();
+            let result = results.pop().unwrap();
+            assert!(!result.execution_info.is_reverted());
+            exec_context.execution_result_to_fee_estimate(&result)
+        };
+
+        assert_eq!(estimate().overall_fee, charged_fee);
+        // The same transaction, estimated again from the same base state.
+        assert_eq!(estimate().overall_fee, charged_fee);
+    }
+
+    #[rstest]
+    fn test_estimate_fee_batch_builds_on_previous_transactions(chain: DevnetForTesting) {
+        let contract_0 = &chain.contracts.0[0];
+        let chain_id = chain.backend.chain_config().chain_id.to_felt();
+        let protocol_version = chain.backend.chain_config().latest_protocol_version;
+        let resource_bounds = ResourceBoundsMapping {
+            l1_gas: ResourceBounds { max_amount: 60000, max_price_per_unit: 10000 },
+            l2_gas: ResourceBounds { max_amount: 60000, max_price_per_unit: 10000 },
+        };
+
+        // A new account, which is not funded: fees are not charged by estimates.
+        let pubkey = SigningKey::from_random().verifying_key().scalar();
+        let account_class_hash = contract_0.class_hash;
+        let account_address = calculate_contract_address(Felt::ZERO, account_class_hash, &[pubkey], Felt::ZERO);
+
+        let deploy_account = BroadcastedTxn::DeployAccount(BroadcastedDeployAccountTxn::V3(DeployAccountTxnV3 {
+            signature: vec![],
+            nonce: Felt::ZERO,
+            contract_address_salt: Felt::ZERO,
+            constructor_calldata: vec![pubkey],
+            class_hash: account_class_hash,
+            resource_bounds: resource_bounds.clone(),
+            tip: 0,
+            paymaster_data: vec![],
+            nonce_data_availability_mode: DaMode::L1,
+            fee_data_availability_mode: DaMode::L1,
+        }));
+        let invoke = |nonce: u64| {
+            BroadcastedTxn::Invoke(BroadcastedInvokeTxn::V3(InvokeTxnV3 {
+                sender_address: account_address,
+                calldata: Multicall::default()
+                    .with(Call {
+                        to: ERC20_STRK_CONTRACT_ADDRESS,
+                        selector: Selector::from("transfer"),
+                        calldata: vec![contract_0.address, Felt::ZERO, Felt::ZERO],
+                    })
+                    .flatten()
+                    .collect(),
+                signature: vec![],
+                nonce: nonce.into(),
+                resource_bounds: resource_bounds.clone(),
+                tip: 0,
+                paymaster_data: vec![],
+                account_deployment_data: vec![],
+                nonce_data_availability_mode: DaMode::L1,
+                fee_data_availability_mode: DaMode::L1,
+            }))
+        };
+        let to_blockifier = |tx: BroadcastedTxn| tx.into_blockifier(chain_id, protocol_version).unwrap().0;
+
+        let block_info = chain.backend.get_block_info(&BlockId::Tag(BlockTag::Latest)).unwrap().unwrap();
+        let exec_context = ExecutionContext::new_at_block_end(Arc::clone(&chain.backend), &block_info).unwrap();
+
+        // Deploying the account, then using it with sequential nonces, as wallets estimate a new account.
+        let results = exec_context
+            .execute_transactions_checking_senders(
+                [deploy_account, invoke(1), invoke(2)].map(to_blockifier),
+                false,
+                false,
+            )
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        for result in &results {
+            assert!(!result.execution_info.is_reverted(), "{:?}", result.execution_info.revert_error);
+        }
+
+        // On its own, the sender of the invoke transaction is not deployed.
+        assert_matches!(
+            exec_context.execute_transactions_checking_senders([to_blockifier(invoke(1))], false, false),
+            Err(mc_exec::Error::SenderNotDeployed { index: 0, sender_address }) if sender_address == account_address
+        );
+    }
+
+    #[rstest]
+    fn test_estimate_fee_blob_data_gas(chain: DevnetForTesting) {
+        let contract_0 = &chain.contracts.0[0];
+        let contract_1 = &chain.contracts.0[1];
+        let chain_id = chain.backend.chain_config().chain_id.to_felt();
+        let protocol_version = chain.backend.chain_config().latest_protocol_version;
+
+        let block_info = chain.backend.get_block_info(&BlockId::Tag(BlockTag::Latest)).unwrap().unwrap();
+        assert_eq!(block_info.as_nonpending().unwrap().header.l1_da_mode, L1DataAvailabilityMode::Blob);
+
+        let tx = BroadcastedTxn::Invoke(BroadcastedInvokeTxn::V3(transfer_tx(
+            contract_0.address,
+            contract_1.address,
+            24235,
+            0,
+        )));
+        let (tx, _) = tx.into_blockifier(chain_id, protocol_version).unwrap();
+
+        let exec_context = ExecutionContext::new_at_block_end(Arc::clone(&chain.backend), &block_info).unwrap();
+        let results = exec_context.re_execute_transactions([], [tx], false, false).unwrap();
+        let fee_estimate = exec_context.execution_result_to_fee_estimate(&results[0]);
+
+        assert_eq!(fee_estimate.unit, mp_rpc::PriceUnit::Fri);
+        // Prices of the genesis block.
+        assert_eq!(fee_estimate.gas_price, Felt::from(5));
+        assert_eq!(fee_estimate.data_gas_price, Felt::from(5));
+        assert_ne!(fee_estimate.gas_consumed, Felt::ZERO);
+        // The state diff of the transfer is paid for in data gas.
+        assert_ne!(fee_estimate.data_gas_consumed, Felt::ZERO);
+        assert_eq!(
+            fee_estimate.overall_fee,
+            fee_estimate.gas_consumed * fee_estimate.gas_price
+                + fee_estimate.data_gas_consumed * fee_estimate.data_gas_price
+        );
+    }
+
+    #[rstest]
+    fn test_simulate_transactions_stop_on_failure(chain: DevnetForTesting) {
+        let contract_0 = &chain.contracts.0[0];
+        let contract_1 = &chain.contracts.0[1];
+        let chain_id = chain.backend.chain_config().chain_id.to_felt();
+        let protocol_version = chain.backend.chain_config().latest_protocol_version;
+
+        let signed_transfer_tx = |nonce: u64, amount: u128| {
+            let mut tx = transfer_tx(contract_0.address, contract_1.address, amount, nonce);
+            let (unsigned, _) = BroadcastedTxn::Invoke(BroadcastedInvokeTxn::V3(tx.clone()))
+                .into_blockifier(chain_id, protocol_version)
+                .unwrap();
+            let signature = contract_0.secret.sign(&transaction_hash(&unsigned)).unwrap();
+            tx.signature = vec![signature.r, signature.s];
+            BroadcastedTxn::Invoke(BroadcastedInvokeTxn::V3(tx)).into_blockifier(chain_id, protocol_version).unwrap().0
+        };
+        // The second transfer is more than the account owns, and reverts.
+        let transactions = || {
+            [
+                signed_transfer_tx(0, 24235),
+                signed_transfer_tx(1, 10_001 * STRK_FRI_DECIMALS),
+                signed_transfer_tx(2, 24235),
+            ]
+        };
+
+        let block_info = chain.backend.get_block_info(&BlockId::Tag(BlockTag::Latest)).unwrap().unwrap();
+        let exec_context = ExecutionContext::new_at_block_end(Arc::clone(&chain.backend), &block_info).unwrap();
+
+        let results = exec_context.re_execute_transactions([], transactions(), true, true).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result.visited_pcs.is_none()));
+
+        let exec_context =
+            ExecutionContext::new_at_block_end(Arc::clone(&chain.backend), &block_info).unwrap().with_visited_pcs();
+        let results = exec_context.re_execute_transactions([], transactions(), true, true).unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].execution_info.is_reverted());
+        assert!(!results[2].execution_info.is_reverted());
+
+        // Each transaction reports the program counters it visited in the account and in the token contract.
+        for address in [contract_0.address, ERC20_STRK_CONTRACT_ADDRESS] {
+            let class_hash =
+                chain.backend.get_contract_class_hash_at(&BlockId::Tag(BlockTag::Latest), &address).unwrap();
+            let class_hash = ClassHash(class_hash.unwrap());
+            assert!(results[0].visited_pcs.as_ref().unwrap().get(&class_hash).is_some_and(|pcs| !pcs.is_empty()));
+        }
+
+        let results = exec_context.re_execute_transactions_until_failure([], transactions(), true, true).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(!results[0].execution_info.is_reverted());
+        assert!(results[1].execution_info.is_reverted());
+    }
+
+    #[rstest]
+    fn test_simulate_transactions_flags_leave_state_unchanged(chain: DevnetForTesting) {
+        let contract_0 = &chain.contracts.0[0];
+        let contract_1 = &chain.contracts.0[1];
+        let chain_id = chain.backend.chain_config().chain_id.to_felt();
+        let protocol_version = chain.backend.chain_config().latest_protocol_version;
+        let latest = BlockId::Tag(BlockTag::Latest);
+
+        let balance_key = get_fee_token_var_address(contract_0.address.try_into().unwrap()).to_felt();
+        let state = || {
+            (
+                chain.backend.get_latest_block_n().unwrap(),
+                chain.backend.get_contract_nonce_at(&latest, &contract_0.address).unwrap(),
+                chain.backend.get_contract_storage_at(&latest, &ERC20_STRK_CONTRACT_ADDRESS, &balance_key).unwrap(),
+            )
+        };
+        let state_before = state();
+
+        let mut tx = transfer_tx(contract_0.address, contract_1.address, 24235, 0);
+        let to_blockifier = |tx: &InvokeTxnV3| {
+            BroadcastedTxn::Invoke(BroadcastedInvokeTxn::V3(tx.clone()))
+                .into_blockifier(chain_id, protocol_version)
+                .unwrap()
+                .0
+        };
+        let unsigned = tx.clone();
+        let signature = contract_0.secret.sign(&transaction_hash(&to_blockifier(&tx))).unwrap();
+        tx.signature = vec![signature.r, signature.s];
+
+        let block_info = chain.backend.get_block_info(&latest).unwrap().unwrap();
+        let exec_context = ExecutionContext::new_at_block_end(Arc::clone(&chain.backend), &block_info).unwrap();
+
+        // `charge_fee` and `validate` are the negations of the `SKIP_FEE_CHARGE` and `SKIP_VALIDATE` flags.
+        for (charge_fee, validate) in [(true, true), (false, true), (true, false), (false, false)] {
+            let results = exec_context.re_execute_transactions([], [to_blockifier(&tx)], charge_fee, validate).unwrap();
+            assert!(!results[0].execution_info.is_reverted(), "charge_fee={charge_fee} validate={validate}");
+            assert_ne!(exec_context.execution_result_to_fee_estimate(&results[0]).overall_fee, Felt::ZERO);
+        }
+
+        // An unsigned transaction can only be simulated without validation.
+        assert!(exec_context.re_execute_transactions([], [to_blockifier(&unsigned)], true, true).is_err());
+        let results = exec_context.re_execute_transactions([], [to_blockifier(&unsigned)], true, false).unwrap();
+        assert!(!results[0].execution_info.is_reverted());
+
+        // Simulations are never written to the database.
+        assert_eq!(state(), state_before);
+    }
+
+    #[rstest]
+    fn test_call_contract_access_list(chain: DevnetForTesting) {
+        let contract_0 = &chain.contracts.0[0];
+
+        let block_info = chain.backend.get_block_info(&BlockId::Tag(BlockTag::Latest)).unwrap().unwrap();
+        let exec_context = ExecutionContext::new_at_block_end(Arc::clone(&chain.backend), &block_info).unwrap();
+
+        let (retdata, access_list) = exec_context
+            .call_contract_with_access_list(
+                &ERC20_STRK_CONTRACT_ADDRESS,
+                &starknet_core::utils::get_selector_from_name("balanceOf").unwrap(),
+                &[contract_0.address],
+            )
+            .unwrap();
+        assert_eq!(retdata, vec![(10_000 * STRK_FRI_DECIMALS).into(), Felt::ZERO]);
+
+        let balance_key = get_fee_token_var_address(contract_0.address.try_into().unwrap()).to_felt();
+        assert!(access_list.contracts.contains(&ERC20_STRK_CONTRACT_ADDRESS));
+        assert!(access_list.storage_keys.contains(&(ERC20_STRK_CONTRACT_ADDRESS, balance_key)));
+        let class_hash = chain
+            .backend
+            .get_contract_class_hash_at(&BlockId::Tag(BlockTag::Latest), &ERC20_STRK_CONTRACT_ADDRESS)
+            .unwrap()
+            .unwrap();
+        assert!(access_list.classes.contains(&class_hash));
+        // The account is only passed as calldata, its own state is not read.
+        assert!(!access_list.contracts.contains(&contract_0.address));
+    }
+
     #[rstest]
     fn test_mempool_tx_limit() {
         let chain = chain_with_mempool_limits(MempoolLimits {
@@ -0,0 +1,89 @@
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::state_api::{StateReader, StateResult};
+use mp_convert::ToFelt;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use crate::BlockifierStateAdapter;
+
+/// Every piece of state read by an execution, see [`RecordingStateReader`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessList {
+    /// Contracts whose nonce, class hash or storage was read.
+    pub contracts: BTreeSet<Felt>,
+    /// `(contract_address, key)` of the storage slots read.
+    pub storage_keys: BTreeSet<(Felt, Felt)>,
+    /// Classes whose definition or compiled class hash was read.
+    pub classes: BTreeSet<Felt>,
+}
+
+/// A [`StateReader`] recording every read made through it, for building access lists of transactions.
+///
+/// When wrapped in a blockifier [`CachedState`], only reads of the state the execution is made on top of reach
+/// this reader: values written by the execution itself are served by the [`CachedState`], and are not recorded.
+///
+/// [`CachedState`]: blockifier::state::cached_state::CachedState
+pub struct RecordingStateReader<S = BlockifierStateAdapter> {
+    inner: S,
+    access_list: Mutex<AccessList>,
+}
+
+impl<S: StateReader> RecordingStateReader<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner, access_list: Default::default() }
+    }
+
+    /// The reads recorded so far.
+    pub fn access_list(&self) -> AccessList {
+        self.access_list.lock().expect("Poisoned lock").clone()
+    }
+
+    pub fn into_access_list(self) -> AccessList {
+        self.access_list.into_inner().expect("Poisoned lock")
+    }
+
+    fn record(&self, f: impl FnOnce(&mut AccessList)) {
+        f(&mut self.access_list.lock().expect("Poisoned lock"))
+    }
+}
+
+impl<S: StateReader> StateReader for RecordingStateReader<S> {
+    fn get_storage_at(&self, contract_address: ContractAddress, key: StorageKey) -> StateResult<Felt> {
+        self.record(|access_list| {
+            access_list.contracts.insert(contract_address.to_felt());
+            access_list.storage_keys.insert((contract_address.to_felt(), key.to_felt()));
+        });
+        self.inner.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.record(|access_list| {
+            access_list.contracts.insert(contract_address.to_felt());
+        });
+        self.inner.get_nonce_at(contract_address)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.record(|access_list| {
+            access_list.contracts.insert(contract_address.to_felt());
+        });
+        self.inner.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.record(|access_list| {
+            access_list.classes.insert(class_hash.to_felt());
+        });
+        self.inner.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.record(|access_list| {
+            access_list.classes.insert(class_hash.to_felt());
+        });
+        self.inner.get_compiled_class_hash(class_hash)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{blockifier_state_adapter::BlockifierStateAdapter, Error};
+use crate::{blockifier_state_adapter::BlockifierStateAdapter, Error, RemoteStateReader, StateReadCache};
 use blockifier::{
     blockifier::{
         config::TransactionExecutorConfig, stateful_validator::StatefulValidator,
@@ -10,6 +10,8 @@ use blockifier::{
 use mc_db::{db_block_id::DbBlockId, MadaraBackend};
 use mp_block::{header::L1DataAvailabilityMode, MadaraMaybePendingBlockInfo};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::ClassHash;
+use starknet_types_core::felt::Felt;
 use std::sync::Arc;
 
 pub struct ExecutionContext {
@@ -17,6 +19,12 @@ pub struct ExecutionContext {
     pub(crate) block_context: BlockContext,
     /// None means we are executing the genesis block. (no latest block)
     pub(crate) latest_visible_block: Option<DbBlockId>,
+    /// Fallback for state which is missing from the local database.
+    pub(crate) remote_state: Option<Arc<dyn RemoteStateReader>>,
+    /// Reads shared by all of the states created from this context.
+    pub(crate) read_cache: Option<Arc<StateReadCache>>,
+    /// Report the program counters visited by each executed transaction, see [`Self::with_visited_pcs`].
+    pub(crate) collect_visited_pcs: bool,
 }
 
 impl ExecutionContext {
@@ -40,11 +48,92 @@ impl ExecutionContext {
             self.block_context.block_info().block_number.0
         );
 
-        CachedState::new(BlockifierStateAdapter::new(
+        CachedState::new(self.init_state_adapter())
+    }
+
+    pub(crate) fn init_state_adapter(&self) -> BlockifierStateAdapter {
+        BlockifierStateAdapter::new(
             Arc::clone(&self.backend),
             self.block_context.block_info().block_number.0,
             self.latest_visible_block,
-        ))
+        )
+        .with_remote_fallback(self.remote_state.clone())
+        .with_read_cache(self.read_cache.clone())
+    }
+
+    /// Fetch the state missing from the local database (for example, because it has been pruned) from a remote.
+    pub fn with_remote_state_reader(mut self, remote_state: Arc<dyn RemoteStateReader>) -> Self {
+        self.remote_state = Some(remote_state);
+        self
+    }
+
+    /// Share the reads of every state created from this context, for batch execution. Each state still has its
+    /// own write overlay.
+    pub fn with_shared_read_cache(mut self) -> Self {
+        let block_number = self.block_context.block_info().block_number.0;
+        self.read_cache = Some(Arc::new(StateReadCache::new(block_number, self.latest_visible_block)));
+        self
+    }
+
+    /// Report the program counters visited by each re-executed transaction in [`ExecutionResult::visited_pcs`], for
+    /// coverage tooling. They are not collected by default, as copying them for every transaction is costly.
+    ///
+    /// [`ExecutionResult::visited_pcs`]: crate::ExecutionResult::visited_pcs
+    pub fn with_visited_pcs(mut self) -> Self {
+        self.collect_visited_pcs = true;
+        self
+    }
+
+    pub fn read_cache(&self) -> Option<&Arc<StateReadCache>> {
+        self.read_cache.as_ref()
+    }
+
+    /// Load the given classes into the shared read cache ahead of execution, with a single database round-trip.
+    /// Returns the number of classes which were loaded. Does nothing when the context has no shared read cache,
+    /// see [`Self::with_shared_read_cache`].
+    ///
+    /// Classes which are not declared or cannot be converted are skipped: the error will be reported by the
+    /// execution which needs them, if any.
+    #[tracing::instrument(skip(self, class_hashes), fields(module = "ExecutionContext"))]
+    pub fn preload_classes(&self, class_hashes: &[Felt]) -> Result<usize, Error> {
+        let (Some(read_cache), Some(on_top_of_block_id)) = (&self.read_cache, self.latest_visible_block) else {
+            return Ok(0);
+        };
+
+        let mut loaded = 0;
+        for converted_class in
+            self.backend.get_converted_classes(&on_top_of_block_id, class_hashes)?.into_iter().flatten()
+        {
+            let class_hash = converted_class.class_hash();
+            match converted_class.to_blockifier_class() {
+                Ok(class) => {
+                    read_cache.preload_compiled_class(ClassHash(class_hash), class);
+                    loaded += 1;
+                }
+                Err(err) => tracing::warn!("Failed to convert class {class_hash:#x} to blockifier format: {err:#}"),
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Same as [`Self::preload_classes`], for the classes of the contracts at the given addresses. The class hashes
+    /// are read in a batch, see [`MadaraBackend::get_contract_class_hashes_at`]. Contracts which are not deployed are
+    /// skipped.
+    #[tracing::instrument(skip(self, contract_addresses), fields(module = "ExecutionContext"))]
+    pub fn preload_contract_classes(&self, contract_addresses: &[Felt]) -> Result<usize, Error> {
+        let (Some(_), Some(on_top_of_block_id)) = (&self.read_cache, self.latest_visible_block) else {
+            return Ok(0);
+        };
+
+        let mut class_hashes: Vec<_> = self
+            .backend
+            .get_contract_class_hashes_at(&on_top_of_block_id, contract_addresses)?
+            .into_iter()
+            .flatten()
+            .collect();
+        class_hashes.sort();
+        class_hashes.dedup();
+        self.preload_classes(&class_hashes)
     }
 
     /// Init execution at the beginning of a block. The header of the block will be used, but all of the
@@ -79,6 +168,9 @@ impl ExecutionContext {
     /// This is essentially as if we're executing on top of the block after all of the transactions
     /// are executed, but before we switched to making a new block.
     ///
+    /// On the pending block, state reads see the pending state diff on top of the latest block, so that fee
+    /// estimates reflect the transactions which are not in a closed block yet.
+    ///
     /// This function is usually what you would want for the `estimateFee`, `simulateTransaction`, `call` rpc endpoints, for example.
     #[tracing::instrument(skip(backend, block_info), fields(module = "ExecutionContext"))]
     pub fn new_at_block_end(
@@ -146,6 +238,9 @@ impl ExecutionContext {
                 backend.chain_config().bouncer_config.clone(),
             ),
             latest_visible_block,
+            remote_state: None,
+            read_cache: None,
+            collect_visited_pcs: false,
             backend,
         })
     }
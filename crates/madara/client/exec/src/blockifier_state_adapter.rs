@@ -1,45 +1,163 @@
 use blockifier::execution::contract_class::ContractClass;
 use blockifier::state::errors::StateError;
 use blockifier::state::state_api::{StateReader, StateResult};
+use mc_db::contract_db::{block_hash_storage_check_range, BLOCK_HASH_CONTRACT_ADDRESS};
 use mc_db::db_block_id::DbBlockId;
-use mc_db::MadaraBackend;
+use mc_db::{MadaraBackend, MadaraStorageError};
 use mp_class::ClassInfo;
 use mp_convert::ToFelt;
-use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
 use starknet_types_core::felt::Felt;
 use std::sync::Arc;
 
+use crate::{RemoteStateReader, StateReadCache};
+
 /// Adapter for the db queries made by blockifier.
 /// There is no actual mutable logic here - when using block production, the actual key value
 /// changes in db are evaluated at the end only from the produced state diff.
+///
+/// Writes such as `increment_nonce` are handled by the blockifier [`CachedState`] wrapping this
+/// adapter: the nonce is read from here once, and every following increment is applied on top of the
+/// cached value. [`CachedState`] requires `&mut self` for writes, so a state is never written to
+/// concurrently. If an adapter ever needs to be shared between executions, each of them must still get
+/// its own [`CachedState`] and the resulting state diffs must be merged in order.
+///
+/// [`CachedState`]: blockifier::state::cached_state::CachedState
+///
+/// When a [`RemoteStateReader`] is configured, values which are not found in the local database are
+/// fetched from it instead, so that execution on top of pruned historical blocks still works.
+///
+/// Reads are cached in a [`StateReadCache`], so that reading the same class many times during an execution
+/// only parses it once. By default, each adapter has its own cache, which does not outlive it. Adapters can
+/// also share a cache, so that a batch of executions on top of the same state does not read and parse the same
+/// classes and values again for every execution.
 pub struct BlockifierStateAdapter {
     backend: Arc<MadaraBackend>,
     /// When this value is None, we are executing the genesis block.
     pub on_top_of_block_id: Option<DbBlockId>,
     pub block_number: u64,
+    remote: Option<Arc<dyn RemoteStateReader>>,
+    read_cache: Arc<StateReadCache>,
 }
 
 impl BlockifierStateAdapter {
     pub fn new(backend: Arc<MadaraBackend>, block_number: u64, on_top_of_block_id: Option<DbBlockId>) -> Self {
-        Self { backend, on_top_of_block_id, block_number }
+        let read_cache = Arc::new(StateReadCache::new(block_number, on_top_of_block_id));
+        Self { backend, on_top_of_block_id, block_number, remote: None, read_cache }
+    }
+
+    pub fn with_remote_fallback(mut self, remote: Option<Arc<dyn RemoteStateReader>>) -> Self {
+        self.remote = remote;
+        self
+    }
+
+    /// Shares reads with the other adapters using the same cache. The cache is ignored if it was created for
+    /// another state, and the adapter keeps its own cache.
+    pub fn with_read_cache(mut self, read_cache: Option<Arc<StateReadCache>>) -> Self {
+        if let Some(read_cache) = read_cache {
+            if read_cache.is_for_state(self.block_number, self.on_top_of_block_id) {
+                self.read_cache = read_cache;
+            } else {
+                tracing::warn!("Ignoring a state read cache created for another state");
+            }
+        }
+        self
+    }
+
+    /// Points the adapter to another state, so that a single adapter can be reused across many executions instead
+    /// of allocating a new one, and a new read cache, for each of them. Nothing read on top of the previous state
+    /// is served afterwards.
+    ///
+    /// The read cache is emptied in place, keeping its allocated capacity. A cache shared with other adapters
+    /// (see [`Self::with_read_cache`]) is left untouched for them, and this adapter gets a new cache of its own.
+    pub fn reset(&mut self, block_number: u64, on_top_of_block_id: Option<DbBlockId>) {
+        self.block_number = block_number;
+        self.on_top_of_block_id = on_top_of_block_id;
+        match Arc::get_mut(&mut self.read_cache) {
+            Some(read_cache) => read_cache.reset(block_number, on_top_of_block_id),
+            None => self.read_cache = Arc::new(StateReadCache::new(block_number, on_top_of_block_id)),
+        }
+    }
+
+    /// The cache the reads of this adapter go through.
+    pub fn read_cache(&self) -> &Arc<StateReadCache> {
+        &self.read_cache
+    }
+
+    /// Reads the nonces and class hashes of the given contracts into the read cache ahead of execution, for
+    /// transactions whose accessed contracts are known in advance, such as the targets of a multicall. These are
+    /// only reads: they never show up in the state diff of the execution.
+    ///
+    /// The contracts are read in a batch, see [`MadaraBackend::get_contract_nonces_at`]. Contracts which are not
+    /// found locally are left to the regular reads, which may query the remote.
+    #[tracing::instrument(skip(self, addresses), fields(module = "BlockifierStateAdapter"))]
+    pub fn prefetch(&self, addresses: &[ContractAddress]) -> StateResult<()> {
+        let Some(on_top_of_block_id) = self.on_top_of_block_id else { return Ok(()) };
+
+        let contracts: Vec<Felt> = addresses.iter().map(|address| address.to_felt()).collect();
+        let to_state_error = |err: MadaraStorageError| {
+            tracing::warn!("Failed to prefetch {} contracts: {err:#}", contracts.len());
+            StateError::StateReadError(format!("Failed to prefetch {} contracts", contracts.len()))
+        };
+        let nonces = self.backend.get_contract_nonces_at(&on_top_of_block_id, &contracts).map_err(to_state_error)?;
+        let class_hashes =
+            self.backend.get_contract_class_hashes_at(&on_top_of_block_id, &contracts).map_err(to_state_error)?;
+
+        for ((&contract_address, nonce), class_hash) in addresses.iter().zip(nonces).zip(class_hashes) {
+            if let Some(nonce) = nonce {
+                self.read_cache.preload_nonce(contract_address, Nonce(nonce));
+            }
+            if let Some(class_hash) = class_hash {
+                self.read_cache.preload_class_hash(contract_address, ClassHash(class_hash));
+            }
+        }
+        Ok(())
+    }
+
+    /// Queries the remote for a value missing locally, when the local state of the block may have been pruned.
+    /// Above the pruning horizon, a missing value has never been written. The pending block only exists locally,
+    /// so it is never forwarded to the remote.
+    fn remote_fallback(
+        &self,
+        on_top_of_block_id: DbBlockId,
+        f: impl FnOnce(&dyn RemoteStateReader, u64) -> StateResult<Option<Felt>>,
+    ) -> StateResult<Option<Felt>> {
+        match (&self.remote, on_top_of_block_id) {
+            (Some(remote), DbBlockId::Number(block_n)) if block_n < remote.pruned_below() => {
+                f(remote.as_ref(), block_n)
+            }
+            _ => Ok(None),
+        }
     }
 }
 
 impl StateReader for BlockifierStateAdapter {
     fn get_storage_at(&self, contract_address: ContractAddress, key: StorageKey) -> StateResult<Felt> {
-        // The `0x1` address is reserved for block hashes: https://docs.starknet.io/architecture-and-concepts/network-architecture/starknet-state/#address_0x1
-        if *contract_address.key() == Felt::ONE {
-            let requested_block_number = (*key.0.key()).try_into().map_err(|_| StateError::OldBlockHashNotProvided)?;
+        self.read_cache.get_storage_at(contract_address, key, || self.load_storage_at(contract_address, key))
+    }
 
-            // Not found if in the last 10 blocks.
-            if !block_hash_storage_check_range(
-                &self.backend.chain_config().chain_id,
-                self.block_number,
-                requested_block_number,
-            ) {
-                return Ok(Felt::ZERO);
-            }
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.read_cache.get_nonce_at(contract_address, || self.load_nonce_at(contract_address))
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.read_cache.get_class_hash_at(contract_address, || self.load_class_hash_at(contract_address))
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.read_cache.get_compiled_contract_class(class_hash, || self.load_compiled_contract_class(class_hash))
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.read_cache.get_compiled_class_hash(class_hash, || self.load_compiled_class_hash(class_hash))
+    }
+}
+
+impl BlockifierStateAdapter {
+    fn load_storage_at(&self, contract_address: ContractAddress, key: StorageKey) -> StateResult<Felt> {
+        if *contract_address.key() == BLOCK_HASH_CONTRACT_ADDRESS {
+            return self.load_block_hash(key);
         }
 
         let Some(on_top_of_block_id) = self.on_top_of_block_id else { return Ok(Felt::ZERO) };
@@ -56,8 +174,15 @@ impl StateReader for BlockifierStateAdapter {
                     "Failed to retrieve storage value for contract {contract_address:#?} at key {:#x}",
                     key.to_felt()
                 ))
-            })?
-            .unwrap_or(Felt::ZERO);
+            })?;
+        let res = match res {
+            Some(res) => res,
+            None => self
+                .remote_fallback(on_top_of_block_id, |remote, block_n| {
+                    remote.get_storage_at(block_n, contract_address.to_felt(), key.to_felt())
+                })?
+                .unwrap_or(Felt::ZERO),
+        };
 
         tracing::debug!(
             "get_storage_at: on={:?}, contract={} key={:#x} => {:#x}",
@@ -70,41 +195,88 @@ impl StateReader for BlockifierStateAdapter {
         Ok(res)
     }
 
-    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+    /// Storage reads of the block hash contract. Like the sequencer, a block hash which is not provided (one of the
+    /// last 10 blocks, or a block before v0.12.0 on mainnet) or not stored reads as zero.
+    fn load_block_hash(&self, key: StorageKey) -> StateResult<Felt> {
+        let requested_block_number = (*key.0.key()).try_into().map_err(|_| StateError::OldBlockHashNotProvided)?;
+        let Some(on_top_of_block_id) = self.on_top_of_block_id else { return Ok(Felt::ZERO) };
+
+        let res = self
+            .backend
+            .get_block_hash_storage_at(&on_top_of_block_id, self.block_number, requested_block_number)
+            .map_err(|err| {
+                tracing::warn!("Failed to retrieve the hash of block {requested_block_number}: {err:#}");
+                StateError::StateReadError(format!("Failed to retrieve the hash of block {requested_block_number}"))
+            })?;
+        let res = match res {
+            Some(res) => Some(res),
+            // Block hashes which are not provided are not fetched from the remote either.
+            None if block_hash_storage_check_range(
+                &self.backend.chain_config().chain_id,
+                self.block_number,
+                requested_block_number,
+            ) =>
+            {
+                self.remote_fallback(on_top_of_block_id, |remote, block_n| {
+                    remote.get_storage_at(block_n, BLOCK_HASH_CONTRACT_ADDRESS, key.to_felt())
+                })?
+            }
+            None => None,
+        };
+
+        tracing::debug!(
+            "get_block_hash: on={:?}, block_n={requested_block_number} => {res:?}",
+            self.on_top_of_block_id
+        );
+
+        Ok(res.unwrap_or(Felt::ZERO))
+    }
+
+    fn load_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
         tracing::debug!("get_nonce_at for {}", contract_address);
         let Some(on_top_of_block_id) = self.on_top_of_block_id else { return Ok(Nonce::default()) };
 
-        Ok(Nonce(
-            self.backend
-                .get_contract_nonce_at(&on_top_of_block_id, &contract_address.to_felt())
-                .map_err(|err| {
-                    tracing::warn!("Failed to retrieve nonce for contract {contract_address}: {err:#}");
-                    StateError::StateReadError(format!("Failed to retrieve nonce for contract {contract_address}",))
-                })?
-                .unwrap_or(Felt::ZERO),
-        ))
+        let nonce =
+            self.backend.get_contract_nonce_at(&on_top_of_block_id, &contract_address.to_felt()).map_err(|err| {
+                tracing::warn!("Failed to retrieve nonce for contract {contract_address}: {err:#}");
+                StateError::StateReadError(format!("Failed to retrieve nonce for contract {contract_address}",))
+            })?;
+        let nonce = match nonce {
+            Some(nonce) => Some(nonce),
+            None => self.remote_fallback(on_top_of_block_id, |remote, block_n| {
+                remote.get_nonce_at(block_n, contract_address.to_felt())
+            })?,
+        };
+
+        Ok(Nonce(nonce.unwrap_or(Felt::ZERO)))
     }
 
-    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+    fn load_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
         tracing::debug!("get_class_hash_at for {}", contract_address);
         let Some(on_top_of_block_id) = self.on_top_of_block_id else { return Ok(ClassHash::default()) };
 
+        let class_hash = self
+            .backend
+            .get_contract_class_hash_at(&on_top_of_block_id, &contract_address.to_felt())
+            .map_err(|err| {
+                StateError::StateReadError(format!(
+                    "Failed to retrieve class hash for contract {:#x}: {:#}",
+                    contract_address.to_felt(),
+                    err
+                ))
+            })?;
+        let class_hash = match class_hash {
+            Some(class_hash) => Some(class_hash),
+            None => self.remote_fallback(on_top_of_block_id, |remote, block_n| {
+                remote.get_class_hash_at(block_n, contract_address.to_felt())
+            })?,
+        };
+
         // Note that blockifier is fine with us returning ZERO as a class_hash if it is not found, they do the check on their end after
-        Ok(ClassHash(
-            self.backend
-                .get_contract_class_hash_at(&on_top_of_block_id, &contract_address.to_felt())
-                .map_err(|err| {
-                    StateError::StateReadError(format!(
-                        "Failed to retrieve class hash for contract {:#x}: {:#}",
-                        contract_address.to_felt(),
-                        err
-                    ))
-                })?
-                .unwrap_or_default(),
-        ))
+        Ok(ClassHash(class_hash.unwrap_or_default()))
     }
 
-    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+    fn load_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
         tracing::debug!("get_compiled_contract_class for {:#x}", class_hash.to_felt());
 
         let Some(on_top_of_block_id) = self.on_top_of_block_id else {
@@ -126,7 +298,7 @@ impl StateReader for BlockifierStateAdapter {
         })
     }
 
-    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+    fn load_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
         tracing::debug!("get_compiled_class_hash for {:#x}", class_hash.to_felt());
 
         let Some(on_top_of_block_id) = self.on_top_of_block_id else {
@@ -153,22 +325,353 @@ impl StateReader for BlockifierStateAdapter {
     }
 }
 
-fn block_hash_storage_check_range(chain_id: &ChainId, current_block: u64, to_check: u64) -> bool {
-    // Allowed range is first_v0_12_0_block..=(current_block - 10).
-    let first_block = if chain_id == &ChainId::Mainnet { 103_129 } else { 0 };
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use assert_matches::assert_matches;
+    use blockifier::state::cached_state::CachedState;
+    use blockifier::state::state_api::State;
 
-    if let Some(end) = current_block.checked_sub(10) {
-        (first_block..=end).contains(&to_check)
-    } else {
-        false
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+    use mp_chain_config::ChainConfig;
+    use mp_class::{ConvertedClass, LegacyClassInfo, LegacyConvertedClass};
+    use mp_state_update::{ContractStorageDiffItem, DeployedContractItem, NonceUpdate, StateDiff, StorageEntry};
+    use starknet_api::core::{ChainId, PatriciaKey};
+    use starknet_core::types::contract::legacy::LegacyContractClass;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MockRemoteStateReader {
+        pruned_below: u64,
+        storage: HashMap<(u64, Felt, Felt), Felt>,
+        nonces: HashMap<(u64, Felt), Felt>,
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use starknet_api::core::ChainId;
+    impl RemoteStateReader for MockRemoteStateReader {
+        fn pruned_below(&self) -> u64 {
+            self.pruned_below
+        }
+        fn get_storage_at(&self, block_n: u64, contract_address: Felt, key: Felt) -> StateResult<Option<Felt>> {
+            Ok(self.storage.get(&(block_n, contract_address, key)).copied())
+        }
+        fn get_nonce_at(&self, block_n: u64, contract_address: Felt) -> StateResult<Option<Felt>> {
+            Ok(self.nonces.get(&(block_n, contract_address)).copied())
+        }
+        fn get_class_hash_at(&self, _block_n: u64, _contract_address: Felt) -> StateResult<Option<Felt>> {
+            Ok(None)
+        }
+    }
+
+    /// Stores an empty block `block_number` with the given state diff and declared classes.
+    fn store_block(backend: &MadaraBackend, block_number: u64, state_diff: StateDiff, classes: Vec<ConvertedClass>) {
+        let block = MadaraMaybePendingBlock {
+            info: MadaraBlockInfo {
+                header: Header { block_number, ..Default::default() },
+                block_hash: Felt::from(block_number),
+                tx_hashes: vec![],
+            }
+            .into(),
+            inner: MadaraBlockInner { transactions: vec![], receipts: vec![] },
+        };
+        backend.store_block(block, state_diff, classes, None, None).unwrap();
+    }
+
+    #[test]
+    fn test_remote_fallback() {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::madara_test()));
+        let contract = Felt::from_hex_unchecked("0x1234");
+        let (local_key, remote_key, unknown_key) = (Felt::ONE, Felt::TWO, Felt::THREE);
+
+        let state_diff = StateDiff {
+            storage_diffs: vec![ContractStorageDiffItem {
+                address: contract,
+                storage_entries: vec![StorageEntry { key: local_key, value: Felt::from(10) }],
+            }],
+            ..Default::default()
+        };
+        store_block(&backend, 0, state_diff, vec![]);
+
+        let remote = |pruned_below| {
+            Arc::new(MockRemoteStateReader {
+                pruned_below,
+                storage: [((0, contract, local_key), Felt::from(99)), ((0, contract, remote_key), Felt::from(20))]
+                    .into(),
+                nonces: [((0, contract), Felt::from(5))].into(),
+            })
+        };
+
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0)))
+            .with_remote_fallback(Some(remote(1)));
+        let address = ContractAddress::try_from(contract).unwrap();
+        let storage_key = |key: Felt| StorageKey(PatriciaKey::try_from(key).unwrap());
+
+        // Local hits are preferred over the remote.
+        assert_eq!(adapter.get_storage_at(address, storage_key(local_key)).unwrap(), Felt::from(10));
+        // Local misses are served by the remote.
+        assert_eq!(adapter.get_storage_at(address, storage_key(remote_key)).unwrap(), Felt::from(20));
+        assert_eq!(adapter.get_nonce_at(address).unwrap(), Nonce(Felt::from(5)));
+        // Missing everywhere.
+        assert_eq!(adapter.get_storage_at(address, storage_key(unknown_key)).unwrap(), Felt::ZERO);
+        assert_eq!(adapter.get_class_hash_at(address).unwrap(), ClassHash::default());
+
+        // Without a remote, local misses are zero.
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0)));
+        assert_eq!(adapter.get_storage_at(address, storage_key(remote_key)).unwrap(), Felt::ZERO);
+
+        // At or above the pruning horizon, local misses have never been written: the remote is not queried.
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0)))
+            .with_remote_fallback(Some(remote(0)));
+        assert_eq!(adapter.get_storage_at(address, storage_key(remote_key)).unwrap(), Felt::ZERO);
+        assert_eq!(adapter.get_nonce_at(address).unwrap(), Nonce::default());
+    }
+
+    #[test]
+    fn test_increment_nonce_accumulates() {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::madara_test()));
+        let contract = Felt::from_hex_unchecked("0x1234");
 
-    use super::block_hash_storage_check_range;
+        let state_diff = StateDiff {
+            nonces: vec![NonceUpdate { contract_address: contract, nonce: Felt::from(5) }],
+            ..Default::default()
+        };
+        store_block(&backend, 0, state_diff, vec![]);
+
+        let mut state =
+            CachedState::new(BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0))));
+        let address = ContractAddress::try_from(contract).unwrap();
+
+        for _ in 0..3 {
+            state.increment_nonce(address).unwrap();
+        }
+
+        assert_eq!(state.get_nonce_at(address).unwrap(), Nonce(Felt::from(8)));
+        // The underlying database is left untouched.
+        assert_eq!(backend.get_contract_nonce_at(&DbBlockId::Number(0), &contract).unwrap(), Some(Felt::from(5)));
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::madara_test()));
+        let contract = Felt::from_hex_unchecked("0x1234");
+        let class_hash = Felt::from_hex_unchecked("0xc1a55");
+
+        let state_diff = StateDiff {
+            nonces: vec![NonceUpdate { contract_address: contract, nonce: Felt::from(5) }],
+            deployed_contracts: vec![DeployedContractItem { address: contract, class_hash }],
+            ..Default::default()
+        };
+        store_block(&backend, 0, state_diff, vec![]);
+
+        let mut state =
+            CachedState::new(BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0))));
+        let address = ContractAddress::try_from(contract).unwrap();
+        state.state.prefetch(&[address]).unwrap();
+
+        let reads = backend.storage_metrics().history_reads();
+        assert_eq!(state.get_nonce_at(address).unwrap(), Nonce(Felt::from(5)));
+        assert_eq!(state.get_class_hash_at(address).unwrap(), ClassHash(class_hash));
+        assert_eq!(backend.storage_metrics().history_reads(), reads);
+
+        // Prefetched values are not writes.
+        assert_eq!(state.to_state_diff().unwrap(), Default::default());
+    }
+
+    const UDC_CLASS_DEFINITION: &[u8] = include_bytes!("../../../../../cairo-artifacts/madara_contracts_UDC.json");
+
+    fn udc_class() -> ConvertedClass {
+        let class: LegacyContractClass = serde_json::from_slice(UDC_CLASS_DEFINITION).unwrap();
+        ConvertedClass::Legacy(LegacyConvertedClass {
+            class_hash: class.class_hash().unwrap(),
+            info: LegacyClassInfo { contract_class: Arc::new(class.compress().unwrap().into()) },
+        })
+    }
+
+    #[test]
+    fn test_shared_read_cache() {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::madara_test()));
+        let contract = Felt::from_hex_unchecked("0x1234");
+        let converted_class = udc_class();
+        let class_hash = converted_class.class_hash();
+
+        let state_diff = StateDiff {
+            storage_diffs: vec![ContractStorageDiffItem {
+                address: contract,
+                storage_entries: vec![StorageEntry { key: Felt::ONE, value: Felt::from(10) }],
+            }],
+            deprecated_declared_classes: vec![class_hash],
+            ..Default::default()
+        };
+        store_block(&backend, 0, state_diff, vec![converted_class]);
+
+        let read_cache = Arc::new(StateReadCache::new(1, Some(DbBlockId::Number(0))));
+        let address = ContractAddress::try_from(contract).unwrap();
+        let storage_key = StorageKey(PatriciaKey::try_from(Felt::ONE).unwrap());
+
+        // One state per transaction: reads are shared, writes are not.
+        for _ in 0..2 {
+            let mut state = CachedState::new(
+                BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0)))
+                    .with_read_cache(Some(Arc::clone(&read_cache))),
+            );
+            state.get_compiled_contract_class(ClassHash(class_hash)).unwrap();
+            assert_eq!(state.get_storage_at(address, storage_key).unwrap(), Felt::from(10));
+            state.set_storage_at(address, storage_key, Felt::from(20)).unwrap();
+        }
+        assert_eq!(read_cache.loaded_classes(), 1);
+
+        // A cache created for another state is not used.
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 2, Some(DbBlockId::Number(1)))
+            .with_read_cache(Some(Arc::clone(&read_cache)));
+        assert!(!Arc::ptr_eq(adapter.read_cache(), &read_cache));
+    }
+
+    #[test]
+    fn test_adapter_read_cache() {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::madara_test()));
+        let converted_class = udc_class();
+        let class_hash = converted_class.class_hash();
+        store_block(
+            &backend,
+            0,
+            StateDiff { deprecated_declared_classes: vec![class_hash], ..Default::default() },
+            vec![converted_class],
+        );
+
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0)));
+        for _ in 0..1000 {
+            adapter.get_compiled_contract_class(ClassHash(class_hash)).unwrap();
+        }
+        assert_eq!(adapter.read_cache().loaded_classes(), 1);
+
+        // A new adapter, as created for another execution, starts with an empty cache.
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0)));
+        assert_eq!(adapter.read_cache().loaded_classes(), 0);
+    }
+
+    #[test]
+    fn test_reset_reused_adapter() {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::madara_test()));
+        let contract = Felt::from_hex_unchecked("0x1234");
+        let address = ContractAddress::try_from(contract).unwrap();
+        let storage_key = StorageKey(PatriciaKey::try_from(Felt::ONE).unwrap());
+
+        for (block_number, value) in [(0, 10), (1, 20)] {
+            let state_diff = StateDiff {
+                storage_diffs: vec![ContractStorageDiffItem {
+                    address: contract,
+                    storage_entries: vec![StorageEntry { key: Felt::ONE, value: Felt::from(value) }],
+                }],
+                ..Default::default()
+            };
+            store_block(&backend, block_number, state_diff, vec![]);
+        }
+
+        // First execution, on top of block 0.
+        let mut state =
+            CachedState::new(BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0))));
+        assert_eq!(state.get_storage_at(address, storage_key).unwrap(), Felt::from(10));
+        state.set_storage_at(address, storage_key, Felt::from(30)).unwrap();
+        state.increment_nonce(address).unwrap();
+
+        // Second execution, on top of block 1, with the same adapter: neither the reads nor the writes of the
+        // first one are visible.
+        let mut adapter = state.state;
+        adapter.reset(2, Some(DbBlockId::Number(1)));
+        let mut state = CachedState::new(adapter);
+        assert_eq!(state.get_storage_at(address, storage_key).unwrap(), Felt::from(20));
+        assert_eq!(state.get_nonce_at(address).unwrap(), Nonce(Felt::ZERO));
+        assert_eq!(state.to_state_diff().unwrap(), Default::default());
+
+        // A shared cache is not emptied for the other adapters using it.
+        let read_cache = Arc::new(StateReadCache::new(1, Some(DbBlockId::Number(0))));
+        let mut adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 1, Some(DbBlockId::Number(0)))
+            .with_read_cache(Some(Arc::clone(&read_cache)));
+        assert_eq!(adapter.get_storage_at(address, storage_key).unwrap(), Felt::from(10));
+        adapter.reset(2, Some(DbBlockId::Number(1)));
+        assert!(!Arc::ptr_eq(adapter.read_cache(), &read_cache));
+        assert_eq!(adapter.get_storage_at(address, storage_key).unwrap(), Felt::from(20));
+        assert_eq!(read_cache.get_storage_at(address, storage_key, || unreachable!()).unwrap(), Felt::from(10));
+    }
+
+    /// Stores a block 0 mapping block `n` to hash `0xb10c + n` at the block hash address, for every `n` in
+    /// `block_hashes`.
+    fn backend_with_block_hashes(block_hashes: impl IntoIterator<Item = u64>) -> Arc<MadaraBackend> {
+        let backend = MadaraBackend::open_for_testing(Arc::new(ChainConfig::madara_test()));
+        let state_diff = StateDiff {
+            storage_diffs: vec![ContractStorageDiffItem {
+                address: Felt::ONE,
+                storage_entries: block_hashes
+                    .into_iter()
+                    .map(|block_n| StorageEntry { key: Felt::from(block_n), value: Felt::from(0xb10c + block_n) })
+                    .collect(),
+            }],
+            ..Default::default()
+        };
+        store_block(&backend, 0, state_diff, vec![]);
+        backend
+    }
+
+    fn get_block_hash(adapter: &BlockifierStateAdapter, block_n: u64) -> StateResult<Felt> {
+        adapter.get_storage_at(
+            ContractAddress::try_from(Felt::ONE).unwrap(),
+            StorageKey(PatriciaKey::try_from(Felt::from(block_n)).unwrap()),
+        )
+    }
+
+    #[test]
+    fn test_block_hash_lookup() {
+        let backend = backend_with_block_hashes([0, 1]);
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 20, Some(DbBlockId::Number(0)));
+
+        assert_eq!(get_block_hash(&adapter, 0).unwrap(), Felt::from(0xb10c));
+        assert_eq!(get_block_hash(&adapter, 1).unwrap(), Felt::from(0xb10c + 1));
+    }
+
+    #[test]
+    fn test_block_hash_lookup_out_of_range() {
+        let backend = backend_with_block_hashes([5, 15]);
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 20, Some(DbBlockId::Number(0)));
+
+        // Within the last 10 blocks: zero like on the sequencer, even though the hash is stored.
+        assert_eq!(get_block_hash(&adapter, 15).unwrap(), Felt::ZERO);
+        // Not a block number.
+        let key = StorageKey(PatriciaKey::try_from(Felt::from(u128::MAX)).unwrap());
+        assert_matches!(
+            adapter.get_storage_at(ContractAddress::try_from(Felt::ONE).unwrap(), key),
+            Err(StateError::OldBlockHashNotProvided)
+        );
+
+        // Before v0.12.0 on mainnet.
+        let mut chain_config = ChainConfig::madara_test();
+        chain_config.chain_id = ChainId::Mainnet;
+        let backend = MadaraBackend::open_for_testing(Arc::new(chain_config));
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 103_200, Some(DbBlockId::Number(0)));
+        assert_eq!(get_block_hash(&adapter, 5).unwrap(), Felt::ZERO);
+    }
+
+    #[test]
+    fn test_block_hash_lookup_missing_mapping() {
+        let backend = backend_with_block_hashes([0]);
+        let adapter = BlockifierStateAdapter::new(Arc::clone(&backend), 20, Some(DbBlockId::Number(0)));
+
+        assert_eq!(get_block_hash(&adapter, 1).unwrap(), Felt::ZERO);
+        // The remote does not have it either.
+        let adapter = adapter.with_remote_fallback(Some(Arc::new(MockRemoteStateReader::default())));
+        assert_eq!(get_block_hash(&adapter, 1).unwrap(), Felt::ZERO);
+    }
+
+    #[test]
+    fn test_block_hash_lookup_db_error() {
+        let backend = backend_with_block_hashes([0]);
+        // The database cannot hold blocks past u32::MAX: reading on top of one fails.
+        let on_top_of = u64::from(u32::MAX) + 1;
+        let adapter =
+            BlockifierStateAdapter::new(Arc::clone(&backend), on_top_of + 1, Some(DbBlockId::Number(on_top_of)));
+
+        assert_matches!(get_block_hash(&adapter, 0), Err(StateError::StateReadError(_)));
+    }
 
     #[test]
     fn check_block_n_range() {
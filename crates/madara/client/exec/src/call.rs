@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use blockifier::context::TransactionContext;
 use blockifier::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
+use blockifier::state::cached_state::CachedState;
 use blockifier::state::state_api::StateReader;
 use blockifier::transaction::errors::TransactionExecutionError;
 use blockifier::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
@@ -10,7 +11,7 @@ use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::transaction::Calldata;
 use starknet_types_core::felt::Felt;
 
-use crate::{CallContractError, Error, ExecutionContext};
+use crate::{AccessList, CallContractError, Error, ExecutionContext, RecordingStateReader};
 
 impl ExecutionContext {
     /// Call a contract, returning the retdata.
@@ -19,6 +20,28 @@ impl ExecutionContext {
         contract_address: &Felt,
         entry_point_selector: &Felt,
         calldata: &[Felt],
+    ) -> Result<Vec<Felt>, Error> {
+        self.call_contract_on(&mut self.init_cached_state(), contract_address, entry_point_selector, calldata)
+    }
+
+    /// Call a contract, returning the retdata and every piece of state the call read.
+    pub fn call_contract_with_access_list(
+        &self,
+        contract_address: &Felt,
+        entry_point_selector: &Felt,
+        calldata: &[Felt],
+    ) -> Result<(Vec<Felt>, AccessList), Error> {
+        let mut cached_state = CachedState::new(RecordingStateReader::new(self.init_state_adapter()));
+        let retdata = self.call_contract_on(&mut cached_state, contract_address, entry_point_selector, calldata)?;
+        Ok((retdata, cached_state.state.into_access_list()))
+    }
+
+    fn call_contract_on<S: StateReader>(
+        &self,
+        cached_state: &mut CachedState<S>,
+        contract_address: &Felt,
+        entry_point_selector: &Felt,
+        calldata: &[Felt],
     ) -> Result<Vec<Felt>, Error> {
         tracing::debug!("calling contract {contract_address:#x}");
 
@@ -52,15 +75,13 @@ impl ExecutionContext {
         )
         .map_err(make_err)?;
 
-        let mut cached_state = self.init_cached_state();
-
         let class_hash = cached_state
             .get_class_hash_at(storage_address)
             .map_err(TransactionExecutionError::StateError)
             .map_err(make_err)?;
 
         let res = entrypoint
-            .execute(&mut cached_state, &mut resources, &mut entry_point_execution_context)
+            .execute(cached_state, &mut resources, &mut entry_point_execution_context)
             .map_err(|error| TransactionExecutionError::ExecutionError {
                 error,
                 class_hash,
@@ -1,13 +1,17 @@
 use blockifier::fee::fee_utils::get_fee_by_gas_vector;
 use blockifier::fee::gas_usage::estimate_minimal_gas_vector;
 use blockifier::state::cached_state::TransactionalState;
+use blockifier::state::state_api::StateReader;
 use blockifier::transaction::account_transaction::AccountTransaction;
 use blockifier::transaction::errors::TransactionExecutionError;
 use blockifier::transaction::objects::{FeeType, HasRelatedFeeType, TransactionExecutionInfo};
 use blockifier::transaction::transaction_execution::Transaction;
 use blockifier::transaction::transaction_types::TransactionType;
 use blockifier::transaction::transactions::{ExecutableTransaction, ExecutionFlags};
+use mp_convert::ToFelt;
+use starknet_api::core::{ClassHash, ContractAddress};
 use starknet_api::transaction::TransactionHash;
+use std::cell::Cell;
 
 use crate::{Error, ExecutionContext, ExecutionResult, TxExecError, TxFeeEstimationError};
 
@@ -20,6 +24,58 @@ impl ExecutionContext {
         transactions_to_trace: impl IntoIterator<Item = Transaction>,
         charge_fee: bool,
         validate: bool,
+    ) -> Result<Vec<ExecutionResult>, Error> {
+        self.re_execute_transactions_inner(
+            transactions_before,
+            transactions_to_trace,
+            charge_fee,
+            validate,
+            false,
+            false,
+        )
+    }
+
+    /// Same as [`Self::re_execute_transactions`] without any transaction before, but first checks that the sender of
+    /// each invoke and declare transaction is deployed in the state it runs on, which includes the changes made by
+    /// the previous transactions, such as a deploy account transaction. Fails with [`Error::SenderNotDeployed`]
+    /// otherwise.
+    pub fn execute_transactions_checking_senders(
+        &self,
+        transactions: impl IntoIterator<Item = Transaction>,
+        charge_fee: bool,
+        validate: bool,
+    ) -> Result<Vec<ExecutionResult>, Error> {
+        self.re_execute_transactions_inner([], transactions, charge_fee, validate, false, true)
+    }
+
+    /// Same as [`Self::re_execute_transactions`], but stops after the first reverted transaction of
+    /// `transactions_to_trace`. The returned `ExecutionResult`s end with the reverted transaction, if any: the
+    /// transactions after it are not executed.
+    pub fn re_execute_transactions_until_failure(
+        &self,
+        transactions_before: impl IntoIterator<Item = Transaction>,
+        transactions_to_trace: impl IntoIterator<Item = Transaction>,
+        charge_fee: bool,
+        validate: bool,
+    ) -> Result<Vec<ExecutionResult>, Error> {
+        self.re_execute_transactions_inner(
+            transactions_before,
+            transactions_to_trace,
+            charge_fee,
+            validate,
+            true,
+            false,
+        )
+    }
+
+    fn re_execute_transactions_inner(
+        &self,
+        transactions_before: impl IntoIterator<Item = Transaction>,
+        transactions_to_trace: impl IntoIterator<Item = Transaction>,
+        charge_fee: bool,
+        validate: bool,
+        stop_on_failure: bool,
+        check_senders: bool,
     ) -> Result<Vec<ExecutionResult>, Error> {
         let mut cached_state = self.init_cached_state();
 
@@ -36,12 +92,24 @@ impl ExecutionContext {
             executed_prev += 1;
         }
 
+        let reverted = Cell::new(false);
         transactions_to_trace
             .into_iter()
+            // Stop right after the first reverted transaction when asked to.
+            .take_while(|_| !(stop_on_failure && reverted.get()))
             .enumerate()
             .map(|(index, tx): (_, Transaction)| {
                 let hash = tx.tx_hash();
                 tracing::debug!("executing {hash:#} (trace)");
+                if let Some(sender_address) = sender_address(&tx).filter(|_| check_senders) {
+                    // Senders deployed by an earlier transaction of the batch are visible in the cached state.
+                    if cached_state.get_class_hash_at(sender_address)? == ClassHash::default() {
+                        return Err(Error::SenderNotDeployed {
+                            index: executed_prev + index,
+                            sender_address: sender_address.to_felt(),
+                        });
+                    }
+                }
                 let tx_type = tx.tx_type();
                 let fee_type = tx.fee_type();
 
@@ -91,7 +159,9 @@ impl ExecutionContext {
                     .to_state_diff()
                     .map_err(TransactionExecutionError::StateError)
                     .map_err(make_reexec_error)?;
+                let visited_pcs = self.collect_visited_pcs.then(|| transactional_state.visited_pcs.clone());
                 transactional_state.commit();
+                reverted.set(execution_info.is_reverted());
 
                 Ok(ExecutionResult {
                     hash,
@@ -100,12 +170,23 @@ impl ExecutionContext {
                     minimal_l1_gas,
                     execution_info,
                     state_diff: state_diff.into(),
+                    visited_pcs,
                 })
             })
             .collect::<Result<Vec<_>, _>>()
     }
 }
 
+/// Sender of an invoke or declare transaction. Deploy account transactions deploy their sender.
+fn sender_address(tx: &Transaction) -> Option<ContractAddress> {
+    match tx {
+        Transaction::AccountTransaction(AccountTransaction::Invoke(tx)) => Some(tx.tx.sender_address()),
+        Transaction::AccountTransaction(AccountTransaction::Declare(tx)) => Some(tx.tx.sender_address()),
+        Transaction::AccountTransaction(AccountTransaction::DeployAccount(_))
+        | Transaction::L1HandlerTransaction(_) => None,
+    }
+}
+
 pub trait TxInfo {
     fn tx_hash(&self) -> TransactionHash;
     fn tx_type(&self) -> TransactionType;
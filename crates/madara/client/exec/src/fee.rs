@@ -2,6 +2,8 @@ use crate::{ExecutionContext, ExecutionResult};
 use blockifier::transaction::objects::FeeType;
 
 impl ExecutionContext {
+    /// Fee estimate of an executed transaction, with its L1 gas and L1 data gas consumption and prices. Consumption
+    /// is never below the minimal gas of the transaction, so that the estimate can be used as resource bounds.
     pub fn execution_result_to_fee_estimate(&self, executions_result: &ExecutionResult) -> mp_rpc::FeeEstimate {
         let gas_price =
             self.block_context.block_info().gas_prices.get_gas_price_by_fee_type(&executions_result.fee_type).get();
@@ -12,14 +14,12 @@ impl ExecutionContext {
             .get_data_gas_price_by_fee_type(&executions_result.fee_type)
             .get();
 
-        let data_gas_consumed = executions_result.execution_info.transaction_receipt.da_gas.l1_data_gas;
-        let data_gas_fee = data_gas_consumed.saturating_mul(data_gas_price);
-        let gas_consumed =
-            executions_result.execution_info.transaction_receipt.fee.0.saturating_sub(data_gas_fee) / gas_price.max(1);
-        let minimal_gas_consumed = executions_result.minimal_l1_gas.unwrap_or_default().l1_gas;
-        let minimal_data_gas_consumed = executions_result.minimal_l1_gas.unwrap_or_default().l1_data_gas;
-        let gas_consumed = gas_consumed.max(minimal_gas_consumed);
-        let data_gas_consumed = data_gas_consumed.max(minimal_data_gas_consumed);
+        // The gas vector of the receipt is blockifier's accounting of the resources used by the transaction. With
+        // blob data availability, the state diff is paid for in L1 data gas rather than in L1 gas.
+        let gas_vector = &executions_result.execution_info.transaction_receipt.gas;
+        let minimal_gas = executions_result.minimal_l1_gas.unwrap_or_default();
+        let gas_consumed = gas_vector.l1_gas.max(minimal_gas.l1_gas);
+        let data_gas_consumed = gas_vector.l1_data_gas.max(minimal_gas.l1_data_gas);
         let overall_fee =
             gas_consumed.saturating_mul(gas_price).saturating_add(data_gas_consumed.saturating_mul(data_gas_price));
 
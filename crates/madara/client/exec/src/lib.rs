@@ -1,4 +1,5 @@
 use core::fmt;
+use std::collections::{HashMap, HashSet};
 
 use blockifier::{
     state::cached_state::CommitmentStateDiff,
@@ -9,19 +10,28 @@ use blockifier::{
     },
 };
 use mc_db::{db_block_id::DbBlockId, MadaraStorageError};
+use starknet_api::core::ClassHash;
 use starknet_api::transaction::TransactionHash;
 use starknet_types_core::felt::Felt;
 
+mod access_list;
 mod block_context;
 mod blockifier_state_adapter;
 mod call;
 pub mod execution;
 mod fee;
+mod read_cache;
+mod reexecute;
+mod remote_state;
 mod trace;
 pub mod transaction;
 
+pub use access_list::{AccessList, RecordingStateReader};
 pub use block_context::ExecutionContext;
 pub use blockifier_state_adapter::BlockifierStateAdapter;
+pub use read_cache::StateReadCache;
+pub use reexecute::{reexecute_block, ReexecReport, StateDiffDivergence, StateDiffEntry};
+pub use remote_state::{JsonRpcRemoteStateReader, RemoteStateReader};
 pub use trace::execution_result_to_tx_trace;
 
 #[derive(Debug)]
@@ -56,6 +66,14 @@ pub enum Error {
     Storage(#[from] MadaraStorageError),
     #[error("Invalid sequencer address: {0:#x}")]
     InvalidSequencerAddress(Felt),
+    #[error("Block not found")]
+    BlockNotFound,
+    #[error("Converting transaction: {0:#}")]
+    TransactionConversion(#[from] transaction::Error),
+    #[error("Computing state diff: {0:#}")]
+    StateDiff(#[from] blockifier::state::errors::StateError),
+    #[error("Sender {sender_address:#x} of tx index {index} is not deployed")]
+    SenderNotDeployed { index: usize, sender_address: Felt },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -68,6 +86,13 @@ pub struct TxExecError {
     err: TransactionExecutionError,
 }
 
+impl TxExecError {
+    /// Index of the transaction which failed.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 #[error("Estimating fee for tx index {index} on top of {block_n}: {err:#}")]
 pub struct TxFeeEstimationError {
@@ -77,6 +102,13 @@ pub struct TxFeeEstimationError {
     err: TransactionExecutionError,
 }
 
+impl TxFeeEstimationError {
+    /// Index of the transaction which failed.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 #[error("Estimating message fee on top of {block_n}: {err:#}")]
 pub struct MessageFeeEstimationError {
@@ -101,4 +133,7 @@ pub struct ExecutionResult {
     pub minimal_l1_gas: Option<GasVector>,
     pub execution_info: TransactionExecutionInfo,
     pub state_diff: CommitmentStateDiff,
+    /// Program counters visited by the transaction in each class it executed, for coverage tooling. Only collected
+    /// when asked for with [`ExecutionContext::with_visited_pcs`].
+    pub visited_pcs: Option<HashMap<ClassHash, HashSet<usize>>>,
 }
@@ -0,0 +1,149 @@
+use blockifier::execution::contract_class::ContractClass;
+use blockifier::state::state_api::StateResult;
+use mc_db::db_block_id::DbBlockId;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Cache of the state read by [`BlockifierStateAdapter`]s. Every adapter has its own cache by default, which can
+/// instead be shared across a batch of executions on top of the same state (for example, when simulating every
+/// transaction of a block one by one).
+///
+/// Only reads from the underlying state are cached here. Each execution still gets its own
+/// [`CachedState`] as a write overlay, which is looked up before this cache: writes, such as declared classes,
+/// always take precedence, and are never visible to the other executions sharing the cache.
+///
+/// A cache is only valid for the state it was created for: adapters for another state will not use it.
+///
+/// [`BlockifierStateAdapter`]: crate::BlockifierStateAdapter
+/// [`CachedState`]: blockifier::state::cached_state::CachedState
+#[derive(Debug)]
+pub struct StateReadCache {
+    block_number: u64,
+    on_top_of_block_id: Option<DbBlockId>,
+    storage: Mutex<HashMap<(ContractAddress, StorageKey), Felt>>,
+    nonces: Mutex<HashMap<ContractAddress, Nonce>>,
+    class_hashes: Mutex<HashMap<ContractAddress, ClassHash>>,
+    compiled_classes: Mutex<HashMap<ClassHash, ContractClass>>,
+    compiled_class_hashes: Mutex<HashMap<ClassHash, CompiledClassHash>>,
+    loaded_classes: AtomicUsize,
+}
+
+impl StateReadCache {
+    pub fn new(block_number: u64, on_top_of_block_id: Option<DbBlockId>) -> Self {
+        Self {
+            block_number,
+            on_top_of_block_id,
+            storage: Default::default(),
+            nonces: Default::default(),
+            class_hashes: Default::default(),
+            compiled_classes: Default::default(),
+            compiled_class_hashes: Default::default(),
+            loaded_classes: Default::default(),
+        }
+    }
+
+    pub(crate) fn is_for_state(&self, block_number: u64, on_top_of_block_id: Option<DbBlockId>) -> bool {
+        self.block_number == block_number && self.on_top_of_block_id == on_top_of_block_id
+    }
+
+    /// Empties the cache and makes it valid for another state. The maps keep their allocated capacity.
+    pub(crate) fn reset(&mut self, block_number: u64, on_top_of_block_id: Option<DbBlockId>) {
+        self.block_number = block_number;
+        self.on_top_of_block_id = on_top_of_block_id;
+        self.storage.get_mut().expect("Poisoned lock").clear();
+        self.nonces.get_mut().expect("Poisoned lock").clear();
+        self.class_hashes.get_mut().expect("Poisoned lock").clear();
+        self.compiled_classes.get_mut().expect("Poisoned lock").clear();
+        self.compiled_class_hashes.get_mut().expect("Poisoned lock").clear();
+        *self.loaded_classes.get_mut() = 0;
+    }
+
+    /// Number of classes which have been loaded and converted to the blockifier format through this cache.
+    pub fn loaded_classes(&self) -> usize {
+        self.loaded_classes.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        load: impl FnOnce() -> StateResult<Felt>,
+    ) -> StateResult<Felt> {
+        get_or_load(&self.storage, (contract_address, key), load)
+    }
+
+    pub(crate) fn get_nonce_at(
+        &self,
+        contract_address: ContractAddress,
+        load: impl FnOnce() -> StateResult<Nonce>,
+    ) -> StateResult<Nonce> {
+        get_or_load(&self.nonces, contract_address, load)
+    }
+
+    pub(crate) fn get_class_hash_at(
+        &self,
+        contract_address: ContractAddress,
+        load: impl FnOnce() -> StateResult<ClassHash>,
+    ) -> StateResult<ClassHash> {
+        get_or_load(&self.class_hashes, contract_address, load)
+    }
+
+    /// Insert a nonce read ahead of execution, unless it is already cached.
+    pub(crate) fn preload_nonce(&self, contract_address: ContractAddress, nonce: Nonce) {
+        self.nonces.lock().expect("Poisoned lock").entry(contract_address).or_insert(nonce);
+    }
+
+    /// Insert a class hash read ahead of execution, unless it is already cached.
+    pub(crate) fn preload_class_hash(&self, contract_address: ContractAddress, class_hash: ClassHash) {
+        self.class_hashes.lock().expect("Poisoned lock").entry(contract_address).or_insert(class_hash);
+    }
+
+    pub(crate) fn get_compiled_contract_class(
+        &self,
+        class_hash: ClassHash,
+        load: impl FnOnce() -> StateResult<ContractClass>,
+    ) -> StateResult<ContractClass> {
+        get_or_load(&self.compiled_classes, class_hash, || {
+            let class = load()?;
+            self.loaded_classes.fetch_add(1, Ordering::Relaxed);
+            Ok(class)
+        })
+    }
+
+    /// Insert a class loaded ahead of execution, unless it is already cached.
+    pub(crate) fn preload_compiled_class(&self, class_hash: ClassHash, class: ContractClass) {
+        let mut compiled_classes = self.compiled_classes.lock().expect("Poisoned lock");
+        if let std::collections::hash_map::Entry::Vacant(entry) = compiled_classes.entry(class_hash) {
+            entry.insert(class);
+            self.loaded_classes.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn get_compiled_class_hash(
+        &self,
+        class_hash: ClassHash,
+        load: impl FnOnce() -> StateResult<CompiledClassHash>,
+    ) -> StateResult<CompiledClassHash> {
+        get_or_load(&self.compiled_class_hashes, class_hash, load)
+    }
+}
+
+/// Errors are not cached. The lock is not held while loading, so that concurrent executions do not wait on each
+/// other: the same value may then be loaded more than once.
+fn get_or_load<K: Eq + Hash, V: Clone>(
+    map: &Mutex<HashMap<K, V>>,
+    key: K,
+    load: impl FnOnce() -> StateResult<V>,
+) -> StateResult<V> {
+    if let Some(value) = map.lock().expect("Poisoned lock").get(&key) {
+        return Ok(value.clone());
+    }
+    let value = load()?;
+    map.lock().expect("Poisoned lock").insert(key, value.clone());
+    Ok(value)
+}
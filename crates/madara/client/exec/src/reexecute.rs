@@ -0,0 +1,210 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use blockifier::{state::cached_state::StateMaps, transaction::transactions::ExecutableTransaction};
+use mc_db::MadaraBackend;
+use mp_block::{BlockId, MadaraMaybePendingBlockInfo};
+use mp_convert::ToFelt;
+use mp_state_update::StateDiff;
+use starknet_api::transaction::TransactionHash;
+use starknet_types_core::felt::Felt;
+
+use crate::{transaction::to_blockifier_transaction, Error, ExecutionContext, TxExecError};
+
+/// Number of blocks between a block and the block whose hash it stores in the block hash registry.
+const STORED_BLOCK_HASH_BUFFER: u64 = 10;
+
+/// An entry of a block state diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StateDiffEntry {
+    Storage {
+        contract_address: Felt,
+        key: Felt,
+    },
+    Nonce {
+        contract_address: Felt,
+    },
+    /// Class hash of a deployed contract, or new class hash of a replaced class.
+    ClassHash {
+        contract_address: Felt,
+    },
+    /// The value is the compiled class hash of the declared class, or zero for a legacy class.
+    DeclaredClass {
+        class_hash: Felt,
+    },
+}
+
+/// A state diff entry which differs between the re-executed and the stored state diffs. The value is `None` when
+/// the entry is missing from one of the state diffs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDiffDivergence {
+    pub entry: StateDiffEntry,
+    pub computed: Option<Felt>,
+    pub stored: Option<Felt>,
+}
+
+/// Result of [`reexecute_block`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReexecReport {
+    pub transaction_count: usize,
+    /// Sorted by entry.
+    pub divergences: Vec<StateDiffDivergence>,
+}
+
+impl ReexecReport {
+    /// Whether the re-executed state diff is the same as the stored one.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// Re-executes the transactions of a block on top of the state of the previous block, and compares the resulting
+/// state diff with the state diff stored for the block. This verifies the local state against the transactions
+/// of the block, like a full node would when syncing.
+///
+/// Transactions are executed with fee charging and validation. Like block production, closed blocks from block 10
+/// onwards also store the hash of block `n - 10` in the block hash registry contract at address 0x1.
+pub fn reexecute_block(backend: &Arc<MadaraBackend>, block_id: BlockId) -> Result<ReexecReport, Error> {
+    let block = backend.get_block(&block_id)?.ok_or(Error::BlockNotFound)?;
+    let stored_state_diff = backend.get_block_state_diff(&block_id)?.ok_or(Error::BlockNotFound)?;
+
+    let context = ExecutionContext::new_at_block_start(Arc::clone(backend), &block.info)?;
+    let mut cached_state = context.init_cached_state();
+
+    let transaction_count = block.inner.transactions.len();
+    for (index, (tx, hash)) in block.inner.transactions.into_iter().zip(block.info.tx_hashes()).enumerate() {
+        let hash = TransactionHash(*hash);
+        let tx = to_blockifier_transaction(Arc::clone(backend), block_id.clone(), tx, &hash)?;
+        tracing::debug!("re-executing {hash:#}");
+        tx.execute(&mut cached_state, &context.block_context, true, true).map_err(|err| TxExecError {
+            block_n: context.latest_visible_block.into(),
+            hash,
+            index,
+            err,
+        })?;
+    }
+
+    // The state diff is built by blockifier from its write cache, which is private to the `CachedState`. The
+    // `StateMaps` it returns are owned, and consumed here without further copies.
+    let mut computed = computed_entries(cached_state.to_state_diff()?);
+    if let MadaraMaybePendingBlockInfo::NotPending(block_info) = &block.info {
+        if let Some((key, block_hash)) = block_hash_registry_entry(backend, block_info.header.block_number)? {
+            computed.insert(key, block_hash);
+        }
+    }
+    let stored = stored_entries(stored_state_diff);
+    Ok(ReexecReport { transaction_count, divergences: divergences(computed, stored) })
+}
+
+/// The storage write of block production to the block hash registry at address 0x1, made after executing the
+/// transactions of block `block_n`.
+fn block_hash_registry_entry(backend: &MadaraBackend, block_n: u64) -> Result<Option<(StateDiffEntry, Felt)>, Error> {
+    let Some(registered_block_n) = block_n.checked_sub(STORED_BLOCK_HASH_BUFFER) else { return Ok(None) };
+    let block_hash = backend.get_block_hash(&BlockId::Number(registered_block_n))?.ok_or(Error::BlockNotFound)?;
+    Ok(Some((StateDiffEntry::Storage { contract_address: Felt::ONE, key: Felt::from(registered_block_n) }, block_hash)))
+}
+
+fn computed_entries(state_diff: StateMaps) -> BTreeMap<StateDiffEntry, Felt> {
+    let StateMaps { nonces, class_hashes, storage, compiled_class_hashes, declared_contracts } = state_diff;
+
+    let storage = storage.into_iter().map(|((contract_address, key), value)| {
+        (StateDiffEntry::Storage { contract_address: contract_address.to_felt(), key: key.to_felt() }, value)
+    });
+    let nonces = nonces.into_iter().map(|(contract_address, nonce)| {
+        (StateDiffEntry::Nonce { contract_address: contract_address.to_felt() }, nonce.to_felt())
+    });
+    let class_hashes = class_hashes.into_iter().map(|(contract_address, class_hash)| {
+        (StateDiffEntry::ClassHash { contract_address: contract_address.to_felt() }, class_hash.to_felt())
+    });
+    let declared_classes = declared_contracts.into_iter().filter(|(_, declared)| *declared).map(|(class_hash, _)| {
+        let compiled_class_hash = compiled_class_hashes.get(&class_hash).map_or(Felt::ZERO, |hash| hash.to_felt());
+        (StateDiffEntry::DeclaredClass { class_hash: class_hash.to_felt() }, compiled_class_hash)
+    });
+
+    storage.chain(nonces).chain(class_hashes).chain(declared_classes).collect()
+}
+
+fn stored_entries(state_diff: StateDiff) -> BTreeMap<StateDiffEntry, Felt> {
+    let mut entries = BTreeMap::new();
+    for item in state_diff.storage_diffs {
+        for entry in item.storage_entries {
+            entries.insert(StateDiffEntry::Storage { contract_address: item.address, key: entry.key }, entry.value);
+        }
+    }
+    for item in state_diff.nonces {
+        entries.insert(StateDiffEntry::Nonce { contract_address: item.contract_address }, item.nonce);
+    }
+    for item in state_diff.deployed_contracts {
+        entries.insert(StateDiffEntry::ClassHash { contract_address: item.address }, item.class_hash);
+    }
+    for item in state_diff.replaced_classes {
+        entries.insert(StateDiffEntry::ClassHash { contract_address: item.contract_address }, item.class_hash);
+    }
+    for item in state_diff.declared_classes {
+        entries.insert(StateDiffEntry::DeclaredClass { class_hash: item.class_hash }, item.compiled_class_hash);
+    }
+    for class_hash in state_diff.deprecated_declared_classes {
+        entries.insert(StateDiffEntry::DeclaredClass { class_hash }, Felt::ZERO);
+    }
+    entries
+}
+
+fn divergences(
+    computed: BTreeMap<StateDiffEntry, Felt>,
+    mut stored: BTreeMap<StateDiffEntry, Felt>,
+) -> Vec<StateDiffDivergence> {
+    let mut divergences: Vec<_> = computed
+        .into_iter()
+        .filter_map(|(entry, computed)| {
+            let stored = stored.remove(&entry);
+            (stored != Some(computed)).then_some(StateDiffDivergence { entry, computed: Some(computed), stored })
+        })
+        .collect();
+    divergences.extend(stored.into_iter().map(|(entry, stored)| StateDiffDivergence {
+        entry,
+        computed: None,
+        stored: Some(stored),
+    }));
+    divergences.sort_by_key(|divergence| divergence.entry);
+    divergences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mp_state_update::{ContractStorageDiffItem, DeployedContractItem, NonceUpdate, StorageEntry};
+
+    #[test]
+    fn test_state_diff_divergences() {
+        let stored = stored_entries(StateDiff {
+            storage_diffs: vec![ContractStorageDiffItem {
+                address: Felt::ONE,
+                storage_entries: vec![
+                    StorageEntry { key: Felt::ONE, value: Felt::ONE },
+                    StorageEntry { key: Felt::TWO, value: Felt::TWO },
+                ],
+            }],
+            nonces: vec![NonceUpdate { contract_address: Felt::ONE, nonce: Felt::ONE }],
+            deployed_contracts: vec![DeployedContractItem { address: Felt::TWO, class_hash: Felt::THREE }],
+            ..Default::default()
+        });
+
+        let mut computed = stored.clone();
+        assert_eq!(divergences(computed.clone(), stored.clone()), vec![]);
+
+        let storage_1 = StateDiffEntry::Storage { contract_address: Felt::ONE, key: Felt::ONE };
+        let storage_2 = StateDiffEntry::Storage { contract_address: Felt::ONE, key: Felt::TWO };
+        let nonce = StateDiffEntry::Nonce { contract_address: Felt::THREE };
+        computed.insert(storage_1, Felt::TWO);
+        computed.remove(&storage_2);
+        computed.insert(nonce, Felt::ONE);
+
+        assert_eq!(
+            divergences(computed, stored),
+            vec![
+                StateDiffDivergence { entry: storage_1, computed: Some(Felt::TWO), stored: Some(Felt::ONE) },
+                StateDiffDivergence { entry: storage_2, computed: None, stored: Some(Felt::TWO) },
+                StateDiffDivergence { entry: nonce, computed: Some(Felt::ONE), stored: None },
+            ]
+        );
+    }
+}
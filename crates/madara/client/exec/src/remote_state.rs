@@ -0,0 +1,134 @@
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::StateResult;
+use starknet_core::types::{BlockId, StarknetError};
+use starknet_providers::jsonrpc::HttpTransport;
+use starknet_providers::{JsonRpcClient, Provider, ProviderError, Url};
+use starknet_types_core::felt::Felt;
+use std::future::Future;
+use std::sync::{mpsc, Arc};
+use tokio::runtime::Handle;
+
+/// Source of historical state used when the local database does not have it, typically a remote full
+/// node queried over RPC by a node which has pruned its old state.
+///
+/// All methods return `Ok(None)` when the remote does not have a value either. The requested block is
+/// always a sealed block: the pending block is never forwarded to the remote.
+pub trait RemoteStateReader: Send + Sync {
+    /// The local state is only incomplete below this block. On top of a block at or above it, a value missing
+    /// locally has never been written, and the remote is not queried.
+    fn pruned_below(&self) -> u64;
+    fn get_storage_at(&self, block_n: u64, contract_address: Felt, key: Felt) -> StateResult<Option<Felt>>;
+    fn get_nonce_at(&self, block_n: u64, contract_address: Felt) -> StateResult<Option<Felt>>;
+    fn get_class_hash_at(&self, block_n: u64, contract_address: Felt) -> StateResult<Option<Felt>>;
+}
+
+/// [`RemoteStateReader`] querying a Starknet JSON-RPC node, such as an archive node.
+///
+/// Executions are synchronous, so each query blocks the execution thread until the remote answers. The requests
+/// themselves are spawned on the tokio runtime the reader was created in, and share a single HTTP client. Queries
+/// must therefore not be made from a thread of that runtime, which is why executions run on the execution pool.
+pub struct JsonRpcRemoteStateReader {
+    client: Arc<JsonRpcClient<HttpTransport>>,
+    runtime: Handle,
+    pruned_below: u64,
+}
+
+impl JsonRpcRemoteStateReader {
+    /// Must be called from within a tokio runtime. The remote is queried for the state on top of blocks below
+    /// `pruned_below`.
+    pub fn new(url: Url, pruned_below: u64) -> Self {
+        Self { client: Arc::new(JsonRpcClient::new(HttpTransport::new(url))), runtime: Handle::current(), pruned_below }
+    }
+
+    fn query<T: Send + 'static, F: Future<Output = Result<T, ProviderError>> + Send + 'static>(
+        &self,
+        what: impl std::fmt::Display,
+        request: impl FnOnce(Arc<JsonRpcClient<HttpTransport>>) -> F,
+    ) -> StateResult<Option<T>> {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let request = request(Arc::clone(&self.client));
+        self.runtime.spawn(async move {
+            // The receiver only goes away if the execution thread is gone too.
+            let _ = sender.send(request.await);
+        });
+        let res = receiver.recv().map_err(|_| {
+            tracing::warn!("Remote state request for {what} did not complete");
+            StateError::StateReadError(format!("Remote state request for {what} did not complete"))
+        })?;
+
+        match res {
+            Ok(value) => Ok(Some(value)),
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound | StarknetError::BlockNotFound)) => {
+                Ok(None)
+            }
+            Err(err) => {
+                tracing::warn!("Failed to retrieve {what} from the remote state reader: {err:#}");
+                Err(StateError::StateReadError(format!("Failed to retrieve {what} from the remote state reader")))
+            }
+        }
+    }
+}
+
+impl RemoteStateReader for JsonRpcRemoteStateReader {
+    fn pruned_below(&self) -> u64 {
+        self.pruned_below
+    }
+
+    fn get_storage_at(&self, block_n: u64, contract_address: Felt, key: Felt) -> StateResult<Option<Felt>> {
+        self.query(format_args!("storage {contract_address:#x}/{key:#x}"), move |client| async move {
+            client.get_storage_at(contract_address, key, BlockId::Number(block_n)).await
+        })
+    }
+
+    fn get_nonce_at(&self, block_n: u64, contract_address: Felt) -> StateResult<Option<Felt>> {
+        self.query(format_args!("nonce of {contract_address:#x}"), move |client| async move {
+            client.get_nonce(BlockId::Number(block_n), contract_address).await
+        })
+    }
+
+    fn get_class_hash_at(&self, block_n: u64, contract_address: Felt) -> StateResult<Option<Felt>> {
+        self.query(format_args!("class hash of {contract_address:#x}"), move |client| async move {
+            client.get_class_hash_at(BlockId::Number(block_n), contract_address).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_json_rpc_remote_state_reader() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).body_contains("starknet_getStorageAt").body_contains("\"block_number\":7");
+            then.status(200).json_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x14" }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).body_contains("starknet_getNonce");
+            then.status(200).json_body(
+                json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": 20, "message": "Contract not found" } }),
+            );
+        });
+        server.mock(|when, then| {
+            when.method(POST).body_contains("starknet_getClassHashAt");
+            then.status(500);
+        });
+
+        let remote = JsonRpcRemoteStateReader::new(Url::parse(&server.base_url()).unwrap(), 10);
+        let contract = Felt::from_hex_unchecked("0x1234");
+
+        // Like executions, queries are made outside of the runtime threads.
+        tokio::task::spawn_blocking(move || {
+            assert_eq!(remote.get_storage_at(7, contract, Felt::ONE).unwrap(), Some(Felt::from(0x14)));
+            // Contracts the remote does not know about are not an error.
+            assert_eq!(remote.get_nonce_at(7, contract).unwrap(), None);
+            // Transport errors are.
+            assert!(remote.get_class_hash_at(7, contract).is_err());
+        })
+        .await
+        .unwrap();
+    }
+}
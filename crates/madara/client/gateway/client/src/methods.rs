@@ -1,7 +1,8 @@
-use std::{borrow::Cow, sync::Arc};
+use std::borrow::Cow;
 
 use mp_block::{BlockId, BlockTag};
-use mp_class::{ContractClass, FlattenedSierraClass};
+use mp_class::raw_class::{RawClassError, RawContractClass};
+use mp_class::ContractClass;
 use mp_gateway::error::{SequencerError, StarknetError};
 use mp_gateway::{
     block::{ProviderBlock, ProviderBlockPending, ProviderBlockPendingMaybe, ProviderBlockSignature},
@@ -16,7 +17,6 @@ use mp_gateway::{
 use mp_rpc::{AddInvokeTransactionResult, ClassAndTxnHash, ContractAndTxnHash};
 use serde::de::DeserializeOwned;
 use serde_json::Value;
-use starknet_core::types::contract::legacy::LegacyContractClass;
 use starknet_types_core::felt::Felt;
 
 use super::{builder::GatewayProvider, request_builder::RequestBuilder};
@@ -88,6 +88,16 @@ impl GatewayProvider {
         class_hash: Felt,
         block_id: BlockId,
     ) -> Result<ContractClass, SequencerError> {
+        Ok(self.get_class_by_hash_raw(class_hash, block_id).await?.class)
+    }
+
+    /// Same as [`GatewayProvider::get_class_by_hash`], but also returns the top-level fields of the class definition
+    /// which are not part of [`ContractClass`].
+    pub async fn get_class_by_hash_raw(
+        &self,
+        class_hash: Felt,
+        block_id: BlockId,
+    ) -> Result<RawContractClass, SequencerError> {
         let request = RequestBuilder::new(&self.client, self.feeder_gateway_url.clone(), self.headers.clone())
             .add_uri_segment("get_class_by_hash")
             .expect("Failed to add URI segment. This should not fail in prod.")
@@ -96,16 +106,13 @@ impl GatewayProvider {
 
         let value = request.send_get::<Value>().await?;
 
-        if value.get("sierra_program").is_some() {
-            let sierra: FlattenedSierraClass = serde_json::from_value(value)?;
-            Ok(ContractClass::Sierra(Arc::new(sierra)))
-        } else if value.get("program").is_some() {
-            let legacy: LegacyContractClass = serde_json::from_value(value)?;
-            Ok(ContractClass::Legacy(Arc::new(legacy.compress()?.into())))
-        } else {
-            let err = serde::de::Error::custom("Unknown contract type".to_string());
-            Err(SequencerError::DeserializeBody { serde_error: err })
-        }
+        RawContractClass::try_from(value).map_err(|err| match err {
+            RawClassError::Json(serde_error) => SequencerError::DeserializeBody { serde_error },
+            RawClassError::CompressProgram(err) => err.into(),
+            RawClassError::UnknownClassType => SequencerError::DeserializeBody {
+                serde_error: serde::de::Error::custom("Unknown contract type".to_string()),
+            },
+        })
     }
 
     async fn add_transaction<T>(&self, transaction: UserTransaction) -> Result<T, SequencerError>
@@ -152,6 +159,7 @@ mod tests {
     use mp_gateway::error::{SequencerError, StarknetError, StarknetErrorCode};
     use rstest::*;
     use serde::de::DeserializeOwned;
+    use starknet_core::types::contract::legacy::LegacyContractClass;
     use starknet_types_core::felt::Felt;
     use std::fs::{remove_file, File};
     use std::io::{BufReader, BufWriter, Read, Write};
@@ -7,7 +7,7 @@ use mc_db::MadaraBackend;
 use mc_rpc::{
     providers::AddTransactionProvider,
     versions::user::v0_7_1::methods::trace::trace_block_transactions::trace_block_transactions as v0_7_1_trace_block_transactions,
-    Starknet,
+    ExecutionPool, Starknet,
 };
 use mp_block::{BlockId, BlockTag, MadaraBlock, MadaraMaybePendingBlockInfo, MadaraPendingBlock};
 use mp_class::{ClassInfo, ContractClass};
@@ -231,6 +231,7 @@ pub async fn handle_get_block_traces(
     req: Request<Incoming>,
     backend: Arc<MadaraBackend>,
     add_transaction_provider: Arc<dyn AddTransactionProvider>,
+    execution_pool: Arc<ExecutionPool>,
     ctx: ServiceContext,
 ) -> Result<Response<String>, GatewayError> {
     let params = get_params_from_request(&req);
@@ -242,7 +243,7 @@ pub async fn handle_get_block_traces(
     }
 
     let traces = v0_7_1_trace_block_transactions(
-        &Starknet::new(backend, add_transaction_provider, Default::default(), ctx),
+        &Starknet::new(backend, add_transaction_provider, Default::default(), execution_pool, ctx),
         block_id,
     )
     .await?;
@@ -268,6 +269,9 @@ pub async fn handle_get_class_by_hash(
 
     let json_response = match class_info.contract_class() {
         ContractClass::Sierra(flattened_sierra_class) => {
+            flattened_sierra_class
+                .check_program_length()
+                .or_internal_server_error(format!("Class {class_hash:#x} is malformed"))?;
             create_json_response(hyper::StatusCode::OK, flattened_sierra_class.as_ref())
         }
         ContractClass::Legacy(compressed_legacy_contract_class) => {
@@ -285,6 +289,7 @@ pub async fn handle_get_class_by_hash(
 pub async fn handle_get_compiled_class_by_class_hash(
     req: Request<Incoming>,
     backend: Arc<MadaraBackend>,
+    execution_pool: Arc<ExecutionPool>,
 ) -> Result<Response<String>, GatewayError> {
     let params = get_params_from_request(&req);
     let block_id = block_id_from_params(&params).unwrap_or(BlockId::Tag(BlockTag::Latest));
@@ -297,17 +302,18 @@ pub async fn handle_get_compiled_class_by_class_hash(
         .or_internal_server_error(format!("Retrieving class info from class hash {class_hash:x}"))?
         .ok_or(StarknetError::class_not_found(class_hash))?;
 
-    let compiled_class_hash = match class_info {
-        ClassInfo::Sierra(class_info) => class_info.compiled_class_hash,
+    let class_info = match class_info {
+        ClassInfo::Sierra(class_info) => class_info,
         ClassInfo::Legacy(_) => {
             return Err(GatewayError::StarknetError(StarknetError::sierra_class_not_found(class_hash)))
         }
     };
 
-    let class_compiled = backend
-        .get_sierra_compiled(&block_id, &compiled_class_hash)
-        .or_internal_server_error(format!("Retrieving compiled Sierra class from class hash {class_hash:x}"))?
-        .ok_or(StarknetError::class_not_found(class_hash))?;
+    // The class may have to be compiled, which is too slow for the async runtime.
+    let class_compiled = execution_pool
+        .spawn(move || backend.get_or_compile_sierra_compiled(&block_id, &class_hash, &class_info))
+        .await
+        .or_internal_server_error(format!("Retrieving compiled Sierra class from class hash {class_hash:x}"))?;
 
     Ok(create_response_with_json_body(hyper::StatusCode::OK, class_compiled.0))
 }
@@ -2,7 +2,7 @@ use std::{convert::Infallible, sync::Arc};
 
 use hyper::{body::Incoming, Method, Request, Response};
 use mc_db::MadaraBackend;
-use mc_rpc::providers::AddTransactionProvider;
+use mc_rpc::{providers::AddTransactionProvider, ExecutionPool};
 use mp_utils::service::ServiceContext;
 
 use super::handler::{
@@ -17,6 +17,7 @@ pub(crate) async fn main_router(
     req: Request<Incoming>,
     backend: Arc<MadaraBackend>,
     add_transaction_provider: Arc<dyn AddTransactionProvider>,
+    execution_pool: Arc<ExecutionPool>,
     ctx: ServiceContext,
     feeder_gateway_enable: bool,
     gateway_enable: bool,
@@ -25,7 +26,7 @@ pub(crate) async fn main_router(
     match (path.as_ref(), feeder_gateway_enable, gateway_enable) {
         ("health", _, _) => Ok(Response::new("OK".to_string())),
         (path, true, _) if path.starts_with("feeder_gateway/") => {
-            feeder_gateway_router(req, path, backend, add_transaction_provider, ctx).await
+            feeder_gateway_router(req, path, backend, add_transaction_provider, execution_pool, ctx).await
         }
         (path, _, true) if path.starts_with("gateway/") => gateway_router(req, path, add_transaction_provider).await,
         (path, false, _) if path.starts_with("feeder_gateway/") => Ok(service_unavailable_response("Feeder Gateway")),
@@ -43,6 +44,7 @@ async fn feeder_gateway_router(
     path: &str,
     backend: Arc<MadaraBackend>,
     add_transaction_provider: Arc<dyn AddTransactionProvider>,
+    execution_pool: Arc<ExecutionPool>,
     ctx: ServiceContext,
 ) -> Result<Response<String>, Infallible> {
     match (req.method(), path) {
@@ -56,13 +58,15 @@ async fn feeder_gateway_router(
             Ok(handle_get_state_update(req, backend).await.unwrap_or_else(Into::into))
         }
         (&Method::GET, "feeder_gateway/get_block_traces") => {
-            Ok(handle_get_block_traces(req, backend, add_transaction_provider, ctx).await.unwrap_or_else(Into::into))
+            Ok(handle_get_block_traces(req, backend, add_transaction_provider, execution_pool, ctx)
+                .await
+                .unwrap_or_else(Into::into))
         }
         (&Method::GET, "feeder_gateway/get_class_by_hash") => {
             Ok(handle_get_class_by_hash(req, backend).await.unwrap_or_else(Into::into))
         }
         (&Method::GET, "feeder_gateway/get_compiled_class_by_class_hash") => {
-            Ok(handle_get_compiled_class_by_class_hash(req, backend).await.unwrap_or_else(Into::into))
+            Ok(handle_get_compiled_class_by_class_hash(req, backend, execution_pool).await.unwrap_or_else(Into::into))
         }
         (&Method::GET, "feeder_gateway/get_contract_addresses") => {
             Ok(handle_get_contract_addresses(backend).await.unwrap_or_else(Into::into))
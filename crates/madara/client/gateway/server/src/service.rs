@@ -7,7 +7,7 @@ use anyhow::Context;
 use hyper::{server::conn::http1, service::service_fn};
 use hyper_util::rt::TokioIo;
 use mc_db::MadaraBackend;
-use mc_rpc::providers::AddTransactionProvider;
+use mc_rpc::{providers::AddTransactionProvider, ExecutionPool};
 use mp_utils::service::ServiceContext;
 use tokio::net::TcpListener;
 
@@ -16,6 +16,7 @@ use super::router::main_router;
 pub async fn start_server(
     db_backend: Arc<MadaraBackend>,
     add_transaction_provider: Arc<dyn AddTransactionProvider>,
+    execution_pool: Arc<ExecutionPool>,
     feeder_gateway_enable: bool,
     gateway_enable: bool,
     gateway_external: bool,
@@ -43,6 +44,7 @@ pub async fn start_server(
 
             let db_backend = Arc::clone(&db_backend);
             let add_transaction_provider = add_transaction_provider.clone();
+            let execution_pool = Arc::clone(&execution_pool);
             let ctx = ctx.clone();
 
             tokio::task::spawn(async move {
@@ -51,6 +53,7 @@ pub async fn start_server(
                         req,
                         Arc::clone(&db_backend),
                         add_transaction_provider.clone(),
+                        Arc::clone(&execution_pool),
                         ctx.clone(),
                         feeder_gateway_enable,
                         gateway_enable,
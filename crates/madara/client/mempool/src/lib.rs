@@ -118,8 +118,15 @@ impl Mempool {
 
     pub fn load_txs_from_db(&mut self) -> Result<(), anyhow::Error> {
         for res in self.backend.get_mempool_transactions() {
-            let (tx_hash, DbMempoolTxInfoDecoder { saved_tx, converted_class, nonce_readiness }) =
-                res.context("Getting mempool transactions")?;
+            // Transactions saved by an older version may not decode anymore, such as Sierra declare transactions
+            // saved before a compilation could be deferred.
+            let (tx_hash, DbMempoolTxInfoDecoder { saved_tx, converted_class, nonce_readiness }) = match res {
+                Ok(res) => res,
+                Err(err) => {
+                    tracing::warn!("Could not load mempool transaction from db, skipping it: {err:#}");
+                    continue;
+                }
+            };
             let (tx, arrived_at) = saved_to_blockifier_tx(saved_tx, tx_hash, &converted_class)
                 .context("Converting saved tx to blockifier")?;
 
@@ -378,7 +385,11 @@ impl MempoolProvider for Mempool {
         };
 
         let tx = BroadcastedTxn::Declare(tx);
-        let (btx, class) = tx.into_blockifier(self.chain_id(), self.backend.chain_config().latest_protocol_version)?;
+        let (btx, class) = tx.into_blockifier_with_casm_cache(
+            self.chain_id(),
+            self.backend.chain_config().latest_protocol_version,
+            self.backend.casm_cache(),
+        )?;
 
         let res = ClassAndTxnHash {
             transaction_hash: transaction_hash(&btx),
@@ -1,7 +1,7 @@
 use std::time::{Duration, SystemTime};
 
 use blockifier::{
-    execution::{contract_class::ClassInfo as BClassInfo, errors::ContractClassError},
+    execution::errors::ContractClassError,
     transaction::{
         account_transaction::AccountTransaction,
         errors::TransactionExecutionError,
@@ -66,6 +66,8 @@ pub enum SavedToBlockifierTxError {
     ClassCompilationError(#[from] ClassCompilationError),
     #[error("Converting class {0:#}")]
     ContractClassError(#[from] ContractClassError),
+    #[error("Declare transaction {tx_version} cannot declare a {class_kind} class")]
+    DeclaredClassKindMismatch { tx_version: u8, class_kind: &'static str },
 }
 
 pub fn saved_to_blockifier_tx(
@@ -87,16 +89,31 @@ pub fn saved_to_blockifier_tx(
             let converted_class =
                 converted_class.as_ref().ok_or(SavedToBlockifierTxError::MissingField("class_info"))?;
 
-            let class_info = match converted_class {
-                ConvertedClass::Legacy(class) => {
-                    BClassInfo::new(&class.info.contract_class.to_blockifier_class()?, 0, 0)?
-                }
-                ConvertedClass::Sierra(class) => BClassInfo::new(
-                    &class.compiled.to_blockifier_class()?,
-                    class.info.contract_class.sierra_program.len(),
-                    class.info.contract_class.abi.len(),
-                )?,
+            // Declare v0 and v1 declare legacy classes, v2 and v3 declare Sierra classes. Check this here rather
+            // than letting blockifier fail on the version of the class with a less helpful error.
+            let (tx_version, declares_sierra) = match &tx {
+                mp_transactions::DeclareTransaction::V0(_) => (0, false),
+                mp_transactions::DeclareTransaction::V1(_) => (1, false),
+                mp_transactions::DeclareTransaction::V2(_) => (2, true),
+                mp_transactions::DeclareTransaction::V3(_) => (3, true),
             };
+            match (declares_sierra, converted_class) {
+                (true, ConvertedClass::Legacy(_)) => {
+                    return Err(SavedToBlockifierTxError::DeclaredClassKindMismatch {
+                        tx_version,
+                        class_kind: "legacy",
+                    })
+                }
+                (false, ConvertedClass::Sierra(_)) => {
+                    return Err(SavedToBlockifierTxError::DeclaredClassKindMismatch {
+                        tx_version,
+                        class_kind: "Sierra",
+                    })
+                }
+                _ => {}
+            }
+
+            let class_info = converted_class.to_blockifier_class_info()?;
             let tx = tx.try_into().map_err(|_| SavedToBlockifierTxError::InvalidContractAddress)?;
             let declare_tx = match saved_tx.only_query {
                 true => DeclareTransaction::new_for_query(tx, tx_hash, class_info)?,
@@ -127,3 +144,80 @@ pub fn saved_to_blockifier_tx(
 
     Ok((tx, arrived_at))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use mp_class::{
+        CompressedLegacyContractClass, EntryPointsByType, FlattenedSierraClass, LegacyClassInfo, LegacyConvertedClass,
+        LegacyEntryPointsByType, SierraClassInfo, SierraConvertedClass,
+    };
+    use std::sync::Arc;
+
+    fn saved_declare(tx: mp_transactions::DeclareTransaction) -> SavedTransaction {
+        SavedTransaction {
+            tx: mp_transactions::Transaction::Declare(tx),
+            paid_fee_on_l1: None,
+            contract_address: None,
+            only_query: false,
+            arrived_at: 0,
+        }
+    }
+
+    fn legacy_class() -> ConvertedClass {
+        ConvertedClass::Legacy(LegacyConvertedClass {
+            class_hash: Felt::ONE,
+            info: LegacyClassInfo {
+                contract_class: Arc::new(CompressedLegacyContractClass {
+                    program: vec![],
+                    entry_points_by_type: LegacyEntryPointsByType {
+                        constructor: vec![],
+                        external: vec![],
+                        l1_handler: vec![],
+                    },
+                    abi: None,
+                }),
+            },
+        })
+    }
+
+    fn sierra_class() -> ConvertedClass {
+        ConvertedClass::Sierra(SierraConvertedClass {
+            class_hash: Felt::ONE,
+            info: SierraClassInfo {
+                contract_class: Arc::new(FlattenedSierraClass {
+                    sierra_program: vec![],
+                    contract_class_version: "0.1.0".into(),
+                    entry_points_by_type: EntryPointsByType {
+                        constructor: vec![],
+                        external: vec![],
+                        l1_handler: vec![],
+                    },
+                    abi: "[]".into(),
+                }),
+                compiled_class_hash: Felt::TWO,
+            },
+            compiled: None,
+        })
+    }
+
+    #[test]
+    fn test_declared_class_kind_mismatch() {
+        let v1 = || saved_declare(mp_transactions::DeclareTransaction::V1(Default::default()));
+        let v2 = || saved_declare(mp_transactions::DeclareTransaction::V2(Default::default()));
+
+        assert_matches!(
+            saved_to_blockifier_tx(v1(), Felt::ONE, &Some(sierra_class())),
+            Err(SavedToBlockifierTxError::DeclaredClassKindMismatch { tx_version: 1, class_kind: "Sierra" })
+        );
+        assert_matches!(
+            saved_to_blockifier_tx(v2(), Felt::ONE, &Some(legacy_class())),
+            Err(SavedToBlockifierTxError::DeclaredClassKindMismatch { tx_version: 2, class_kind: "legacy" })
+        );
+        assert_matches!(
+            saved_to_blockifier_tx(v1(), Felt::ONE, &None),
+            Err(SavedToBlockifierTxError::MissingField("class_info"))
+        );
+    }
+}
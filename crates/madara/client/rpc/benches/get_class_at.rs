@@ -0,0 +1,77 @@
+//! Benchmarks of `starknet_getClassAt` over a large Sierra class and a legacy class, with a cold and a warm class
+//! cache.
+//!
+//! Compare against a baseline to see regressions:
+//!
+//! ```sh
+//! cargo bench -p mc-rpc --features testing --bench get_class_at -- --save-baseline main
+//! # after the change
+//! cargo bench -p mc-rpc --features testing --bench get_class_at -- --baseline main
+//! ```
+//!
+//! Changes larger than the noise threshold are reported as regressions or improvements.
+//! `cargo test -p mc-rpc --features testing --benches` runs every benchmark once, as a smoke test.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use mc_db::MadaraBackend;
+use mc_rpc::test_utils::{class_fixtures, make_sample_chain_with_classes, rpc_test_setup};
+use mc_rpc::versions::user::v0_7_1::methods::read::get_class_at::get_class_at;
+use mc_rpc::Starknet;
+use mp_block::BlockId;
+use starknet_types_core::felt::Felt;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Relative change in run time under which a difference with the baseline is considered noise.
+const REGRESSION_THRESHOLD: f64 = 0.05;
+
+/// A large Sierra class and a legacy class from [`class_fixtures`].
+const BENCHED_CLASSES: [(&str, &str); 2] = [("sierra", "openzeppelin erc20"), ("legacy", "mainnet erc1155")];
+
+/// Returns the contract deployed for each of the [`BENCHED_CLASSES`].
+fn setup() -> (Arc<MadaraBackend>, Starknet, Vec<(&'static str, Felt)>) {
+    let (backend, rpc) = rpc_test_setup();
+    let classes: Vec<_> = class_fixtures()
+        .into_iter()
+        .filter(|class| BENCHED_CLASSES.iter().any(|(_, fixture)| *fixture == class.name))
+        .collect();
+    let contracts = make_sample_chain_with_classes(&backend, &classes);
+    let benched = BENCHED_CLASSES
+        .iter()
+        .map(|(name, fixture)| {
+            let i = classes.iter().position(|class| class.name == *fixture).expect("Class fixture not found");
+            (*name, contracts[i])
+        })
+        .collect();
+    (backend, rpc, benched)
+}
+
+fn bench_get_class_at(c: &mut Criterion) {
+    let (backend, rpc, contracts) = setup();
+    let mut group = c.benchmark_group("get_class_at");
+
+    for (name, contract) in contracts {
+        group.bench_function(format!("{name}/cold"), |b| {
+            b.iter(|| {
+                backend.class_cache().clear();
+                black_box(get_class_at(&rpc, BlockId::Number(0), contract).unwrap())
+            })
+        });
+        group.bench_function(format!("{name}/warm"), |b| {
+            get_class_at(&rpc, BlockId::Number(0), contract).unwrap();
+            b.iter(|| black_box(get_class_at(&rpc, BlockId::Number(0), contract).unwrap()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .noise_threshold(REGRESSION_THRESHOLD)
+        .measurement_time(Duration::from_secs(10));
+    targets = bench_get_class_at
+}
+criterion_main!(benches);
@@ -2,3 +2,6 @@
 pub const MAX_EVENTS_KEYS: usize = 100;
 /// Maximum number of events that can be fetched in a single chunk for the `get_events` RPC.
 pub const MAX_EVENTS_CHUNK_SIZE: usize = 1000;
+/// Maximum size in bytes of the events returned in a single chunk by the `get_events` RPC. When this is reached
+/// before `chunk_size`, the chunk is cut short and a continuation token is returned.
+pub const MAX_EVENTS_CHUNK_BYTES: usize = 10 * 1024 * 1024;
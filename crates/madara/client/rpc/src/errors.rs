@@ -98,6 +98,8 @@ pub enum StarknetRpcApiError {
     UnsupportedContractClassVersion,
     #[error("An unexpected error occurred")]
     ErrUnexpectedError { data: String },
+    #[error("Invalid params")]
+    InvalidParams { data: String },
     #[error("Internal server error")]
     InternalServerError,
     #[error("Unimplemented method")]
@@ -106,6 +108,8 @@ pub enum StarknetRpcApiError {
     ProofLimitExceeded { kind: StorageProofLimit, limit: usize, got: usize },
     #[error("Cannot create a storage proof for a block that old")]
     CannotMakeProofOnOldBlock,
+    #[error("Class too large to serve over RPC")]
+    ClassTooLargeToServe { class_hash: Felt, size: usize, max_size: usize },
 }
 
 impl From<&StarknetRpcApiError> for i32 {
@@ -140,10 +144,12 @@ impl From<&StarknetRpcApiError> for i32 {
             StarknetRpcApiError::UnsupportedTxnVersion => 61,
             StarknetRpcApiError::UnsupportedContractClassVersion => 62,
             StarknetRpcApiError::ErrUnexpectedError { .. } => 63,
+            StarknetRpcApiError::InvalidParams { .. } => jsonrpsee::types::error::INVALID_PARAMS_CODE,
             StarknetRpcApiError::InternalServerError => 500,
             StarknetRpcApiError::UnimplementedMethod => 501,
             StarknetRpcApiError::ProofLimitExceeded { .. } => 10000,
             StarknetRpcApiError::CannotMakeProofOnOldBlock => 10001,
+            StarknetRpcApiError::ClassTooLargeToServe { .. } => 10002,
         }
     }
 }
@@ -152,6 +158,7 @@ impl StarknetRpcApiError {
     pub fn data(&self) -> Option<serde_json::Value> {
         match self {
             StarknetRpcApiError::ErrUnexpectedError { data } => Some(json!(data)),
+            StarknetRpcApiError::InvalidParams { data } => Some(json!(data)),
             StarknetRpcApiError::ValidationFailure { error } => Some(json!(error)),
             StarknetRpcApiError::FailedToReceiveTxn { err } => err.as_ref().map(|err| json!(err)),
             StarknetRpcApiError::TxnExecutionError { tx_index, error } => Some(json!({
@@ -161,6 +168,12 @@ impl StarknetRpcApiError {
             StarknetRpcApiError::ProofLimitExceeded { kind, limit, got } => {
                 Some(json!({ "kind": kind, "limit": limit, "got": got }))
             }
+            StarknetRpcApiError::ClassTooLargeToServe { class_hash, size, max_size } => Some(json!({
+                "class_hash": class_hash,
+                "size": size,
+                "max_size": max_size,
+                "hint": "fetch the class from the feeder gateway `get_class_by_hash` endpoint instead",
+            })),
             _ => None,
         }
     }
@@ -0,0 +1,127 @@
+use mp_rpc::TransactionTrace;
+use starknet_types_core::felt::Felt;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Default number of execution results kept by the [`ExecutionCache`].
+pub const DEFAULT_EXECUTION_CACHE_CAPACITY: usize = 1024;
+
+/// Cache of the execution traces of already-sealed transactions, keyed by `(block_hash, transaction_hash)`.
+///
+/// Re-executing a transaction from a sealed block always yields the same result, so there is no need to
+/// go through the blockifier again when an explorer asks for the same trace multiple times. Entries are keyed by
+/// block hash rather than block number, so that after a reorg, a transaction included again at the same height is
+/// not served the trace of its execution in the replaced block. Pending transactions must never be inserted here,
+/// as the pending block can change under us.
+///
+/// When the cache is full, the oldest entry is evicted. A capacity of 0 disables the cache.
+#[derive(Debug)]
+pub struct ExecutionCache {
+    capacity: usize,
+    inner: Mutex<ExecutionCacheInner>,
+}
+
+#[derive(Debug, Default)]
+struct ExecutionCacheInner {
+    traces: HashMap<(Felt, Felt), TransactionTrace>,
+    insertion_order: VecDeque<(Felt, Felt)>,
+}
+
+impl Default for ExecutionCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_EXECUTION_CACHE_CAPACITY)
+    }
+}
+
+impl ExecutionCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, inner: Default::default() }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().expect("Poisoned lock").traces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get_trace(&self, block_hash: &Felt, transaction_hash: &Felt) -> Option<TransactionTrace> {
+        self.inner.lock().expect("Poisoned lock").traces.get(&(*block_hash, *transaction_hash)).cloned()
+    }
+
+    pub fn insert_trace(&self, block_hash: Felt, transaction_hash: Felt, trace: TransactionTrace) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.lock().expect("Poisoned lock");
+        let key = (block_hash, transaction_hash);
+        if inner.traces.insert(key, trace).is_some() {
+            return; // already cached, keep the original insertion order
+        }
+        inner.insertion_order.push_back(key);
+
+        while inner.insertion_order.len() > self.capacity {
+            if let Some(evicted) = inner.insertion_order.pop_front() {
+                inner.traces.remove(&evicted);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use mp_rpc::{DataAvailability, DeclareTransactionTrace, ExecutionResources};
+
+    pub(crate) fn sample_trace(steps: u64) -> TransactionTrace {
+        TransactionTrace::Declare(DeclareTransactionTrace {
+            execution_resources: ExecutionResources {
+                bitwise_builtin_applications: None,
+                ec_op_builtin_applications: None,
+                ecdsa_builtin_applications: None,
+                keccak_builtin_applications: None,
+                memory_holes: None,
+                pedersen_builtin_applications: None,
+                poseidon_builtin_applications: None,
+                range_check_builtin_applications: None,
+                segment_arena_builtin: None,
+                steps,
+                data_availability: DataAvailability { l1_data_gas: 0, l1_gas: 0 },
+            },
+            fee_transfer_invocation: None,
+            state_diff: None,
+            validate_invocation: None,
+        })
+    }
+
+    #[test]
+    fn test_execution_cache_eviction() {
+        let cache = ExecutionCache::new(2);
+        let (block_a, block_b) = (Felt::from(0xa), Felt::from(0xb));
+
+        cache.insert_trace(block_a, Felt::ONE, sample_trace(1));
+        cache.insert_trace(block_a, Felt::TWO, sample_trace(2));
+        assert_eq!(cache.get_trace(&block_a, &Felt::ONE), Some(sample_trace(1)));
+        assert_eq!(cache.get_trace(&block_b, &Felt::ONE), None);
+
+        cache.insert_trace(block_b, Felt::THREE, sample_trace(3));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get_trace(&block_a, &Felt::ONE), None);
+        assert_eq!(cache.get_trace(&block_a, &Felt::TWO), Some(sample_trace(2)));
+        assert_eq!(cache.get_trace(&block_b, &Felt::THREE), Some(sample_trace(3)));
+    }
+
+    #[test]
+    fn test_execution_cache_disabled() {
+        let cache = ExecutionCache::new(0);
+        cache.insert_trace(Felt::ZERO, Felt::ONE, sample_trace(1));
+        assert!(cache.is_empty());
+        assert_eq!(cache.get_trace(&Felt::ZERO, &Felt::ONE), None);
+    }
+}
@@ -0,0 +1,86 @@
+use anyhow::{ensure, Context};
+use std::panic::AssertUnwindSafe;
+
+/// Thread pool on which blockifier executions of the RPCs (`call`, `estimateFee`, `simulateTransactions`...)
+/// are run.
+///
+/// Executing transactions is CPU-bound and can take a long time. Running it directly on the tokio worker threads
+/// would starve the async runtime, and every other RPC request with it. Executions are instead handed off to this
+/// dedicated pool, which is also separate from the global rayon pool used by block import.
+#[derive(Debug)]
+pub struct ExecutionPool {
+    pool: rayon::ThreadPool,
+}
+
+impl ExecutionPool {
+    /// Creates a pool with `n_threads` threads.
+    pub fn new(n_threads: usize) -> anyhow::Result<Self> {
+        ensure!(n_threads > 0, "The execution thread pool needs at least one thread");
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(n_threads)
+            .thread_name(|i| format!("rpc-execution-{i}"))
+            .build()
+            .context("Building the execution thread pool")?;
+        Ok(Self { pool })
+    }
+
+    pub fn n_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Runs `func` on the pool, and waits for its result without blocking the async runtime.
+    pub async fn spawn<F, R>(&self, func: F) -> R
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        // Important: fifo mode.
+        self.pool.spawn_fifo(move || {
+            // We bubble up the panics to the tokio pool.
+            let _result = tx.send(std::panic::catch_unwind(AssertUnwindSafe(func)));
+        });
+
+        rx.await.expect("Tokio channel closed").expect("Rayon task panicked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::rpc_test_setup;
+    use crate::versions::user::v0_7_1::StarknetReadRpcApiV0_7_1Server;
+    use crate::{ExecutionPool, Starknet};
+    use mc_db::MadaraBackend;
+    use mp_block::{BlockId, BlockTag};
+    use rstest::rstest;
+    use starknet_types_core::felt::Felt;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    #[rstest]
+    async fn test_execution_does_not_block_runtime(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (_backend, rpc) = rpc_test_setup;
+
+        // A long-running simulation, on a single threaded runtime.
+        let simulation = tokio::spawn({
+            let rpc = rpc.clone();
+            async move { rpc.execution_pool.spawn(|| std::thread::sleep(Duration::from_millis(500))).await }
+        });
+        tokio::task::yield_now().await;
+
+        // Had the simulation run on the runtime thread, it would have finished before we could get here.
+        let _res = rpc.get_storage_at(Felt::ONE, Felt::ONE, BlockId::Tag(BlockTag::Latest));
+        assert!(!simulation.is_finished());
+
+        simulation.await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Rayon task panicked")]
+    async fn test_execution_panic_is_propagated() {
+        let pool = ExecutionPool::new(1).unwrap();
+        pool.spawn(|| panic!("execution panicked")).await
+    }
+}
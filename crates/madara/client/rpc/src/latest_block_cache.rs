@@ -0,0 +1,170 @@
+use mc_db::MadaraBackend;
+use mp_block::MadaraBlockInfo;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+/// Memoizes the number of the latest block, which is resolved by almost every RPC request.
+///
+/// The cached value is dropped whenever the backend announces a new block. Blocks are announced before they are
+/// committed to the database, so a freshly loaded value is only kept once it has caught up with the highest
+/// announced block: until then, every lookup goes to the database.
+///
+/// Lookups do not wait on each other while loading: the cached value is an atomic, and a value loaded while a new
+/// block was announced is discarded thanks to a generation counter bumped on every announcement.
+#[derive(Debug)]
+pub struct LatestBlockCache {
+    /// Cached block number plus one, 0 when nothing is cached.
+    block_n: AtomicU64,
+    /// Highest announced block number plus one, 0 when no block was announced.
+    highest_announced: AtomicU64,
+    /// Incremented on every announcement.
+    generation: AtomicU64,
+    /// Only locked to drain the announcements.
+    new_blocks: Mutex<broadcast::Receiver<MadaraBlockInfo>>,
+}
+
+impl LatestBlockCache {
+    pub fn new(backend: &MadaraBackend) -> Self {
+        Self {
+            block_n: AtomicU64::new(0),
+            highest_announced: AtomicU64::new(0),
+            generation: AtomicU64::new(0),
+            new_blocks: Mutex::new(backend.subscribe_block_info()),
+        }
+    }
+
+    /// Returns the cached latest block number, calling `load` when no up-to-date value is cached.
+    pub fn get_or_load(&self, load: impl FnOnce() -> anyhow::Result<Option<u64>>) -> anyhow::Result<Option<u64>> {
+        self.drain_announcements();
+
+        if let Some(block_n) = self.block_n.load(Ordering::Acquire).checked_sub(1) {
+            return Ok(Some(block_n));
+        }
+
+        let generation = self.generation.load(Ordering::Acquire);
+        let block_n = load()?;
+        if let Some(block_n) = block_n {
+            if block_n + 1 >= self.highest_announced.load(Ordering::Acquire) {
+                self.block_n.store(block_n + 1, Ordering::Release);
+                // A block was announced while loading: the value may be outdated, and the announcement may have
+                // been processed before it was stored.
+                if self.generation.load(Ordering::Acquire) != generation {
+                    let _ = self.block_n.compare_exchange(block_n + 1, 0, Ordering::AcqRel, Ordering::Relaxed);
+                }
+            }
+        }
+        Ok(block_n)
+    }
+
+    fn drain_announcements(&self) {
+        let mut new_blocks = self.new_blocks.lock().expect("Poisoned lock");
+        loop {
+            match new_blocks.try_recv() {
+                Ok(info) => {
+                    self.highest_announced.fetch_max(info.header.block_number + 1, Ordering::AcqRel);
+                    self.invalidate();
+                }
+                // Some announcements were dropped, the next ones are still in the channel.
+                Err(TryRecvError::Lagged(_)) => self.invalidate(),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+    }
+
+    fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.block_n.store(0, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::rpc_test_setup;
+    use crate::Starknet;
+    use mp_block::{BlockId, BlockTag, Header, MadaraBlockInner, MadaraMaybePendingBlock, MadaraMaybePendingBlockInfo};
+    use mp_state_update::StateDiff;
+    use rstest::rstest;
+    use starknet_types_core::felt::Felt;
+    use std::sync::Arc;
+
+    fn store_block(backend: &MadaraBackend, block_number: u64) {
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                        header: Header { block_number, ..Default::default() },
+                        block_hash: Felt::from(block_number),
+                        tx_hashes: vec![],
+                    }),
+                    inner: MadaraBlockInner { transactions: vec![], receipts: vec![] },
+                },
+                StateDiff::default(),
+                vec![],
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    fn not_cached() -> anyhow::Result<Option<u64>> {
+        panic!("the latest block number should have been cached")
+    }
+
+    #[rstest]
+    fn test_latest_block_cache(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, _) = rpc_test_setup;
+        let cache = LatestBlockCache::new(&backend);
+
+        store_block(&backend, 0);
+        assert_eq!(cache.get_or_load(|| Ok(Some(0))).unwrap(), Some(0));
+        assert_eq!(cache.get_or_load(not_cached).unwrap(), Some(0));
+
+        // Block 1 is announced: a value older than the announcement is served but never cached.
+        store_block(&backend, 1);
+        assert_eq!(cache.get_or_load(|| Ok(Some(0))).unwrap(), Some(0));
+        assert_eq!(cache.get_or_load(|| Ok(Some(1))).unwrap(), Some(1));
+        assert_eq!(cache.get_or_load(not_cached).unwrap(), Some(1));
+    }
+
+    #[rstest]
+    fn test_latest_block_cache_announcement_while_loading(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, _) = rpc_test_setup;
+        let cache = LatestBlockCache::new(&backend);
+        store_block(&backend, 0);
+        store_block(&backend, 1);
+
+        // Block 2 is announced while loading, after the announcements were drained: the loaded value is served
+        // but not kept, even though it is up to date.
+        let loaded = cache.get_or_load(|| {
+            store_block(&backend, 2);
+            cache.drain_announcements();
+            Ok(Some(2))
+        });
+        assert_eq!(loaded.unwrap(), Some(2));
+
+        let reloaded = std::cell::Cell::new(false);
+        let load = || {
+            reloaded.set(true);
+            Ok(Some(2))
+        };
+        assert_eq!(cache.get_or_load(load).unwrap(), Some(2));
+        assert!(reloaded.get());
+        assert_eq!(cache.get_or_load(not_cached).unwrap(), Some(2));
+    }
+
+    #[rstest]
+    fn test_current_block_number_follows_imports(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+
+        store_block(&backend, 0);
+        assert_eq!(rpc.current_block_number(), Ok(0));
+        assert_eq!(rpc.current_block_number(), Ok(0));
+
+        store_block(&backend, 1);
+        store_block(&backend, 2);
+        assert_eq!(rpc.current_block_number(), Ok(2));
+        assert_eq!(rpc.get_block_n(&BlockId::Tag(BlockTag::Latest)), Ok(2));
+    }
+}
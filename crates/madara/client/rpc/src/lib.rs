@@ -4,8 +4,11 @@
 
 mod constants;
 mod errors;
+pub mod execution_cache;
+pub mod execution_pool;
+pub mod latest_block_cache;
 pub mod providers;
-#[cfg(test)]
+#[cfg(any(test, feature = "testing"))]
 pub mod test_utils;
 mod types;
 pub mod utils;
@@ -14,9 +17,12 @@ pub mod versions;
 use jsonrpsee::RpcModule;
 use mc_db::db_block_id::DbBlockIdResolvable;
 use mc_db::MadaraBackend;
+use mc_exec::{ExecutionContext, RemoteStateReader};
 use mp_block::{BlockId, BlockTag, MadaraMaybePendingBlock, MadaraMaybePendingBlockInfo};
 use mp_chain_config::ChainConfig;
+use mp_class::{convert::DecompressionLimiter, ContractClass};
 use mp_convert::ToFelt;
+use mp_rpc::MaybeDeprecatedContractClass;
 use mp_utils::service::ServiceContext;
 use providers::AddTransactionProvider;
 use starknet_types_core::felt::Felt;
@@ -24,6 +30,9 @@ use std::sync::Arc;
 use utils::ResultExt;
 
 pub use errors::{StarknetRpcApiError, StarknetRpcResult};
+pub use execution_cache::ExecutionCache;
+pub use execution_pool::ExecutionPool;
+pub use latest_block_cache::LatestBlockCache;
 
 /// Limits to the storage proof endpoint.
 #[derive(Clone, Debug)]
@@ -48,6 +57,15 @@ pub struct Starknet {
     backend: Arc<MadaraBackend>,
     pub(crate) add_transaction_provider: Arc<dyn AddTransactionProvider>,
     storage_proof_config: StorageProofConfig,
+    pub(crate) execution_cache: Arc<ExecutionCache>,
+    pub(crate) execution_pool: Arc<ExecutionPool>,
+    /// Bounds the executions decompressing classes at the same time.
+    decompression_limiter: DecompressionLimiter,
+    latest_block_cache: Arc<LatestBlockCache>,
+    /// Fallback for the state missing from the local database, used by every execution.
+    remote_state: Option<Arc<dyn RemoteStateReader>>,
+    /// Classes with a JSON encoding larger than this are not served, in bytes. 0 means no limit.
+    max_served_class_size: usize,
     pub ctx: ServiceContext,
 }
 
@@ -56,9 +74,112 @@ impl Starknet {
         backend: Arc<MadaraBackend>,
         add_transaction_provider: Arc<dyn AddTransactionProvider>,
         storage_proof_config: StorageProofConfig,
+        execution_pool: Arc<ExecutionPool>,
         ctx: ServiceContext,
     ) -> Self {
-        Self { backend, add_transaction_provider, storage_proof_config, ctx }
+        Self {
+            latest_block_cache: Arc::new(LatestBlockCache::new(&backend)),
+            backend,
+            add_transaction_provider,
+            storage_proof_config,
+            execution_cache: Arc::new(ExecutionCache::default()),
+            execution_pool,
+            decompression_limiter: DecompressionLimiter::default(),
+            remote_state: None,
+            max_served_class_size: 0,
+            ctx,
+        }
+    }
+
+    /// Sets how many execution results of sealed transactions are kept in memory. 0 disables the cache.
+    pub fn with_execution_cache_capacity(mut self, capacity: usize) -> Self {
+        self.execution_cache = Arc::new(ExecutionCache::new(capacity));
+        self
+    }
+
+    /// Fetch the state missing from the local database from a remote when executing transactions, for example
+    /// to trace old blocks on a node which pruned their state.
+    pub fn with_remote_state_reader(mut self, remote_state: Arc<dyn RemoteStateReader>) -> Self {
+        self.remote_state = Some(remote_state);
+        self
+    }
+
+    /// Sets how many executions, which decompress the classes they use, can run at the same time. Executions over
+    /// the limit wait for one to finish. 0 disables the limit.
+    pub fn with_class_decompression_limit(mut self, max: usize) -> Self {
+        self.decompression_limiter = DecompressionLimiter::new(max);
+        self
+    }
+
+    /// Runs an execution on the [`ExecutionPool`], once the class decompression limit allows it.
+    pub(crate) async fn spawn_execution<F, R>(&self, func: F) -> R
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let _permit = self.decompression_limiter.acquire().await;
+        self.execution_pool.spawn(func).await
+    }
+
+    /// [`ExecutionContext::new_at_block_start`], with the configured remote state reader.
+    pub(crate) fn exec_context_at_block_start(
+        &self,
+        block_info: &MadaraMaybePendingBlockInfo,
+    ) -> Result<ExecutionContext, mc_exec::Error> {
+        Ok(self.with_remote_state(ExecutionContext::new_at_block_start(Arc::clone(&self.backend), block_info)?))
+    }
+
+    /// [`ExecutionContext::new_at_block_end`], with the configured remote state reader.
+    pub(crate) fn exec_context_at_block_end(
+        &self,
+        block_info: &MadaraMaybePendingBlockInfo,
+    ) -> Result<ExecutionContext, mc_exec::Error> {
+        Ok(self.with_remote_state(ExecutionContext::new_at_block_end(Arc::clone(&self.backend), block_info)?))
+    }
+
+    fn with_remote_state(&self, exec_context: ExecutionContext) -> ExecutionContext {
+        match &self.remote_state {
+            Some(remote_state) => exec_context.with_remote_state_reader(Arc::clone(remote_state)),
+            None => exec_context,
+        }
+    }
+
+    /// Sets the maximum size in bytes of the class definitions served by `getClass` and `getClassAt`. 0 disables
+    /// the limit.
+    pub fn with_max_served_class_size(mut self, max_size: usize) -> Self {
+        self.max_served_class_size = max_size;
+        self
+    }
+
+    /// Fails with [`StarknetRpcApiError::ErrUnexpectedError`] for a stored Sierra class whose program is too short
+    /// to be valid, see [`mp_class::FlattenedSierraClass::check_program_length`]: it would not hash to its class hash.
+    pub(crate) fn check_served_class(&self, class_hash: Felt, class: &ContractClass) -> StarknetRpcResult<()> {
+        let ContractClass::Sierra(sierra) = class else { return Ok(()) };
+        sierra.check_program_length().map_err(|err| {
+            tracing::error!("Stored class {class_hash:#x} is malformed: {err}");
+            StarknetRpcApiError::ErrUnexpectedError { data: format!("Class {class_hash:#x} is malformed: {err}") }
+        })
+    }
+
+    /// Fails with [`StarknetRpcApiError::ClassTooLargeToServe`] when the JSON encoding of `class` is over the
+    /// configured limit.
+    pub(crate) fn check_served_class_size(
+        &self,
+        class_hash: Felt,
+        class: &MaybeDeprecatedContractClass,
+    ) -> StarknetRpcResult<()> {
+        if self.max_served_class_size == 0 {
+            return Ok(());
+        }
+        let size = utils::json_size(class).or_internal_server_error("Error encoding contract class")?;
+        if size > self.max_served_class_size {
+            return Err(StarknetRpcApiError::ClassTooLargeToServe {
+                class_hash,
+                size,
+                max_size: self.max_served_class_size,
+            });
+        }
+        Ok(())
     }
 
     pub fn clone_backend(&self) -> Arc<MadaraBackend> {
@@ -79,7 +200,11 @@ impl Starknet {
             .ok_or(StarknetRpcApiError::BlockNotFound)
     }
 
-    pub fn get_block_n(&self, block_id: &impl DbBlockIdResolvable) -> StarknetRpcResult<u64> {
+    /// The latest block is resolved through the [`LatestBlockCache`].
+    pub fn get_block_n(&self, block_id: &BlockId) -> StarknetRpcResult<u64> {
+        if let BlockId::Tag(BlockTag::Latest) = block_id {
+            return self.current_block_number();
+        }
         self.backend
             .get_block_n(block_id)
             .or_internal_server_error("Error getting block from storage")?
@@ -93,12 +218,53 @@ impl Starknet {
             .ok_or(StarknetRpcApiError::BlockNotFound)
     }
 
+    /// Returns the number of the latest block with a timestamp lower or equal to `timestamp`, ie. the
+    /// block that was active at that time. Timestamps past the tip of the chain resolve to the tip.
+    ///
+    /// Block timestamps are monotonic, so this is a binary search over the block headers.
+    pub fn get_block_by_timestamp(&self, timestamp: u64) -> StarknetRpcResult<u64> {
+        let latest_block_n = self
+            .backend
+            .get_latest_block_n()
+            .or_internal_server_error("Error getting latest block number")?
+            .ok_or(StarknetRpcApiError::NoBlocks)?;
+
+        let block_timestamp = |block_n: u64| -> StarknetRpcResult<u64> {
+            Ok(self.get_block_info(&BlockId::Number(block_n))?.block_timestamp().0)
+        };
+
+        if block_timestamp(0)? > timestamp {
+            // Timestamp is before genesis.
+            return Err(StarknetRpcApiError::BlockNotFound);
+        }
+        if block_timestamp(latest_block_n)? <= timestamp {
+            return Ok(latest_block_n);
+        }
+
+        // Invariant: block_timestamp(low) <= timestamp < block_timestamp(high)
+        let (mut low, mut high) = (0, latest_block_n);
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if block_timestamp(mid)? <= timestamp {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(low)
+    }
+
     pub fn chain_id(&self) -> Felt {
         self.backend.chain_config().chain_id.clone().to_felt()
     }
 
+    /// Number of the latest block. This is served from the [`LatestBlockCache`].
     pub fn current_block_number(&self) -> StarknetRpcResult<u64> {
-        self.get_block_n(&BlockId::Tag(BlockTag::Latest))
+        self.latest_block_cache
+            .get_or_load(|| self.backend.get_latest_block_n())
+            .or_internal_server_error("Error getting latest block number")?
+            .ok_or(StarknetRpcApiError::BlockNotFound)
     }
 
     pub fn get_l1_last_confirmed_block(&self) -> StarknetRpcResult<u64> {
@@ -119,6 +285,7 @@ pub fn rpc_api_user(starknet: &Starknet) -> anyhow::Result<RpcModule<()>> {
     rpc_api.merge(versions::user::v0_7_1::StarknetWriteRpcApiV0_7_1Server::into_rpc(starknet.clone()))?;
     rpc_api.merge(versions::user::v0_7_1::StarknetTraceRpcApiV0_7_1Server::into_rpc(starknet.clone()))?;
     rpc_api.merge(versions::user::v0_8_0::StarknetWsRpcApiV0_8_0Server::into_rpc(starknet.clone()))?;
+    rpc_api.merge(versions::user::v0_7_1::MadaraReadRpcApiV0_7_1Server::into_rpc(starknet.clone()))?;
 
     Ok(rpc_api)
 }
@@ -132,3 +299,94 @@ pub fn rpc_api_admin(starknet: &Starknet) -> anyhow::Result<RpcModule<()>> {
 
     Ok(rpc_api)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::rpc_test_setup;
+    use mp_block::header::BlockTimestamp;
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner};
+    use mp_state_update::StateDiff;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_get_block_by_timestamp(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+
+        assert_eq!(rpc.get_block_by_timestamp(100), Err(StarknetRpcApiError::NoBlocks));
+
+        let timestamps = [100, 110, 110, 125, 140];
+        for (block_number, timestamp) in timestamps.into_iter().enumerate() {
+            backend
+                .store_block(
+                    MadaraMaybePendingBlock {
+                        info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                            header: Header {
+                                block_number: block_number as u64,
+                                block_timestamp: BlockTimestamp(timestamp),
+                                ..Default::default()
+                            },
+                            block_hash: Felt::from(block_number),
+                            tx_hashes: vec![],
+                        }),
+                        inner: MadaraBlockInner { transactions: vec![], receipts: vec![] },
+                    },
+                    StateDiff::default(),
+                    vec![],
+                    None,
+                    None,
+                )
+                .unwrap();
+        }
+
+        // Before genesis
+        assert_eq!(rpc.get_block_by_timestamp(0), Err(StarknetRpcApiError::BlockNotFound));
+        assert_eq!(rpc.get_block_by_timestamp(99), Err(StarknetRpcApiError::BlockNotFound));
+
+        // Exact matches and in-between timestamps
+        assert_eq!(rpc.get_block_by_timestamp(100), Ok(0));
+        assert_eq!(rpc.get_block_by_timestamp(109), Ok(0));
+        assert_eq!(rpc.get_block_by_timestamp(110), Ok(2)); // latest of the blocks sharing a timestamp
+        assert_eq!(rpc.get_block_by_timestamp(124), Ok(2));
+        assert_eq!(rpc.get_block_by_timestamp(125), Ok(3));
+        assert_eq!(rpc.get_block_by_timestamp(139), Ok(3));
+
+        // Tip and after
+        assert_eq!(rpc.get_block_by_timestamp(140), Ok(4));
+        assert_eq!(rpc.get_block_by_timestamp(u64::MAX), Ok(4));
+    }
+
+    /// Returns the error code of the JSON-RPC response to a `starknet_getBlockWithTxHashes` call.
+    async fn get_block_error_code(rpc_api: &RpcModule<()>, block_id: serde_json::Value) -> i64 {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "starknet_V0_7_1_getBlockWithTxHashes",
+            "params": [block_id],
+        });
+        let (response, _) = rpc_api.raw_json_request(&request.to_string(), 1).await.unwrap();
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        response["error"]["code"].as_i64().unwrap_or_else(|| panic!("Expected an error response, got {response}"))
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_malformed_block_id_is_invalid_params(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (_backend, rpc) = rpc_test_setup;
+        let rpc_api = rpc_api_user(&rpc).unwrap();
+        let invalid_params = i64::from(jsonrpsee::types::ErrorCode::InvalidParams.code());
+
+        for malformed in [
+            serde_json::json!({ "block_hash": "0xnothex" }),
+            serde_json::json!({ "block_number": -1 }),
+            serde_json::json!("finalized"),
+        ] {
+            assert_eq!(get_block_error_code(&rpc_api, malformed.clone()).await, invalid_params, "{malformed}");
+        }
+
+        // A well-formed id of a block which does not exist is not a parameter error.
+        let block_not_found = i64::from(i32::from(&StarknetRpcApiError::BlockNotFound));
+        assert_eq!(get_block_error_code(&rpc_api, serde_json::json!({ "block_hash": "0x123" })).await, block_not_found);
+        assert_eq!(get_block_error_code(&rpc_api, serde_json::json!({ "block_number": 5 })).await, block_not_found);
+    }
+}
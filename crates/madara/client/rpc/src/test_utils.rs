@@ -6,6 +6,7 @@ use mp_block::{
     MadaraPendingBlockInfo,
 };
 use mp_chain_config::{ChainConfig, StarknetVersion};
+use mp_class::{ConvertedClass, LegacyClassInfo, LegacyConvertedClass, SierraClassInfo, SierraConvertedClass};
 use mp_receipt::{
     ExecutionResources, ExecutionResult, FeePayment, InvokeTransactionReceipt, PriceUnit, TransactionReceipt,
 };
@@ -20,37 +21,38 @@ use mp_state_update::{
 use mp_transactions::{BroadcastedDeclareTransactionV0, InvokeTransaction, InvokeTransactionV0, Transaction};
 use mp_utils::service::ServiceContext;
 use rstest::fixture;
+use starknet_core::types::contract::{legacy::LegacyContractClass, SierraClass};
 use starknet_types_core::felt::Felt;
+use std::io::Read;
 use std::sync::Arc;
 
-use crate::{providers::AddTransactionProvider, Starknet};
+use crate::{providers::AddTransactionProvider, ExecutionPool, Starknet, StarknetRpcApiError};
 
-#[cfg(test)]
+/// Rejects every transaction: the tests and benchmarks using it do not add transactions.
 pub struct TestTransactionProvider;
 
-#[cfg(test)]
 #[async_trait]
 impl AddTransactionProvider for TestTransactionProvider {
     async fn add_declare_v0_transaction(
         &self,
         _declare_v0_transaction: BroadcastedDeclareTransactionV0,
     ) -> RpcResult<ClassAndTxnHash> {
-        unimplemented!()
+        Err(StarknetRpcApiError::UnimplementedMethod.into())
     }
     async fn add_declare_transaction(&self, _declare_transaction: BroadcastedDeclareTxn) -> RpcResult<ClassAndTxnHash> {
-        unimplemented!()
+        Err(StarknetRpcApiError::UnimplementedMethod.into())
     }
     async fn add_deploy_account_transaction(
         &self,
         _deploy_account_transaction: BroadcastedDeployAccountTxn,
     ) -> RpcResult<ContractAndTxnHash> {
-        unimplemented!()
+        Err(StarknetRpcApiError::UnimplementedMethod.into())
     }
     async fn add_invoke_transaction(
         &self,
         _invoke_transaction: BroadcastedInvokeTxn,
     ) -> RpcResult<AddInvokeTransactionResult> {
-        unimplemented!()
+        Err(StarknetRpcApiError::UnimplementedMethod.into())
     }
 }
 
@@ -62,6 +64,7 @@ pub fn rpc_test_setup() -> (Arc<MadaraBackend>, Starknet) {
         backend.clone(),
         Arc::new(TestTransactionProvider),
         Default::default(),
+        Arc::new(ExecutionPool::new(2).unwrap()),
         ServiceContext::new_for_testing(),
     );
     (backend, rpc)
@@ -639,3 +642,110 @@ pub fn make_sample_chain_for_state_updates(backend: &MadaraBackend) -> SampleCha
         state_diffs,
     }
 }
+
+/// A real class definition, along with the class hash it was declared with.
+pub struct ClassFixture {
+    pub name: &'static str,
+    pub class_hash: Felt,
+    pub converted_class: ConvertedClass,
+}
+
+pub fn legacy_class_fixture(name: &'static str, definition: &[u8], class_hash: Option<Felt>) -> ClassFixture {
+    let class: LegacyContractClass = serde_json::from_slice(definition).unwrap();
+    let class_hash = class_hash.unwrap_or_else(|| class.class_hash().unwrap());
+    ClassFixture {
+        name,
+        class_hash,
+        converted_class: ConvertedClass::Legacy(LegacyConvertedClass {
+            class_hash,
+            info: LegacyClassInfo { contract_class: Arc::new(class.compress().unwrap().into()) },
+        }),
+    }
+}
+
+pub fn sierra_class_fixture(name: &'static str, definition: &[u8]) -> ClassFixture {
+    let class: SierraClass = serde_json::from_slice(definition).unwrap();
+    let class_hash = class.class_hash().unwrap();
+    ClassFixture {
+        name,
+        class_hash,
+        converted_class: ConvertedClass::Sierra(SierraConvertedClass {
+            class_hash,
+            info: SierraClassInfo {
+                contract_class: Arc::new(class.flatten().unwrap().into()),
+                compiled_class_hash: Felt::ZERO,
+            },
+            // Serving a class does not need its compiled class.
+            compiled: None,
+        }),
+    }
+}
+
+fn gunzip(bytes: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    flate2::read::GzDecoder::new(bytes).read_to_end(&mut out).unwrap();
+    out
+}
+
+/// Real classes, Sierra and legacy. The class hashes of Sierra classes and of the UDC are computed with
+/// starknet-rs, and the mainnet classes come with the hash they were declared with on mainnet.
+pub fn class_fixtures() -> Vec<ClassFixture> {
+    vec![
+        legacy_class_fixture(
+            "mainnet erc721",
+            &gunzip(include_bytes!(
+                "../../gateway/client/src/mocks/class_block_3125_erc721_0x074a7ed7f1236225600f355efe70812129658c82c295ff0f8307b3fad4bf09a9.gz"
+            )),
+            Some(Felt::from_hex_unchecked("0x074a7ed7f1236225600f355efe70812129658c82c295ff0f8307b3fad4bf09a9")),
+        ),
+        legacy_class_fixture(
+            "mainnet erc1155",
+            &gunzip(include_bytes!(
+                "../../gateway/client/src/mocks/class_block_18507_erc1155_0x04be7f1bace6f593abd8e56947c11151f45498030748a950fdaf0b79ac3dc03f.gz"
+            )),
+            Some(Felt::from_hex_unchecked("0x04be7f1bace6f593abd8e56947c11151f45498030748a950fdaf0b79ac3dc03f")),
+        ),
+        legacy_class_fixture("udc", include_bytes!("../../../../../cairo-artifacts/madara_contracts_UDC.json"), None),
+        sierra_class_fixture(
+            "openzeppelin erc20",
+            include_bytes!("../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"),
+        ),
+        sierra_class_fixture(
+            "openzeppelin account",
+            include_bytes!("../../../../../cairo-artifacts/openzeppelin_AccountUpgradeable.contract_class.json"),
+        ),
+    ]
+}
+
+/// Stores a block 0 which declares every class and deploys one contract of each, at the address returned for
+/// each class.
+pub fn make_sample_chain_with_classes(backend: &MadaraBackend, classes: &[ClassFixture]) -> Vec<Felt> {
+    let contracts: Vec<_> = (0..classes.len()).map(|i| Felt::from(0x1000 + i as u64)).collect();
+    let state_diff = StateDiff {
+        deployed_contracts: contracts
+            .iter()
+            .zip(classes)
+            .map(|(address, class)| DeployedContractItem { address: *address, class_hash: class.class_hash })
+            .collect(),
+        ..Default::default()
+    };
+
+    backend
+        .store_block(
+            MadaraMaybePendingBlock {
+                info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                    header: Header { block_number: 0, ..Default::default() },
+                    block_hash: Felt::from_hex_unchecked("0x12345"),
+                    tx_hashes: vec![],
+                }),
+                inner: MadaraBlockInner { transactions: vec![], receipts: vec![] },
+            },
+            state_diff,
+            classes.iter().map(|class| class.converted_class.clone()).collect(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    contracts
+}
@@ -114,6 +114,26 @@ impl<T> OptionExt<T> for Option<T> {
     }
 }
 
+/// Size of the JSON encoding of `value`, computed without allocating the encoding.
+pub fn json_size(value: &impl serde::Serialize) -> serde_json::Result<usize> {
+    struct Counter(usize);
+
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    serde_json::to_writer(&mut counter, value)?;
+    Ok(counter.0)
+}
+
 /// Filters events based on the provided address and keys.
 ///
 /// This function checks if an event matches the given address and keys.
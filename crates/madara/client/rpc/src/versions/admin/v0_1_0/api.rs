@@ -4,6 +4,7 @@ use mp_rpc::ClassAndTxnHash;
 use mp_transactions::BroadcastedDeclareTransactionV0;
 use mp_utils::service::{MadaraServiceId, MadaraServiceStatus};
 use serde::{Deserialize, Serialize};
+use starknet_types_core::felt::Felt;
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "snake_case")]
@@ -22,6 +23,22 @@ pub trait MadaraWriteRpcApi {
         &self,
         declare_v0_transaction: BroadcastedDeclareTransactionV0,
     ) -> RpcResult<ClassAndTxnHash>;
+
+    /// Sets a human-readable label for a class hash, such as "OpenZeppelin ERC20 v0.8". This is a purely
+    /// informational annotation. A `null` label removes the current label.
+    #[method(name = "setClassLabel")]
+    async fn set_class_label(&self, class_hash: Felt, label: Option<String>) -> RpcResult<()>;
+
+    /// Loads classes into the class cache, so that the following reads of these classes are served from memory.
+    /// Returns the number of classes which were found.
+    #[method(name = "warmClassCache")]
+    async fn warm_class_cache(&self, class_hashes: Vec<Felt>) -> RpcResult<usize>;
+
+    /// Compiles Sierra classes again, by class hash, and replaces their stored compiled class. Fails without
+    /// replacing anything when a class does not compile to its declared compiled class hash.
+    /// Returns the number of compiled classes which were replaced.
+    #[method(name = "invalidateCompiledClasses")]
+    async fn invalidate_compiled_classes(&self, class_hashes: Vec<Felt>) -> RpcResult<usize>;
 }
 
 #[versioned_rpc("V0_1_0", "madara")]
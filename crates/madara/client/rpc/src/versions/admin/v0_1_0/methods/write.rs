@@ -1,8 +1,11 @@
 use jsonrpsee::core::{async_trait, RpcResult};
+use mc_db::MadaraStorageError;
 use mp_rpc::ClassAndTxnHash;
 use mp_transactions::BroadcastedDeclareTransactionV0;
+use starknet_types_core::felt::Felt;
+use std::sync::Arc;
 
-use crate::{versions::admin::v0_1_0::MadaraWriteRpcApiV0_1_0Server, Starknet};
+use crate::{utils::ResultExt, versions::admin::v0_1_0::MadaraWriteRpcApiV0_1_0Server, Starknet, StarknetRpcApiError};
 
 #[async_trait]
 impl MadaraWriteRpcApiV0_1_0Server for Starknet {
@@ -21,4 +24,53 @@ impl MadaraWriteRpcApiV0_1_0Server for Starknet {
     ) -> RpcResult<ClassAndTxnHash> {
         self.add_transaction_provider.add_declare_v0_transaction(declare_transaction).await
     }
+
+    /// Set or remove the human-readable label of a class
+    ///
+    /// # Arguments
+    ///
+    /// * `class_hash` - the hash of the class to label
+    /// * `label` - the new label, or `None` to remove the current one
+    async fn set_class_label(&self, class_hash: Felt, label: Option<String>) -> RpcResult<()> {
+        self.backend.set_class_label(&class_hash, label.as_deref()).or_internal_server_error("Setting class label")?;
+        Ok(())
+    }
+
+    /// Preload classes into the class cache
+    ///
+    /// # Arguments
+    ///
+    /// * `class_hashes` - the hashes of the classes to load
+    ///
+    /// # Returns
+    ///
+    /// * the number of classes found, classes which are unknown or only pending are skipped
+    async fn warm_class_cache(&self, class_hashes: Vec<Felt>) -> RpcResult<usize> {
+        self.backend.warm_class_cache(&class_hashes).or_internal_server_error("Warming the class cache")
+    }
+
+    /// Compile classes again and replace their stored compiled class
+    ///
+    /// # Arguments
+    ///
+    /// * `class_hashes` - the hashes of the Sierra classes to compile again
+    ///
+    /// # Returns
+    ///
+    /// * the number of compiled classes replaced, unknown and legacy classes are skipped
+    ///
+    /// # Errors
+    ///
+    /// * if a class does not compile to its declared compiled class hash with the current compiler, in which case no
+    ///   compiled class is replaced
+    async fn invalidate_compiled_classes(&self, class_hashes: Vec<Felt>) -> RpcResult<usize> {
+        // Compiling classes takes a while, do not block the async runtime.
+        let backend = Arc::clone(&self.backend);
+        match self.execution_pool.spawn(move || backend.invalidate_sierra_compiled(&class_hashes)).await {
+            Err(MadaraStorageError::CompilationClassError(data)) => {
+                Err(StarknetRpcApiError::ErrUnexpectedError { data }.into())
+            }
+            res => res.or_internal_server_error("Invalidating compiled classes"),
+        }
+    }
 }
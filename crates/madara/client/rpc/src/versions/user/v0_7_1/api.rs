@@ -3,12 +3,14 @@ use m_proc_macros::versioned_rpc;
 use mp_block::BlockId;
 use mp_rpc::{
     AddInvokeTransactionResult, BlockHashAndNumber, BroadcastedDeclareTxn, BroadcastedDeployAccountTxn,
-    BroadcastedInvokeTxn, BroadcastedTxn, ClassAndTxnHash, ContractAndTxnHash, EventFilterWithPageRequest, EventsChunk,
-    FeeEstimate, FunctionCall, MaybeDeprecatedContractClass, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
-    MaybePendingStateUpdate, MsgFromL1, SimulateTransactionsResult, SimulationFlag, SimulationFlagForEstimateFee,
+    BroadcastedInvokeTxn, BroadcastedTxn, ClassAndTxnHash, ContractAndTxnHash, DeprecatedContractClass, EmittedEvent,
+    EntryPointsByType, EventFilterWithPageRequest, EventsChunk, FeeEstimate, FunctionCall,
+    MaybeDeprecatedContractClass, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs, MaybePendingStateUpdate,
+    MsgFromL1, SimulateTransactionsResult, SimulationFlag, SimulationFlagForEstimateFee,
     StarknetGetBlockWithTxsAndReceiptsResult, SyncingStatus, TraceBlockTransactionsResult,
     TxnFinalityAndExecutionStatus, TxnReceiptWithBlockInfo, TxnWithHash,
 };
+use serde::{Deserialize, Serialize};
 use starknet_types_core::felt::Felt;
 
 // Starknet RPC API trait and types
@@ -54,7 +56,7 @@ pub trait StarknetReadRpcApi {
 
     /// Call a contract function at a given block id
     #[method(name = "call", and_versions = ["V0_8_0"])]
-    fn call(&self, request: FunctionCall, block_id: BlockId) -> RpcResult<Vec<Felt>>;
+    async fn call(&self, request: FunctionCall, block_id: BlockId) -> RpcResult<Vec<Felt>>;
 
     /// Get the chain id
     #[method(name = "chainId", and_versions = ["V0_8_0"])]
@@ -158,3 +160,105 @@ pub trait StarknetTraceRpcApi {
     /// Returns the execution trace of a transaction
     async fn trace_transaction(&self, transaction_hash: Felt) -> RpcResult<TraceBlockTransactionsResult>;
 }
+
+/// A storage value, along with the concrete block it was read from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageAtResult {
+    pub value: Felt,
+    /// For the pending block, this is the number the block will have once it is closed.
+    pub block_number: u64,
+    /// `None` for the pending block.
+    pub block_hash: Option<Felt>,
+}
+
+/// A Sierra contract class, with its `sierra_program` encoded as a single base64 string instead of an array
+/// of felts. See [`crate::versions::user::v0_7_1::methods::madara::get_class_at_compact`] for the encoding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactContractClass {
+    #[serde(default)]
+    pub abi: Option<String>,
+    pub contract_class_version: String,
+    pub entry_points_by_type: EntryPointsByType,
+    /// Base64 of the concatenated 32-byte big-endian representation of every felt of the program.
+    pub sierra_program: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MaybeDeprecatedCompactContractClass {
+    Deprecated(DeprecatedContractClass),
+    ContractClass(CompactContractClass),
+}
+
+/// Result of `madara_getClassAtIfChanged`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ClassAtIfChangedResult {
+    /// The contract class hash is the one the client already knows: the class is not sent again.
+    Unchanged {
+        class_hash: Felt,
+    },
+    Changed {
+        class_hash: Felt,
+        contract_class: MaybeDeprecatedContractClass,
+    },
+}
+
+/// Madara-specific, non-standard extensions to the read API.
+#[versioned_rpc("V0_7_1", "madara")]
+pub trait MadaraReadRpcApi {
+    /// Same as `starknet_getStorageAt`, but also returns the concrete block which served the read, so that
+    /// clients can cache `latest`/`pending` reads by block and detect reorgs.
+    #[method(name = "getStorageAtEx", and_versions = ["V0_8_0"])]
+    fn get_storage_at_ex(&self, contract_address: Felt, key: Felt, block_id: BlockId) -> RpcResult<StorageAtResult>;
+
+    /// Same as `starknet_getStorageAt`, but returns `null` for a storage slot which has never been written to,
+    /// so that it can be told apart from a slot explicitly set to 0.
+    #[method(name = "getStorageAtOpt", and_versions = ["V0_8_0"])]
+    fn get_storage_at_opt(&self, contract_address: Felt, key: Felt, block_id: BlockId) -> RpcResult<Option<Felt>>;
+
+    /// Same as `starknet_getClassAt`, but the `sierra_program` of Sierra classes is returned as a base64 blob,
+    /// which is considerably smaller than the JSON array of felts. Legacy classes are returned unchanged.
+    #[method(name = "getClassAtCompact", and_versions = ["V0_8_0"])]
+    fn get_class_at_compact(
+        &self,
+        block_id: BlockId,
+        contract_address: Felt,
+    ) -> RpcResult<MaybeDeprecatedCompactContractClass>;
+
+    /// Same as `starknet_getClassAt`, but the class is returned as a base64-encoded binary blob instead of JSON.
+    /// See [`crate::versions::user::v0_7_1::methods::madara::get_class_at_binary`] for the encoding.
+    #[method(name = "getClassAtBinary", and_versions = ["V0_8_0"])]
+    fn get_class_at_binary(&self, block_id: BlockId, contract_address: Felt) -> RpcResult<String>;
+
+    /// Same as `starknet_getClassAt`, but also returns the top-level fields of the class definition which are
+    /// unknown to this node, such as fields added by newer Starknet versions, as they were declared.
+    #[method(name = "getClassAtRaw", and_versions = ["V0_8_0"])]
+    fn get_class_at_raw(&self, block_id: BlockId, contract_address: Felt) -> RpcResult<serde_json::Value>;
+
+    /// Same as `starknet_getClassAt`, but only returns the class when the class hash of the contract is not
+    /// `if_class_hash_not`, so that clients caching the class do not download it again when it did not change.
+    #[method(name = "getClassAtIfChanged", and_versions = ["V0_8_0"])]
+    fn get_class_at_if_changed(
+        &self,
+        block_id: BlockId,
+        contract_address: Felt,
+        if_class_hash_not: Felt,
+    ) -> RpcResult<ClassAtIfChangedResult>;
+
+    /// Returns the events emitted by a transaction, in order, without scanning a block range like
+    /// `starknet_getEvents`.
+    #[method(name = "getEventsForTransaction", and_versions = ["V0_8_0"])]
+    fn get_events_for_transaction(&self, transaction_hash: Felt) -> RpcResult<Vec<EmittedEvent>>;
+
+    /// Same as `starknet_simulateTransactions`. When `stop_on_failure` is set, the simulation stops at the first
+    /// reverted transaction: the results end with that transaction, and the following ones are not executed.
+    #[method(name = "simulateTransactions", and_versions = ["V0_8_0"])]
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTxn>,
+        simulation_flags: Vec<SimulationFlag>,
+        stop_on_failure: bool,
+    ) -> RpcResult<Vec<SimulateTransactionsResult>>;
+}
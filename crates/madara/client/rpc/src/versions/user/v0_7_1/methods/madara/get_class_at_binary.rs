@@ -0,0 +1,132 @@
+use anyhow::Context;
+use base64::Engine;
+use mp_block::BlockId;
+use mp_class::ContractClass;
+use starknet_types_core::felt::Felt;
+
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::utils::ResultExt;
+use crate::Starknet;
+
+/// Version of the binary class encoding, which is the first byte of the encoded class. It changes whenever the
+/// encoding of [`ContractClass`] does, so that clients can reject the classes they cannot decode.
+pub const CLASS_BINARY_FORMAT_VERSION: u8 = 1;
+
+/// Get the contract class definition at a given address in a specific block, as a binary blob instead of JSON.
+///
+/// The returned string is the standard (padded) base64 encoding of:
+///
+/// ```text
+/// <format version: u8, see CLASS_BINARY_FORMAT_VERSION> <class>
+/// ```
+///
+/// where the class is the [`mp_class::ContractClass`] stored in the database of the node, encoded with `bincode`
+/// 1.x and its default options. Unlike `starknet_getClassAt`, legacy classes stored without their ABI are served
+/// as is. This is considerably smaller and faster to parse than JSON for Rust clients, which can decode it with
+/// [`decode_class_binary`].
+///
+/// ### Errors
+///
+/// Same as `starknet_getClassAt`. The size limit on served classes applies to the encoded binary class.
+pub fn get_class_at_binary(
+    starknet: &Starknet,
+    block_id: BlockId,
+    contract_address: Felt,
+) -> StarknetRpcResult<String> {
+    let resolved_block_id = starknet
+        .backend
+        .resolve_block_id(&block_id)
+        .or_internal_server_error("Error resolving block id")?
+        .ok_or(StarknetRpcApiError::BlockNotFound)?;
+
+    let class_hash = starknet
+        .backend
+        .get_deployed_contract_class_hash_at(&resolved_block_id, &contract_address)
+        .or_internal_server_error("Error getting contract class hash at")?
+        .ok_or(StarknetRpcApiError::ContractNotFound)?;
+
+    let class_info = starknet
+        .backend
+        .get_class_info_or_archived(&resolved_block_id, &class_hash)
+        .or_internal_server_error("Error getting contract class info")?
+        .ok_or(StarknetRpcApiError::ClassHashNotFound)?;
+
+    let class = class_info.contract_class();
+    starknet.check_served_class(class_hash, &class)?;
+    let encoded = encode_class_binary(&class).or_internal_server_error("Error encoding contract class")?;
+    if starknet.max_served_class_size != 0 && encoded.len() > starknet.max_served_class_size {
+        return Err(StarknetRpcApiError::ClassTooLargeToServe {
+            class_hash,
+            size: encoded.len(),
+            max_size: starknet.max_served_class_size,
+        });
+    }
+    Ok(encoded)
+}
+
+pub fn encode_class_binary(class: &ContractClass) -> anyhow::Result<String> {
+    let mut bytes = vec![CLASS_BINARY_FORMAT_VERSION];
+    bincode::serialize_into(&mut bytes, class)?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+pub fn decode_class_binary(encoded: &str) -> anyhow::Result<ContractClass> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(encoded)?;
+    let (&version, class) = bytes.split_first().context("Empty binary class")?;
+    anyhow::ensure!(
+        version == CLASS_BINARY_FORMAT_VERSION,
+        "Unsupported binary class format version {version}, expected {CLASS_BINARY_FORMAT_VERSION}"
+    );
+    Ok(bincode::deserialize(class)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        legacy_class_fixture, make_sample_chain_with_classes, rpc_test_setup, sierra_class_fixture,
+    };
+    use mc_db::MadaraBackend;
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    #[rstest]
+    fn test_get_class_at_binary(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let classes = [
+            sierra_class_fixture(
+                "OZ ERC20",
+                include_bytes!(
+                    "../../../../../../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+                ),
+            ),
+            legacy_class_fixture(
+                "UDC",
+                include_bytes!("../../../../../../../../../../cairo-artifacts/madara_contracts_UDC.json"),
+                None,
+            ),
+        ];
+        let contracts = make_sample_chain_with_classes(&backend, &classes);
+
+        for (class, &contract) in classes.iter().zip(&contracts) {
+            let encoded = get_class_at_binary(&rpc, BlockId::Number(0), contract).unwrap();
+            let decoded = decode_class_binary(&encoded).unwrap();
+            assert_eq!(encode_class_binary(&decoded).unwrap(), encoded, "{}", class.name);
+
+            // This is the class stored in the database, without going through its RPC representation.
+            assert_eq!(decoded, class.converted_class.info().contract_class(), "{}", class.name);
+        }
+        let encoded_erc20 = get_class_at_binary(&rpc, BlockId::Number(0), contracts[0]).unwrap();
+
+        assert!(decode_class_binary("not base64!").is_err());
+        assert!(decode_class_binary("").is_err());
+        assert!(decode_class_binary(&base64::engine::general_purpose::STANDARD.encode([1u8; 8])).is_err());
+
+        // The format version comes first, and an unknown one is rejected.
+        let mut bytes = base64::engine::general_purpose::STANDARD.decode(&encoded_erc20).unwrap();
+        assert_eq!(bytes[0], CLASS_BINARY_FORMAT_VERSION);
+        bytes[0] = CLASS_BINARY_FORMAT_VERSION + 1;
+        let err = decode_class_binary(&base64::engine::general_purpose::STANDARD.encode(bytes)).unwrap_err();
+        assert!(format!("{err:#}").contains("Unsupported binary class format version"), "{err:#}");
+    }
+}
@@ -0,0 +1,71 @@
+use base64::Engine;
+use mp_block::BlockId;
+use mp_rpc::MaybeDeprecatedContractClass;
+use starknet_types_core::felt::Felt;
+
+use crate::errors::StarknetRpcResult;
+use crate::versions::user::v0_7_1::methods::read::get_class_at::get_class_at;
+use crate::versions::user::v0_7_1::{CompactContractClass, MaybeDeprecatedCompactContractClass};
+use crate::Starknet;
+
+/// Get the contract class definition at a given address in a specific block, with the `sierra_program`
+/// encoded as base64.
+///
+/// The encoded program is the standard (padded) base64 encoding of the concatenation of every felt of the
+/// `sierra_program`, each one as its 32-byte big-endian representation, in order. To decode it, base64-decode
+/// the string, split the result into 32-byte chunks and read each chunk as a big-endian felt. See
+/// [`decode_sierra_program`].
+///
+/// Legacy (Cairo 0) classes are returned unchanged, their program is already a compressed base64 blob.
+///
+/// ### Errors
+///
+/// Same as `starknet_getClassAt`.
+pub fn get_class_at_compact(
+    starknet: &Starknet,
+    block_id: BlockId,
+    contract_address: Felt,
+) -> StarknetRpcResult<MaybeDeprecatedCompactContractClass> {
+    Ok(match get_class_at(starknet, block_id, contract_address)? {
+        MaybeDeprecatedContractClass::Deprecated(class) => MaybeDeprecatedCompactContractClass::Deprecated(class),
+        MaybeDeprecatedContractClass::ContractClass(class) => {
+            MaybeDeprecatedCompactContractClass::ContractClass(CompactContractClass {
+                abi: class.abi,
+                contract_class_version: class.contract_class_version,
+                entry_points_by_type: class.entry_points_by_type,
+                sierra_program: encode_sierra_program(&class.sierra_program),
+            })
+        }
+    })
+}
+
+pub fn encode_sierra_program(sierra_program: &[Felt]) -> String {
+    let bytes: Vec<u8> = sierra_program.iter().flat_map(Felt::to_bytes_be).collect();
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+pub fn decode_sierra_program(encoded: &str) -> anyhow::Result<Vec<Felt>> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(encoded)?;
+    anyhow::ensure!(bytes.len() % 32 == 0, "Encoded sierra program length {} is not a multiple of 32", bytes.len());
+    Ok(bytes
+        .chunks_exact(32)
+        .map(|chunk| Felt::from_bytes_be(chunk.try_into().expect("Chunk is exactly 32 bytes")))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sierra_program_compact_round_trip() {
+        let sierra_program = vec![Felt::ZERO, Felt::ONE, Felt::from_hex_unchecked("0x1234abcd"), Felt::MAX];
+
+        let encoded = encode_sierra_program(&sierra_program);
+        assert_eq!(decode_sierra_program(&encoded).unwrap(), sierra_program);
+
+        assert_eq!(decode_sierra_program(&encode_sierra_program(&[])).unwrap(), Vec::<Felt>::new());
+        assert!(decode_sierra_program(&base64::engine::general_purpose::STANDARD.encode([1u8; 31])).is_err());
+        assert!(decode_sierra_program("not base64!").is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use mc_db::db_block_id::DbBlockId;
+use mp_block::{BlockId, BlockTag};
+use starknet_types_core::felt::Felt;
+
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::utils::ResultExt;
+use crate::versions::user::v0_7_1::methods::read::get_class_at::get_class_at;
+use crate::versions::user::v0_7_1::ClassAtIfChangedResult;
+use crate::Starknet;
+
+/// Get the contract class definition at a given address in a specific block, unless the class hash of the
+/// contract is `if_class_hash_not`.
+///
+/// This lets clients which poll a contract class at `latest` skip downloading the whole class again when the
+/// contract has not been upgraded since the last time they fetched it.
+///
+/// ### Errors
+///
+/// Same as `starknet_getClassAt`.
+pub fn get_class_at_if_changed(
+    starknet: &Starknet,
+    block_id: BlockId,
+    contract_address: Felt,
+    if_class_hash_not: Felt,
+) -> StarknetRpcResult<ClassAtIfChangedResult> {
+    let resolved_block_id = starknet
+        .backend
+        .resolve_block_id(&block_id)
+        .or_internal_server_error("Error resolving block id")?
+        .ok_or(StarknetRpcApiError::BlockNotFound)?;
+
+    let class_hash = starknet
+        .backend
+        .get_contract_class_hash_at(&resolved_block_id, &contract_address)
+        .or_internal_server_error("Error getting contract class hash at")?
+        .ok_or(StarknetRpcApiError::ContractNotFound)?;
+
+    if class_hash == if_class_hash_not {
+        return Ok(ClassAtIfChangedResult::Unchanged { class_hash });
+    }
+
+    // Read the class at the same block as the class hash, even if a new block was imported in the meantime.
+    let concrete_block_id = match resolved_block_id {
+        DbBlockId::Pending => BlockId::Tag(BlockTag::Pending),
+        DbBlockId::Number(block_n) => BlockId::Number(block_n),
+    };
+    let contract_class = get_class_at(starknet, concrete_block_id, contract_address)?;
+
+    Ok(ClassAtIfChangedResult::Changed { class_hash, contract_class })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{class_fixtures, make_sample_chain_with_classes, rpc_test_setup};
+    use mc_db::MadaraBackend;
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    #[rstest]
+    fn test_get_class_at_if_changed(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let classes = class_fixtures();
+        let contracts = make_sample_chain_with_classes(&backend, &classes);
+        let latest = || BlockId::Tag(BlockTag::Latest);
+
+        let class_hash = classes[0].class_hash;
+        assert_eq!(
+            get_class_at_if_changed(&rpc, latest(), contracts[0], class_hash).unwrap(),
+            ClassAtIfChangedResult::Unchanged { class_hash }
+        );
+
+        // The client has the class of another contract.
+        assert_eq!(
+            get_class_at_if_changed(&rpc, latest(), contracts[0], classes[1].class_hash).unwrap(),
+            ClassAtIfChangedResult::Changed {
+                class_hash,
+                contract_class: get_class_at(&rpc, latest(), contracts[0]).unwrap()
+            }
+        );
+
+        assert_eq!(
+            serde_json::to_value(ClassAtIfChangedResult::Unchanged { class_hash: Felt::ONE }).unwrap(),
+            serde_json::json!({ "status": "UNCHANGED", "class_hash": "0x1" })
+        );
+    }
+}
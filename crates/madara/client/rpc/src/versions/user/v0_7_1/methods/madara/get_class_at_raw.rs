@@ -0,0 +1,82 @@
+use mp_block::BlockId;
+use serde_json::Value;
+use starknet_types_core::felt::Felt;
+
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::utils::{OptionExt, ResultExt};
+use crate::versions::user::v0_7_1::methods::read::get_class_at::get_class_at;
+use crate::Starknet;
+
+/// Get the contract class definition at a given address in a specific block, including the top-level fields of the
+/// class definition which are not part of the class as known by this node.
+///
+/// Newer Starknet versions may add fields to class definitions, which `starknet_getClassAt` does not return. This
+/// returns the class as `starknet_getClassAt` would, with these fields added back as they were declared.
+///
+/// ### Errors
+///
+/// Same as `starknet_getClassAt`.
+pub fn get_class_at_raw(starknet: &Starknet, block_id: BlockId, contract_address: Felt) -> StarknetRpcResult<Value> {
+    let class = get_class_at(starknet, block_id.clone(), contract_address)?;
+
+    let class_hash = starknet
+        .backend
+        .get_contract_class_hash_at(&block_id, &contract_address)
+        .or_internal_server_error("Error getting contract class hash at")?
+        .ok_or(StarknetRpcApiError::ContractNotFound)?;
+    let unknown_fields = starknet
+        .backend
+        .get_class_unknown_fields(&class_hash)
+        .or_internal_server_error("Error getting unknown class fields")?
+        .unwrap_or_default();
+
+    let mut value = serde_json::to_value(class).or_internal_server_error("Error serializing contract class")?;
+    let fields = value.as_object_mut().ok_or_internal_server_error("Contract class is not a JSON object")?;
+    for (key, field) in unknown_fields {
+        fields.entry(key).or_insert(field);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sample_chain_with_classes, rpc_test_setup, sierra_class_fixture};
+    use mc_db::MadaraBackend;
+    use mp_class::raw_class::RawContractClass;
+    use mp_class::ContractClass;
+    use rstest::rstest;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[rstest]
+    fn test_get_class_at_raw(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let class = sierra_class_fixture(
+            "OZ ERC20",
+            include_bytes!(
+                "../../../../../../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+            ),
+        );
+        let contract_class = class.converted_class.info().contract_class();
+
+        // The class definition as served by the feeder gateway, carrying a field unknown to this node.
+        let ContractClass::Sierra(sierra) = &contract_class else { unreachable!() };
+        let mut declared = serde_json::to_value(sierra.as_ref()).unwrap();
+        declared.as_object_mut().unwrap().insert("future_field".into(), json!({ "some": ["data"] }));
+        let raw = RawContractClass::try_from(declared).unwrap();
+        assert_eq!(raw.class, contract_class);
+
+        let contracts = make_sample_chain_with_classes(&backend, std::slice::from_ref(&class));
+        backend.store_class_unknown_fields(&[(class.class_hash, raw.unknown_fields)]).unwrap();
+
+        let served = get_class_at_raw(&rpc, BlockId::Number(0), contracts[0]).unwrap();
+        assert_eq!(served["future_field"], json!({ "some": ["data"] }));
+
+        // Without the unknown fields, this is the same class as served by `starknet_getClassAt`.
+        let mut served = served;
+        served.as_object_mut().unwrap().remove("future_field");
+        let class_at = get_class_at(&rpc, BlockId::Number(0), contracts[0]).unwrap();
+        assert_eq!(served, serde_json::to_value(class_at).unwrap());
+    }
+}
@@ -0,0 +1,116 @@
+use mp_block::MadaraMaybePendingBlockInfo;
+use mp_rpc::{EmittedEvent, Event, EventContent};
+use starknet_types_core::felt::Felt;
+
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::utils::ResultExt;
+use crate::Starknet;
+
+/// Get the events emitted by a transaction, in the order they were emitted.
+///
+/// Same as calling `starknet_getEvents` on the block of the transaction and keeping its events, without scanning a
+/// block range. Events of a transaction in the pending block have no block hash and number.
+///
+/// ### Errors
+///
+/// * `TXN_HASH_NOT_FOUND` - If the transaction is not in a block nor in the pending block.
+pub fn get_events_for_transaction(starknet: &Starknet, transaction_hash: Felt) -> StarknetRpcResult<Vec<EmittedEvent>> {
+    let (block, tx_index) = starknet
+        .backend
+        .find_tx_hash_block(&transaction_hash)
+        .or_internal_server_error("Error getting block from tx_hash")?
+        .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
+
+    let events = block.inner.receipts.get(tx_index.0 as usize).ok_or(StarknetRpcApiError::TxnHashNotFound)?.events();
+
+    let (block_hash, block_number) = match &block.info {
+        MadaraMaybePendingBlockInfo::Pending(_) => (None, None),
+        MadaraMaybePendingBlockInfo::NotPending(block) => (Some(block.block_hash), Some(block.header.block_number)),
+    };
+
+    Ok(events
+        .iter()
+        .map(|event| EmittedEvent {
+            event: Event {
+                from_address: event.from_address,
+                event_content: EventContent { keys: event.keys.clone(), data: event.data.clone() },
+            },
+            block_hash,
+            block_number,
+            transaction_hash,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::rpc_test_setup;
+    use mc_db::MadaraBackend;
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock};
+    use mp_receipt::{Event, InvokeTransactionReceipt, TransactionReceipt};
+    use mp_state_update::StateDiff;
+    use mp_transactions::{InvokeTransaction, InvokeTransactionV0, Transaction};
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    fn event(key: u64) -> Event {
+        Event { from_address: Felt::ONE, keys: vec![Felt::from(key)], data: vec![Felt::from(key + 1)] }
+    }
+
+    #[rstest]
+    fn test_get_events_for_transaction(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+
+        let transactions = [(Felt::from(0x10), vec![event(1), event(2)]), (Felt::from(0x11), vec![event(3)])];
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                        header: Header { block_number: 0, ..Default::default() },
+                        block_hash: Felt::from(0xb10c),
+                        tx_hashes: transactions.iter().map(|(hash, _)| *hash).collect(),
+                    }),
+                    inner: MadaraBlockInner {
+                        transactions: transactions
+                            .iter()
+                            .map(|_| Transaction::Invoke(InvokeTransaction::V0(InvokeTransactionV0::default())))
+                            .collect(),
+                        receipts: transactions
+                            .iter()
+                            .map(|(transaction_hash, events)| {
+                                TransactionReceipt::Invoke(InvokeTransactionReceipt {
+                                    transaction_hash: *transaction_hash,
+                                    events: events.clone(),
+                                    ..Default::default()
+                                })
+                            })
+                            .collect(),
+                    },
+                },
+                StateDiff::default(),
+                vec![],
+                None,
+                None,
+            )
+            .unwrap();
+
+        for (transaction_hash, events) in &transactions {
+            let expected: Vec<_> = events
+                .iter()
+                .map(|event| EmittedEvent {
+                    event: mp_rpc::Event {
+                        from_address: event.from_address,
+                        event_content: EventContent { keys: event.keys.clone(), data: event.data.clone() },
+                    },
+                    block_hash: Some(Felt::from(0xb10c)),
+                    block_number: Some(0),
+                    transaction_hash: *transaction_hash,
+                })
+                .collect();
+            assert_eq!(get_events_for_transaction(&rpc, *transaction_hash).unwrap(), expected);
+        }
+
+        assert_eq!(get_events_for_transaction(&rpc, Felt::from(0x12)), Err(StarknetRpcApiError::TxnHashNotFound));
+    }
+}
@@ -0,0 +1,99 @@
+use mc_db::db_block_id::DbBlockId;
+use mp_block::{BlockId, BlockTag};
+use starknet_types_core::felt::Felt;
+
+use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
+use crate::utils::{OptionExt, ResultExt};
+use crate::versions::user::v0_7_1::methods::read::get_storage_at::get_storage_at;
+use crate::versions::user::v0_7_1::StorageAtResult;
+use crate::Starknet;
+
+/// Get the value of the storage at the given address and key, along with the number and hash of the block
+/// which served the read.
+///
+/// The block id is resolved to a block number first, and the storage is then read at that block, so that
+/// for `latest` and block numbers the returned value matches the returned block even if a new block is
+/// imported in the meantime. The pending block has no number yet: when it is closed between the read and
+/// the block number lookup, the returned number is the one of the next pending block, which the value may
+/// not match.
+///
+/// ### Errors
+///
+/// Same as `starknet_getStorageAt`.
+pub fn get_storage_at_ex(
+    starknet: &Starknet,
+    contract_address: Felt,
+    key: Felt,
+    block_id: BlockId,
+) -> StarknetRpcResult<StorageAtResult> {
+    let resolved_block_id = starknet
+        .backend
+        .resolve_block_id(&block_id)
+        .or_internal_server_error("Error resolving block id")?
+        .ok_or(StarknetRpcApiError::BlockNotFound)?;
+
+    let concrete_block_id = match resolved_block_id {
+        DbBlockId::Pending => BlockId::Tag(BlockTag::Pending),
+        DbBlockId::Number(block_n) => BlockId::Number(block_n),
+    };
+    let value = get_storage_at(starknet, contract_address, key, concrete_block_id)?;
+
+    let (block_number, block_hash) = match resolved_block_id {
+        DbBlockId::Pending => {
+            let pending_block_n = starknet
+                .backend
+                .get_latest_block_n()
+                .or_internal_server_error("Error getting latest block number")?
+                .map(|block_n| block_n + 1)
+                .unwrap_or(0);
+            (pending_block_n, None)
+        }
+        DbBlockId::Number(block_n) => {
+            let block_hash = starknet
+                .get_block_info(&resolved_block_id)?
+                .block_hash()
+                .ok_or_internal_server_error("Closed block has no block hash")?;
+            (block_n, Some(block_hash))
+        }
+    };
+
+    Ok(StorageAtResult { value, block_number, block_hash })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{sample_chain_for_state_updates, SampleChainForStateUpdates};
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_get_storage_at_ex(sample_chain_for_state_updates: (SampleChainForStateUpdates, Starknet)) {
+        let (SampleChainForStateUpdates { keys, values, contracts, block_hashes, .. }, rpc) =
+            sample_chain_for_state_updates;
+
+        let latest_block_n = rpc.current_block_number().unwrap();
+        let expected = get_storage_at(&rpc, contracts[0], keys[0], BlockId::Tag(BlockTag::Latest)).unwrap();
+        assert_eq!(
+            get_storage_at_ex(&rpc, contracts[0], keys[0], BlockId::Tag(BlockTag::Latest)).unwrap(),
+            StorageAtResult {
+                value: expected,
+                block_number: latest_block_n,
+                block_hash: Some(block_hashes[latest_block_n as usize])
+            }
+        );
+
+        assert_eq!(
+            get_storage_at_ex(&rpc, contracts[0], keys[0], BlockId::Number(0)).unwrap(),
+            StorageAtResult { value: values[0], block_number: 0, block_hash: Some(block_hashes[0]) }
+        );
+
+        let pending = get_storage_at_ex(&rpc, contracts[0], keys[0], BlockId::Tag(BlockTag::Pending)).unwrap();
+        assert_eq!(pending.block_number, latest_block_n + 1);
+        assert_eq!(pending.block_hash, None);
+
+        assert_eq!(
+            get_storage_at_ex(&rpc, contracts[0], keys[0], BlockId::Number(100)),
+            Err(StarknetRpcApiError::BlockNotFound)
+        );
+    }
+}
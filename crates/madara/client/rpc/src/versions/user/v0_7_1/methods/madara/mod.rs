@@ -0,0 +1,67 @@
+use crate::versions::user::v0_7_1::methods::read::get_storage_at::get_storage_at_opt;
+use crate::versions::user::v0_7_1::methods::trace::simulate_transactions::simulate_transactions;
+use crate::versions::user::v0_7_1::{
+    ClassAtIfChangedResult, MadaraReadRpcApiV0_7_1Server, MaybeDeprecatedCompactContractClass, StorageAtResult,
+};
+use crate::Starknet;
+use jsonrpsee::core::{async_trait, RpcResult};
+use mp_block::BlockId;
+use mp_rpc::{BroadcastedTxn, EmittedEvent, SimulateTransactionsResult, SimulationFlag};
+use starknet_types_core::felt::Felt;
+
+pub mod get_class_at_binary;
+pub mod get_class_at_compact;
+pub mod get_class_at_if_changed;
+pub mod get_class_at_raw;
+pub mod get_events_for_transaction;
+pub mod get_storage_at_ex;
+
+#[async_trait]
+impl MadaraReadRpcApiV0_7_1Server for Starknet {
+    fn get_storage_at_ex(&self, contract_address: Felt, key: Felt, block_id: BlockId) -> RpcResult<StorageAtResult> {
+        Ok(get_storage_at_ex::get_storage_at_ex(self, contract_address, key, block_id)?)
+    }
+
+    fn get_storage_at_opt(&self, contract_address: Felt, key: Felt, block_id: BlockId) -> RpcResult<Option<Felt>> {
+        Ok(get_storage_at_opt(self, contract_address, key, block_id)?)
+    }
+
+    fn get_class_at_compact(
+        &self,
+        block_id: BlockId,
+        contract_address: Felt,
+    ) -> RpcResult<MaybeDeprecatedCompactContractClass> {
+        Ok(get_class_at_compact::get_class_at_compact(self, block_id, contract_address)?)
+    }
+
+    fn get_class_at_binary(&self, block_id: BlockId, contract_address: Felt) -> RpcResult<String> {
+        Ok(get_class_at_binary::get_class_at_binary(self, block_id, contract_address)?)
+    }
+
+    fn get_class_at_raw(&self, block_id: BlockId, contract_address: Felt) -> RpcResult<serde_json::Value> {
+        Ok(get_class_at_raw::get_class_at_raw(self, block_id, contract_address)?)
+    }
+
+    fn get_class_at_if_changed(
+        &self,
+        block_id: BlockId,
+        contract_address: Felt,
+        if_class_hash_not: Felt,
+    ) -> RpcResult<ClassAtIfChangedResult> {
+        Ok(get_class_at_if_changed::get_class_at_if_changed(self, block_id, contract_address, if_class_hash_not)?)
+    }
+
+    fn get_events_for_transaction(&self, transaction_hash: Felt) -> RpcResult<Vec<EmittedEvent>> {
+        Ok(get_events_for_transaction::get_events_for_transaction(self, transaction_hash)?)
+    }
+
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTxn>,
+        simulation_flags: Vec<SimulationFlag>,
+        stop_on_failure: bool,
+    ) -> RpcResult<Vec<SimulateTransactionsResult>> {
+        Ok(simulate_transactions(self, block_id, transactions, simulation_flags, stop_on_failure).await?)
+    }
+}
@@ -1,6 +1,3 @@
-use std::sync::Arc;
-
-use mc_exec::ExecutionContext;
 use mp_block::BlockId;
 use mp_rpc::FunctionCall;
 use starknet_types_core::felt::Felt;
@@ -30,17 +27,21 @@ use crate::Starknet;
 /// * `CONTRACT_NOT_FOUND` - If the specified contract address does not exist.
 /// * `CONTRACT_ERROR` - If there is an error with the contract or the function call.
 /// * `BLOCK_NOT_FOUND` - If the specified block does not exist in the blockchain.
-pub fn call(starknet: &Starknet, request: FunctionCall, block_id: BlockId) -> StarknetRpcResult<Vec<Felt>> {
+pub async fn call(starknet: &Starknet, request: FunctionCall, block_id: BlockId) -> StarknetRpcResult<Vec<Felt>> {
     let block_info = starknet.get_block_info(&block_id)?;
 
-    let exec_context = ExecutionContext::new_at_block_end(Arc::clone(&starknet.backend), &block_info)?;
+    let exec_context = starknet.exec_context_at_block_end(&block_info)?;
 
     if block_info.protocol_version() < &EXECUTION_UNSUPPORTED_BELOW_VERSION {
         return Err(StarknetRpcApiError::UnsupportedTxnVersion);
     }
 
     let FunctionCall { contract_address, entry_point_selector, calldata } = request;
-    let results = exec_context.call_contract(&contract_address, &entry_point_selector, &calldata)?;
-
-    Ok(results)
+    starknet
+        .spawn_execution(move || {
+            exec_context
+                .call_contract(&contract_address, &entry_point_selector, &calldata)
+                .map_err(StarknetRpcApiError::from)
+        })
+        .await
 }
@@ -1,16 +1,20 @@
 use crate::errors::StarknetRpcApiError;
 use crate::errors::StarknetRpcResult;
-use crate::utils::ResultExt;
+use crate::utils::{display_internal_server_error, ResultExt};
 use crate::versions::user::v0_7_1::methods::trace::trace_transaction::EXECUTION_UNSUPPORTED_BELOW_VERSION;
 use crate::Starknet;
-use mc_exec::ExecutionContext;
 use mp_block::BlockId;
 use mp_rpc::{BroadcastedTxn, FeeEstimate, SimulationFlagForEstimateFee};
 use mp_transactions::BroadcastedTransactionExt;
-use std::sync::Arc;
 
 /// Estimate the fee associated with transaction
 ///
+/// The transactions of the batch are executed one after the other, each on top of the changes made by the ones
+/// before it: an invoke transaction can follow the deploy account transaction of its sender, or transactions with
+/// sequential nonces can be estimated together. Fees are not charged, so that the balance of the sender does not
+/// need to cover the resource bounds of the transaction. Validation can be skipped with the `SKIP_VALIDATE` flag,
+/// in which case the estimate does not include the cost of `__validate__`.
+///
 /// # Arguments
 ///
 /// * `request` - starknet transaction request
@@ -19,6 +23,11 @@ use std::sync::Arc;
 /// # Returns
 ///
 /// * `fee_estimate` - fee estimate in gwei
+///
+/// ### Errors
+///
+/// * `CONTRACT_NOT_FOUND` - If the sender of an invoke or declare transaction is not deployed when it runs.
+/// * `TRANSACTION_EXECUTION_ERROR` - If a transaction fails or reverts, with the index of the transaction.
 pub async fn estimate_fee(
     starknet: &Starknet,
     request: Vec<BroadcastedTxn>,
@@ -33,31 +42,84 @@ pub async fn estimate_fee(
         return Err(StarknetRpcApiError::UnsupportedTxnVersion);
     }
 
-    let exec_context = ExecutionContext::new_at_block_end(Arc::clone(&starknet.backend), &block_info)?;
+    let exec_context = starknet.exec_context_at_block_end(&block_info)?;
 
     let transactions = request
         .into_iter()
-        .map(|tx| tx.into_blockifier(starknet.chain_id(), starknet_version).map(|(tx, _)| tx))
+        .map(|tx| {
+            tx.into_blockifier_with_casm_cache(starknet.chain_id(), starknet_version, starknet.backend.casm_cache())
+                .map(|(tx, _)| tx)
+        })
         .collect::<Result<Vec<_>, _>>()
         .or_internal_server_error("Failed to convert BroadcastedTransaction to AccountTransaction")?;
 
     let validate = !simulation_flags.contains(&SimulationFlagForEstimateFee::SkipValidate);
 
-    let execution_results = exec_context.re_execute_transactions([], transactions, true, validate)?;
-
-    let fee_estimates = execution_results.iter().enumerate().try_fold(
-        Vec::with_capacity(execution_results.len()),
-        |mut acc, (index, result)| {
-            if result.execution_info.is_reverted() {
-                return Err(StarknetRpcApiError::TxnExecutionError {
-                    tx_index: index,
-                    error: result.execution_info.revert_error.clone().unwrap_or_default(),
-                });
-            }
-            acc.push(exec_context.execution_result_to_fee_estimate(result));
-            Ok(acc)
-        },
-    )?;
-
-    Ok(fee_estimates)
+    starknet
+        .spawn_execution(move || -> StarknetRpcResult<Vec<FeeEstimate>> {
+            let execution_results = exec_context
+                .execute_transactions_checking_senders(transactions, false, validate)
+                .map_err(estimate_error)?;
+
+            execution_results
+                .iter()
+                .enumerate()
+                .map(|(tx_index, result)| {
+                    if result.execution_info.is_reverted() {
+                        return Err(StarknetRpcApiError::TxnExecutionError {
+                            tx_index,
+                            error: result.execution_info.revert_error.clone().unwrap_or_default(),
+                        });
+                    }
+                    Ok(exec_context.execution_result_to_fee_estimate(result))
+                })
+                .collect()
+        })
+        .await
+}
+
+/// Failures of a transaction are reported with its index, other errors are internal errors.
+fn estimate_error(err: mc_exec::Error) -> StarknetRpcApiError {
+    match err {
+        mc_exec::Error::SenderNotDeployed { .. } => StarknetRpcApiError::ContractNotFound,
+        mc_exec::Error::Reexecution(err) => {
+            StarknetRpcApiError::TxnExecutionError { tx_index: err.index(), error: format!("{err:#}") }
+        }
+        mc_exec::Error::FeeEstimation(err) => {
+            StarknetRpcApiError::TxnExecutionError { tx_index: err.index(), error: format!("{err:#}") }
+        }
+        err => {
+            display_internal_server_error(format!("Error estimating fee: {err:#}"));
+            StarknetRpcApiError::InternalServerError
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters};
+    use mp_rpc::{BroadcastedInvokeTxn, InvokeTxnV1};
+    use rstest::rstest;
+    use starknet_types_core::felt::Felt;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_estimate_fee_sender_not_deployed(
+        sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet),
+    ) {
+        let (_, rpc) = sample_chain_for_block_getters;
+
+        let tx = BroadcastedTxn::Invoke(BroadcastedInvokeTxn::V1(InvokeTxnV1 {
+            calldata: vec![],
+            max_fee: Felt::ZERO,
+            nonce: Felt::ZERO,
+            sender_address: Felt::from_hex_unchecked("0xdead"),
+            signature: vec![],
+        }));
+        assert_eq!(
+            estimate_fee(&rpc, vec![tx], vec![SimulationFlagForEstimateFee::SkipValidate], BlockId::Number(2)).await,
+            Err(StarknetRpcApiError::ContractNotFound)
+        );
+    }
 }
@@ -1,6 +1,3 @@
-use std::sync::Arc;
-
-use mc_exec::ExecutionContext;
 use mp_block::BlockId;
 use mp_rpc::{FeeEstimate, MsgFromL1};
 use mp_transactions::L1HandlerTransaction;
@@ -40,17 +37,19 @@ pub async fn estimate_message_fee(
         return Err(StarknetRpcApiError::UnsupportedTxnVersion);
     }
 
-    let exec_context = ExecutionContext::new_at_block_end(Arc::clone(&starknet.backend), &block_info)?;
+    let exec_context = starknet.exec_context_at_block_end(&block_info)?;
 
     let transaction = convert_message_into_transaction(message, starknet.chain_id());
-    let execution_result = exec_context
-        .re_execute_transactions([], [transaction], false, true)?
-        .pop()
-        .ok_or_internal_server_error("Failed to convert BroadcastedTransaction to AccountTransaction")?;
-
-    let fee_estimate = exec_context.execution_result_to_fee_estimate(&execution_result);
+    starknet
+        .spawn_execution(move || -> StarknetRpcResult<FeeEstimate> {
+            let execution_result = exec_context
+                .re_execute_transactions([], [transaction], false, true)?
+                .pop()
+                .ok_or_internal_server_error("Failed to convert BroadcastedTransaction to AccountTransaction")?;
 
-    Ok(fee_estimate)
+            Ok(exec_context.execution_result_to_fee_estimate(&execution_result))
+        })
+        .await
 }
 
 pub fn convert_message_into_transaction(
@@ -11,7 +11,8 @@ use crate::{errors::StarknetRpcResult, Starknet};
 ///
 /// ### Returns
 ///
-/// * `transaction_count` - The number of transactions in the specified block.
+/// * `transaction_count` - The number of transactions in the specified block. When there is no pending block, the
+///   `pending` tag refers to an empty pending block, so its count is zero.
 ///
 /// ### Errors
 ///
@@ -33,11 +34,13 @@ mod tests {
     use super::*;
     use crate::{
         errors::StarknetRpcApiError,
-        test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters},
+        test_utils::{rpc_test_setup, sample_chain_for_block_getters, SampleChainForBlockGetters},
     };
+    use mc_db::MadaraBackend;
     use mp_block::BlockTag;
     use rstest::rstest;
     use starknet_types_core::felt::Felt;
+    use std::sync::Arc;
 
     #[rstest]
     fn test_get_block_transaction_count(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
@@ -70,4 +73,16 @@ mod tests {
             Err(StarknetRpcApiError::BlockNotFound)
         );
     }
+
+    #[rstest]
+    fn test_get_block_transaction_count_no_pending_block(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (_backend, rpc) = rpc_test_setup;
+
+        // There is always a pending block, empty when none was stored.
+        assert_eq!(get_block_transaction_count(&rpc, BlockId::Tag(BlockTag::Pending)), Ok(0));
+        assert_eq!(
+            get_block_transaction_count(&rpc, BlockId::Tag(BlockTag::Latest)),
+            Err(StarknetRpcApiError::BlockNotFound)
+        );
+    }
 }
@@ -17,5 +17,87 @@ pub fn get_class(
         .or_internal_server_error("Error getting contract class info")?
         .ok_or(StarknetRpcApiError::ClassHashNotFound)?;
 
-    Ok(class_data.contract_class().into())
+    let class = class_data.contract_class();
+    starknet.check_served_class(class_hash, &class)?;
+    let class = class.into();
+    starknet.check_served_class_size(class_hash, &class)?;
+    Ok(class)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        legacy_class_fixture, make_sample_chain_with_classes, rpc_test_setup, sierra_class_fixture,
+    };
+    use mc_db::MadaraBackend;
+    use mp_block::{
+        BlockTag, Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock, MadaraMaybePendingBlockInfo,
+    };
+    use mp_class::{ContractClass, ConvertedClass, SierraConvertedClass};
+    use mp_state_update::StateDiff;
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    #[rstest]
+    fn test_get_class_not_declared_yet(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let class = legacy_class_fixture(
+            "udc",
+            include_bytes!("../../../../../../../../../../cairo-artifacts/madara_contracts_UDC.json"),
+            None,
+        );
+
+        // The class is declared in block 1.
+        for (block_number, classes) in [(0, vec![]), (1, vec![class.converted_class.clone()])] {
+            backend
+                .store_block(
+                    MadaraMaybePendingBlock {
+                        info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                            header: Header { block_number, ..Default::default() },
+                            block_hash: Felt::from(block_number),
+                            tx_hashes: vec![],
+                        }),
+                        inner: MadaraBlockInner { transactions: vec![], receipts: vec![] },
+                    },
+                    StateDiff::default(),
+                    classes,
+                    None,
+                    None,
+                )
+                .unwrap();
+        }
+
+        for block_id in [BlockId::Number(1), BlockId::Hash(Felt::from(1)), BlockId::Tag(BlockTag::Latest)] {
+            let served = get_class(&rpc, block_id, class.class_hash).unwrap();
+            assert_eq!(ContractClass::try_from(served).unwrap().compute_class_hash().unwrap(), class.class_hash);
+        }
+        assert_eq!(get_class(&rpc, BlockId::Number(0), class.class_hash), Err(StarknetRpcApiError::ClassHashNotFound));
+        assert_eq!(
+            get_class(&rpc, BlockId::Hash(Felt::from(0)), class.class_hash),
+            Err(StarknetRpcApiError::ClassHashNotFound)
+        );
+    }
+
+    #[rstest]
+    fn test_get_class_sierra_program_too_short(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let mut class = sierra_class_fixture(
+            "openzeppelin erc20",
+            include_bytes!(
+                "../../../../../../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+            ),
+        );
+        let ConvertedClass::Sierra(SierraConvertedClass { info, .. }) = &mut class.converted_class else {
+            unreachable!()
+        };
+        Arc::make_mut(&mut info.contract_class).sierra_program.clear();
+        let class_hash = class.class_hash;
+        make_sample_chain_with_classes(&backend, &[class]);
+
+        assert!(matches!(
+            get_class(&rpc, BlockId::Number(0), class_hash),
+            Err(StarknetRpcApiError::ErrUnexpectedError { .. })
+        ));
+    }
 }
@@ -3,7 +3,7 @@ use mp_rpc::MaybeDeprecatedContractClass;
 use starknet_types_core::felt::Felt;
 
 use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
-use crate::utils::{OptionExt, ResultExt};
+use crate::utils::ResultExt;
 use crate::Starknet;
 
 /// Get the Contract Class Definition at a Given Address in a Specific Block
@@ -11,20 +11,25 @@ use crate::Starknet;
 /// ### Arguments
 ///
 /// * `block_id` - The identifier of the block. This can be the hash of the block, its number
-///   (height), or a specific block tag.
+///   (height), or a specific block tag. With the `pending` tag, the contracts deployed, the classes replaced and
+///   the classes declared in the pending block are taken into account.
 /// * `contract_address` - The address of the contract whose class definition will be returned.
 ///
 /// ### Returns
 ///
 /// * `contract_class` - The contract class definition. This may be either a standard contract class
 ///   or a deprecated contract class, depending on the contract's status and the blockchain's
-///   version.
+///   version. A legacy class stored without its ABI is served with a stub ABI reconstructed from its entry
+///   points, see [`mp_class::CompressedLegacyContractClass::reconstructed_abi`].
 ///
 /// ### Errors
 ///
 /// This method may return the following errors:
 /// * `BLOCK_NOT_FOUND` - If the specified block does not exist in the blockchain.
 /// * `CONTRACT_NOT_FOUND` - If the specified contract address does not exist.
+/// * `CLASS_HASH_NOT_FOUND` - If the class of the contract was declared after the specified block, or is found
+///   neither in the database nor in the class archive.
+/// * `CLASS_TOO_LARGE_TO_SERVE` - If the class definition is over the size limit configured for the node.
 pub fn get_class_at(
     starknet: &Starknet,
     block_id: BlockId,
@@ -38,15 +43,309 @@ pub fn get_class_at(
 
     let class_hash = starknet
         .backend
-        .get_contract_class_hash_at(&resolved_block_id, &contract_address)
+        .get_deployed_contract_class_hash_at(&resolved_block_id, &contract_address)
         .or_internal_server_error("Error getting contract class hash at")?
         .ok_or(StarknetRpcApiError::ContractNotFound)?;
 
+    // On pruned nodes, the class definition may only be available in the archive tier.
     let class_data = starknet
         .backend
-        .get_class_info(&resolved_block_id, &class_hash)
+        .get_class_info_or_archived(&resolved_block_id, &class_hash)
         .or_internal_server_error("Error getting contract class info")?
-        .ok_or_internal_server_error("Class has no info")?;
+        .ok_or(StarknetRpcApiError::ClassHashNotFound)?;
 
-    Ok(class_data.contract_class().into())
+    let class = class_data.contract_class();
+    starknet.check_served_class(class_hash, &class)?;
+    let class = class.with_reconstructed_legacy_abi().into();
+    starknet.check_served_class_size(class_hash, &class)?;
+    Ok(class)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        class_fixtures, legacy_class_fixture, make_sample_chain_with_classes, rpc_test_setup, sierra_class_fixture,
+    };
+    use crate::utils::json_size;
+    use mc_db::MadaraBackend;
+    use mp_block::{
+        header::PendingHeader, BlockTag, Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock,
+        MadaraMaybePendingBlockInfo, MadaraPendingBlockInfo,
+    };
+    use mp_class::{
+        ContractClass, ConvertedClass, LegacyConvertedClass, SierraConvertedClass, RECONSTRUCTED_ABI_ENTRY_PREFIX,
+    };
+    use mp_rpc::{ContractAbiEntry, MaybeDeprecatedContractClass};
+    use mp_state_update::{DeployedContractItem, ReplacedClassItem, StateDiff};
+    use rstest::rstest;
+    use std::sync::Arc;
+
+    /// Stores the closed block `block_number`, with no transactions.
+    fn store_closed_block(
+        backend: &MadaraBackend,
+        block_number: u64,
+        state_diff: StateDiff,
+        classes: Vec<ConvertedClass>,
+    ) {
+        let block = MadaraMaybePendingBlock {
+            info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                header: Header { block_number, ..Default::default() },
+                block_hash: Felt::from(block_number),
+                tx_hashes: vec![],
+            }),
+            inner: MadaraBlockInner { transactions: vec![], receipts: vec![] },
+        };
+        backend.store_block(block, state_diff, classes, None, None).unwrap();
+    }
+
+    /// Regression guard for the class conversions: every class served back must hash to the class hash it was
+    /// declared with.
+    #[rstest]
+    fn test_get_class_at_fidelity(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let classes = class_fixtures();
+        let contracts = make_sample_chain_with_classes(&backend, &classes);
+
+        for (class, contract_address) in classes.iter().zip(contracts) {
+            let served = get_class_at(&rpc, BlockId::Number(0), contract_address).unwrap();
+            let served = ContractClass::try_from(served).unwrap();
+            assert_eq!(served.compute_class_hash().unwrap(), class.class_hash, "class {}", class.name);
+        }
+    }
+
+    #[rstest]
+    fn test_get_class_at_reconstructs_missing_legacy_abi(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let mut class = legacy_class_fixture(
+            "udc",
+            include_bytes!("../../../../../../../../../../cairo-artifacts/madara_contracts_UDC.json"),
+            None,
+        );
+        let ConvertedClass::Legacy(LegacyConvertedClass { info, .. }) = &mut class.converted_class else {
+            unreachable!()
+        };
+        Arc::make_mut(&mut info.contract_class).abi = None;
+        let entry_points = info.contract_class.entry_points_by_type.clone();
+        let contracts = make_sample_chain_with_classes(&backend, &[class]);
+
+        let MaybeDeprecatedContractClass::Deprecated(served) =
+            get_class_at(&rpc, BlockId::Number(0), contracts[0]).unwrap()
+        else {
+            unreachable!("legacy class served as a sierra class")
+        };
+        let served_names: Vec<_> = served
+            .abi
+            .unwrap()
+            .into_iter()
+            .map(|entry| match entry {
+                ContractAbiEntry::Function(entry) => entry.name,
+                _ => unreachable!("reconstructed ABIs only contain functions"),
+            })
+            .collect();
+
+        let expected_names: Vec<_> = [&entry_points.constructor, &entry_points.external, &entry_points.l1_handler]
+            .into_iter()
+            .flatten()
+            .map(|entry_point| format!("{RECONSTRUCTED_ABI_ENTRY_PREFIX}{:#x}", entry_point.selector))
+            .collect();
+        assert!(!expected_names.is_empty());
+        assert_eq!(served_names, expected_names);
+    }
+
+    #[rstest]
+    fn test_get_class_at_max_served_class_size(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let classes = class_fixtures();
+        let contracts = make_sample_chain_with_classes(&backend, &classes);
+
+        let sizes: Vec<_> = contracts
+            .iter()
+            .map(|contract| json_size(&get_class_at(&rpc, BlockId::Number(0), *contract).unwrap()).unwrap())
+            .collect();
+        let (small, large) = if sizes[0] < sizes[1] { (0, 1) } else { (1, 0) };
+        assert_ne!(sizes[small], sizes[large]);
+
+        let rpc = rpc.with_max_served_class_size(sizes[small]);
+        let served = get_class_at(&rpc, BlockId::Number(0), contracts[small]).unwrap();
+        assert_eq!(ContractClass::try_from(served).unwrap().compute_class_hash().unwrap(), classes[small].class_hash);
+
+        assert_eq!(
+            get_class_at(&rpc, BlockId::Number(0), contracts[large]),
+            Err(StarknetRpcApiError::ClassTooLargeToServe {
+                class_hash: classes[large].class_hash,
+                size: sizes[large],
+                max_size: sizes[small],
+            })
+        );
+    }
+
+    #[rstest]
+    fn test_get_class_at_class_declared_later(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let class = legacy_class_fixture(
+            "udc",
+            include_bytes!("../../../../../../../../../../cairo-artifacts/madara_contracts_UDC.json"),
+            None,
+        );
+        let contract_address = Felt::from(0x1000);
+
+        // The contract points to the class from block 0, but the class is only declared in block 100.
+        for block_number in 0..=100 {
+            let (state_diff, classes) = match block_number {
+                0 => (
+                    StateDiff {
+                        deployed_contracts: vec![DeployedContractItem {
+                            address: contract_address,
+                            class_hash: class.class_hash,
+                        }],
+                        ..Default::default()
+                    },
+                    vec![],
+                ),
+                100 => (StateDiff::default(), vec![class.converted_class.clone()]),
+                _ => (StateDiff::default(), vec![]),
+            };
+            store_closed_block(&backend, block_number, state_diff, classes);
+        }
+
+        assert_eq!(
+            get_class_at(&rpc, BlockId::Number(50), contract_address),
+            Err(StarknetRpcApiError::ClassHashNotFound)
+        );
+        let served = get_class_at(&rpc, BlockId::Number(100), contract_address).unwrap();
+        assert_eq!(ContractClass::try_from(served).unwrap().compute_class_hash().unwrap(), class.class_hash);
+    }
+
+    #[rstest]
+    fn test_get_class_at_after_replacement(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let classes = class_fixtures();
+        let (class_a, class_b) = (&classes[0], &classes[1]);
+        let contract_address = Felt::from(0x1000);
+
+        // Class A is deployed in block 3, and the contract is replaced with class B in block 8.
+        for block_number in 0..=9 {
+            let (state_diff, classes) = match block_number {
+                3 => (
+                    StateDiff {
+                        deployed_contracts: vec![DeployedContractItem {
+                            address: contract_address,
+                            class_hash: class_a.class_hash,
+                        }],
+                        ..Default::default()
+                    },
+                    vec![class_a.converted_class.clone()],
+                ),
+                8 => (
+                    StateDiff {
+                        replaced_classes: vec![ReplacedClassItem { contract_address, class_hash: class_b.class_hash }],
+                        ..Default::default()
+                    },
+                    vec![class_b.converted_class.clone()],
+                ),
+                _ => (StateDiff::default(), vec![]),
+            };
+            store_closed_block(&backend, block_number, state_diff, classes);
+        }
+
+        let served_class_hash = |block_id| {
+            let served = get_class_at(&rpc, block_id, contract_address).unwrap();
+            ContractClass::try_from(served).unwrap().compute_class_hash().unwrap()
+        };
+        assert_eq!(
+            get_class_at(&rpc, BlockId::Number(2), contract_address),
+            Err(StarknetRpcApiError::ContractNotFound)
+        );
+        assert_eq!(served_class_hash(BlockId::Number(3)), class_a.class_hash);
+        assert_eq!(served_class_hash(BlockId::Number(5)), class_a.class_hash);
+        assert_eq!(served_class_hash(BlockId::Number(7)), class_a.class_hash);
+        assert_eq!(served_class_hash(BlockId::Number(8)), class_b.class_hash);
+        assert_eq!(served_class_hash(BlockId::Number(9)), class_b.class_hash);
+        assert_eq!(served_class_hash(BlockId::Tag(BlockTag::Latest)), class_b.class_hash);
+    }
+
+    #[rstest]
+    fn test_get_class_at_pending(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let classes = class_fixtures();
+        let (class_a, class_b) = (&classes[0], &classes[1]);
+        let (committed_contract, pending_contract) = (Felt::from(0x1000), Felt::from(0x2000));
+
+        store_closed_block(
+            &backend,
+            0,
+            StateDiff {
+                deployed_contracts: vec![DeployedContractItem {
+                    address: committed_contract,
+                    class_hash: class_a.class_hash,
+                }],
+                ..Default::default()
+            },
+            vec![class_a.converted_class.clone()],
+        );
+        // Class B is declared in the pending block, which deploys a contract with it and replaces the class of the
+        // committed contract.
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraMaybePendingBlockInfo::Pending(MadaraPendingBlockInfo {
+                        header: PendingHeader { parent_block_hash: Felt::ZERO, ..Default::default() },
+                        tx_hashes: vec![],
+                    }),
+                    inner: MadaraBlockInner { transactions: vec![], receipts: vec![] },
+                },
+                StateDiff {
+                    deployed_contracts: vec![DeployedContractItem {
+                        address: pending_contract,
+                        class_hash: class_b.class_hash,
+                    }],
+                    replaced_classes: vec![ReplacedClassItem {
+                        contract_address: committed_contract,
+                        class_hash: class_b.class_hash,
+                    }],
+                    ..Default::default()
+                },
+                vec![class_b.converted_class.clone()],
+                None,
+                None,
+            )
+            .unwrap();
+
+        let served_class_hash = |block_id, contract_address| {
+            let served = get_class_at(&rpc, block_id, contract_address).unwrap();
+            ContractClass::try_from(served).unwrap().compute_class_hash().unwrap()
+        };
+        let (pending, latest) = (BlockId::Tag(BlockTag::Pending), BlockId::Tag(BlockTag::Latest));
+
+        assert_eq!(served_class_hash(pending.clone(), pending_contract), class_b.class_hash);
+        assert_eq!(get_class_at(&rpc, latest.clone(), pending_contract), Err(StarknetRpcApiError::ContractNotFound));
+
+        assert_eq!(served_class_hash(pending.clone(), committed_contract), class_b.class_hash);
+        assert_eq!(served_class_hash(latest, committed_contract), class_a.class_hash);
+
+        assert_eq!(get_class_at(&rpc, pending, Felt::from(0x3000)), Err(StarknetRpcApiError::ContractNotFound));
+    }
+
+    /// A stored Sierra class with a truncated program would not hash to its class hash, it is not served.
+    #[rstest]
+    fn test_get_class_at_sierra_program_too_short(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let mut class = sierra_class_fixture(
+            "openzeppelin erc20",
+            include_bytes!(
+                "../../../../../../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+            ),
+        );
+        let ConvertedClass::Sierra(SierraConvertedClass { info, .. }) = &mut class.converted_class else {
+            unreachable!()
+        };
+        Arc::make_mut(&mut info.contract_class).sierra_program.truncate(3);
+        let contracts = make_sample_chain_with_classes(&backend, &[class]);
+
+        assert!(matches!(
+            get_class_at(&rpc, BlockId::Number(0), contracts[0]),
+            Err(StarknetRpcApiError::ErrUnexpectedError { .. })
+        ));
+    }
 }
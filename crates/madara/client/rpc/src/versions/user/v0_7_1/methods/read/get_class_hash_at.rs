@@ -18,16 +18,17 @@ use crate::Starknet;
 ///
 /// * `class_hash` - The class hash of the given contract
 pub fn get_class_hash_at(starknet: &Starknet, block_id: BlockId, contract_address: Felt) -> StarknetRpcResult<Felt> {
-    // Check if block exists. We have to return a different error in that case.
-    let block_exists =
-        starknet.backend.contains_block(&block_id).or_internal_server_error("Checking if block is in database")?;
-    if !block_exists {
-        return Err(StarknetRpcApiError::BlockNotFound);
-    }
+    // Resolve the block once, so that the class hash is read at the block which was checked to exist even if a new
+    // block is imported in the meantime. We have to return a different error when the block does not exist.
+    let resolved_block_id = starknet
+        .backend
+        .resolve_block_id(&block_id)
+        .or_internal_server_error("Error resolving block id")?
+        .ok_or(StarknetRpcApiError::BlockNotFound)?;
 
     let class_hash = starknet
         .backend
-        .get_contract_class_hash_at(&block_id, &contract_address)
+        .get_contract_class_hash_at(&resolved_block_id, &contract_address)
         .or_internal_server_error("Error getting contract class hash at")?
         .ok_or(StarknetRpcApiError::ContractNotFound)?;
 
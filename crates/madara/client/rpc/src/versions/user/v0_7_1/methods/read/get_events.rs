@@ -1,10 +1,10 @@
 use mp_block::{BlockId, BlockTag, MadaraMaybePendingBlock, MadaraMaybePendingBlockInfo};
 use mp_rpc::{EmittedEvent, Event, EventContent, EventFilterWithPageRequest, EventsChunk};
 
-use crate::constants::{MAX_EVENTS_CHUNK_SIZE, MAX_EVENTS_KEYS};
+use crate::constants::{MAX_EVENTS_CHUNK_BYTES, MAX_EVENTS_CHUNK_SIZE, MAX_EVENTS_KEYS};
 use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
 use crate::types::ContinuationToken;
-use crate::utils::event_match_filter;
+use crate::utils::{event_match_filter, json_size, ResultExt};
 use crate::Starknet;
 
 /// Returns all events matching the given filter.
@@ -24,9 +24,12 @@ use crate::Starknet;
 ///
 /// Returns a chunk of event objects that match the filter criteria, encapsulated in an
 /// `EventsChunk` type. The chunk includes details about the events, such as their data, the
-/// block in which they occurred, and the transaction that triggered them. In case of
+/// block in which they occurred, and the transaction that triggered them. The chunk is cut short
+/// with a continuation token when its size exceeds [`MAX_EVENTS_CHUNK_BYTES`]. In case of
 /// errors, such as `PAGE_SIZE_TOO_BIG`, `INVALID_CONTINUATION_TOKEN`, `BLOCK_NOT_FOUND`, or
-/// `TOO_MANY_KEYS_IN_FILTER`, returns a `StarknetRpcApiError` indicating the specific issue.
+/// `TOO_MANY_KEYS_IN_FILTER`, returns a `StarknetRpcApiError` indicating the specific issue. A
+/// chunk size of zero is rejected as invalid params. A range starting after its end, which happens when polling
+/// from a block which is not produced yet, returns an empty chunk.
 pub async fn get_events(starknet: &Starknet, filter: EventFilterWithPageRequest) -> StarknetRpcResult<EventsChunk> {
     let from_address = filter.address;
     let keys = filter.keys;
@@ -40,6 +43,10 @@ pub async fn get_events(starknet: &Starknet, filter: EventFilterWithPageRequest)
     if chunk_size > MAX_EVENTS_CHUNK_SIZE as u64 {
         return Err(StarknetRpcApiError::PageSizeTooBig);
     }
+    // An empty chunk would return the same continuation token forever.
+    if chunk_size == 0 {
+        return Err(StarknetRpcApiError::InvalidParams { data: "The chunk size must be at least 1".into() });
+    }
 
     // Get the block numbers for the requested range
     let (from_block, to_block, latest_block) = block_range(starknet, filter.from_block, filter.to_block)?;
@@ -49,13 +56,17 @@ pub async fn get_events(starknet: &Starknet, filter: EventFilterWithPageRequest)
         None => ContinuationToken { block_n: from_block, event_n: 0 },
     };
 
-    // Verify that the requested range is valid
     if from_block > to_block {
         return Ok(EventsChunk { events: vec![], continuation_token: None });
     }
+    // The token points at a block and an event index within it, so that it stays valid when new blocks are added.
+    if !(from_block..=to_block).contains(&continuation_token.block_n) {
+        return Err(StarknetRpcApiError::InvalidContinuationToken);
+    }
 
     let from_block = continuation_token.block_n;
     let mut filtered_events: Vec<EmittedEvent> = Vec::new();
+    let mut chunk_bytes = 0;
 
     for current_block in from_block..=to_block {
         let (_pending, block) = if current_block <= latest_block {
@@ -72,20 +83,27 @@ pub async fn get_events(starknet: &Starknet, filter: EventFilterWithPageRequest)
             return Err(StarknetRpcApiError::InvalidContinuationToken);
         }
 
-        #[allow(clippy::iter_skip_zero)]
-        let block_filtered_reduced_events: Vec<EmittedEvent> = block_filtered_events
-            .into_iter()
-            .skip(if current_block == from_block { continuation_token.event_n as usize } else { 0 })
-            .take(chunk_size as usize - filtered_events.len())
-            .collect();
-
-        let num_events = block_filtered_reduced_events.len();
-
-        filtered_events.extend(block_filtered_reduced_events);
+        let skip = if current_block == from_block { continuation_token.event_n as usize } else { 0 };
+        let mut num_events = 0;
+        let mut byte_budget_reached = false;
+        for event in block_filtered_events.into_iter().skip(skip) {
+            if filtered_events.len() == chunk_size as usize {
+                break;
+            }
+            let event_size = json_size(&event).or_internal_server_error("Error encoding event")?;
+            // At least one event is always returned, so that the client can make progress.
+            if !filtered_events.is_empty() && chunk_bytes + event_size > MAX_EVENTS_CHUNK_BYTES {
+                byte_budget_reached = true;
+                break;
+            }
+            chunk_bytes += event_size;
+            filtered_events.push(event);
+            num_events += 1;
+        }
 
-        if filtered_events.len() == chunk_size as usize {
+        if filtered_events.len() == chunk_size as usize || byte_budget_reached {
             let event_n =
-                if current_block == from_block { continuation_token.event_n + chunk_size } else { num_events as u64 };
+                if current_block == from_block { continuation_token.event_n + num_events } else { num_events };
             let token = Some(ContinuationToken { block_n: current_block, event_n }.to_string());
 
             return Ok(EventsChunk { events: filtered_events, continuation_token: token });
@@ -99,7 +117,7 @@ fn block_range(
     from_block: Option<BlockId>,
     to_block: Option<BlockId>,
 ) -> StarknetRpcResult<(u64, u64, u64)> {
-    let latest_block_n = starknet.get_block_n(&BlockId::Tag(BlockTag::Latest))?;
+    let latest_block_n = starknet.current_block_number()?;
     let from_block_n = match from_block {
         Some(BlockId::Tag(BlockTag::Pending)) => latest_block_n + 1,
         Some(block_id) => starknet.get_block_n(&block_id)?,
@@ -150,3 +168,214 @@ pub fn drain_block_events(block: MadaraMaybePendingBlock) -> impl Iterator<Item
         transaction_hash,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::rpc_test_setup;
+    use mc_db::MadaraBackend;
+    use mp_block::{Header, MadaraBlockInfo, MadaraBlockInner};
+    use mp_receipt::{InvokeTransactionReceipt, TransactionReceipt};
+    use mp_state_update::StateDiff;
+    use mp_transactions::{InvokeTransaction, InvokeTransactionV0, Transaction};
+    use rstest::rstest;
+    use starknet_types_core::felt::Felt;
+    use std::sync::Arc;
+
+    /// Stores a block per entry of `blocks` starting at `first_block`, with one transaction per event, emitting
+    /// events with these keys.
+    fn store_blocks_with_events(backend: &MadaraBackend, first_block: u64, blocks: &[Vec<Vec<Felt>>]) {
+        for (block_number, events) in (first_block..).zip(blocks) {
+            let receipts = events
+                .iter()
+                .enumerate()
+                .map(|(i, keys)| {
+                    TransactionReceipt::Invoke(InvokeTransactionReceipt {
+                        transaction_hash: Felt::from(i),
+                        events: vec![mp_receipt::Event { from_address: Felt::ONE, keys: keys.clone(), data: vec![] }],
+                        ..Default::default()
+                    })
+                })
+                .collect();
+            backend
+                .store_block(
+                    MadaraMaybePendingBlock {
+                        info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                            header: Header { block_number, ..Default::default() },
+                            block_hash: Felt::from(block_number),
+                            tx_hashes: (0..events.len()).map(Felt::from).collect(),
+                        }),
+                        inner: MadaraBlockInner {
+                            transactions: events
+                                .iter()
+                                .map(|_| Transaction::Invoke(InvokeTransaction::V0(InvokeTransactionV0::default())))
+                                .collect(),
+                            receipts,
+                        },
+                    },
+                    StateDiff::default(),
+                    vec![],
+                    None,
+                    None,
+                )
+                .unwrap();
+        }
+    }
+
+    fn events_filter(
+        keys: Option<Vec<Vec<Felt>>>,
+        chunk_size: u64,
+        continuation_token: Option<String>,
+    ) -> EventFilterWithPageRequest {
+        EventFilterWithPageRequest {
+            address: None,
+            from_block: Some(BlockId::Number(0)),
+            keys,
+            to_block: Some(BlockId::Number(1)),
+            chunk_size,
+            continuation_token,
+        }
+    }
+
+    #[tokio::test]
+    #[rstest]
+    async fn test_get_events_resumes_across_blocks(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let key = |i: u64| vec![Felt::from(i)];
+        store_blocks_with_events(&backend, 0, &[vec![key(0), key(1), key(2)], vec![key(3), key(4), key(5)]]);
+        let keys = |chunk: &EventsChunk| -> Vec<Vec<Felt>> {
+            chunk.events.iter().map(|event| event.event.event_content.keys.clone()).collect()
+        };
+
+        // The first chunk stops in the middle of the second block.
+        let first = get_events(&rpc, events_filter(None, 4, None)).await.unwrap();
+        assert_eq!(keys(&first), vec![key(0), key(1), key(2), key(3)]);
+        assert_eq!(first.events[3].block_number, Some(1));
+        assert_eq!(first.continuation_token, Some("1-1".into()));
+
+        // New blocks do not shift the events of the following chunk.
+        store_blocks_with_events(&backend, 2, &[vec![key(6)]]);
+        let second = get_events(&rpc, events_filter(None, 4, first.continuation_token)).await.unwrap();
+        assert_eq!(keys(&second), vec![key(4), key(5)]);
+        assert_eq!(second.continuation_token, None);
+
+        // A token outside of the requested range, or past the events of its block, is rejected.
+        for token in ["2-0", "1-4", "not a token"] {
+            assert!(
+                matches!(
+                    get_events(&rpc, events_filter(None, 4, Some(token.into()))).await,
+                    Err(StarknetRpcApiError::InvalidContinuationToken)
+                ),
+                "{token}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    #[rstest]
+    async fn test_get_events_key_filter(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let (a, b, c) = (Felt::from(0xa), Felt::from(0xb), Felt::from(0xc));
+        store_blocks_with_events(&backend, 0, &[vec![vec![a, b, c], vec![a, c], vec![b]], vec![vec![a]]]);
+        async fn matching(rpc: &Starknet, keys: Vec<Vec<Felt>>) -> Vec<Vec<Felt>> {
+            let chunk = get_events(rpc, events_filter(Some(keys), 10, None)).await.unwrap();
+            chunk.events.into_iter().map(|event| event.event.event_content.keys).collect()
+        }
+
+        // The filter matches a prefix of the keys of the events, an empty position matching any key.
+        assert_eq!(matching(&rpc, vec![vec![a]]).await, vec![vec![a, b, c], vec![a, c], vec![a]]);
+        assert_eq!(matching(&rpc, vec![vec![], vec![c]]).await, vec![vec![a, c]]);
+        assert_eq!(matching(&rpc, vec![vec![a, b], vec![b, c]]).await, vec![vec![a, b, c], vec![a, c]]);
+        assert_eq!(matching(&rpc, vec![vec![], vec![], vec![c]]).await, vec![vec![a, b, c]]);
+        // Events with fewer keys than the filter do not match.
+        assert_eq!(matching(&rpc, vec![vec![a], vec![], vec![], vec![]]).await, Vec::<Vec<Felt>>::new());
+    }
+
+    #[tokio::test]
+    #[rstest]
+    async fn test_get_events_invalid_requests(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        store_blocks_with_events(&backend, 0, &[vec![vec![Felt::ONE]], vec![vec![Felt::TWO]]]);
+
+        assert!(matches!(
+            get_events(&rpc, events_filter(None, 0, None)).await,
+            Err(StarknetRpcApiError::InvalidParams { .. })
+        ));
+
+        // A range starting after its end is empty.
+        let mut filter = events_filter(None, 10, None);
+        filter.from_block = Some(BlockId::Number(1));
+        filter.to_block = Some(BlockId::Number(0));
+        assert_eq!(get_events(&rpc, filter).await.unwrap(), EventsChunk { events: vec![], continuation_token: None });
+    }
+
+    #[tokio::test]
+    #[rstest]
+    async fn test_get_events_byte_cap(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+
+        // ~680KB per event once serialized: the byte cap is reached well before the chunk size.
+        let n_events = 20;
+        let receipts = (0..n_events)
+            .map(|i| {
+                TransactionReceipt::Invoke(InvokeTransactionReceipt {
+                    transaction_hash: Felt::from(i),
+                    events: vec![mp_receipt::Event {
+                        from_address: Felt::ONE,
+                        keys: vec![Felt::from(i)],
+                        data: vec![Felt::MAX; 10_000],
+                    }],
+                    ..Default::default()
+                })
+            })
+            .collect();
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                        header: Header::default(),
+                        block_hash: Felt::ONE,
+                        tx_hashes: (0..n_events).map(Felt::from).collect(),
+                    }),
+                    inner: MadaraBlockInner {
+                        transactions: (0..n_events)
+                            .map(|_| Transaction::Invoke(InvokeTransaction::V0(InvokeTransactionV0::default())))
+                            .collect(),
+                        receipts,
+                    },
+                },
+                StateDiff::default(),
+                vec![],
+                None,
+                None,
+            )
+            .unwrap();
+
+        let filter = |continuation_token| EventFilterWithPageRequest {
+            address: None,
+            from_block: Some(BlockId::Number(0)),
+            keys: None,
+            to_block: Some(BlockId::Number(0)),
+            chunk_size: 100,
+            continuation_token,
+        };
+
+        let first = get_events(&rpc, filter(None)).await.unwrap();
+        let n_first = first.events.len();
+        assert!(n_first > 0 && n_first < n_events as usize);
+        assert!(first.events.iter().map(|event| json_size(event).unwrap()).sum::<usize>() <= MAX_EVENTS_CHUNK_BYTES);
+        assert_eq!(first.continuation_token, Some(format!("0-{n_first}")));
+
+        let mut events = first.events;
+        let mut continuation_token = first.continuation_token;
+        while let Some(token) = continuation_token {
+            let chunk = get_events(&rpc, filter(Some(token))).await.unwrap();
+            events.extend(chunk.events);
+            continuation_token = chunk.continuation_token;
+        }
+        assert_eq!(events.len(), n_events as usize);
+        for (i, event) in events.iter().enumerate() {
+            assert_eq!(event.event.event_content.keys, vec![Felt::from(i)]);
+        }
+    }
+}
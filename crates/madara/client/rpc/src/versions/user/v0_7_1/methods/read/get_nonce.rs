@@ -1,3 +1,4 @@
+use mc_db::db_block_id::DbBlockIdResolvable;
 use mp_block::BlockId;
 use starknet_types_core::felt::Felt;
 
@@ -22,12 +23,12 @@ use crate::Starknet;
 /// `BLOCK_NOT_FOUND` or `CONTRACT_NOT_FOUND`, returns a `StarknetRpcApiError` indicating the
 /// specific issue.
 pub fn get_nonce(starknet: &Starknet, block_id: BlockId, contract_address: Felt) -> StarknetRpcResult<Felt> {
-    // Check if block exists. We have to return a different error in that case.
-    let block_exists =
-        starknet.backend.contains_block(&block_id).or_internal_server_error("Checking if block is in database")?;
-    if !block_exists {
-        return Err(StarknetRpcApiError::BlockNotFound);
-    }
+    // Both reads below use this resolved block, so that a block imported in between cannot split them.
+    let block_id = starknet
+        .backend
+        .resolve_block_id(&block_id)
+        .or_internal_server_error("Error resolving block id")?
+        .ok_or(StarknetRpcApiError::BlockNotFound)?;
 
     if !starknet
         .backend
@@ -37,9 +38,22 @@ pub fn get_nonce(starknet: &Starknet, block_id: BlockId, contract_address: Felt)
         return Err(StarknetRpcApiError::ContractNotFound);
     }
 
+    get_nonce_unchecked(starknet, &block_id, contract_address)
+}
+
+/// Same as [`get_nonce`], without checking that the contract is deployed: this saves a database read for callers
+/// which do not need that check, such as the contract leaves of `starknet_getStorageProof`.
+/// Returns 0 for a contract which is not deployed, and for a block which does not exist.
+///
+/// `starknet_getNonce` must still return `CONTRACT_NOT_FOUND` for missing contracts, and uses [`get_nonce`].
+pub fn get_nonce_unchecked(
+    starknet: &Starknet,
+    block_id: &impl DbBlockIdResolvable,
+    contract_address: Felt,
+) -> StarknetRpcResult<Felt> {
     let nonce = starknet
         .backend
-        .get_contract_nonce_at(&block_id, &contract_address)
+        .get_contract_nonce_at(block_id, &contract_address)
         .or_internal_server_error("Error getting nonce")?
         .unwrap_or(Felt::ZERO);
 
@@ -94,4 +108,24 @@ mod tests {
         let does_not_exist = Felt::from_hex_unchecked("0x7128638126378");
         assert_eq!(get_nonce(&rpc, block_n, does_not_exist), Err(StarknetRpcApiError::ContractNotFound));
     }
+
+    #[rstest]
+    fn test_get_nonce_unchecked(sample_chain_for_state_updates: (SampleChainForStateUpdates, Starknet)) {
+        let (SampleChainForStateUpdates { contracts, .. }, rpc) = sample_chain_for_state_updates;
+        let block_n = BlockId::Number(1);
+
+        // The checked variant reads the class hash of the contract, then its nonce.
+        let reads = rpc.backend.storage_metrics().history_reads();
+        assert_eq!(get_nonce(&rpc, block_n.clone(), contracts[0]).unwrap(), 1.into());
+        assert_eq!(rpc.backend.storage_metrics().history_reads() - reads, 2);
+
+        let reads = rpc.backend.storage_metrics().history_reads();
+        assert_eq!(get_nonce_unchecked(&rpc, &block_n, contracts[0]).unwrap(), 1.into());
+        assert_eq!(rpc.backend.storage_metrics().history_reads() - reads, 1);
+
+        // A contract which is not deployed has a nonce of 0.
+        let block_n = BlockId::Number(0);
+        assert_eq!(get_nonce(&rpc, block_n.clone(), contracts[1]), Err(StarknetRpcApiError::ContractNotFound));
+        assert_eq!(get_nonce_unchecked(&rpc, &block_n, contracts[1]).unwrap(), Felt::ZERO);
+    }
 }
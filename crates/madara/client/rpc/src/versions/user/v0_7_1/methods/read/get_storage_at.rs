@@ -1,4 +1,5 @@
-use mc_db::db_block_id::{DbBlockId, DbBlockIdResolvable};
+use mc_db::contract_db::BLOCK_HASH_CONTRACT_ADDRESS;
+use mc_db::db_block_id::DbBlockId;
 use mp_block::BlockId;
 use starknet_types_core::felt::Felt;
 
@@ -39,45 +40,76 @@ pub fn get_storage_at(
     key: Felt,
     block_id: BlockId,
 ) -> StarknetRpcResult<Felt> {
-    // Check if block exists. We have to return a different error in that case.
-    let block_exists =
-        starknet.backend.contains_block(&block_id).or_internal_server_error("Checking if block is in database")?;
-    if !block_exists {
-        return Err(StarknetRpcApiError::BlockNotFound);
+    Ok(get_storage_at_opt(starknet, contract_address, key, block_id)?.unwrap_or(Felt::ZERO))
+}
+
+/// Same as [`get_storage_at`], but returns `None` when the storage slot has never been written to, instead of 0.
+/// A slot explicitly written to 0 returns `Some(0)`.
+pub fn get_storage_at_opt(
+    starknet: &Starknet,
+    contract_address: Felt,
+    key: Felt,
+    block_id: BlockId,
+) -> StarknetRpcResult<Option<Felt>> {
+    // The contract check and the storage read must agree on the block, so `Latest` is pinned to a number here.
+    let block_id = starknet
+        .backend
+        .resolve_block_id(&block_id)
+        .or_internal_server_error("Error resolving block id")?
+        .ok_or(StarknetRpcApiError::BlockNotFound)?;
+
+    // No contract is deployed at the block hash address: its storage maps block numbers to block hashes, and is read
+    // the same way as during execution.
+    if contract_address == BLOCK_HASH_CONTRACT_ADDRESS {
+        return get_block_hash_storage_at(starknet, block_id, key);
     }
 
-    let block_number = block_id.resolve_db_block_id(&starknet.backend)?;
-
-    // Felt::ONE is a special contract address that is a mapping of the block number to the block hash.
-    // no contract is deployed at this address, so we skip the contract check.
-    let skip_contract_check = matches!(
-        block_number,
-        Some(DbBlockId::Number(num)) if num >= 10 && contract_address == Felt::ONE
-    );
-
-    if !skip_contract_check {
-        starknet
-            .backend
-            .get_contract_class_hash_at(&block_id, &contract_address)
-            .or_internal_server_error("Failed to check if contract is deployed")?
-            .ok_or(StarknetRpcApiError::ContractNotFound)?;
+    if !starknet
+        .backend
+        .is_contract_deployed_at(&block_id, &contract_address)
+        .or_internal_server_error("Failed to check if contract is deployed")?
+    {
+        return Err(StarknetRpcApiError::ContractNotFound);
     }
 
     let storage = starknet
         .backend
         .get_contract_storage_at(&block_id, &contract_address, &key)
-        .or_internal_server_error("Error getting contract storage at")?
-        .unwrap_or(Felt::ZERO);
+        .or_internal_server_error("Error getting contract storage at")?;
 
     Ok(storage)
 }
 
+/// Reads the state at `block_id` as a contract executed in the following block would. Block hashes which are not
+/// provided to contracts, such as the hashes of the last 10 blocks, are `None` and read as zero.
+fn get_block_hash_storage_at(starknet: &Starknet, block_id: DbBlockId, key: Felt) -> StarknetRpcResult<Option<Felt>> {
+    let current_block_n = match block_id {
+        DbBlockId::Number(block_n) => block_n + 1,
+        DbBlockId::Pending => {
+            let latest_block_n =
+                starknet.backend.get_latest_block_n().or_internal_server_error("Error getting latest block number")?;
+            latest_block_n.map_or(0, |block_n| block_n + 1) + 1
+        }
+    };
+
+    let Ok(requested_block_n) = u64::try_from(key) else { return Ok(None) };
+    starknet
+        .backend
+        .get_block_hash_storage_at(&block_id, current_block_n, requested_block_n)
+        .or_internal_server_error("Error getting block hash storage at")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::{sample_chain_for_state_updates, SampleChainForStateUpdates};
-    use mp_block::BlockTag;
+    use crate::test_utils::{rpc_test_setup, sample_chain_for_state_updates, SampleChainForStateUpdates};
+    use mc_db::MadaraBackend;
+    use mp_block::{
+        BlockTag, Header, MadaraBlockInfo, MadaraBlockInner, MadaraMaybePendingBlock, MadaraMaybePendingBlockInfo,
+    };
+    use mp_state_update::{ContractStorageDiffItem, DeployedContractItem, StateDiff, StorageEntry};
     use rstest::rstest;
+    use std::sync::Arc;
 
     #[rstest]
     fn test_get_storage_at(sample_chain_for_state_updates: (SampleChainForStateUpdates, Starknet)) {
@@ -162,4 +194,89 @@ mod tests {
             Ok(Felt::ZERO) // return ZERO when key not found
         );
     }
+
+    #[rstest]
+    fn test_get_storage_at_opt(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let contract = Felt::from_hex_unchecked("0x1234");
+        let (zeroed_key, untouched_key) = (Felt::ONE, Felt::TWO);
+
+        backend
+            .store_block(
+                MadaraMaybePendingBlock {
+                    info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                        header: Header { block_number: 0, ..Default::default() },
+                        block_hash: Felt::from_hex_unchecked("0x12345"),
+                        tx_hashes: vec![],
+                    }),
+                    inner: MadaraBlockInner { transactions: vec![], receipts: vec![] },
+                },
+                StateDiff {
+                    deployed_contracts: vec![DeployedContractItem { address: contract, class_hash: Felt::ONE }],
+                    storage_diffs: vec![ContractStorageDiffItem {
+                        address: contract,
+                        storage_entries: vec![StorageEntry { key: zeroed_key, value: Felt::ZERO }],
+                    }],
+                    ..Default::default()
+                },
+                vec![],
+                None,
+                None,
+            )
+            .unwrap();
+
+        let block_id = BlockId::Tag(BlockTag::Latest);
+        assert_eq!(get_storage_at_opt(&rpc, contract, zeroed_key, block_id.clone()), Ok(Some(Felt::ZERO)));
+        assert_eq!(get_storage_at_opt(&rpc, contract, untouched_key, block_id.clone()), Ok(None));
+        // The standard method does not tell them apart.
+        assert_eq!(get_storage_at(&rpc, contract, zeroed_key, block_id.clone()), Ok(Felt::ZERO));
+        assert_eq!(get_storage_at(&rpc, contract, untouched_key, block_id), Ok(Felt::ZERO));
+    }
+
+    #[rstest]
+    fn test_get_storage_at_block_hash(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let block_hash_0 = Felt::from_hex_unchecked("0xb10c");
+
+        for block_number in 0..=10 {
+            let storage_diffs = match block_number {
+                0 => vec![ContractStorageDiffItem {
+                    address: BLOCK_HASH_CONTRACT_ADDRESS,
+                    storage_entries: vec![StorageEntry { key: Felt::ZERO, value: block_hash_0 }],
+                }],
+                _ => vec![],
+            };
+            backend
+                .store_block(
+                    MadaraMaybePendingBlock {
+                        info: MadaraMaybePendingBlockInfo::NotPending(MadaraBlockInfo {
+                            header: Header { block_number, ..Default::default() },
+                            block_hash: Felt::from(block_number),
+                            tx_hashes: vec![],
+                        }),
+                        inner: MadaraBlockInner { transactions: vec![], receipts: vec![] },
+                    },
+                    StateDiff { storage_diffs, ..Default::default() },
+                    vec![],
+                    None,
+                    None,
+                )
+                .unwrap();
+        }
+
+        let read_block_hash =
+            |block_n: u64, key: Felt| get_storage_at(&rpc, BLOCK_HASH_CONTRACT_ADDRESS, key, BlockId::Number(block_n));
+
+        // The hash of block 0 is provided to the contracts of block 10, which run on top of block 9.
+        assert_eq!(read_block_hash(9, Felt::ZERO), Ok(block_hash_0));
+        assert_eq!(read_block_hash(10, Felt::ZERO), Ok(block_hash_0));
+        // Hashes which are not provided read as zero, as they do during execution.
+        assert_eq!(read_block_hash(8, Felt::ZERO), Ok(Felt::ZERO));
+        // Not provided yet.
+        assert_eq!(read_block_hash(9, Felt::ONE), Ok(Felt::ZERO));
+        // Provided, but not stored.
+        assert_eq!(read_block_hash(10, Felt::ONE), Ok(Felt::ZERO));
+        // Not a block number.
+        assert_eq!(read_block_hash(10, Felt::MAX), Ok(Felt::ZERO));
+    }
 }
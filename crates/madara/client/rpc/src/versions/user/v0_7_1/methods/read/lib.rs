@@ -48,8 +48,8 @@ impl StarknetReadRpcApiV0_7_1Server for Starknet {
         Ok(block_hash_and_number(self)?)
     }
 
-    fn call(&self, request: FunctionCall, block_id: BlockId) -> RpcResult<Vec<Felt>> {
-        Ok(call(self, request, block_id)?)
+    async fn call(&self, request: FunctionCall, block_id: BlockId) -> RpcResult<Vec<Felt>> {
+        Ok(call(self, request, block_id).await?)
     }
 
     fn chain_id(&self) -> RpcResult<Felt> {
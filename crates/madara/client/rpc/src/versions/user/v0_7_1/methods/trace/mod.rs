@@ -19,7 +19,7 @@ impl StarknetTraceRpcApiV0_7_1Server for Starknet {
         transactions: Vec<BroadcastedTxn>,
         simulation_flags: Vec<SimulationFlag>,
     ) -> RpcResult<Vec<SimulateTransactionsResult>> {
-        Ok(simulate_transactions(self, block_id, transactions, simulation_flags).await?)
+        Ok(simulate_transactions(self, block_id, transactions, simulation_flags, false).await?)
     }
 
     async fn trace_block_transactions(&self, block_id: BlockId) -> RpcResult<Vec<TraceBlockTransactionsResult>> {
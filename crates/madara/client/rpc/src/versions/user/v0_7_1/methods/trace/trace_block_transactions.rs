@@ -1,10 +1,10 @@
+use mc_exec::execution_result_to_tx_trace;
 use mc_exec::transaction::to_blockifier_transaction;
-use mc_exec::{execution_result_to_tx_trace, ExecutionContext};
 use mp_block::BlockId;
 use mp_convert::ToFelt;
 use mp_rpc::TraceBlockTransactionsResult;
+use mp_transactions::Transaction;
 use starknet_api::transaction::TransactionHash;
-use std::sync::Arc;
 
 use super::trace_transaction::EXECUTION_UNSUPPORTED_BELOW_VERSION;
 use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
@@ -21,7 +21,37 @@ pub async fn trace_block_transactions(
         return Err(StarknetRpcApiError::UnsupportedTxnVersion);
     }
 
-    let exec_context = ExecutionContext::new_at_block_start(Arc::clone(&starknet.backend), &block.info)?;
+    let exec_context = starknet.exec_context_at_block_start(&block.info)?.with_shared_read_cache();
+
+    // Load the classes of the contracts invoked, deployed or upgraded in this block, so that they are read from the
+    // database in a batch instead of one by one during execution. Classes declared in this block are not visible at
+    // block start, and are left to the execution.
+    let state_diff = starknet
+        .backend
+        .get_block_state_diff(&block_id)
+        .or_internal_server_error("Error getting block state diff")?
+        .unwrap_or_default();
+    let mut class_hashes: Vec<_> = state_diff
+        .deployed_contracts
+        .iter()
+        .map(|item| item.class_hash)
+        .chain(state_diff.replaced_classes.iter().map(|item| item.class_hash))
+        .collect();
+    class_hashes.sort();
+    class_hashes.dedup();
+    let mut invoked_contracts: Vec<_> = block
+        .inner
+        .transactions
+        .iter()
+        .filter_map(|tx| match tx {
+            Transaction::Invoke(tx) => Some(*tx.sender_address()),
+            Transaction::L1Handler(tx) => Some(tx.contract_address),
+            Transaction::Declare(tx) => Some(*tx.sender_address()),
+            Transaction::Deploy(_) | Transaction::DeployAccount(_) => None,
+        })
+        .collect();
+    invoked_contracts.sort();
+    invoked_contracts.dedup();
 
     let transactions: Vec<_> = block
         .inner
@@ -34,7 +64,13 @@ pub async fn trace_block_transactions(
         })
         .collect::<Result<_, _>>()?;
 
-    let executions_results = exec_context.re_execute_transactions([], transactions, true, true)?;
+    let executions_results = starknet
+        .spawn_execution(move || -> StarknetRpcResult<_> {
+            exec_context.preload_classes(&class_hashes)?;
+            exec_context.preload_contract_classes(&invoked_contracts)?;
+            Ok(exec_context.re_execute_transactions([], transactions, true, true)?)
+        })
+        .await?;
 
     let traces = executions_results
         .into_iter()
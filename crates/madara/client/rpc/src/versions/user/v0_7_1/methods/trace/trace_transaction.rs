@@ -4,12 +4,10 @@ use crate::utils::{OptionExt, ResultExt};
 use crate::Starknet;
 use mc_exec::execution_result_to_tx_trace;
 use mc_exec::transaction::to_blockifier_transaction;
-use mc_exec::ExecutionContext;
 use mp_chain_config::StarknetVersion;
 use mp_rpc::TraceBlockTransactionsResult;
 use starknet_api::transaction::TransactionHash;
 use starknet_types_core::felt::Felt;
-use std::sync::Arc;
 
 /// Blockifier does not support execution for versions earlier than that.
 pub const EXECUTION_UNSUPPORTED_BELOW_VERSION: StarknetVersion = StarknetVersion::V0_13_0;
@@ -24,11 +22,19 @@ pub async fn trace_transaction(
         .or_internal_server_error("Error while getting block from tx hash")?
         .ok_or(StarknetRpcApiError::TxnHashNotFound)?;
 
+    // The execution result of a sealed transaction is deterministic, pending transactions are never cached.
+    let sealed_block_hash = block.info.block_hash();
+    let cached_trace =
+        sealed_block_hash.and_then(|block_hash| starknet.execution_cache.get_trace(&block_hash, &transaction_hash));
+    if let Some(trace) = cached_trace {
+        return Ok(TraceBlockTransactionsResult { transaction_hash, trace_root: trace });
+    }
+
     if block.info.protocol_version() < &EXECUTION_UNSUPPORTED_BELOW_VERSION {
         return Err(StarknetRpcApiError::UnsupportedTxnVersion);
     }
 
-    let exec_context = ExecutionContext::new_at_block_start(Arc::clone(&starknet.backend), &block.info)?;
+    let exec_context = starknet.exec_context_at_block_start(&block.info)?;
 
     let mut block_txs =
         Iterator::zip(block.inner.transactions.into_iter(), block.info.tx_hashes()).map(|(tx, hash)| {
@@ -51,5 +57,64 @@ pub async fn trace_transaction(
     let trace = execution_result_to_tx_trace(&execution_result)
         .or_internal_server_error("Converting execution infos to tx trace")?;
 
+    if let Some(block_hash) = sealed_block_hash {
+        starknet.execution_cache.insert_trace(block_hash, transaction_hash, trace.clone());
+    }
+
     Ok(TraceBlockTransactionsResult { transaction_hash, trace_root: trace })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution_cache::tests::sample_trace;
+    use crate::test_utils::{sample_chain_for_block_getters, SampleChainForBlockGetters};
+    use mp_block::{BlockId, MadaraMaybePendingBlockInfo};
+    use rstest::rstest;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_trace_transaction_cached(sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet)) {
+        let (SampleChainForBlockGetters { block_hashes, tx_hashes, .. }, rpc) = sample_chain_for_block_getters;
+
+        // Sealed transactions are served from the cache without being re-executed.
+        rpc.execution_cache.insert_trace(block_hashes[0], tx_hashes[0], sample_trace(42));
+        assert_eq!(
+            trace_transaction(&rpc, tx_hashes[0]).await,
+            Ok(TraceBlockTransactionsResult { transaction_hash: tx_hashes[0], trace_root: sample_trace(42) })
+        );
+        assert_eq!(
+            trace_transaction(&rpc, tx_hashes[0]).await,
+            Ok(TraceBlockTransactionsResult { transaction_hash: tx_hashes[0], trace_root: sample_trace(42) })
+        );
+
+        // Pending transactions are never served from the cache.
+        rpc.execution_cache.insert_trace(Felt::ZERO, tx_hashes[3], sample_trace(42));
+        assert_ne!(
+            trace_transaction(&rpc, tx_hashes[3]).await,
+            Ok(TraceBlockTransactionsResult { transaction_hash: tx_hashes[3], trace_root: sample_trace(42) })
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_trace_transaction_cache_reorg(
+        sample_chain_for_block_getters: (SampleChainForBlockGetters, Starknet),
+    ) {
+        let (SampleChainForBlockGetters { block_hashes, tx_hashes, .. }, rpc) = sample_chain_for_block_getters;
+        rpc.execution_cache.insert_trace(block_hashes[0], tx_hashes[0], sample_trace(42));
+
+        // Block 0 is replaced by a block with another hash, holding the same transaction.
+        let mut block = rpc.backend.get_block(&BlockId::Number(0)).unwrap().unwrap();
+        let state_diff = rpc.backend.get_block_state_diff(&BlockId::Number(0)).unwrap().unwrap();
+        let MadaraMaybePendingBlockInfo::NotPending(info) = &mut block.info else { unreachable!() };
+        info.block_hash = Felt::from_hex_unchecked("0xdead");
+        rpc.backend.store_block(block, state_diff, vec![], None, None).unwrap();
+
+        // The trace of the transaction in the replaced block is not served anymore.
+        assert_ne!(
+            trace_transaction(&rpc, tx_hashes[0]).await,
+            Ok(TraceBlockTransactionsResult { transaction_hash: tx_hashes[0], trace_root: sample_trace(42) })
+        );
+    }
+}
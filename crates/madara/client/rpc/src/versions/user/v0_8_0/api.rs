@@ -73,7 +73,7 @@ pub trait StarknetReadRpcApi {
     fn spec_version(&self) -> RpcResult<String>;
 
     #[method(name = "getCompiledCasm")]
-    fn get_compiled_casm(&self, class_hash: Felt) -> RpcResult<serde_json::Value>;
+    async fn get_compiled_casm(&self, class_hash: Felt) -> RpcResult<serde_json::Value>;
 
     #[method(name = "getStorageProof")]
     fn get_storage_proof(
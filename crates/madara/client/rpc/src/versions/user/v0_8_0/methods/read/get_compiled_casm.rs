@@ -1,32 +1,105 @@
 use std::str::FromStr;
+use std::sync::Arc;
 
 use mp_block::{BlockId, BlockTag};
+use mp_class::ClassInfo;
 use starknet_types_core::felt::Felt;
 
 use crate::errors::{StarknetRpcApiError, StarknetRpcResult};
 use crate::utils::ResultExt;
 use crate::Starknet;
 
-pub fn get_compiled_casm(starknet: &Starknet, class_hash: Felt) -> StarknetRpcResult<serde_json::Value> {
-    let compiled_class_hash = starknet
+pub async fn get_compiled_casm(starknet: &Starknet, class_hash: Felt) -> StarknetRpcResult<serde_json::Value> {
+    let ClassInfo::Sierra(class_info) = starknet
         .backend
         .get_class_info(&BlockId::Tag(BlockTag::Latest), &class_hash)
         .or_internal_server_error("Error getting contract class info")?
         .ok_or(StarknetRpcApiError::ClassHashNotFound)?
-        .compiled_class_hash()
-        .ok_or(StarknetRpcApiError::ClassHashNotFound)?;
+    else {
+        return Err(StarknetRpcApiError::ClassHashNotFound);
+    };
+    let compiled_class_hash = class_info.compiled_class_hash;
 
+    // The class may have to be compiled, which is too slow for the async runtime.
+    let backend = Arc::clone(&starknet.backend);
+    let info = class_info.clone();
     let compiled_class = starknet
+        .execution_pool
+        .spawn(move || backend.get_or_compile_sierra_compiled(&BlockId::Tag(BlockTag::Latest), &class_hash, &info))
+        .await
+        .or_internal_server_error("Error getting compiled contract class")?;
+
+    let stored_compiler_version = starknet
         .backend
-        .get_sierra_compiled(&BlockId::Tag(BlockTag::Latest), &compiled_class_hash)
-        .or_internal_server_error("Error getting compiled contract class")?
-        .ok_or(StarknetRpcApiError::ClassHashNotFound)?;
+        .get_sierra_compiler_version(&compiled_class_hash)
+        .or_internal_server_error("Error getting compiler version of compiled contract class")?;
+    if let (Some(stored), Ok(current)) = (&stored_compiler_version, class_info.contract_class.casm_compiler_version()) {
+        if stored != current {
+            tracing::warn!(
+                "Class {class_hash:#x} was compiled with compiler version {stored}, but the current compiler version \
+                 for this class is {current}: compiling it again may not give the same result"
+            );
+        }
+    }
 
     // Using `Value::from_str` to deserialize `compiled_class` from a JSON string stored in the database.
     // Since `compiled_class` is stored as a raw JSON string in the DB, we need to parse it into a
     // `serde_json::Value` to work with it as a structured JSON object for serialization.
-    let res = serde_json::Value::from_str(compiled_class.0.as_str())
+    let mut res = serde_json::Value::from_str(compiled_class.0.as_str())
         .or_internal_server_error("Error serializing compiled contract class")?;
 
+    // The compiler version recorded when the class was compiled, so that clients can reproduce the compilation.
+    if let (Some(compiler_version), Some(res)) = (stored_compiler_version, res.as_object_mut()) {
+        res.insert("compiler_version".into(), compiler_version.into());
+    }
+
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{make_sample_chain_with_classes, rpc_test_setup, ClassFixture};
+    use mc_db::MadaraBackend;
+    use mp_class::{
+        CompiledSierra, ConvertedClass, EntryPointsByType, FlattenedSierraClass, SierraClassInfo, SierraConvertedClass,
+    };
+    use rstest::rstest;
+
+    #[tokio::test]
+    #[rstest]
+    async fn test_get_compiled_casm_returns_compiler_version(rpc_test_setup: (Arc<MadaraBackend>, Starknet)) {
+        let (backend, rpc) = rpc_test_setup;
+        let class_hash = Felt::from_hex_unchecked("0x9100000001");
+        let contract_class = FlattenedSierraClass {
+            // Sierra version 1.6.0
+            sierra_program: vec![Felt::from(1), Felt::from(6), Felt::from(0)],
+            contract_class_version: "0.1.0".into(),
+            entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+            abi: "[]".into(),
+        };
+        let compiler_version = contract_class.casm_compiler_version().unwrap();
+        let class = ClassFixture {
+            name: "sierra 1.6.0",
+            class_hash,
+            converted_class: ConvertedClass::Sierra(SierraConvertedClass {
+                class_hash,
+                info: SierraClassInfo {
+                    contract_class: Arc::new(contract_class),
+                    compiled_class_hash: Felt::from_hex_unchecked("0x9100000002"),
+                },
+                compiled: Some(Arc::new(CompiledSierra(r#"{"compiler_version":"0.0.0","bytecode":[]}"#.into()))),
+            }),
+        };
+        make_sample_chain_with_classes(&backend, &[class]);
+
+        assert_eq!(
+            get_compiled_casm(&rpc, class_hash).await.unwrap(),
+            serde_json::json!({ "compiler_version": compiler_version, "bytecode": [] })
+        );
+        assert_eq!(
+            get_compiled_casm(&rpc, Felt::from_hex_unchecked("0x9100000003")).await,
+            Err(StarknetRpcApiError::ClassHashNotFound)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     errors::{StarknetRpcApiError, StorageProofLimit, StorageProofTrie},
     utils::ResultExt,
+    versions::user::v0_7_1::methods::read::get_nonce::get_nonce_unchecked,
     versions::user::v0_8_0::{
         ContractLeavesDataItem, ContractStorageKeysItem, ContractsProof, GetStorageProofResult, GlobalRoots,
         MerkleNode, NodeHashToNodeMappingItem,
@@ -155,11 +156,7 @@ pub fn get_storage_proof(
         .iter()
         .map(|contract_addr| {
             Ok(ContractLeavesDataItem {
-                nonce: starknet
-                    .backend
-                    .get_contract_nonce_at(&DbBlockId::Number(block_n), contract_addr)
-                    .or_internal_server_error("Getting contract nonce")?
-                    .unwrap_or(Felt::ZERO),
+                nonce: get_nonce_unchecked(starknet, &DbBlockId::Number(block_n), *contract_addr)?,
                 class_hash: starknet
                     .backend
                     .get_contract_class_hash_at(&DbBlockId::Number(block_n), contract_addr)
@@ -14,8 +14,8 @@ impl StarknetReadRpcApiV0_8_0Server for Starknet {
         Ok(RpcVersion::RPC_VERSION_0_8_0.to_string())
     }
 
-    fn get_compiled_casm(&self, class_hash: Felt) -> RpcResult<serde_json::Value> {
-        Ok(get_compiled_casm::get_compiled_casm(self, class_hash)?)
+    async fn get_compiled_casm(&self, class_hash: Felt) -> RpcResult<serde_json::Value> {
+        Ok(get_compiled_casm::get_compiled_casm(self, class_hash).await?)
     }
 
     fn get_storage_proof(
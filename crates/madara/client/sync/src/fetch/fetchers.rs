@@ -8,6 +8,7 @@ use mc_block_import::{UnverifiedCommitments, UnverifiedFullBlock, UnverifiedPend
 use mc_gateway_client::GatewayProvider;
 use mp_block::{BlockId, BlockTag};
 use mp_class::class_update::{ClassUpdate, LegacyClassUpdate, SierraClassUpdate};
+use mp_class::raw_class::RawContractClass;
 use mp_class::{ContractClass, MISSED_CLASS_HASHES};
 use mp_gateway::block::{ProviderBlock, ProviderBlockPending};
 use mp_gateway::error::{SequencerError, StarknetError, StarknetErrorCode};
@@ -49,6 +50,14 @@ pub struct FetchConfig {
     pub stop_on_sync: bool,
     /// Number of blocks to fetch in parallel during the sync process
     pub sync_parallelism: u8,
+    /// Defer the compilation of Sierra classes to CASM to their first use
+    pub lazy_casm_compilation: bool,
+    /// Validate the blocks without writing them to the database
+    pub dry_run: bool,
+    /// Reject the legacy classes whose class hash does not match their definition
+    pub reject_legacy_class_hash_mismatches: bool,
+    /// Legacy classes imported even though their class hash does not match their definition
+    pub allowed_legacy_class_hash_mismatches: Vec<Felt>,
     /// Warp update configuration
     pub warp_update: Option<WarpUpdateConfig>,
 }
@@ -208,7 +217,7 @@ async fn fetch_class_updates(
     let legacy_class_futures = legacy_classes.into_iter().map(|class_hash| {
         let block_id = block_id.clone();
         async move {
-            let (class_hash, contract_class) =
+            let (class_hash, RawContractClass { class: contract_class, unknown_fields }) =
                 retry(|| fetch_class(class_hash, block_id.clone(), provider), MAX_RETRY, BASE_DELAY).await?;
 
             let ContractClass::Legacy(contract_class) = contract_class else {
@@ -217,7 +226,7 @@ async fn fetch_class_updates(
             let contract_class = Arc::try_unwrap(contract_class)
                 .expect("Contract class should only have one referenced when it is fetched");
 
-            Ok::<_, L2SyncError>(ClassUpdate::Legacy(LegacyClassUpdate { class_hash, contract_class }))
+            Ok::<_, L2SyncError>(ClassUpdate::Legacy(LegacyClassUpdate { class_hash, contract_class, unknown_fields }))
         }
         .boxed()
     });
@@ -225,7 +234,7 @@ async fn fetch_class_updates(
     let sierra_class_futures = sierra_classes.into_iter().map(|(class_hash, &compiled_class_hash)| {
         let block_id = block_id.clone();
         async move {
-            let (class_hash, contract_class) =
+            let (class_hash, RawContractClass { class: contract_class, unknown_fields }) =
                 retry(|| fetch_class(class_hash, block_id.clone(), provider), MAX_RETRY, BASE_DELAY).await?;
 
             let ContractClass::Sierra(contract_class) = contract_class else {
@@ -238,6 +247,7 @@ async fn fetch_class_updates(
                 class_hash,
                 contract_class,
                 compiled_class_hash,
+                unknown_fields,
             }))
         }
         .boxed()
@@ -252,8 +262,8 @@ async fn fetch_class(
     class_hash: Felt,
     block_id: BlockId,
     provider: &GatewayProvider,
-) -> Result<(Felt, ContractClass), SequencerError> {
-    let contract_class = provider.get_class_by_hash(class_hash, block_id).await?;
+) -> Result<(Felt, RawContractClass), SequencerError> {
+    let contract_class = provider.get_class_by_hash_raw(class_hash, block_id).await?;
     tracing::debug!("Got the contract class {:?}", class_hash);
     Ok((class_hash, contract_class))
 }
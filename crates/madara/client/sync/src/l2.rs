@@ -81,6 +81,10 @@ async fn l2_verify_and_apply_task(
 
     while let Some(Some(block)) = ctx.run_until_cancelled(pin!(block_conv_receiver.recv())).await {
         let BlockImportResult { header, block_hash } = block_import.verify_apply(block, validation.clone()).await?;
+        if validation.dry_run {
+            tracing::info!("✨ Validated #{} ({}) without storing it", header.block_number, trim_hash(&block_hash));
+            continue;
+        }
 
         if header.block_number - last_block_n >= flush_every_n_blocks || instant.elapsed() >= target_duration {
             last_block_n = header.block_number;
@@ -236,6 +240,10 @@ pub struct L2SyncConfig {
     pub flush_every_n_seconds: u64,
     pub pending_block_poll_interval: Duration,
     pub ignore_block_order: bool,
+    pub lazy_casm_compilation: bool,
+    pub dry_run: bool,
+    pub reject_legacy_class_hash_mismatches: bool,
+    pub allowed_legacy_class_hash_mismatches: Vec<Felt>,
     pub chain_id: ChainId,
     pub telemetry: Arc<TelemetryHandle>,
     pub block_importer: Arc<BlockImporter>,
@@ -269,7 +277,12 @@ pub async fn sync(
         trust_global_tries: !config.verify,
         chain_id: config.chain_id,
         trust_class_hashes: false,
-        ignore_block_order: config.ignore_block_order,
+        // Blocks are not stored in a dry run, so every block after the first one is ahead of the tip.
+        ignore_block_order: config.ignore_block_order || config.dry_run,
+        lazy_casm_compilation: config.lazy_casm_compilation,
+        dry_run: config.dry_run,
+        reject_legacy_class_hash_mismatches: config.reject_legacy_class_hash_mismatches,
+        allowed_legacy_class_hash_mismatches: config.allowed_legacy_class_hash_mismatches,
     };
 
     let mut join_set = JoinSet::new();
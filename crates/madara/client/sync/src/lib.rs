@@ -66,6 +66,10 @@ pub async fn l2_sync_worker(
         flush_every_n_seconds: fetch_config.flush_every_n_seconds,
         pending_block_poll_interval: sync_config.pending_block_poll_interval,
         ignore_block_order,
+        lazy_casm_compilation: fetch_config.lazy_casm_compilation,
+        dry_run: fetch_config.dry_run,
+        reject_legacy_class_hash_mismatches: fetch_config.reject_legacy_class_hash_mismatches,
+        allowed_legacy_class_hash_mismatches: fetch_config.allowed_legacy_class_hash_mismatches,
         sync_parallelism: fetch_config.sync_parallelism,
         chain_id: backend.chain_config().chain_id.clone(),
         telemetry: sync_config.telemetry,
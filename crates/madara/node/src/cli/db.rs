@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use mc_db::{class_cache::DEFAULT_CASM_CACHE_SIZE, ClassStorageFormat};
+
 #[derive(Clone, Debug, clap::Args)]
 pub struct DbParams {
     /// The path where madara will store the database. You should probably change it.
@@ -34,4 +36,73 @@ pub struct DbParams {
     /// See `--db-max-kept-snapshots` to understand what snapshots are used for.
     #[clap(env = "MADARA_DB_SNAPSHOT_INTERVAL", long, default_value_t = 5)]
     pub db_snapshot_interval: u64,
+
+    /// Format of the class definitions stored in the database: `bincode`, or `json` for tooling outside of Rust
+    /// which reads the database directly. Classes which are already stored are not converted, and can still be
+    /// read after changing this.
+    #[clap(env = "MADARA_DB_CLASS_STORAGE_FORMAT", long, default_value_t = ClassStorageFormat::Bincode, value_name = "FORMAT")]
+    pub db_class_storage_format: ClassStorageFormat,
+
+    /// Store identical class definitions only once. Classes which are already stored are not deduplicated.
+    #[clap(env = "MADARA_DB_DEDUP_CLASSES", long)]
+    pub db_dedup_classes: bool,
+
+    /// Class definitions smaller than this, in bytes, are stored uncompressed, and larger ones are gzip-compressed
+    /// one by one. Compressing small classes costs more CPU than it saves space. 0 leaves the compression of every
+    /// class to the database.
+    #[clap(env = "MADARA_DB_CLASS_COMPRESSION_THRESHOLD", long, default_value_t = 0, value_name = "BYTES")]
+    pub db_class_compression_threshold: usize,
+
+    /// Directory of the class archive, used to serve the class definitions which are not in the database, for
+    /// example on a pruned node. It holds one file per class, named after its class hash.
+    #[clap(env = "MADARA_DB_CLASS_ARCHIVE_DIR", long, value_name = "PATH")]
+    pub db_class_archive_dir: Option<PathBuf>,
+
+    /// Number of the classes read the most before the node was last stopped to load into the class cache on
+    /// startup. Set this to 0 to disable warming the class cache.
+    #[clap(env = "MADARA_DB_WARM_HOT_CLASSES", long, default_value_t = 64, value_name = "N")]
+    pub db_warm_hot_classes: usize,
+
+    /// Size in bytes of the cache of the Sierra classes compiled when receiving declare transactions, so that the
+    /// same class is not compiled again when it is declared or simulated again. Set this to 0 to disable the cache.
+    #[clap(env = "MADARA_DB_CASM_CACHE_SIZE", long, default_value_t = DEFAULT_CASM_CACHE_SIZE, value_name = "BYTES")]
+    pub db_casm_cache_size: usize,
+
+    /// Keep an in-memory bloom filter of the storage keys ever written to, sized for this many keys, so that reads
+    /// of storage slots which were never set return right away. There is a single filter for every block rather than
+    /// one per block. It is populated from the database in the background on startup, which is slow on a big
+    /// database; storage reads go to the database as usual until then.
+    #[clap(env = "MADARA_DB_STORAGE_BLOOM_FILTER_ITEMS", long, value_name = "N")]
+    pub db_storage_bloom_filter_items: Option<usize>,
+
+    /// False positive rate of the storage bloom filter, see `--db-storage-bloom-filter-items`.
+    #[clap(
+        env = "MADARA_DB_STORAGE_BLOOM_FILTER_FP_RATE",
+        long,
+        default_value_t = 0.01,
+        value_name = "RATE",
+        requires = "db_storage_bloom_filter_items"
+    )]
+    pub db_storage_bloom_filter_fp_rate: f64,
+
+    /// Check on startup that the sync tip does not point past the blocks stored in the database, which can happen
+    /// when the node is stopped while storing a block: the classes and contract updates of the last blocks are
+    /// checked. See also `--db-check-sync-tip-depth` and `--db-repair-sync-tip`.
+    #[clap(env = "MADARA_DB_CHECK_SYNC_TIP", long)]
+    pub db_check_sync_tip: bool,
+
+    /// Number of blocks up to the sync tip checked by `--db-check-sync-tip`.
+    #[clap(
+        env = "MADARA_DB_CHECK_SYNC_TIP_DEPTH",
+        long,
+        default_value_t = 1,
+        value_name = "N",
+        requires = "db_check_sync_tip"
+    )]
+    pub db_check_sync_tip_depth: u64,
+
+    /// When the sync tip is ahead of the blocks stored, set it back to the last block fully stored so that the
+    /// missing blocks are synced again.
+    #[clap(env = "MADARA_DB_REPAIR_SYNC_TIP", long, requires = "db_check_sync_tip")]
+    pub db_repair_sync_tip: bool,
 }
@@ -5,7 +5,8 @@ use mp_chain_config::ChainConfig;
 use starknet_api::core::ChainId;
 
 use mc_sync::fetch::fetchers::FetchConfig;
-use mp_utils::parsers::{parse_duration, parse_url};
+use mp_utils::parsers::{parse_duration, parse_felt, parse_url};
+use starknet_types_core::felt::Felt;
 use url::Url;
 
 use super::FGW_DEFAULT_PORT;
@@ -144,6 +145,55 @@ pub struct L2SyncParams {
         value_parser = clap::value_parser!(u8).range(1..)
     )]
     pub sync_parallelism: u8,
+
+    /// Do not compile Sierra classes to CASM while syncing. Classes are compiled
+    /// the first time they are needed (execution, `getCompiledCasm`...) and the
+    /// result is then stored in the database. This speeds up sync at the cost of
+    /// a slower first use of each class.
+    #[clap(env = "MADARA_LAZY_CASM_COMPILATION", long, default_value_t = false)]
+    pub lazy_casm_compilation: bool,
+
+    /// Validate the synced blocks without writing anything to the database,
+    /// to debug sync. Every block is validated on top of the current tip of
+    /// the database, with the parent hash it comes with: the global state root
+    /// is not recomputed, and the block hash is only checked against the
+    /// global state root given by the feeder gateway.
+    #[clap(env = "MADARA_SYNC_DRY_RUN", long, default_value_t = false)]
+    pub sync_dry_run: bool,
+
+    /// When `--lazy-casm-compilation` is set, compile the synced Sierra classes
+    /// in the background instead of on their first use. This is the maximum
+    /// number of classes waiting for compilation: sync slows down when the
+    /// compiler falls behind. Set to 0 to disable background compilation.
+    #[clap(env = "MADARA_CASM_COMPILE_QUEUE_SIZE", long, value_name = "SIZE", default_value_t = 0)]
+    pub casm_compile_queue_size: usize,
+
+    /// Stop sync on a legacy class whose class hash does not match the hash
+    /// computed from its definition. The class hash of some legacy classes of
+    /// the early networks cannot be recomputed, so by default mismatches are
+    /// only logged.
+    #[clap(env = "MADARA_REJECT_LEGACY_CLASS_HASH_MISMATCH", long, default_value_t = false)]
+    pub reject_legacy_class_hash_mismatch: bool,
+
+    /// Class hash of a legacy class to import even though it does not match
+    /// the hash computed from its definition, when
+    /// `--reject-legacy-class-hash-mismatch` is set. Can be repeated.
+    #[clap(
+        env = "MADARA_ALLOW_LEGACY_CLASS_HASH_MISMATCH",
+        long,
+        value_name = "CLASS_HASH",
+        value_parser = parse_felt,
+        value_delimiter = ','
+    )]
+    pub allow_legacy_class_hash_mismatch: Vec<Felt>,
+
+    /// Maximum number of classes being decompressed at the same time when
+    /// validating the synced blocks. Blocks declaring classes over the limit
+    /// wait for the previous ones, which bounds the memory used by sync. This
+    /// is separate from `--rpc-max-concurrent-class-decompressions`. Set to 0
+    /// to disable.
+    #[clap(env = "MADARA_SYNC_MAX_CONCURRENT_CLASS_DECOMPRESSIONS", long, value_name = "MAX", default_value_t = 0)]
+    pub sync_max_concurrent_class_decompressions: usize,
 }
 
 impl L2SyncParams {
@@ -175,6 +225,10 @@ impl L2SyncParams {
             flush_every_n_seconds: self.flush_every_n_seconds,
             stop_on_sync: self.stop_on_sync,
             sync_parallelism: self.sync_parallelism,
+            lazy_casm_compilation: self.lazy_casm_compilation,
+            dry_run: self.sync_dry_run,
+            reject_legacy_class_hash_mismatches: self.reject_legacy_class_hash_mismatch,
+            allowed_legacy_class_hash_mismatches: self.allow_legacy_class_hash_mismatch.clone(),
             warp_update,
         }
     }
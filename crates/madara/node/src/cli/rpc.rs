@@ -4,6 +4,7 @@ use std::str::FromStr;
 
 use jsonrpsee::server::BatchRequestConfig;
 use mc_rpc::StorageProofConfig;
+use url::Url;
 
 /// The default port.
 pub const RPC_DEFAULT_PORT: u16 = 9944;
@@ -156,6 +157,42 @@ pub struct RpcParams {
     /// storage is queried count as one each.
     #[arg(env = "MADARA_RPC_STORAGE_PROOF_MAX_TRIES", long, default_value_t = 5)]
     pub rpc_storage_proof_max_tries: usize,
+
+    /// How many execution traces of already-sealed transactions are kept in memory, so that tracing the
+    /// same historical transaction again does not re-execute it. Set to 0 to disable the cache. Default: 1024.
+    #[arg(env = "MADARA_RPC_EXECUTION_CACHE_SIZE", long, default_value_t = 1024)]
+    pub rpc_execution_cache_size: usize,
+
+    /// Number of threads used to run the blockifier executions of `call`, `estimateFee` and
+    /// `simulateTransactions`, separately from the threads serving the other RPC requests and from block import.
+    /// Default: 4.
+    #[arg(env = "MADARA_RPC_EXECUTION_THREADS", long, default_value_t = 4)]
+    pub rpc_execution_threads: usize,
+
+    /// Maximum number of RPC executions decompressing class definitions at the same time, for `call`,
+    /// `estimateFee` and `simulateTransactions`. Requests over the limit wait for an execution to finish, which
+    /// bounds the memory used under load. This limit only applies to the RPC, see
+    /// `--sync-max-concurrent-class-decompressions` for sync. Set to 0 to disable. Default: 0.
+    #[arg(env = "MADARA_RPC_MAX_CONCURRENT_CLASS_DECOMPRESSIONS", long, default_value_t = 0)]
+    pub rpc_max_concurrent_class_decompressions: usize,
+
+    /// Maximum size in bytes of the class definitions served by `starknet_getClass` and `starknet_getClassAt`.
+    /// Larger classes are rejected with an error giving their size, and can be fetched from the feeder gateway
+    /// instead. Set to 0 to disable. Default: 0.
+    #[arg(env = "MADARA_RPC_MAX_SERVED_CLASS_SIZE", long, value_name = "BYTES", default_value_t = 0)]
+    pub rpc_max_served_class_size: usize,
+
+    /// JSON-RPC endpoint of a node, such as an archive node, which is queried for the contract storage, nonces
+    /// and class hashes missing from the local database when executing transactions on top of a block below
+    /// `--rpc-remote-state-pruned-below`. This allows tracing and simulating on top of blocks whose state has been
+    /// pruned locally. Disabled by default.
+    #[arg(env = "MADARA_RPC_REMOTE_STATE_URL", long, value_name = "URL", requires = "rpc_remote_state_pruned_below")]
+    pub rpc_remote_state_url: Option<Url>,
+
+    /// Block number below which the local state may have been pruned. Only executions on top of an earlier block
+    /// query the remote state node: above it, a value missing locally has never been written.
+    #[arg(env = "MADARA_RPC_REMOTE_STATE_PRUNED_BELOW", long, value_name = "BLOCK_N")]
+    pub rpc_remote_state_pruned_below: Option<u64>,
 }
 
 impl RpcParams {
@@ -11,10 +11,11 @@ use cli::RunCmd;
 use http::{HeaderName, HeaderValue};
 use mc_analytics::Analytics;
 use mc_block_import::BlockImporter;
-use mc_db::{DatabaseService, TrieLogConfig};
+use mc_db::{DatabaseService, DirClassArchive, TrieLogConfig};
 use mc_gateway_client::GatewayProvider;
 use mc_mempool::{GasPriceProvider, L1DataProvider, Mempool, MempoolLimits};
 use mc_rpc::providers::{AddTransactionProvider, ForwardToProvider, MempoolAddTxProvider};
+use mc_rpc::ExecutionPool;
 use mc_sync::fetch::fetchers::WarpUpdateConfig;
 use mc_telemetry::{SysInfo, TelemetryService};
 use mp_oracle::pragma::PragmaOracleBuilder;
@@ -112,6 +113,49 @@ async fn main() -> anyhow::Result<()> {
     )
     .await
     .context("Initializing db service")?;
+    service_db
+        .backend()
+        .set_class_storage_format(run_cmd.db_params.db_class_storage_format)
+        .context("Setting the class storage format")?;
+    service_db
+        .backend()
+        .set_dedup_classes(run_cmd.db_params.db_dedup_classes)
+        .context("Setting class deduplication")?;
+    service_db
+        .backend()
+        .set_class_compression_threshold(run_cmd.db_params.db_class_compression_threshold)
+        .context("Setting the class compression threshold")?;
+    service_db
+        .backend()
+        .set_casm_cache_size(run_cmd.db_params.db_casm_cache_size)
+        .context("Setting the CASM cache size")?;
+    if let Some(expected_items) = run_cmd.db_params.db_storage_bloom_filter_items {
+        let backend = Arc::clone(service_db.backend());
+        let false_positive_rate = run_cmd.db_params.db_storage_bloom_filter_fp_rate;
+        tokio::task::spawn_blocking(move || {
+            if let Err(err) = backend.enable_storage_bloom_filter(expected_items, false_positive_rate) {
+                tracing::error!("Failed to enable the storage bloom filter: {err:#}");
+            }
+        });
+    }
+    if let Some(class_archive_dir) = &run_cmd.db_params.db_class_archive_dir {
+        service_db
+            .backend()
+            .set_class_archive(Arc::new(DirClassArchive::new(class_archive_dir)))
+            .context("Setting the class archive")?;
+    }
+    if run_cmd.db_params.db_check_sync_tip {
+        service_db
+            .backend()
+            .check_sync_tip(run_cmd.db_params.db_check_sync_tip_depth, run_cmd.db_params.db_repair_sync_tip)
+            .context("Checking the sync tip")?;
+    }
+    if run_cmd.db_params.db_warm_hot_classes > 0 {
+        service_db
+            .backend()
+            .warm_class_cache_with_hot_classes(run_cmd.db_params.db_warm_hot_classes)
+            .context("Warming the class cache")?;
+    }
 
     // L1 Sync
 
@@ -178,10 +222,14 @@ async fn main() -> anyhow::Result<()> {
 
     // L2 Sync
 
-    let importer = Arc::new(
+    let mut importer =
         BlockImporter::new(Arc::clone(service_db.backend()), run_cmd.l2_sync_params.unsafe_starting_block)
-            .context("Initializing importer service")?,
-    );
+            .context("Initializing importer service")?
+            .with_class_decompression_limit(run_cmd.l2_sync_params.sync_max_concurrent_class_decompressions);
+    if run_cmd.l2_sync_params.lazy_casm_compilation && run_cmd.l2_sync_params.casm_compile_queue_size > 0 {
+        importer = importer.with_casm_compile_queue(run_cmd.l2_sync_params.casm_compile_queue_size)?;
+    }
+    let importer = Arc::new(importer);
 
     let warp_update = if run_cmd.args_preset.warp_update_receiver {
         let mut deferred_service_start = vec![];
@@ -261,11 +309,17 @@ async fn main() -> anyhow::Result<()> {
 
     // User-facing RPC
 
+    // Shared by the RPC servers and the feeder gateway.
+    let execution_pool = Arc::new(
+        ExecutionPool::new(run_cmd.rpc_params.rpc_execution_threads).context("Initializing execution thread pool")?,
+    );
+
     let service_rpc_user = RpcService::user(
         run_cmd.rpc_params.clone(),
         Arc::clone(service_db.backend()),
         Arc::clone(&add_tx_provider_l2_sync),
         Arc::clone(&add_tx_provider_mempool),
+        Arc::clone(&execution_pool),
     );
 
     // Admin-facing RPC (for node operators)
@@ -275,6 +329,7 @@ async fn main() -> anyhow::Result<()> {
         Arc::clone(service_db.backend()),
         Arc::clone(&add_tx_provider_l2_sync),
         Arc::clone(&add_tx_provider_mempool),
+        Arc::clone(&execution_pool),
     );
 
     // Feeder gateway
@@ -284,6 +339,7 @@ async fn main() -> anyhow::Result<()> {
         Arc::clone(service_db.backend()),
         Arc::clone(&add_tx_provider_l2_sync),
         Arc::clone(&add_tx_provider_mempool),
+        execution_pool,
     )
     .await
     .context("Initializing gateway service")?;
@@ -1,6 +1,7 @@
 use crate::cli::GatewayParams;
 use mc_db::MadaraBackend;
 use mc_rpc::providers::{AddTransactionProvider, AddTransactionProviderGroup};
+use mc_rpc::ExecutionPool;
 use mp_utils::service::{MadaraServiceId, PowerOfTwo, Service, ServiceId, ServiceRunner};
 use std::sync::Arc;
 
@@ -10,6 +11,7 @@ pub struct GatewayService {
     db_backend: Arc<MadaraBackend>,
     add_txs_provider_l2_sync: Arc<dyn AddTransactionProvider>,
     add_txs_provider_mempool: Arc<dyn AddTransactionProvider>,
+    execution_pool: Arc<ExecutionPool>,
 }
 
 impl GatewayService {
@@ -18,15 +20,17 @@ impl GatewayService {
         db_backend: Arc<MadaraBackend>,
         add_txs_provider_l2_sync: Arc<dyn AddTransactionProvider>,
         add_txs_provider_mempool: Arc<dyn AddTransactionProvider>,
+        execution_pool: Arc<ExecutionPool>,
     ) -> anyhow::Result<Self> {
-        Ok(Self { config, db_backend, add_txs_provider_l2_sync, add_txs_provider_mempool })
+        Ok(Self { config, db_backend, add_txs_provider_l2_sync, add_txs_provider_mempool, execution_pool })
     }
 }
 
 #[async_trait::async_trait]
 impl Service for GatewayService {
     async fn start<'a>(&mut self, runner: ServiceRunner<'a>) -> anyhow::Result<()> {
-        let GatewayService { config, db_backend, add_txs_provider_l2_sync, add_txs_provider_mempool } = self.clone();
+        let GatewayService { config, db_backend, add_txs_provider_l2_sync, add_txs_provider_mempool, execution_pool } =
+            self.clone();
 
         runner.service_loop(move |ctx| {
             let add_tx_provider = Arc::new(AddTransactionProviderGroup::new(
@@ -38,6 +42,7 @@ impl Service for GatewayService {
             mc_gateway_server::service::start_server(
                 db_backend,
                 add_tx_provider,
+                execution_pool,
                 config.feeder_gateway_enable,
                 config.gateway_enable,
                 config.gateway_external,
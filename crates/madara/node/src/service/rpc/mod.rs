@@ -3,9 +3,10 @@ use std::sync::Arc;
 use jsonrpsee::server::ServerHandle;
 
 use mc_db::MadaraBackend;
+use mc_exec::JsonRpcRemoteStateReader;
 use mc_rpc::{
     providers::{AddTransactionProvider, AddTransactionProviderGroup},
-    rpc_api_admin, rpc_api_user, Starknet,
+    rpc_api_admin, rpc_api_user, ExecutionPool, Starknet,
 };
 use mp_utils::service::{MadaraServiceId, PowerOfTwo, Service, ServiceId, ServiceRunner};
 
@@ -31,6 +32,7 @@ pub struct RpcService {
     backend: Arc<MadaraBackend>,
     add_txs_provider_l2_sync: Arc<dyn AddTransactionProvider>,
     add_txs_provider_mempool: Arc<dyn AddTransactionProvider>,
+    execution_pool: Arc<ExecutionPool>,
     server_handle: Option<ServerHandle>,
     rpc_type: RpcType,
 }
@@ -41,12 +43,14 @@ impl RpcService {
         backend: Arc<MadaraBackend>,
         add_txs_provider_l2_sync: Arc<dyn AddTransactionProvider>,
         add_txs_provider_mempool: Arc<dyn AddTransactionProvider>,
+        execution_pool: Arc<ExecutionPool>,
     ) -> Self {
         Self {
             config,
             backend,
             add_txs_provider_l2_sync,
             add_txs_provider_mempool,
+            execution_pool,
             server_handle: None,
             rpc_type: RpcType::User,
         }
@@ -57,12 +61,14 @@ impl RpcService {
         backend: Arc<MadaraBackend>,
         add_txs_provider_l2_sync: Arc<dyn AddTransactionProvider>,
         add_txs_provider_mempool: Arc<dyn AddTransactionProvider>,
+        execution_pool: Arc<ExecutionPool>,
     ) -> Self {
         Self {
             config,
             backend,
             add_txs_provider_l2_sync,
             add_txs_provider_mempool,
+            execution_pool,
             server_handle: None,
             rpc_type: RpcType::Admin,
         }
@@ -76,6 +82,7 @@ impl Service for RpcService {
         let backend = Arc::clone(&self.backend);
         let add_tx_provider_l2_sync = Arc::clone(&self.add_txs_provider_l2_sync);
         let add_tx_provider_mempool = Arc::clone(&self.add_txs_provider_mempool);
+        let execution_pool = Arc::clone(&self.execution_pool);
         let rpc_type = self.rpc_type.clone();
 
         let (stop_handle, server_handle) = jsonrpsee::server::stop_channel();
@@ -89,7 +96,21 @@ impl Service for RpcService {
                 ctx.clone(),
             ));
 
-            let starknet = Starknet::new(backend.clone(), add_tx_provider, config.storage_proof_config(), ctx.clone());
+            let starknet = Starknet::new(
+                backend.clone(),
+                add_tx_provider,
+                config.storage_proof_config(),
+                execution_pool,
+                ctx.clone(),
+            )
+            .with_execution_cache_capacity(config.rpc_execution_cache_size)
+            .with_max_served_class_size(config.rpc_max_served_class_size)
+            .with_class_decompression_limit(config.rpc_max_concurrent_class_decompressions);
+            let starknet = match (&config.rpc_remote_state_url, config.rpc_remote_state_pruned_below) {
+                (Some(url), Some(pruned_below)) => starknet
+                    .with_remote_state_reader(Arc::new(JsonRpcRemoteStateReader::new(url.clone(), pruned_below))),
+                _ => starknet,
+            };
             let metrics = RpcMetrics::register()?;
 
             let server_config = {
@@ -0,0 +1,43 @@
+//! Benchmarks of the conversion of a large Sierra class to a blockifier class, going through the JSON encoding of the
+//! compiled class or building the blockifier class from the in-memory compiled class.
+//!
+//! ```sh
+//! cargo bench -p mp-class --bench compile_to_blockifier
+//! ```
+//!
+//! `cargo test -p mp-class --benches` runs every benchmark once, as a smoke test.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use mp_class::FlattenedSierraClass;
+use std::hint::black_box;
+use std::time::Duration;
+
+fn sierra_class() -> FlattenedSierraClass {
+    let class: starknet_core::types::contract::SierraClass = serde_json::from_slice(include_bytes!(
+        "../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+    ))
+    .unwrap();
+    class.flatten().unwrap().into()
+}
+
+fn bench_compile_to_blockifier(c: &mut Criterion) {
+    let sierra = sierra_class();
+    let mut group = c.benchmark_group("compile_to_blockifier");
+
+    group.bench_function("json_round_trip", |b| {
+        b.iter(|| {
+            let (compiled_class_hash, compiled) = sierra.compile_to_casm().unwrap();
+            black_box((compiled_class_hash, compiled.to_blockifier_class().unwrap()))
+        })
+    });
+    group.bench_function("in_memory", |b| b.iter(|| black_box(sierra.compile_to_casm_and_blockifier().unwrap())));
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20).measurement_time(Duration::from_secs(20));
+    targets = bench_compile_to_blockifier
+}
+criterion_main!(benches);
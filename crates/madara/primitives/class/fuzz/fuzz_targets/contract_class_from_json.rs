@@ -0,0 +1,18 @@
+//! Feeds arbitrary JSON to the contract class parser used for declare transactions. Malformed classes
+//! must be rejected with an error, never with a panic.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mp_class::ContractClass;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(value) = serde_json::from_slice::<serde_json::Value>(data) else { return };
+    let Ok(class) = ContractClass::try_from(value) else { return };
+
+    // These decompress and walk the whole class, which is where most of the untrusted input handling is.
+    let _ = class.compute_class_hash();
+    if let ContractClass::Legacy(legacy) = &class {
+        let _ = legacy.serialize_to_json();
+    }
+});
@@ -0,0 +1,176 @@
+//! Builder for hand-made legacy classes, mostly useful in tests and tooling.
+
+use crate::{
+    CompressedLegacyContractClass, LegacyContractAbiEntry, LegacyContractEntryPoint, LegacyEntryPointsByType,
+    LegacyFunctionAbiType, RECONSTRUCTED_ABI_ENTRY_PREFIX,
+};
+use starknet_types_core::felt::Felt;
+use std::collections::HashSet;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum LegacyClassBuildError {
+    #[error("{entry_point_type:?} entry point {selector:#x} has no matching function in the ABI")]
+    MissingAbiFunction { entry_point_type: LegacyFunctionAbiType, selector: Felt },
+}
+
+/// Builds a [`CompressedLegacyContractClass`], checking that every entry point has a function of the same type in
+/// the ABI. A class built without an ABI is not checked.
+///
+/// The default entry points (`__default__` and `__l1_default__`) have the zero selector, which does not match any
+/// function name, and are always accepted.
+#[derive(Debug, Default)]
+pub struct LegacyClassBuilder {
+    program: Vec<u8>,
+    entry_points_by_type: LegacyEntryPointsByType,
+    abi: Option<Vec<LegacyContractAbiEntry>>,
+}
+
+impl LegacyClassBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The gzip-compressed program.
+    pub fn with_program(mut self, program: Vec<u8>) -> Self {
+        self.program = program;
+        self
+    }
+
+    pub fn with_constructor(mut self, entry_point: LegacyContractEntryPoint) -> Self {
+        self.entry_points_by_type.constructor.push(entry_point);
+        self
+    }
+
+    pub fn with_external(mut self, entry_point: LegacyContractEntryPoint) -> Self {
+        self.entry_points_by_type.external.push(entry_point);
+        self
+    }
+
+    pub fn with_l1_handler(mut self, entry_point: LegacyContractEntryPoint) -> Self {
+        self.entry_points_by_type.l1_handler.push(entry_point);
+        self
+    }
+
+    pub fn with_abi(mut self, abi: Vec<LegacyContractAbiEntry>) -> Self {
+        self.abi = Some(abi);
+        self
+    }
+
+    pub fn build(self) -> Result<CompressedLegacyContractClass, LegacyClassBuildError> {
+        if let Some(abi) = &self.abi {
+            let functions: HashSet<_> = abi
+                .iter()
+                .filter_map(|entry| match entry {
+                    LegacyContractAbiEntry::Function(function) => {
+                        Some((function.r#type.clone(), abi_function_selector(&function.name)?))
+                    }
+                    _ => None,
+                })
+                .collect();
+
+            let entry_points = &self.entry_points_by_type;
+            for (entry_point_type, entry_points) in [
+                (LegacyFunctionAbiType::Constructor, &entry_points.constructor),
+                (LegacyFunctionAbiType::Function, &entry_points.external),
+                (LegacyFunctionAbiType::L1Handler, &entry_points.l1_handler),
+            ] {
+                for entry_point in entry_points {
+                    let selector = entry_point.selector;
+                    if selector != Felt::ZERO && !functions.contains(&(entry_point_type.clone(), selector)) {
+                        return Err(LegacyClassBuildError::MissingAbiFunction { entry_point_type, selector });
+                    }
+                }
+            }
+        }
+
+        Ok(CompressedLegacyContractClass {
+            program: self.program,
+            entry_points_by_type: self.entry_points_by_type,
+            abi: self.abi,
+        })
+    }
+}
+
+/// Selector of the entry point of an ABI function, also for the functions of
+/// [`CompressedLegacyContractClass::reconstructed_abi`].
+fn abi_function_selector(name: &str) -> Option<Felt> {
+    match name.strip_prefix(RECONSTRUCTED_ABI_ENTRY_PREFIX) {
+        Some(selector) => Felt::from_hex(selector).ok(),
+        None => starknet_core::utils::get_selector_from_name(name).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LegacyFunctionAbiEntry;
+    use starknet_core::utils::get_selector_from_name;
+
+    fn function(r#type: LegacyFunctionAbiType, name: &str) -> LegacyContractAbiEntry {
+        LegacyContractAbiEntry::Function(LegacyFunctionAbiEntry {
+            r#type,
+            name: name.to_string(),
+            inputs: vec![],
+            outputs: vec![],
+            state_mutability: None,
+        })
+    }
+
+    fn entry_point(offset: u64, name: &str) -> LegacyContractEntryPoint {
+        LegacyContractEntryPoint { offset, selector: get_selector_from_name(name).unwrap() }
+    }
+
+    #[test]
+    fn test_legacy_class_builder_consistent() {
+        let class = LegacyClassBuilder::new()
+            .with_constructor(entry_point(0, "constructor"))
+            .with_external(entry_point(1, "transfer"))
+            .with_external(LegacyContractEntryPoint { offset: 2, selector: Felt::ZERO }) // __default__
+            .with_l1_handler(entry_point(3, "deposit"))
+            .with_abi(vec![
+                function(LegacyFunctionAbiType::Constructor, "constructor"),
+                function(LegacyFunctionAbiType::Function, "transfer"),
+                function(LegacyFunctionAbiType::L1Handler, "deposit"),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(class.entry_points_by_type.external.len(), 2);
+        assert_eq!(class.abi.as_ref().map(Vec::len), Some(3));
+
+        // A reconstructed ABI always matches the entry points it was made from.
+        let reconstructed = class.reconstructed_abi();
+        let mut builder = LegacyClassBuilder::new().with_abi(reconstructed);
+        builder.entry_points_by_type = class.entry_points_by_type.clone();
+        builder.build().unwrap();
+
+        // Without an ABI, there is nothing to check.
+        LegacyClassBuilder::new().with_external(entry_point(0, "transfer")).build().unwrap();
+    }
+
+    #[test]
+    fn test_legacy_class_builder_inconsistent() {
+        assert_eq!(
+            LegacyClassBuilder::new()
+                .with_external(entry_point(0, "transfer"))
+                .with_abi(vec![function(LegacyFunctionAbiType::Function, "approve")])
+                .build(),
+            Err(LegacyClassBuildError::MissingAbiFunction {
+                entry_point_type: LegacyFunctionAbiType::Function,
+                selector: get_selector_from_name("transfer").unwrap(),
+            })
+        );
+
+        // The ABI function must have the same type as the entry point.
+        assert_eq!(
+            LegacyClassBuilder::new()
+                .with_l1_handler(entry_point(0, "deposit"))
+                .with_abi(vec![function(LegacyFunctionAbiType::Function, "deposit")])
+                .build(),
+            Err(LegacyClassBuildError::MissingAbiFunction {
+                entry_point_type: LegacyFunctionAbiType::L1Handler,
+                selector: get_selector_from_name("deposit").unwrap(),
+            })
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Cache of the Sierra to CASM compilation results, keyed by Sierra class hash.
+
+use crate::{compile::ClassCompilationError, CompiledSierra, FlattenedSierraClass};
+use blockifier::execution::contract_class::ContractClass as BContractClass;
+use starknet_types_core::felt::Felt;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+/// Default size of the cache, in bytes of compiled classes.
+pub const DEFAULT_CASM_CACHE_SIZE: usize = 256 * 1024 * 1024;
+
+/// LRU cache of compiled classes bounded by their size in bytes, so that declaring or executing the same class again
+/// does not compile it again. Compilation errors are not cached, and neither are classes larger than the whole cache.
+///
+/// The cache is not locked during compilation: a class compiled concurrently by several callers may be compiled
+/// more than once.
+#[derive(Debug)]
+pub struct CasmCache {
+    inner: Mutex<Lru>,
+}
+
+#[derive(Debug)]
+struct Lru {
+    max_size: usize,
+    /// Size of the cached compiled classes, in bytes.
+    size: usize,
+    tick: u64,
+    /// class_hash => (compiled_class_hash, compiled class, last use)
+    entries: HashMap<Felt, (Felt, Arc<CompiledSierra>, u64)>,
+    /// last use => class_hash, the least recently used class comes first.
+    recency: BTreeMap<u64, Felt>,
+}
+
+impl Default for CasmCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CASM_CACHE_SIZE)
+    }
+}
+
+impl CasmCache {
+    /// Caches up to `max_size` bytes of compiled classes. 0 disables the cache.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            inner: Mutex::new(Lru { max_size, size: 0, tick: 0, entries: HashMap::new(), recency: BTreeMap::new() }),
+        }
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.lock().max_size
+    }
+
+    /// Size of the cached compiled classes, in bytes.
+    pub fn size(&self) -> usize {
+        self.lock().size
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, class_hash: &Felt) -> bool {
+        self.lock().entries.contains_key(class_hash)
+    }
+
+    /// Returns the compiled class hash and compiled class of `sierra`, compiling it only when `class_hash` is not
+    /// in the cache.
+    pub fn get_or_compile(
+        &self,
+        class_hash: Felt,
+        sierra: &FlattenedSierraClass,
+    ) -> Result<(Felt, Arc<CompiledSierra>), ClassCompilationError> {
+        self.get_or_compile_with(class_hash, || sierra.compile_to_casm())
+    }
+
+    /// Same as [`CasmCache::get_or_compile`], also returning the class converted to a blockifier class. A class
+    /// compiled now is converted from the in-memory compiled class, see
+    /// [`FlattenedSierraClass::compile_to_casm_and_blockifier`].
+    pub fn get_or_compile_to_blockifier(
+        &self,
+        class_hash: Felt,
+        sierra: &FlattenedSierraClass,
+    ) -> Result<(Felt, Arc<CompiledSierra>, BContractClass), ClassCompilationError> {
+        let cached = self.lock().get(&class_hash);
+        if let Some((compiled_class_hash, compiled)) = cached {
+            let class = compiled.to_blockifier_class()?;
+            return Ok((compiled_class_hash, compiled, class));
+        }
+
+        let (compiled_class_hash, compiled, class) = sierra.compile_to_casm_and_blockifier()?;
+        let compiled = Arc::new(compiled);
+        self.lock().insert(class_hash, compiled_class_hash, Arc::clone(&compiled));
+        Ok((compiled_class_hash, compiled, class))
+    }
+
+    fn get_or_compile_with(
+        &self,
+        class_hash: Felt,
+        compile: impl FnOnce() -> Result<(Felt, CompiledSierra), ClassCompilationError>,
+    ) -> Result<(Felt, Arc<CompiledSierra>), ClassCompilationError> {
+        if let Some(cached) = self.lock().get(&class_hash) {
+            return Ok(cached);
+        }
+
+        let (compiled_class_hash, compiled) = compile()?;
+        let compiled = Arc::new(compiled);
+        self.lock().insert(class_hash, compiled_class_hash, Arc::clone(&compiled));
+        Ok((compiled_class_hash, compiled))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        // The cache is left consistent even if a holder of the lock panicked.
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Lru {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn get(&mut self, class_hash: &Felt) -> Option<(Felt, Arc<CompiledSierra>)> {
+        let tick = self.next_tick();
+        let (compiled_class_hash, compiled, last_use) = self.entries.get_mut(class_hash)?;
+        self.recency.remove(last_use);
+        self.recency.insert(tick, *class_hash);
+        *last_use = tick;
+        Some((*compiled_class_hash, Arc::clone(compiled)))
+    }
+
+    fn insert(&mut self, class_hash: Felt, compiled_class_hash: Felt, compiled: Arc<CompiledSierra>) {
+        let entry_size = compiled.0.len();
+        if entry_size > self.max_size {
+            return;
+        }
+
+        let tick = self.next_tick();
+        self.size += entry_size;
+        if let Some((_, previous, last_use)) = self.entries.insert(class_hash, (compiled_class_hash, compiled, tick)) {
+            self.size -= previous.0.len();
+            self.recency.remove(&last_use);
+        }
+        self.recency.insert(tick, class_hash);
+
+        while self.size > self.max_size {
+            let Some((_, evicted)) = self.recency.pop_first() else { break };
+            if let Some((_, compiled, _)) = self.entries.remove(&evicted) {
+                self.size -= compiled.0.len();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the compilations, and compiles every class to a dummy class.
+    #[derive(Default)]
+    struct CountingCompiler(AtomicUsize);
+
+    impl CountingCompiler {
+        fn compile(&self, class_hash: Felt) -> Result<(Felt, CompiledSierra), ClassCompilationError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok((class_hash + Felt::ONE, CompiledSierra(format!("{class_hash:#x}"))))
+        }
+
+        fn count(&self) -> usize {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    #[test]
+    fn test_casm_cache_hit_does_not_recompile() {
+        let cache = CasmCache::default();
+        let compiler = CountingCompiler::default();
+        assert_eq!(cache.max_size(), DEFAULT_CASM_CACHE_SIZE);
+
+        let class_hash = Felt::from(0xa);
+        let first = cache.get_or_compile_with(class_hash, || compiler.compile(class_hash)).unwrap();
+        let second = cache.get_or_compile_with(class_hash, || compiler.compile(class_hash)).unwrap();
+
+        assert_eq!(compiler.count(), 1);
+        assert_eq!(first.0, Felt::from(0xb));
+        assert_eq!(first.0, second.0);
+        assert!(Arc::ptr_eq(&first.1, &second.1));
+    }
+
+    #[test]
+    fn test_casm_cache_eviction() {
+        // Every dummy compiled class is 3 bytes long, so the cache holds two of them.
+        let cache = CasmCache::new(6);
+        let compiler = CountingCompiler::default();
+        let (a, b, c) = (Felt::from(0xa), Felt::from(0xb), Felt::from(0xc));
+
+        cache.get_or_compile_with(a, || compiler.compile(a)).unwrap();
+        cache.get_or_compile_with(b, || compiler.compile(b)).unwrap();
+        // `a` becomes the most recently used class, so `b` is evicted.
+        cache.get_or_compile_with(a, || compiler.compile(a)).unwrap();
+        cache.get_or_compile_with(c, || compiler.compile(c)).unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.size(), 6);
+        assert!(cache.contains(&a));
+        assert!(!cache.contains(&b));
+        assert!(cache.contains(&c));
+        assert_eq!(compiler.count(), 3);
+
+        cache.get_or_compile_with(b, || compiler.compile(b)).unwrap();
+        assert_eq!(compiler.count(), 4);
+        assert!(!cache.contains(&a));
+    }
+
+    #[test]
+    fn test_casm_cache_skips_classes_larger_than_the_cache() {
+        let cache = CasmCache::new(2);
+        let compiler = CountingCompiler::default();
+        let class_hash = Felt::from(0xa);
+
+        cache.get_or_compile_with(class_hash, || compiler.compile(class_hash)).unwrap();
+        cache.get_or_compile_with(class_hash, || compiler.compile(class_hash)).unwrap();
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.size(), 0);
+        assert_eq!(compiler.count(), 2);
+    }
+
+    #[test]
+    fn test_casm_cache_does_not_cache_errors() {
+        let cache = CasmCache::new(6);
+        let class_hash = Felt::from(0xa);
+
+        let res = cache.get_or_compile_with(class_hash, || Err(ClassCompilationError::ProgramIsNotAnObject));
+        assert!(res.is_err());
+        assert!(cache.is_empty());
+    }
+}
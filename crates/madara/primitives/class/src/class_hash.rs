@@ -17,6 +17,27 @@ pub enum ComputeClassHashError {
     StarknetError(#[from] StarknetComputeClassHashError),
     #[error(transparent)]
     ParseError(#[from] ParseCompressedLegacyClassError),
+    #[error("Failed to serialize ABI: {0}")]
+    AbiSerialization(#[from] serde_json::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyClassHashError {
+    #[error("Class hash mismatch: expected {expected:#x}, computed {computed:#x}")]
+    ClassHashMismatch { expected: Felt, computed: Felt },
+    #[error(transparent)]
+    ComputeClassHash(#[from] ComputeClassHashError),
+}
+
+fn verify_class_hash(
+    computed: Result<Felt, ComputeClassHashError>,
+    expected: Felt,
+) -> Result<(), VerifyClassHashError> {
+    let computed = computed?;
+    if computed != expected {
+        return Err(VerifyClassHashError::ClassHashMismatch { expected, computed });
+    }
+    Ok(())
 }
 
 impl ContractClass {
@@ -26,6 +47,31 @@ impl ContractClass {
             ContractClass::Legacy(legacy) => legacy.compute_class_hash(),
         }
     }
+
+    /// Recomputes the class hash and checks it against the hash the class was served with, for classes coming
+    /// from an untrusted source.
+    pub fn verify_class_hash(&self, expected: Felt) -> Result<(), VerifyClassHashError> {
+        verify_class_hash(self.compute_class_hash(), expected)
+    }
+
+    /// Keccak hash of the ABI content.
+    pub fn compute_abi_hash(&self) -> Result<Felt, ComputeClassHashError> {
+        match self {
+            ContractClass::Sierra(sierra) => Ok(starknet_core::utils::starknet_keccak(sierra.abi.as_bytes())),
+            ContractClass::Legacy(legacy) => {
+                Ok(starknet_core::utils::starknet_keccak(&serde_json::to_vec(&legacy.abi)?))
+            }
+        }
+    }
+
+    /// Semantic equality: two classes are equal when they have the same computed class hash and ABI hash.
+    ///
+    /// Unlike `==`, this does not depend on the representation of the class. For example, the same legacy
+    /// program compressed twice may not give the same bytes.
+    pub fn class_eq(&self, other: &ContractClass) -> Result<bool, ComputeClassHashError> {
+        Ok(self.compute_class_hash()? == other.compute_class_hash()?
+            && self.compute_abi_hash()? == other.compute_abi_hash()?)
+    }
 }
 
 const SIERRA_VERSION: Felt = Felt::from_hex_unchecked("0x434f4e54524143545f434c4153535f56302e312e30"); //b"CONTRACT_CLASS_V0.1.0"
@@ -51,6 +97,11 @@ impl FlattenedSierraClass {
             program_hash,
         ]))
     }
+
+    /// See [`ContractClass::verify_class_hash`].
+    pub fn verify_class_hash(&self, expected: Felt) -> Result<(), VerifyClassHashError> {
+        verify_class_hash(self.compute_class_hash(), expected)
+    }
 }
 
 fn compute_hash_entries_point(entry_points: &[SierraEntryPoint]) -> Felt {
@@ -66,6 +117,11 @@ impl CompressedLegacyContractClass {
         let legacy_contract_class = parse_compressed_legacy_class(self.clone().into())?;
         legacy_contract_class.class_hash().map_err(ComputeClassHashError::from)
     }
+
+    /// See [`ContractClass::verify_class_hash`].
+    pub fn verify_class_hash(&self, expected: Felt) -> Result<(), VerifyClassHashError> {
+        verify_class_hash(self.compute_class_hash(), expected)
+    }
 }
 
 #[cfg(test)]
@@ -75,7 +131,8 @@ mod tests {
     use starknet_providers::{Provider, SequencerGatewayProvider};
     use starknet_types_core::felt::Felt;
 
-    use crate::ContractClass;
+    use super::VerifyClassHashError;
+    use crate::{ContractClass, FlattenedSierraClass};
 
     #[tokio::test]
     async fn test_compute_sierra_class_hash() {
@@ -95,4 +152,26 @@ mod tests {
         println!("computed_class_hash in {:?}", start.elapsed());
         assert_eq!(computed_class_hash, class_hash);
     }
+
+    #[test]
+    fn test_verify_class_hash() {
+        let class: starknet_core::types::contract::SierraClass = serde_json::from_slice(include_bytes!(
+            "../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+        ))
+        .unwrap();
+        let class_hash = class.class_hash().unwrap();
+        let sierra: FlattenedSierraClass = class.flatten().unwrap().into();
+
+        sierra.verify_class_hash(class_hash).unwrap();
+        ContractClass::Sierra(sierra.clone().into()).verify_class_hash(class_hash).unwrap();
+
+        let mut tampered = sierra;
+        tampered.sierra_program[42] += Felt::ONE;
+        let computed = tampered.compute_class_hash().unwrap();
+        assert_ne!(computed, class_hash);
+        assert!(matches!(
+            tampered.verify_class_hash(class_hash),
+            Err(VerifyClassHashError::ClassHashMismatch { expected, computed: got }) if expected == class_hash && got == computed
+        ));
+    }
 }
@@ -19,6 +19,7 @@ impl ClassUpdate {
 pub struct LegacyClassUpdate {
     pub class_hash: Felt,
     pub contract_class: crate::CompressedLegacyContractClass,
+    pub unknown_fields: crate::raw_class::UnknownClassFields,
 }
 
 #[derive(Clone, Debug)]
@@ -26,4 +27,5 @@ pub struct SierraClassUpdate {
     pub class_hash: Felt,
     pub contract_class: crate::FlattenedSierraClass,
     pub compiled_class_hash: Felt,
+    pub unknown_fields: crate::raw_class::UnknownClassFields,
 }
@@ -25,12 +25,15 @@ pub enum ClassCompilationError {
     ParsingSierraVersion(Cow<'static, str>),
     #[error("Failed to construct a blockifier class: {0}")]
     BlockifierClassConstructionFailed(#[from] cairo_vm::types::errors::program_errors::ProgramError),
+    #[error("Compiled class hash mismatch: expected {expected:#x}, got {got:#x}")]
+    CompiledClassHashMismatch { expected: Felt, got: Felt },
 }
 
 impl CompressedLegacyContractClass {
-    pub fn serialize_to_json(&self) -> Result<String, ClassCompilationError> {
+    /// Decompresses the program, adding the `debug_info` field expected by the blockifier when it is missing.
+    fn program_json(&self) -> Result<serde_json::Value, ClassCompilationError> {
         let mut program: serde_json::Value =
-            serde_json::from_reader(crate::convert::gz_decompress_stream(self.program.as_slice()))?;
+            serde_json::from_reader(crate::convert::gz_decompress_slice(self.program.as_slice())?)?;
 
         let program_object = program.as_object_mut().ok_or(ClassCompilationError::ProgramIsNotAnObject)?;
 
@@ -38,6 +41,12 @@ impl CompressedLegacyContractClass {
             program_object.insert("debug_info".to_owned(), serde_json::json!(""));
         }
 
+        Ok(program)
+    }
+
+    pub fn serialize_to_json(&self) -> Result<String, ClassCompilationError> {
+        let program = self.program_json()?;
+
         // This convoluted JSON serialization is a way to get around bincode's
         // lack of support for #[serde(tag = "type")]. Abi entries should be
         // serialized as typed JSON structs, so we have to do this manually.
@@ -51,8 +60,8 @@ impl CompressedLegacyContractClass {
                 abi.iter()
                     .map(|entry| match entry {
                         LegacyContractAbiEntry::Function(entry) => serde_json::to_value(entry).map(|mut v| {
-                            if entry.state_mutability.is_none() {
-                                v.as_object_mut().unwrap().remove("stateMutability");
+                            if let (None, Some(object)) = (&entry.state_mutability, v.as_object_mut()) {
+                                object.remove("stateMutability");
                             }
                             v
                         }),
@@ -77,6 +86,16 @@ impl CompressedLegacyContractClass {
         let class_json = self.serialize_to_json()?;
         Ok(BContractClass::V0(BContractClassV0::try_from_json_string(&class_json)?))
     }
+
+    /// Same as [`CompressedLegacyContractClass::to_blockifier_class`], without the ABI. The blockifier only needs
+    /// the program and the entry points to execute a class, so execution does not have to serialize the ABI.
+    pub fn to_executable(&self) -> Result<BContractClass, ClassCompilationError> {
+        let class_json = serde_json::json!({
+            "program": self.program_json()?,
+            "entry_points_by_type": self.entry_points_by_type,
+        });
+        Ok(BContractClass::V0(BContractClassV0::try_from_json_string(&serde_json::to_string(&class_json)?)?))
+    }
 }
 
 impl FlattenedSierraClass {
@@ -108,6 +127,44 @@ impl FlattenedSierraClass {
         };
         Ok((compiled_class_hash, CompiledSierra(compiled_class)))
     }
+
+    /// Compiles the class and converts it to a blockifier class, returning the compiled class hash, the compiled
+    /// class and the blockifier class.
+    ///
+    /// This is the fast path for callers which need to execute the class: with the latest compiler, the blockifier
+    /// class is built from the in-memory compiled class instead of being parsed back from its JSON encoding, as
+    /// [`CompiledSierra::to_blockifier_class`] does.
+    pub fn compile_to_casm_and_blockifier(
+        &self,
+    ) -> Result<(Felt, CompiledSierra, BContractClass), ClassCompilationError> {
+        match parse_sierra_version(&self.sierra_program)? {
+            SierraVersion(0, 1, 0) | SierraVersion(1, 0, 0) | SierraVersion(1, 1, 0) => {
+                let (compiled_class_hash, compiled_class) = self.compile_to_casm()?;
+                let class = compiled_class.to_blockifier_class()?;
+                Ok((compiled_class_hash, compiled_class, class))
+            }
+            _ => {
+                let casm_class = v2::compile_casm(self)?;
+                let compiled_class_hash = casm_class.compiled_class_hash();
+                let compiled_class = CompiledSierra(serde_json::to_string(&casm_class)?);
+                let class = BContractClass::V1(BContractClassV1::try_from(casm_class)?);
+                Ok((compiled_class_hash, compiled_class, class))
+            }
+        }
+    }
+
+    /// Version of the Sierra to CASM compiler used by [`FlattenedSierraClass::compile_to_casm`] for this class.
+    ///
+    /// The compiled class hash depends on the compiler version: this is stored alongside the compiled classes so
+    /// that we can tell whether compiling the class again with the current binary may give a different result.
+    pub fn casm_compiler_version(&self) -> Result<&'static str, ClassCompilationError> {
+        Ok(match parse_sierra_version(&self.sierra_program)? {
+            SierraVersion(0, 1, 0) => v1_0_0_alpha6::COMPILER_VERSION,
+            SierraVersion(1, 0, 0) => v1_0_0_rc0::COMPILER_VERSION,
+            SierraVersion(1, 1, 0) => v1_1_1::COMPILER_VERSION,
+            _ => v2::COMPILER_VERSION,
+        })
+    }
 }
 
 impl CompiledSierra {
@@ -144,6 +201,9 @@ mod v1_0_0_alpha6 {
     use casm_compiler_v1_0_0_alpha6::contract_class::{ContractClass, ContractEntryPoint, ContractEntryPoints};
     use casm_utils_v1_0_0_alpha6::bigint::BigUintAsHex;
 
+    /// Must match the version of the compiler crate in `Cargo.toml`.
+    pub(super) const COMPILER_VERSION: &str = "1.0.0-alpha.6";
+
     pub(super) fn compile(sierra: &FlattenedSierraClass) -> Result<String, ClassCompilationError> {
         let sierra_class = to_cairo_lang(sierra);
 
@@ -201,6 +261,9 @@ mod v1_0_0_rc0 {
     use casm_compiler_v1_0_0_rc0::contract_class::{ContractClass, ContractEntryPoint, ContractEntryPoints};
     use casm_utils_v1_0_0_rc0::bigint::BigUintAsHex;
 
+    /// Must match the version of the compiler crate in `Cargo.toml`.
+    pub(super) const COMPILER_VERSION: &str = "1.0.0-rc0";
+
     pub(super) fn compile(sierra: &FlattenedSierraClass) -> Result<String, ClassCompilationError> {
         let sierra_class = to_cairo_lang(sierra);
 
@@ -258,6 +321,9 @@ mod v1_1_1 {
     use casm_compiler_v1_1_1::contract_class::{ContractClass, ContractEntryPoint, ContractEntryPoints};
     use casm_utils_v1_1_1::bigint::BigUintAsHex;
 
+    /// Must match the version of the compiler crate in `Cargo.toml`.
+    pub(super) const COMPILER_VERSION: &str = "1.1.1";
+
     pub(super) fn compile(sierra: &FlattenedSierraClass) -> Result<String, ClassCompilationError> {
         let sierra_class = to_cairo_lang(sierra);
 
@@ -315,17 +381,22 @@ mod v2 {
     use casm_classes_v2::contract_class::{ContractClass, ContractEntryPoint, ContractEntryPoints};
     use casm_utils_v2::bigint::BigUintAsHex;
 
-    pub(super) fn compile(sierra: &FlattenedSierraClass) -> Result<(Felt, String), ClassCompilationError> {
-        let sierra_class = to_cairo_lang(sierra);
+    /// Must match the version of the compiler crate in `Cargo.toml`.
+    pub(super) const COMPILER_VERSION: &str = "2.8.4";
 
-        let casm_class = CasmContractClass::from_contract_class(sierra_class, true, usize::MAX)
-            .map_err(|e| ClassCompilationError::CompilationFailed(e.to_string()))?;
+    pub(super) fn compile(sierra: &FlattenedSierraClass) -> Result<(Felt, String), ClassCompilationError> {
+        let casm_class = compile_casm(sierra)?;
         let compiled_class_hash = casm_class.compiled_class_hash();
         let casm_definition = serde_json::to_string(&casm_class)?;
 
         Ok((compiled_class_hash, casm_definition))
     }
 
+    pub(super) fn compile_casm(sierra: &FlattenedSierraClass) -> Result<CasmContractClass, ClassCompilationError> {
+        CasmContractClass::from_contract_class(to_cairo_lang(sierra), true, usize::MAX)
+            .map_err(|e| ClassCompilationError::CompilationFailed(e.to_string()))
+    }
+
     pub(super) fn compute_compiled_class_hash(casm_definition: &str) -> Result<Felt, ClassCompilationError> {
         let casm_class: CasmContractClass = serde_json::from_str(casm_definition)?;
         Ok(casm_class.compiled_class_hash())
@@ -378,11 +449,15 @@ fn felt_to_big_uint(value: &Felt) -> BigUint {
 
 #[cfg(test)]
 mod tests {
-    use crate::ContractClass;
+    use crate::{
+        CompressedLegacyContractClass, ContractClass, LegacyContractAbiEntry, LegacyFunctionAbiEntry,
+        LegacyFunctionAbiType, LegacyStructAbiEntry, LegacyStructAbiType, LegacyTypedParameter,
+    };
     use starknet_core::types::BlockId;
     use starknet_core::types::BlockTag;
     use starknet_providers::{Provider, SequencerGatewayProvider};
     use starknet_types_core::felt::Felt;
+    use std::io::Read;
 
     #[tokio::test]
     async fn test_compressed_legacy_class_to_blockifier() {
@@ -416,4 +491,56 @@ mod tests {
             panic!("Not a Sierra contract");
         }
     }
+
+    #[test]
+    fn test_compile_to_casm_and_blockifier() {
+        let class: starknet_core::types::contract::SierraClass = serde_json::from_slice(include_bytes!(
+            "../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+        ))
+        .unwrap();
+        let sierra: crate::FlattenedSierraClass = class.flatten().unwrap().into();
+
+        let (compiled_class_hash, compiled) = sierra.compile_to_casm().unwrap();
+        let blockifier_class = compiled.to_blockifier_class().unwrap();
+
+        assert_eq!(sierra.compile_to_casm_and_blockifier().unwrap(), (compiled_class_hash, compiled, blockifier_class));
+    }
+
+    #[test]
+    fn test_legacy_to_executable_ignores_abi() {
+        let mut definition = vec![];
+        flate2::read::GzDecoder::new(
+            &include_bytes!(
+                "../../../client/gateway/client/src/mocks/class_block_18507_erc1155_0x04be7f1bace6f593abd8e56947c11151f45498030748a950fdaf0b79ac3dc03f.gz"
+            )[..],
+        )
+        .read_to_end(&mut definition)
+        .unwrap();
+        let class: starknet_core::types::contract::legacy::LegacyContractClass =
+            serde_json::from_slice(&definition).unwrap();
+        let mut class: CompressedLegacyContractClass = class.compress().unwrap().into();
+        let expected = class.to_blockifier_class().unwrap();
+
+        // An ABI which does not match the program at all: unknown functions and types, and a struct whose size
+        // does not match its members.
+        class.abi = Some(vec![
+            LegacyContractAbiEntry::Function(LegacyFunctionAbiEntry {
+                r#type: LegacyFunctionAbiType::Function,
+                name: "not_in_the_program".into(),
+                inputs: vec![LegacyTypedParameter { name: "x".into(), r#type: "UndefinedStruct***".into() }],
+                outputs: vec![],
+                state_mutability: None,
+            }),
+            LegacyContractAbiEntry::Struct(LegacyStructAbiEntry {
+                r#type: LegacyStructAbiType::Struct,
+                name: "".into(),
+                size: 42,
+                members: vec![],
+            }),
+        ]);
+        assert_eq!(class.to_executable().unwrap(), expected);
+
+        class.abi = None;
+        assert_eq!(class.to_executable().unwrap(), expected);
+    }
 }
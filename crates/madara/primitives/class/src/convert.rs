@@ -7,6 +7,8 @@ use starknet_core::types::{
     CompressedLegacyContractClass,
 };
 use std::io::{self, Read};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ParseCompressedLegacyClassError {
@@ -24,7 +26,96 @@ const CLASS_SIZE_LIMIT: u64 = 4 * MiB;
 
 /// Decompress and limits the size of the decompression stream, to avoid potential DoS vectors.
 pub fn gz_decompress_stream(r: impl io::Read) -> impl io::Read {
-    ReadSizeLimiter::new(GzDecoder::new(r), CLASS_SIZE_LIMIT)
+    gz_decompress_stream_with_limit(r, CLASS_SIZE_LIMIT)
+}
+
+/// Same as [`gz_decompress_stream`], with a custom limit in bytes on the decompressed output. Reading past the
+/// limit fails instead of decompressing further, which protects against decompression bombs.
+pub fn gz_decompress_stream_with_limit(r: impl io::Read, limit: u64) -> impl io::Read {
+    ReadSizeLimiter::new(GzDecoder::new(r), limit)
+}
+
+/// Same as [`gz_decompress_stream`] for a compressed buffer, which is checked to start with the gzip magic bytes
+/// first: data that is not gzip-compressed fails with a [`NotGzip`] error instead of an opaque gzip decoding error.
+pub fn gz_decompress_slice(data: &[u8]) -> io::Result<impl io::Read + '_> {
+    check_gzip_magic(data)?;
+    Ok(gz_decompress_stream(data))
+}
+
+/// Decompresses `data` into `out` chunk by chunk, without buffering the whole decompressed output, so that it
+/// can be piped directly into a deserializer or a bounded buffer. Fails once the output exceeds `limit` bytes,
+/// or with a [`NotGzip`] error when `data` is not gzip-compressed. Returns the number of decompressed bytes.
+pub fn gz_decompress_into<W: io::Write>(data: &[u8], out: &mut W, limit: u64) -> io::Result<u64> {
+    check_gzip_magic(data)?;
+    io::copy(&mut gz_decompress_stream_with_limit(data, limit), out)
+}
+
+/// First bytes of every gzip stream.
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Data expected to be gzip-compressed does not start with [`GZIP_MAGIC`]. This usually means it was stored
+/// uncompressed or with another encoding, as opposed to a corrupt gzip stream. It is returned wrapped in an
+/// [`io::Error`] of kind [`io::ErrorKind::InvalidData`], see [`is_not_gzip`].
+#[derive(thiserror::Error, Debug)]
+#[error("Data is not gzip-compressed: it does not start with the gzip magic bytes")]
+pub struct NotGzip;
+
+/// Whether decompression failed because the input is not gzip-compressed.
+pub fn is_not_gzip(err: &io::Error) -> bool {
+    err.get_ref().is_some_and(|err| err.is::<NotGzip>())
+}
+
+fn check_gzip_magic(data: &[u8]) -> io::Result<()> {
+    if !data.starts_with(&GZIP_MAGIC) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, NotGzip));
+    }
+    Ok(())
+}
+
+/// Bounds the number of class decompressions running concurrently in a service, as each of them allocates
+/// buffers of up to the size of the decompressed class. Each service (RPC, sync) has its own limiter, and takes
+/// a permit before starting work which decompresses classes. Waiting for a permit does not block the async
+/// runtime. Clones share the same permits.
+#[derive(Clone, Debug, Default)]
+pub struct DecompressionLimiter {
+    max: usize,
+    /// None means unlimited.
+    semaphore: Option<Arc<Semaphore>>,
+}
+
+impl DecompressionLimiter {
+    /// Allows at most `max` concurrent decompressions. 0 means unlimited.
+    pub fn new(max: usize) -> Self {
+        Self { max, semaphore: (max > 0).then(|| Arc::new(Semaphore::new(max))) }
+    }
+
+    /// Waits for a permit for a single decompression at a time.
+    pub async fn acquire(&self) -> DecompressionPermit {
+        self.acquire_many(1).await
+    }
+
+    /// Waits for a permit for `n` decompressions running in parallel, for instance to convert all of the classes
+    /// declared in a block. `n` is capped to the limit, so that such a batch can always proceed. The permit is
+    /// released when dropped.
+    pub async fn acquire_many(&self, n: usize) -> DecompressionPermit {
+        let Some(semaphore) = &self.semaphore else { return DecompressionPermit { _permit: None } };
+        if n == 0 {
+            return DecompressionPermit { _permit: None };
+        }
+        let n = u32::try_from(n.min(self.max)).unwrap_or(u32::MAX);
+        let permit = Arc::clone(semaphore).acquire_many_owned(n).await.expect("Decompression semaphore closed");
+        DecompressionPermit { _permit: Some(permit) }
+    }
+
+    /// Number of decompressions which can start without waiting, None when unlimited.
+    pub fn available(&self) -> Option<usize> {
+        self.semaphore.as_ref().map(|semaphore| semaphore.available_permits())
+    }
+}
+
+#[derive(Debug)]
+pub struct DecompressionPermit {
+    _permit: Option<OwnedSemaphorePermit>,
 }
 
 /// Attempts to recover a compressed legacy program.
@@ -125,6 +216,26 @@ impl<R: Read> Read for ReadSizeLimiter<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn parse_compressed_legacy_class_without_abi() {
+        let mut definition = vec![];
+        GzDecoder::new(
+            &include_bytes!(
+                "../../../client/gateway/client/src/mocks/class_block_18507_erc1155_0x04be7f1bace6f593abd8e56947c11151f45498030748a950fdaf0b79ac3dc03f.gz"
+            )[..],
+        )
+        .read_to_end(&mut definition)
+        .unwrap();
+        let class: LegacyContractClass = serde_json::from_slice(&definition).unwrap();
+        let mut class = class.compress().unwrap();
+        class.abi = None;
+
+        // A class stored without its ABI is parsed and executed with an empty ABI.
+        assert!(crate::CompressedLegacyContractClass::from(class.clone()).to_blockifier_class().is_ok());
+        assert!(parse_compressed_legacy_class(class).unwrap().abi.is_empty());
+    }
 
     #[test]
     fn read_size_limiter() {
@@ -133,4 +244,82 @@ mod tests {
         assert!(ReadSizeLimiter::new(&[0u8; 6][..], 5).read_to_end(&mut vec![]).is_err());
         assert!(ReadSizeLimiter::new(&[0u8; 64][..], 5).read_to_end(&mut vec![]).is_err());
     }
+
+    #[test]
+    fn gz_decompress_into_limit() {
+        let program = serde_json::to_vec(&serde_json::json!({ "data": vec!["0x40780017fff7fff"; 1000] })).unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(&program).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut out = vec![];
+        assert_eq!(gz_decompress_into(&compressed, &mut out, CLASS_SIZE_LIMIT).unwrap(), program.len() as u64);
+        assert_eq!(out, program);
+
+        // A small payload which decompresses to a lot of data.
+        let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::best());
+        encoder.write_all(&vec![0u8; 64 * MiB as usize]).unwrap();
+        let bomb = encoder.finish().unwrap();
+        assert!(bomb.len() < 128 * 1024);
+
+        let err = gz_decompress_into(&bomb, &mut io::sink(), CLASS_SIZE_LIMIT).unwrap_err();
+        assert!(err.get_ref().is_some_and(|err| err.is::<InputTooLarge>()));
+    }
+
+    #[test]
+    fn gz_decompress_checks_gzip_magic() {
+        let program = serde_json::to_vec(&serde_json::json!({ "data": ["0x40780017fff7fff"] })).unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        encoder.write_all(&program).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut out = vec![];
+        gz_decompress_into(&compressed, &mut out, CLASS_SIZE_LIMIT).unwrap();
+        assert_eq!(out, program);
+        let mut out = vec![];
+        gz_decompress_slice(&compressed).unwrap().read_to_end(&mut out).unwrap();
+        assert_eq!(out, program);
+
+        // The class was stored without being compressed.
+        let err = gz_decompress_into(&program, &mut io::sink(), CLASS_SIZE_LIMIT).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(is_not_gzip(&err));
+        assert!(gz_decompress_slice(&program).is_err_and(|err| is_not_gzip(&err)));
+        assert!(gz_decompress_slice(&[]).is_err_and(|err| is_not_gzip(&err)));
+
+        // A truncated gzip stream is a decoding error.
+        let err =
+            gz_decompress_into(&compressed[..compressed.len() / 2], &mut io::sink(), CLASS_SIZE_LIMIT).unwrap_err();
+        assert!(!is_not_gzip(&err));
+    }
+
+    #[tokio::test]
+    async fn decompression_limiter_waits_when_saturated() {
+        let limiter = DecompressionLimiter::new(2);
+        // Capped to the limit.
+        let permit = limiter.acquire_many(5).await;
+        assert_eq!(limiter.available(), Some(0));
+
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire().await }
+        });
+
+        // The next decompression does not proceed while the limit is reached...
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert!(!waiting.is_finished());
+
+        // ...and does as soon as the slots are released.
+        drop(permit);
+        let _permit = waiting.await.unwrap();
+        assert_eq!(limiter.available(), Some(1));
+
+        // Limiters do not share permits, and 0 means unlimited.
+        assert_eq!(DecompressionLimiter::new(2).available(), Some(2));
+        let unlimited = DecompressionLimiter::new(0);
+        let _permits = [unlimited.acquire_many(100).await, unlimited.acquire_many(100).await];
+        assert_eq!(unlimited.available(), None);
+    }
 }
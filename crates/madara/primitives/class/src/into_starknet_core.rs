@@ -416,6 +416,7 @@ mod tests {
         LegacyStructMember, LegacyTypedParameter, SierraEntryPoint,
     };
     use mp_convert::test::assert_consistent_conversion;
+    use starknet_core::types::contract::legacy::RawLegacyAbiEntry;
     use starknet_core::types::ContractClass as StarknetContractClass;
     use starknet_types_core::felt::Felt;
 
@@ -477,4 +478,122 @@ mod tests {
 
         assert_consistent_conversion::<_, StarknetContractClass>(contract_class);
     }
+
+    /// Legacy class definitions hold raw ABI entries, which are converted to [`LegacyContractAbiEntry`] when the
+    /// class is compressed, and back when it is parsed again to compute its class hash.
+    fn raw_abi_round_trip(raw: &RawLegacyAbiEntry) -> RawLegacyAbiEntry {
+        let entry: LegacyContractAbiEntry = starknet_core::types::LegacyContractAbiEntry::from(raw.clone()).into();
+        starknet_core::types::LegacyContractAbiEntry::from(entry).into()
+    }
+
+    /// The ABI of a legacy class is also served by `starknet_getClass` in the shape it was declared with, including
+    /// the constructor and L1 handler entries.
+    #[test]
+    fn test_legacy_abi_entry_raw_round_trip() {
+        let abi = serde_json::json!([
+            {
+                "type": "function",
+                "name": "transfer",
+                "inputs": [{ "name": "recipient", "type": "felt" }, { "name": "amount", "type": "Uint256" }],
+                "outputs": [{ "name": "success", "type": "felt" }],
+            },
+            {
+                "type": "function",
+                "name": "balanceOf",
+                "inputs": [{ "name": "account", "type": "felt" }],
+                "outputs": [{ "name": "balance", "type": "Uint256" }],
+                "stateMutability": "view",
+            },
+            {
+                "type": "constructor",
+                "name": "constructor",
+                "inputs": [{ "name": "owner", "type": "felt" }],
+                "outputs": [],
+            },
+            {
+                "type": "l1_handler",
+                "name": "deposit",
+                "inputs": [{ "name": "from_address", "type": "felt" }, { "name": "amount", "type": "felt" }],
+                "outputs": [],
+            },
+            {
+                "type": "event",
+                "name": "Transfer",
+                "keys": [],
+                "data": [{ "name": "from_", "type": "felt" }, { "name": "to", "type": "felt" }],
+            },
+            {
+                "type": "struct",
+                "name": "Uint256",
+                "size": 2,
+                "members": [{ "name": "low", "type": "felt", "offset": 0 }, { "name": "high", "type": "felt", "offset": 1 }],
+            },
+        ]);
+        let raw_entries: Vec<RawLegacyAbiEntry> = serde_json::from_value(abi.clone()).unwrap();
+
+        for raw in &raw_entries {
+            assert_eq!(
+                serde_json::to_value(raw_abi_round_trip(raw)).unwrap(),
+                serde_json::to_value(raw).unwrap(),
+                "{raw:?}"
+            );
+        }
+
+        // The function types survive the conversion.
+        let types: Vec<_> = raw_entries
+            .iter()
+            .map(|raw| LegacyContractAbiEntry::from(starknet_core::types::LegacyContractAbiEntry::from(raw.clone())))
+            .filter_map(|entry| match entry {
+                LegacyContractAbiEntry::Function(entry) => Some(entry.r#type),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            types,
+            [
+                LegacyFunctionAbiType::Function,
+                LegacyFunctionAbiType::Function,
+                LegacyFunctionAbiType::Constructor,
+                LegacyFunctionAbiType::L1Handler
+            ]
+        );
+
+        // The same ABI, as part of a class.
+        let class: starknet_core::types::CompressedLegacyContractClass = serde_json::from_value(serde_json::json!({
+            "program": "",
+            "entry_points_by_type": { "CONSTRUCTOR": [], "EXTERNAL": [], "L1_HANDLER": [] },
+            "abi": abi,
+        }))
+        .unwrap();
+        let original_abi = serde_json::to_vec(&class.abi).unwrap();
+        let converted: CompressedLegacyContractClass = class.into();
+        let served = mp_rpc::MaybeDeprecatedContractClass::from(ContractClass::Legacy(converted.clone().into()));
+        assert_eq!(
+            serde_json::to_value(served).unwrap()["abi"],
+            serde_json::from_slice::<serde_json::Value>(&original_abi).unwrap()
+        );
+
+        let back: starknet_core::types::CompressedLegacyContractClass = converted.into();
+        assert_eq!(serde_json::to_vec(&back.abi).unwrap(), original_abi);
+    }
+
+    /// Constructors and L1 handlers have no state mutability in the raw ABI: it is dropped by the round-trip.
+    #[test]
+    fn test_legacy_abi_entry_raw_round_trip_drops_constructor_state_mutability() {
+        for r#type in [LegacyFunctionAbiType::Constructor, LegacyFunctionAbiType::L1Handler] {
+            let entry = LegacyContractAbiEntry::Function(LegacyFunctionAbiEntry {
+                r#type,
+                name: "name".to_string(),
+                inputs: vec![LegacyTypedParameter { r#type: "felt".to_string(), name: "input".to_string() }],
+                outputs: vec![],
+                state_mutability: Some(FunctionStateMutability::View),
+            });
+            let raw: RawLegacyAbiEntry = starknet_core::types::LegacyContractAbiEntry::from(entry.clone()).into();
+            let back: LegacyContractAbiEntry = starknet_core::types::LegacyContractAbiEntry::from(raw).into();
+
+            let LegacyContractAbiEntry::Function(mut expected) = entry else { unreachable!() };
+            expected.state_mutability = None;
+            assert_eq!(back, LegacyContractAbiEntry::Function(expected));
+        }
+    }
 }
@@ -22,6 +22,17 @@ impl TryFrom<mp_rpc::MaybeDeprecatedContractClass> for ContractClass {
     }
 }
 
+/// Parses a contract class in the RPC format, such as the ones found in declare transactions.
+///
+/// This is used on untrusted input and must never panic: every malformed class is reported as an error.
+impl TryFrom<serde_json::Value> for ContractClass {
+    type Error = std::io::Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        serde_json::from_value::<mp_rpc::MaybeDeprecatedContractClass>(value)?.try_into()
+    }
+}
+
 impl From<ContractClass> for mp_rpc::MaybeDeprecatedContractClass {
     fn from(contract_class: ContractClass) -> Self {
         match contract_class {
@@ -485,4 +496,16 @@ mod tests {
 
         assert_consistent_conversion::<_, StarknetContractClass>(contract_class);
     }
+
+    #[test]
+    fn test_contract_class_from_invalid_json() {
+        assert!(ContractClass::try_from(serde_json::json!(null)).is_err());
+        assert!(ContractClass::try_from(serde_json::json!({ "abi": [] })).is_err());
+        assert!(ContractClass::try_from(serde_json::json!({
+            "program": "not base64 gzip",
+            "entry_points_by_type": { "CONSTRUCTOR": [], "EXTERNAL": [], "L1_HANDLER": [] },
+            "abi": []
+        }))
+        .is_err());
+    }
 }
@@ -3,12 +3,15 @@ use compile::ClassCompilationError;
 use starknet_types_core::felt::Felt;
 use std::{collections::HashMap, sync::Arc};
 
+pub mod builder;
+pub mod casm_cache;
 pub mod class_hash;
 pub mod class_update;
 pub mod compile;
 pub mod convert;
 mod into_starknet_core;
 mod into_starknet_types;
+pub mod raw_class;
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ConvertedClass {
@@ -33,20 +36,22 @@ impl ConvertedClass {
 
     pub fn to_blockifier_class(&self) -> Result<BContractClass, ClassCompilationError> {
         Ok(match self {
-            ConvertedClass::Legacy(class) => class.info.contract_class.to_blockifier_class()?,
-            ConvertedClass::Sierra(class) => class.compiled.to_blockifier_class()?,
+            ConvertedClass::Legacy(class) => class.info.contract_class.to_executable()?,
+            ConvertedClass::Sierra(class) => class.to_blockifier_class()?,
         })
     }
 
+    /// The Sierra program and ABI lengths are the ones of [`ContractClass::sierra_program_length`] and
+    /// [`ContractClass::abi_length`], which are also the ones stored in the class metadata of the database.
     pub fn to_blockifier_class_info(&self) -> Result<BClassInfo, ClassCompilationError> {
-        Ok(match self {
-            ConvertedClass::Legacy(class) => BClassInfo::new(&class.info.contract_class.to_blockifier_class()?, 0, 0)?,
-            ConvertedClass::Sierra(class) => BClassInfo::new(
-                &class.compiled.to_blockifier_class()?,
-                class.info.contract_class.sierra_program.len(),
-                class.info.contract_class.abi.len(),
-            )?,
-        })
+        self.blockifier_class_info(&self.to_blockifier_class()?)
+    }
+
+    /// Same as [`ConvertedClass::to_blockifier_class_info`], for a class which has already been converted to
+    /// `class`.
+    pub fn blockifier_class_info(&self, class: &BContractClass) -> Result<BClassInfo, ClassCompilationError> {
+        let contract_class = self.info().contract_class();
+        Ok(BClassInfo::new(class, contract_class.sierra_program_length(), contract_class.abi_length())?)
     }
 }
 
@@ -60,7 +65,39 @@ pub struct LegacyConvertedClass {
 pub struct SierraConvertedClass {
     pub class_hash: Felt,
     pub info: SierraClassInfo,
-    pub compiled: Arc<CompiledSierra>,
+    /// `None` when CASM compilation has been deferred to the first use of the class.
+    pub compiled: Option<Arc<CompiledSierra>>,
+}
+
+impl SierraConvertedClass {
+    /// Returns the compiled class, compiling the Sierra class now if compilation was deferred.
+    pub fn compiled_class(&self) -> Result<Arc<CompiledSierra>, ClassCompilationError> {
+        match &self.compiled {
+            Some(compiled) => Ok(Arc::clone(compiled)),
+            None => {
+                let (compiled_class_hash, compiled) = self.info.contract_class.compile_to_casm()?;
+                self.check_compiled_class_hash(compiled_class_hash)?;
+                Ok(Arc::new(compiled))
+            }
+        }
+    }
+
+    /// Returns the class converted to a blockifier class, compiling the Sierra class now if compilation was
+    /// deferred.
+    pub fn to_blockifier_class(&self) -> Result<BContractClass, ClassCompilationError> {
+        self.compiled_class()?.to_blockifier_class()
+    }
+
+    /// A deferred compilation has not been checked against the declared compiled class hash yet.
+    fn check_compiled_class_hash(&self, compiled_class_hash: Felt) -> Result<(), ClassCompilationError> {
+        if compiled_class_hash != self.info.compiled_class_hash {
+            return Err(ClassCompilationError::CompiledClassHashMismatch {
+                expected: self.info.compiled_class_hash,
+                got: compiled_class_hash,
+            });
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -148,6 +185,28 @@ impl ContractClass {
     pub fn is_legacy(&self) -> bool {
         matches!(self, ContractClass::Legacy(_))
     }
+
+    /// Struct definitions of the ABI of a legacy class. `None` for Sierra classes, whose ABI does not describe
+    /// struct layouts.
+    pub fn legacy_abi_structs(&self) -> Option<Vec<LegacyStructAbiEntry>> {
+        match self {
+            ContractClass::Sierra(_) => None,
+            ContractClass::Legacy(legacy) => Some(legacy.abi_structs()),
+        }
+    }
+
+    /// For legacy classes stored without their ABI, fills in the stub ABI from
+    /// [`CompressedLegacyContractClass::reconstructed_abi`]. Other classes are returned unchanged.
+    pub fn with_reconstructed_legacy_abi(self) -> Self {
+        match self {
+            ContractClass::Legacy(legacy) if legacy.abi.is_none() => {
+                let mut legacy = Arc::unwrap_or_clone(legacy);
+                legacy.abi = Some(legacy.reconstructed_abi());
+                ContractClass::Legacy(Arc::new(legacy))
+            }
+            class => class,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -158,11 +217,28 @@ pub struct FlattenedSierraClass {
     pub abi: String,
 }
 
+/// Every Sierra program starts with the Sierra version and the compiler version, three felts each. A shorter
+/// program cannot be a valid class, and would not hash to the class hash it was declared with.
+pub const MIN_SIERRA_PROGRAM_LENGTH: usize = 6;
+
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("Sierra program is empty or too short ({0} felts)")]
+pub struct SierraProgramTooShort(pub usize);
+
 impl FlattenedSierraClass {
     pub fn program_length(&self) -> usize {
         self.sierra_program.len()
     }
 
+    /// Fails when the Sierra program is shorter than [`MIN_SIERRA_PROGRAM_LENGTH`]. Such a class must not be served
+    /// or declared.
+    pub fn check_program_length(&self) -> Result<(), SierraProgramTooShort> {
+        if self.sierra_program.len() < MIN_SIERRA_PROGRAM_LENGTH {
+            return Err(SierraProgramTooShort(self.sierra_program.len()));
+        }
+        Ok(())
+    }
+
     pub fn abi_length(&self) -> usize {
         self.abi.len()
     }
@@ -181,10 +257,20 @@ impl TryFrom<FlattenedSierraClass> for CompressedSierraClass {
     type Error = std::io::Error;
 
     fn try_from(flattened_sierra_class: FlattenedSierraClass) -> Result<Self, Self::Error> {
+        flattened_sierra_class.compress_with(flate2::Compression::default())
+    }
+}
+
+impl FlattenedSierraClass {
+    /// Compresses the Sierra program with the given gzip level. [`Compression::best`] gives smaller classes to keep
+    /// or send, at the cost of a slower compression.
+    ///
+    /// [`Compression::best`]: flate2::Compression::best
+    pub fn compress_with(self, level: flate2::Compression) -> std::io::Result<CompressedSierraClass> {
         let mut base64_encoder =
             base64::write::EncoderWriter::new(Vec::new(), &base64::engine::general_purpose::STANDARD);
-        let mut gzip_encoder = flate2::write::GzEncoder::new(&mut base64_encoder, flate2::Compression::default());
-        serde_json::to_writer(&mut gzip_encoder, &flattened_sierra_class.sierra_program)?;
+        let mut gzip_encoder = flate2::write::GzEncoder::new(&mut base64_encoder, level);
+        serde_json::to_writer(&mut gzip_encoder, &self.sierra_program)?;
         gzip_encoder.try_finish()?;
         drop(gzip_encoder);
         let encoded_data = base64_encoder
@@ -193,11 +279,11 @@ impl TryFrom<FlattenedSierraClass> for CompressedSierraClass {
         let sierra_program = String::from_utf8(encoded_data)
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "base64 encoding error: invalid utf8"))?;
 
-        Ok(Self {
+        Ok(CompressedSierraClass {
             sierra_program,
-            contract_class_version: flattened_sierra_class.contract_class_version,
-            entry_points_by_type: flattened_sierra_class.entry_points_by_type,
-            abi: flattened_sierra_class.abi,
+            contract_class_version: self.contract_class_version,
+            entry_points_by_type: self.entry_points_by_type,
+            abi: self.abi,
         })
     }
 }
@@ -208,16 +294,17 @@ impl TryFrom<CompressedSierraClass> for FlattenedSierraClass {
     fn try_from(compressed_sierra_class: CompressedSierraClass) -> Result<Self, Self::Error> {
         let s = compressed_sierra_class.sierra_program;
         // base64 -> gz -> json
-        let sierra_program = serde_json::from_reader(crate::convert::gz_decompress_stream(
+        let sierra_program: Vec<Felt> = serde_json::from_reader(crate::convert::gz_decompress_stream(
             base64::read::DecoderReader::new(s.as_bytes(), &base64::engine::general_purpose::STANDARD),
         ))?;
-
-        Ok(Self {
+        let class = Self {
             sierra_program,
             contract_class_version: compressed_sierra_class.contract_class_version,
             entry_points_by_type: compressed_sierra_class.entry_points_by_type,
             abi: compressed_sierra_class.abi,
-        })
+        };
+        class.check_program_length().map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        Ok(class)
     }
 }
 
@@ -242,7 +329,50 @@ pub struct CompressedLegacyContractClass {
     pub abi: Option<Vec<LegacyContractAbiEntry>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+impl CompressedLegacyContractClass {
+    /// Struct definitions of the ABI, with the offsets of their members. These are needed to decode the calldata
+    /// and events of legacy contracts.
+    pub fn abi_structs(&self) -> Vec<LegacyStructAbiEntry> {
+        self.abi
+            .iter()
+            .flatten()
+            .filter_map(|entry| match entry {
+                LegacyContractAbiEntry::Struct(entry) => Some(entry.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Best-effort ABI for a class whose ABI is missing: one function entry per entry point, without inputs nor
+    /// outputs. The function names are unknown, so each entry is named [`RECONSTRUCTED_ABI_ENTRY_PREFIX`] followed
+    /// by the entry point selector, which also marks the ABI as reconstructed.
+    pub fn reconstructed_abi(&self) -> Vec<LegacyContractAbiEntry> {
+        let entry_points = &self.entry_points_by_type;
+        [
+            (LegacyFunctionAbiType::Constructor, &entry_points.constructor),
+            (LegacyFunctionAbiType::Function, &entry_points.external),
+            (LegacyFunctionAbiType::L1Handler, &entry_points.l1_handler),
+        ]
+        .into_iter()
+        .flat_map(|(r#type, entry_points)| {
+            entry_points.iter().map(move |entry_point| {
+                LegacyContractAbiEntry::Function(LegacyFunctionAbiEntry {
+                    r#type: r#type.clone(),
+                    name: format!("{RECONSTRUCTED_ABI_ENTRY_PREFIX}{:#x}", entry_point.selector),
+                    inputs: vec![],
+                    outputs: vec![],
+                    state_mutability: None,
+                })
+            })
+        })
+        .collect()
+    }
+}
+
+/// Name prefix of the ABI entries of [`CompressedLegacyContractClass::reconstructed_abi`].
+pub const RECONSTRUCTED_ABI_ENTRY_PREFIX: &str = "__reconstructed_selector_";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct LegacyEntryPointsByType {
     #[serde(rename = "CONSTRUCTOR")]
     pub constructor: Vec<LegacyContractEntryPoint>,
@@ -271,6 +401,8 @@ pub struct LegacyFunctionAbiEntry {
     pub name: String,
     pub inputs: Vec<LegacyTypedParameter>,
     pub outputs: Vec<LegacyTypedParameter>,
+    /// Only set for [`LegacyFunctionAbiType::Function`] entries. The raw ABI of legacy class definitions has no
+    /// state mutability for constructors and L1 handlers, so it is dropped when converting them.
     #[serde(rename = "stateMutability")]
     pub state_mutability: Option<FunctionStateMutability>,
 }
@@ -304,7 +436,7 @@ pub struct LegacyTypedParameter {
     pub r#type: String,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum LegacyFunctionAbiType {
     Function,
@@ -350,10 +482,199 @@ lazy_static::lazy_static! {
 mod test {
     use super::*;
 
+    #[test]
+    fn test_sierra_class_compression_levels() {
+        let class: starknet_core::types::contract::SierraClass = serde_json::from_slice(include_bytes!(
+            "../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+        ))
+        .unwrap();
+        let sierra: FlattenedSierraClass = class.flatten().unwrap().into();
+
+        let fast = sierra.clone().compress_with(flate2::Compression::fast()).unwrap();
+        let best = sierra.clone().compress_with(flate2::Compression::best()).unwrap();
+        assert!(
+            best.sierra_program.len() < fast.sierra_program.len(),
+            "best: {} bytes, fast: {} bytes",
+            best.sierra_program.len(),
+            fast.sierra_program.len()
+        );
+
+        assert_eq!(FlattenedSierraClass::try_from(fast).unwrap(), sierra);
+        assert_eq!(FlattenedSierraClass::try_from(best).unwrap(), sierra);
+    }
+
+    #[test]
+    fn test_blockifier_class_info_lengths() {
+        let class: starknet_core::types::contract::SierraClass = serde_json::from_slice(include_bytes!(
+            "../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+        ))
+        .unwrap();
+        let sierra: FlattenedSierraClass = class.flatten().unwrap().into();
+        let (compiled_class_hash, _) = sierra.compile_to_casm().unwrap();
+        let class = ConvertedClass::Sierra(SierraConvertedClass {
+            class_hash: sierra.compute_class_hash().unwrap(),
+            info: SierraClassInfo { contract_class: Arc::new(sierra.clone()), compiled_class_hash },
+            compiled: None,
+        });
+
+        let class_info = class.to_blockifier_class_info().unwrap();
+        assert_eq!(class_info.sierra_program_length(), sierra.sierra_program.len());
+        assert_eq!(class_info.sierra_program_length(), class.info().contract_class().sierra_program_length());
+        assert_eq!(class_info.abi_length(), class.info().contract_class().abi_length());
+    }
+
+    #[test]
+    fn test_deferred_compilation_checks_compiled_class_hash() {
+        let class: starknet_core::types::contract::SierraClass = serde_json::from_slice(include_bytes!(
+            "../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+        ))
+        .unwrap();
+        let sierra: FlattenedSierraClass = class.flatten().unwrap().into();
+        let (compiled_class_hash, _) = sierra.compile_to_casm().unwrap();
+        let class = SierraConvertedClass {
+            class_hash: sierra.compute_class_hash().unwrap(),
+            info: SierraClassInfo { contract_class: Arc::new(sierra), compiled_class_hash: Felt::ONE },
+            compiled: None,
+        };
+
+        assert!(matches!(
+            class.to_blockifier_class(),
+            Err(ClassCompilationError::CompiledClassHashMismatch { expected, got })
+                if expected == Felt::ONE && got == compiled_class_hash
+        ));
+        assert!(matches!(class.compiled_class(), Err(ClassCompilationError::CompiledClassHashMismatch { .. })));
+    }
+
+    #[test]
+    fn test_deferred_compilation_round_trip() {
+        let sierra = FlattenedSierraClass {
+            sierra_program: vec![Felt::ONE, Felt::TWO, Felt::THREE],
+            contract_class_version: "0.1.0".into(),
+            entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+            abi: "[]".into(),
+        };
+        let info = SierraClassInfo { contract_class: Arc::new(sierra), compiled_class_hash: Felt::ONE };
+
+        // An empty compiled class is not mistaken for a deferred compilation.
+        for compiled in [None, Some(Arc::new(CompiledSierra(String::new())))] {
+            let class = SierraConvertedClass { class_hash: Felt::TWO, info: info.clone(), compiled };
+            let encoded = serde_json::to_string(&class).unwrap();
+            assert_eq!(serde_json::from_str::<SierraConvertedClass>(&encoded).unwrap(), class);
+        }
+    }
+
     #[test]
     fn test_load_missing_class_hashes() {
         let missed_class_hashes = &MISSED_CLASS_HASHES;
         assert_eq!(missed_class_hashes.len(), 38);
         assert_eq!(missed_class_hashes.iter().map(|(_, v)| v.len()).sum::<usize>(), 57);
     }
+
+    /// Checks an entry against the `FUNCTION_ABI_ENTRY`, `EVENT_ABI_ENTRY` and `STRUCT_ABI_ENTRY` schemas of the
+    /// JSON-RPC spec. None of them allow additional properties.
+    fn assert_spec_abi_entry(entry: &serde_json::Value) {
+        fn keys(object: &serde_json::Value) -> Vec<&str> {
+            let mut keys: Vec<_> = object.as_object().unwrap().keys().map(String::as_str).collect();
+            keys.sort_unstable();
+            keys
+        }
+        fn assert_typed_parameters(parameters: &serde_json::Value, fields: &[&str]) {
+            for parameter in parameters.as_array().unwrap() {
+                assert_eq!(keys(parameter), fields, "{parameter}");
+                assert!(parameter["name"].is_string() && parameter["type"].is_string(), "{parameter}");
+            }
+        }
+
+        assert!(entry["name"].is_string(), "{entry}");
+        match entry["type"].as_str().unwrap() {
+            "function" | "l1_handler" | "constructor" => {
+                let expected = match entry.get("stateMutability") {
+                    Some(state_mutability) => {
+                        assert_eq!(state_mutability, "view");
+                        vec!["inputs", "name", "outputs", "stateMutability", "type"]
+                    }
+                    None => vec!["inputs", "name", "outputs", "type"],
+                };
+                assert_eq!(keys(entry), expected, "{entry}");
+                assert_typed_parameters(&entry["inputs"], &["name", "type"]);
+                assert_typed_parameters(&entry["outputs"], &["name", "type"]);
+            }
+            "event" => {
+                assert_eq!(keys(entry), ["data", "keys", "name", "type"], "{entry}");
+                assert_typed_parameters(&entry["keys"], &["name", "type"]);
+                assert_typed_parameters(&entry["data"], &["name", "type"]);
+            }
+            "struct" => {
+                assert_eq!(keys(entry), ["members", "name", "size", "type"], "{entry}");
+                assert!(entry["size"].is_u64(), "{entry}");
+                assert_typed_parameters(&entry["members"], &["name", "offset", "type"]);
+                assert!(entry["members"].as_array().unwrap().iter().all(|member| member["offset"].is_u64()));
+            }
+            ty => panic!("Unexpected ABI entry type {ty}"),
+        }
+    }
+
+    /// The ABI of legacy classes is served over RPC in the exact JSON shape of the spec.
+    #[test]
+    fn test_served_legacy_abi_matches_spec() {
+        const ERC721_CLASS_DEFINITION: &[u8] = include_bytes!(
+            "../../../client/gateway/client/src/mocks/class_block_3125_erc721_0x074a7ed7f1236225600f355efe70812129658c82c295ff0f8307b3fad4bf09a9.gz"
+        );
+        let class: starknet_core::types::contract::legacy::LegacyContractClass =
+            serde_json::from_reader(flate2::read::GzDecoder::new(ERC721_CLASS_DEFINITION)).unwrap();
+        let class: ContractClass = CompressedLegacyContractClass::from(class.compress().unwrap()).into();
+
+        let served = serde_json::to_value(mp_rpc::MaybeDeprecatedContractClass::from(class)).unwrap();
+        let abi = served["abi"].as_array().unwrap();
+        for ty in ["function", "constructor", "event", "struct"] {
+            assert!(abi.iter().any(|entry| entry["type"] == ty), "no {ty} in the ABI");
+        }
+        // Functions without a state mutability must not have a `null` one.
+        assert!(abi.iter().any(|entry| entry["type"] == "function" && entry.get("stateMutability").is_none()));
+        abi.iter().for_each(assert_spec_abi_entry);
+    }
+
+    #[test]
+    fn test_decompress_sierra_program_too_short() {
+        let sierra_class = |sierra_program: Vec<Felt>| FlattenedSierraClass {
+            sierra_program,
+            contract_class_version: "0.1.0".into(),
+            entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+            abi: "[]".into(),
+        };
+        let round_trip = |class: FlattenedSierraClass| {
+            FlattenedSierraClass::try_from(CompressedSierraClass::try_from(class).unwrap())
+        };
+
+        let err = round_trip(sierra_class(vec![])).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "Sierra program is empty or too short (0 felts)");
+        assert!(round_trip(sierra_class(vec![Felt::ONE; MIN_SIERRA_PROGRAM_LENGTH - 1])).is_err());
+
+        let class = sierra_class(vec![Felt::ONE; MIN_SIERRA_PROGRAM_LENGTH]);
+        assert_eq!(round_trip(class.clone()).unwrap(), class);
+    }
+
+    #[test]
+    fn test_legacy_abi_structs() {
+        const ERC721_CLASS_DEFINITION: &[u8] = include_bytes!(
+            "../../../client/gateway/client/src/mocks/class_block_3125_erc721_0x074a7ed7f1236225600f355efe70812129658c82c295ff0f8307b3fad4bf09a9.gz"
+        );
+        let class: starknet_core::types::contract::legacy::LegacyContractClass =
+            serde_json::from_reader(flate2::read::GzDecoder::new(ERC721_CLASS_DEFINITION)).unwrap();
+        let class: ContractClass = CompressedLegacyContractClass::from(class.compress().unwrap()).into();
+
+        assert_eq!(
+            class.legacy_abi_structs(),
+            Some(vec![LegacyStructAbiEntry {
+                r#type: LegacyStructAbiType::Struct,
+                name: "Uint256".into(),
+                size: 2,
+                members: vec![
+                    LegacyStructMember { name: "low".into(), r#type: "felt".into(), offset: 0 },
+                    LegacyStructMember { name: "high".into(), r#type: "felt".into(), offset: 1 },
+                ],
+            }])
+        );
+    }
 }
@@ -0,0 +1,77 @@
+//! Parsing of class definitions which keeps the top-level JSON fields unknown to this node.
+//!
+//! Newer Starknet versions may add fields to class definitions. They are not part of [`ContractClass`] and would
+//! be dropped when parsing the class, so they are kept aside to serve the class definition back as it was declared.
+
+use crate::{CompressedLegacyContractClass, ContractClass, FlattenedSierraClass};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use starknet_core::types::contract::{legacy::LegacyContractClass, CompressProgramError};
+use std::sync::Arc;
+
+/// Top-level fields of a class definition which are not part of [`ContractClass`].
+pub type UnknownClassFields = Map<String, Value>;
+
+const SIERRA_CLASS_FIELDS: &[&str] = &["sierra_program", "contract_class_version", "entry_points_by_type", "abi"];
+const LEGACY_CLASS_FIELDS: &[&str] = &["program", "entry_points_by_type", "abi"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum RawClassError {
+    #[error("Unknown contract class type")]
+    UnknownClassType,
+    #[error("Invalid class definition: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to compress legacy program: {0}")]
+    CompressProgram(#[from] CompressProgramError),
+}
+
+/// A class definition, along with its top-level JSON fields unknown to this node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawContractClass {
+    pub class: ContractClass,
+    pub unknown_fields: UnknownClassFields,
+}
+
+impl TryFrom<Value> for RawContractClass {
+    type Error = RawClassError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let (class, known_fields) = if value.get("sierra_program").is_some() {
+            let sierra = FlattenedSierraClass::deserialize(&value)?;
+            (ContractClass::Sierra(Arc::new(sierra)), SIERRA_CLASS_FIELDS)
+        } else if value.get("program").is_some() {
+            let legacy: CompressedLegacyContractClass = LegacyContractClass::deserialize(&value)?.compress()?.into();
+            (ContractClass::Legacy(Arc::new(legacy)), LEGACY_CLASS_FIELDS)
+        } else {
+            return Err(RawClassError::UnknownClassType);
+        };
+
+        let Value::Object(fields) = value else { unreachable!("Class fields were found in the value") };
+        let unknown_fields = fields.into_iter().filter(|(key, _)| !known_fields.contains(&key.as_str())).collect();
+        Ok(Self { class, unknown_fields })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use starknet_core::types::contract::SierraClass;
+
+    #[test]
+    fn test_raw_class_keeps_unknown_fields() {
+        let sierra: SierraClass = serde_json::from_slice(include_bytes!(
+            "../../../../../cairo-artifacts/openzeppelin_ERC20Upgradeable.contract_class.json"
+        ))
+        .unwrap();
+        // The compiler artifact is not a class definition as declared, flatten it first.
+        let flattened: FlattenedSierraClass = sierra.flatten().unwrap().into();
+        let mut value = serde_json::to_value(&flattened).unwrap();
+        let Value::Object(fields) = &mut value else { unreachable!() };
+        fields.insert("future_field".into(), json!({ "some": ["data"] }));
+
+        let raw = RawContractClass::try_from(value).unwrap();
+        assert_eq!(raw.class, ContractClass::Sierra(Arc::new(flattened)));
+        assert_eq!(raw.unknown_fields, Map::from_iter([("future_field".into(), json!({ "some": ["data"] }))]));
+    }
+}
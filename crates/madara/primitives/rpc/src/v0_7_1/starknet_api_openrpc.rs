@@ -235,6 +235,7 @@ pub struct ComputationResources {
     pub steps: u64,
 }
 
+/// Serializes to the `CONTRACT_ABI` shape of the spec: optional fields which are not set are omitted, not `null`.
 pub type ContractAbi = Vec<ContractAbiEntry>;
 
 #[derive(Eq, Hash, PartialEq, Serialize, Deserialize, Clone, Debug)]
@@ -469,6 +470,7 @@ pub struct DeprecatedCairoEntryPoint {
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct DeprecatedContractClass {
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub abi: Option<ContractAbi>,
     pub entry_points_by_type: DeprecatedEntryPointsByType,
     /// A base64 representation of the compressed program code
@@ -623,6 +625,7 @@ pub struct FunctionAbiEntry {
     pub name: String,
     pub outputs: Vec<TypedParameter>,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "stateMutability")]
     pub state_mutability: Option<FunctionStateMutability>,
     #[serde(rename = "type")]
@@ -8,8 +8,9 @@ use blockifier::{
 };
 use mp_chain_config::StarknetVersion;
 use mp_class::{
-    class_hash, compile::ClassCompilationError, CompressedLegacyContractClass, ConvertedClass, FlattenedSierraClass,
-    LegacyClassInfo, LegacyConvertedClass, SierraClassInfo, SierraConvertedClass,
+    casm_cache::CasmCache, class_hash, compile::ClassCompilationError, CompressedLegacyContractClass, ConvertedClass,
+    FlattenedSierraClass, LegacyClassInfo, LegacyConvertedClass, SierraClassInfo, SierraConvertedClass,
+    SierraProgramTooShort,
 };
 use mp_rpc::{BroadcastedDeclareTxn, BroadcastedTxn};
 use starknet_api::transaction::{Fee, TransactionHash};
@@ -70,6 +71,15 @@ pub trait BroadcastedTransactionExt {
         chain_id: Felt,
         starknet_version: StarknetVersion,
     ) -> Result<(BTransaction, Option<ConvertedClass>), ToBlockifierError>;
+
+    /// Same as [`BroadcastedTransactionExt::into_blockifier`], but the declared Sierra classes are compiled through
+    /// `casm_cache`.
+    fn into_blockifier_with_casm_cache(
+        self,
+        chain_id: Felt,
+        starknet_version: StarknetVersion,
+        casm_cache: &CasmCache,
+    ) -> Result<(BTransaction, Option<ConvertedClass>), ToBlockifierError>;
 }
 
 impl BroadcastedTransactionExt for BroadcastedTxn {
@@ -78,44 +88,62 @@ impl BroadcastedTransactionExt for BroadcastedTxn {
         chain_id: Felt,
         starknet_version: StarknetVersion,
     ) -> Result<(BTransaction, Option<ConvertedClass>), ToBlockifierError> {
-        let (class_info, converted_class, class_hash) = match &self {
-            BroadcastedTxn::Declare(tx) => match tx {
-                BroadcastedDeclareTxn::V1(tx) | BroadcastedDeclareTxn::QueryV1(tx) => {
-                    handle_class_legacy(Arc::new((tx.contract_class).clone().try_into()?))?
-                }
-                BroadcastedDeclareTxn::V2(tx) | BroadcastedDeclareTxn::QueryV2(tx) => {
-                    handle_class_sierra(Arc::new((tx.contract_class).clone().into()), tx.compiled_class_hash)?
-                }
-                BroadcastedDeclareTxn::V3(tx) | BroadcastedDeclareTxn::QueryV3(tx) => {
-                    handle_class_sierra(Arc::new((tx.contract_class).clone().into()), tx.compiled_class_hash)?
-                }
-            },
-            _ => (None, None, None),
-        };
-
-        let is_query = is_query(&self);
-        let TransactionWithHash { transaction, hash } =
-            TransactionWithHash::from_broadcasted(self, chain_id, starknet_version, class_hash);
-        let deployed_address = match &transaction {
-            Transaction::DeployAccount(tx) => Some(tx.calculate_contract_address()),
-            _ => None,
-        };
-        let transaction: starknet_api::transaction::Transaction = transaction.try_into()?;
+        broadcasted_into_blockifier(self, chain_id, starknet_version, None)
+    }
 
-        Ok((
-            BTransaction::from_api(
-                transaction,
-                TransactionHash(hash),
-                class_info,
-                None,
-                deployed_address.map(|address| address.try_into().expect("Address conversion should never fail")),
-                is_query,
-            )?,
-            converted_class,
-        ))
+    fn into_blockifier_with_casm_cache(
+        self,
+        chain_id: Felt,
+        starknet_version: StarknetVersion,
+        casm_cache: &CasmCache,
+    ) -> Result<(BTransaction, Option<ConvertedClass>), ToBlockifierError> {
+        broadcasted_into_blockifier(self, chain_id, starknet_version, Some(casm_cache))
     }
 }
 
+fn broadcasted_into_blockifier(
+    tx: BroadcastedTxn,
+    chain_id: Felt,
+    starknet_version: StarknetVersion,
+    casm_cache: Option<&CasmCache>,
+) -> Result<(BTransaction, Option<ConvertedClass>), ToBlockifierError> {
+    let (class_info, converted_class, class_hash) = match &tx {
+        BroadcastedTxn::Declare(tx) => match tx {
+            BroadcastedDeclareTxn::V1(tx) | BroadcastedDeclareTxn::QueryV1(tx) => {
+                handle_class_legacy(Arc::new((tx.contract_class).clone().try_into()?))?
+            }
+            BroadcastedDeclareTxn::V2(tx) | BroadcastedDeclareTxn::QueryV2(tx) => {
+                handle_class_sierra(Arc::new((tx.contract_class).clone().into()), tx.compiled_class_hash, casm_cache)?
+            }
+            BroadcastedDeclareTxn::V3(tx) | BroadcastedDeclareTxn::QueryV3(tx) => {
+                handle_class_sierra(Arc::new((tx.contract_class).clone().into()), tx.compiled_class_hash, casm_cache)?
+            }
+        },
+        _ => (None, None, None),
+    };
+
+    let is_query = is_query(&tx);
+    let TransactionWithHash { transaction, hash } =
+        TransactionWithHash::from_broadcasted(tx, chain_id, starknet_version, class_hash);
+    let deployed_address = match &transaction {
+        Transaction::DeployAccount(tx) => Some(tx.calculate_contract_address()),
+        _ => None,
+    };
+    let transaction: starknet_api::transaction::Transaction = transaction.try_into()?;
+
+    Ok((
+        BTransaction::from_api(
+            transaction,
+            TransactionHash(hash),
+            class_info,
+            None,
+            deployed_address.map(|address| address.try_into().expect("Address conversion should never fail")),
+            is_query,
+        )?,
+        converted_class,
+    ))
+}
+
 impl L1HandlerTransaction {
     pub fn into_blockifier(
         self,
@@ -165,15 +193,17 @@ pub enum ToBlockifierError {
     #[error("Failed to convert program: {0}")]
     ProgramError(#[from] cairo_vm::types::errors::program_errors::ProgramError),
     #[error("Failed to compute legacy class hash: {0}")]
-    ComputeLegacyClassHashFailed(anyhow::Error),
+    ComputeLegacyClassHashFailed(class_hash::ComputeClassHashError),
     #[error("Failed to compute sierra class hash: {0}")]
-    ComputeSierraClassHashFailed(#[from] class_hash::ComputeClassHashError),
+    ComputeSierraClassHashFailed(class_hash::ComputeClassHashError),
     #[error("Failed to convert transaction to starkneti-api: {0}")]
     ConvertToTxApiError(#[from] TransactionApiError),
     #[error("Failed to convert transaction to blockifier: {0}")]
     ConvertTxBlockifierError(#[from] TransactionExecutionError),
     #[error("Failed to convert contract class: {0}")]
     ConvertContractClassError(#[from] ContractClassError),
+    #[error("Invalid Sierra class: {0}")]
+    InvalidSierraProgram(#[from] SierraProgramTooShort),
     #[error("Compiled class hash mismatch: expected {expected}, actual {compilation}")]
     CompiledClassHashMismatch { expected: Felt, compilation: Felt },
     #[error("Failed to convert base64 program to cairo program: {0}")]
@@ -186,7 +216,7 @@ pub enum ToBlockifierError {
 fn handle_class_legacy(
     contract_class: Arc<CompressedLegacyContractClass>,
 ) -> Result<(Option<BClassInfo>, Option<ConvertedClass>, Option<Felt>), ToBlockifierError> {
-    let class_hash = contract_class.compute_class_hash()?;
+    let class_hash = contract_class.compute_class_hash().map_err(ToBlockifierError::ComputeLegacyClassHashFailed)?;
     tracing::debug!("Computed legacy class hash: {:?}", class_hash);
     let converted_class =
         ConvertedClass::Legacy(LegacyConvertedClass { class_hash, info: LegacyClassInfo { contract_class } });
@@ -197,9 +227,17 @@ fn handle_class_legacy(
 fn handle_class_sierra(
     contract_class: Arc<FlattenedSierraClass>,
     expected_compiled_class_hash: Felt,
+    casm_cache: Option<&CasmCache>,
 ) -> Result<(Option<BClassInfo>, Option<ConvertedClass>, Option<Felt>), ToBlockifierError> {
-    let class_hash = contract_class.compute_class_hash()?;
-    let (compiled_class_hash, compiled) = contract_class.compile_to_casm()?;
+    let class_hash = contract_class.compute_class_hash().map_err(ToBlockifierError::ComputeSierraClassHashFailed)?;
+    contract_class.check_program_length()?;
+    let (compiled_class_hash, compiled, blockifier_class) = match casm_cache {
+        Some(casm_cache) => casm_cache.get_or_compile_to_blockifier(class_hash, &contract_class)?,
+        None => {
+            let (compiled_class_hash, compiled, blockifier_class) = contract_class.compile_to_casm_and_blockifier()?;
+            (compiled_class_hash, Arc::new(compiled), blockifier_class)
+        }
+    };
     if expected_compiled_class_hash != compiled_class_hash {
         return Err(ToBlockifierError::CompiledClassHashMismatch {
             expected: expected_compiled_class_hash,
@@ -209,7 +247,55 @@ fn handle_class_sierra(
     let converted_class = ConvertedClass::Sierra(SierraConvertedClass {
         class_hash,
         info: SierraClassInfo { contract_class, compiled_class_hash },
-        compiled: Arc::new(compiled),
+        compiled: Some(compiled),
     });
-    Ok((Some(converted_class.to_blockifier_class_info()?), Some(converted_class), Some(class_hash)))
+    Ok((Some(converted_class.blockifier_class_info(&blockifier_class)?), Some(converted_class), Some(class_hash)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use mp_class::{EntryPointsByType, LegacyEntryPointsByType};
+
+    #[test]
+    fn test_class_hash_errors_keep_the_class_kind() {
+        let legacy = CompressedLegacyContractClass {
+            program: b"not gzip".to_vec(),
+            entry_points_by_type: LegacyEntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+            abi: None,
+        };
+        assert_matches!(
+            handle_class_legacy(Arc::new(legacy)),
+            Err(ToBlockifierError::ComputeLegacyClassHashFailed(class_hash::ComputeClassHashError::ParseError(_)))
+        );
+
+        let sierra = FlattenedSierraClass {
+            sierra_program: vec![],
+            contract_class_version: "0.2.0".into(),
+            entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+            abi: String::new(),
+        };
+        assert_matches!(
+            handle_class_sierra(Arc::new(sierra), Felt::ZERO, None),
+            Err(ToBlockifierError::ComputeSierraClassHashFailed(
+                class_hash::ComputeClassHashError::UnsupportedSierraVersion(_)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_declared_sierra_program_too_short() {
+        // The class hash can be computed, but the program is too short to be a valid class.
+        let sierra = FlattenedSierraClass {
+            sierra_program: vec![Felt::ONE, Felt::from(6), Felt::ZERO],
+            contract_class_version: "0.1.0".into(),
+            entry_points_by_type: EntryPointsByType { constructor: vec![], external: vec![], l1_handler: vec![] },
+            abi: String::new(),
+        };
+        assert_matches!(
+            handle_class_sierra(Arc::new(sierra), Felt::ZERO, None),
+            Err(ToBlockifierError::InvalidSierraProgram(SierraProgramTooShort(3)))
+        );
+    }
 }